@@ -14,6 +14,27 @@ pub fn criterion_benchmark(c: &mut Criterion) {
 			camera.render(black_box(&scene));
 		})
 	});
+
+	// Analytic sphere compared to its tessellation
+	let sphere = Sphere::new(
+		Point::new(0, 0, -1),
+		0.5,
+		Material::Matte {
+			color: Color(0.8, 0.6, 0.2),
+		},
+	);
+	let analytic = Scene::from([sphere.clone()]);
+	let mesh = Scene::from([sphere.to_mesh(32)]);
+	c.bench_function("sphere analytic", |b| {
+		b.iter(|| {
+			camera.render(black_box(&analytic));
+		})
+	});
+	c.bench_function("sphere mesh", |b| {
+		b.iter(|| {
+			camera.render(black_box(&mesh));
+		})
+	});
 }
 
 fn camera() -> Camera {
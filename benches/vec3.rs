@@ -0,0 +1,31 @@
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use raytracer::types::Vec3;
+
+/// Amount of dot products per iteration.
+const COUNT: usize = 1_000_000;
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+	let vecs = (0..COUNT)
+		.map(|_| (Vec3::random(-1..1), Vec3::random(-1..1)))
+		.collect::<Vec<_>>();
+
+	let mut group = c.benchmark_group("dot");
+	group.bench_function("scalar", |b| {
+		b.iter(|| {
+			black_box(&vecs)
+				.iter()
+				.map(|(u, v)| u.0 * v.0 + u.1 * v.1 + u.2 * v.2)
+				.sum::<f64>()
+		})
+	});
+	// Uses SIMD instructions if the `simd` feature is enabled
+	group.bench_function("vec3", |b| {
+		b.iter(|| black_box(&vecs).iter().map(|(u, v)| u.dot(*v)).sum::<f64>())
+	});
+	group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -1,14 +1,21 @@
 mod helpers;
 
+use std::convert::Infallible;
 use std::path::PathBuf;
+use std::str::FromStr;
 
 use clap::builder::Styles;
 use clap::builder::styling::AnsiColor;
-use clap::{ArgAction, Parser};
-use helpers::{UnquotedArgString, arg_desc, parse_point};
+use clap::error::ErrorKind;
+use clap::{ArgAction, CommandFactory, Parser, ValueEnum};
+use helpers::{
+	UnquotedArgString, arg_desc, parse_byte_size, parse_color, parse_key_value, parse_point,
+};
 
 use raytracer::camera::CameraSetup;
-use raytracer::types::Point;
+
+use crate::demo::AvailableDemo;
+use raytracer::types::{Color, Point};
 
 const ABOUT: &str = "Creates ray traced images.";
 
@@ -23,24 +30,101 @@ mod headings {
 #[derive(Parser)]
 #[command(version, about = ABOUT, styles = help_style(), disable_help_flag = true, disable_version_flag = true)]
 pub struct Args {
-	/// The path to the input JSON file
-	#[arg(help_heading = headings::INPUT)]
-	pub input: PathBuf,
+	/// The path to the input JSON file, or '-' to read from stdin
+	#[arg(
+		required_unless_present_any = ["input_flag", "demo", "list_demos"],
+		help_heading = headings::INPUT
+	)]
+	pub input: Option<InputSource>,
+	/// The path to the input JSON file, or '-' to read from stdin
+	#[arg(
+		id = "input_flag",
+		short,
+		long = "input",
+		value_name = "INPUT",
+		conflicts_with = "input",
+		help_heading = headings::INPUT
+	)]
+	pub input_flag: Option<InputSource>,
+	/// Render a built-in demo scene, or a demo JSON file, instead of an input file
+	#[arg(
+		long,
+		value_name = "DEMO",
+		help = AvailableDemo::help(),
+		long_help = AvailableDemo::long_help(),
+		conflicts_with_all = ["input", "input_flag"],
+		help_heading = headings::INPUT
+	)]
+	pub demo: Option<AvailableDemo>,
+	/// Render the frames of the input's animation to numbered output files
+	#[arg(
+		long,
+		requires = "output",
+		conflicts_with_all = ["demo", "watch"],
+		help_heading = headings::INPUT
+	)]
+	pub animate: bool,
+	/// Re-render whenever the input file changes
+	#[arg(long, requires = "output", conflicts_with = "demo", help_heading = headings::INPUT)]
+	pub watch: bool,
+	/// Time to wait for further changes before re-rendering, in milliseconds
+	#[arg(
+		long,
+		default_value_t = Args::default().debounce_ms,
+		hide_default_value = true,
+		help = arg_desc(
+			"Time to wait for further changes before re-rendering, in milliseconds",
+			None,
+			Some(Args::default().debounce_ms)
+		),
+		help_heading = headings::INPUT
+	)]
+	pub debounce_ms: u64,
 
 	/// Width of the image in pixels
-	#[arg(short, long, help_heading = headings::OUTPUT)]
-	pub width: usize,
+	#[arg(short, long, required_unless_present = "list_demos", help_heading = headings::OUTPUT)]
+	pub width: Option<usize>,
 	/// Height of the image in pixels
-	#[arg(short, long, help_heading = headings::OUTPUT)]
-	pub height: usize,
+	#[arg(short, long, required_unless_present = "list_demos", help_heading = headings::OUTPUT)]
+	pub height: Option<usize>,
 	/// Path to the output file
 	#[arg(
 		short,
 		long,
-		help = arg_desc("Path to the output file", None, Some(UnquotedArgString("stdout"))),
+		help = arg_desc("Path to the output file, or '-' for stdout", None, Some(UnquotedArgString("stdout"))),
+		help_heading = headings::OUTPUT
+	)]
+	pub output: Option<OutputTarget>,
+	/// Format of the output image
+	#[arg(
+		long,
+		value_enum,
+		default_value_t = Args::default().format,
+		hide_default_value = true,
+		help = arg_desc("Format of the output image", None, Some(UnquotedArgString("ppm"))),
+		help_heading = headings::OUTPUT
+	)]
+	pub format: OutputFormat,
+	/// Bits per color channel of PNG output
+	#[cfg(feature = "png-output")]
+	#[arg(
+		long,
+		value_enum,
+		help = arg_desc("Bits per color channel of PNG output", None, Some(UnquotedArgString("8"))),
 		help_heading = headings::OUTPUT
 	)]
-	pub output: Option<PathBuf>,
+	pub bits: Option<ChannelDepth>,
+	/// Quality of JPEG output
+	#[cfg(feature = "jpeg-output")]
+	#[arg(
+		long,
+		value_parser = clap::value_parser!(u8).range(1..=100),
+		default_value_t = Args::default().quality,
+		hide_default_value = true,
+		help = arg_desc("Quality of JPEG output, from 1 to 100", None, Some(Args::default().quality)),
+		help_heading = headings::OUTPUT
+	)]
+	pub quality: u8,
 	/// Value used for gamma correction
 	#[arg(
 		short,
@@ -51,6 +135,14 @@ pub struct Args {
 		help_heading = headings::OUTPUT
 	)]
 	pub gamma: f64,
+	/// Metadata embedded as comments in PPM output (not supported by other formats), can be repeated
+	#[arg(
+		long,
+		value_name = "KEY=VALUE",
+		value_parser = parse_key_value,
+		help_heading = headings::OUTPUT
+	)]
+	pub metadata: Vec<(String, String)>,
 
 	/// Camera center
 	#[arg(
@@ -98,27 +190,71 @@ pub struct Args {
 	)]
 	pub fov: Option<f64>,
 
+	/// Color of the background straight up, blended towards the bottom color downwards
+	#[arg(
+		long,
+		value_name = "R,G,B",
+		value_parser = parse_color,
+		help_heading = headings::RENDERING
+	)]
+	pub background_top: Option<Color>,
+	/// Color of the background straight down, blended towards the top color upwards
+	#[arg(
+		long,
+		value_name = "R,G,B",
+		value_parser = parse_color,
+		help_heading = headings::RENDERING
+	)]
+	pub background_bottom: Option<Color>,
+
 	/// Samples per pixel
 	#[arg(
 		short,
 		long,
-		default_value_t = Args::default().samples,
-		hide_default_value = true,
-		help = arg_desc("Samples per pixel (increase for SSAA)", None, Some(Args::default().samples)),
+		help = arg_desc("Samples per pixel (increase for SSAA)", None, Args::default().samples),
 		help_heading = headings::RENDERING
 	)]
-	pub samples: u32,
+	pub samples: Option<u32>,
+	/// Time budget of the render in seconds, within which as many samples as possible are taken
+	/// (at most 10000)
+	#[arg(
+		long,
+		value_name = "SECONDS",
+		conflicts_with = "samples",
+		help_heading = headings::RENDERING
+	)]
+	pub samples_auto: Option<f64>,
 	/// Max. amount of bounces per ray
 	#[arg(
 		short,
 		long,
-		default_value_t = Args::default().bounces,
-		hide_default_value = true,
-		help = arg_desc("Max. amount of bounces per ray", None, Some(Args::default().bounces)),
+		help = arg_desc("Max. amount of bounces per ray", None, Args::default().bounces),
 		help_heading = headings::RENDERING
 	)]
-	pub bounces: u32,
+	pub bounces: Option<u32>,
+	/// Maximum memory the render may use, such as '512MB' or '4GB'
+	#[arg(
+		long,
+		value_name = "SIZE",
+		value_parser = parse_byte_size,
+		help_heading = headings::RENDERING
+	)]
+	pub max_memory: Option<usize>,
+	/// Index of the tile to render, out of --tile-count tiles
+	#[arg(
+		long,
+		requires_all = ["tile_count", "output"],
+		conflicts_with_all = ["animate", "watch"],
+		help_heading = headings::RENDERING
+	)]
+	pub tile_index: Option<usize>,
+	/// Amount of equally sized tiles the image is split into
+	#[arg(long, requires = "tile_index", help_heading = headings::RENDERING)]
+	pub tile_count: Option<usize>,
 
+	/// Print the available demos and exit
+	#[arg(long, exclusive = true, help_heading = headings::INFO)]
+	pub list_demos: bool,
 	/// Print help message and exit
 	#[arg(short = 'H', long, action = ArgAction::Help, help_heading = headings::INFO)]
 	pub help: Option<bool>,
@@ -128,9 +264,50 @@ pub struct Args {
 }
 
 impl Args {
-	/// Parses CLI arguments.
+	/// Parses CLI arguments, and exits with an error if options are used with an output format
+	/// that doesn't support them (see [`Args::check_format_options`]).
 	pub fn parse() -> Self {
-		<Self as Parser>::parse()
+		let args = <Self as Parser>::parse();
+		if let Err(message) = args.check_format_options() {
+			Self::command()
+				.error(ErrorKind::ArgumentConflict, message)
+				.exit();
+		}
+		args
+	}
+	/// Checks that options specific to an output format are only used with that format.
+	pub fn check_format_options(&self) -> Result<(), String> {
+		let format = self
+			.format
+			.to_possible_value()
+			.expect("format should have a name");
+		if !self.metadata.is_empty() && self.format != OutputFormat::Ppm {
+			return Err(format!(
+				"--metadata is only supported for PPM output, but the format is {}",
+				format.get_name()
+			));
+		}
+		#[cfg(feature = "png-output")]
+		if self.bits.is_some() && self.format != OutputFormat::Png {
+			return Err(format!(
+				"--bits is only supported for PNG output, but the format is {}",
+				format.get_name()
+			));
+		}
+		Ok(())
+	}
+	/// Returns the dimensions `(width, height)` of the output image.
+	///
+	/// # Panics
+	/// Panics if either dimension is missing, which the parser only allows when listing demos.
+	pub fn image_size(&self) -> (usize, usize) {
+		let width = self.width.expect("width should be present");
+		let height = self.height.expect("height should be present");
+		(width, height)
+	}
+	/// Returns the source of the input, whether specified positionally or with `--input`.
+	pub fn input_source(&self) -> Option<&InputSource> {
+		self.input.as_ref().or(self.input_flag.as_ref())
 	}
 }
 
@@ -138,24 +315,108 @@ impl Default for Args {
 	fn default() -> Self {
 		let setup = CameraSetup::default();
 		Self {
-			input: PathBuf::from("./inputs/test.json"),
-			width: 0,
-			height: 0,
+			input: Some(InputSource::File(PathBuf::from("./inputs/test.json"))),
+			input_flag: None,
+			demo: None,
+			animate: false,
+			watch: false,
+			debounce_ms: 500,
+			width: Some(0),
+			height: Some(0),
 			output: None,
+			format: OutputFormat::Ppm,
+			#[cfg(feature = "png-output")]
+			bits: None,
+			#[cfg(feature = "jpeg-output")]
+			quality: 90,
 			gamma: 2.2,
+			metadata: Vec::new(),
 			center: Some(setup.lookfrom),
 			target: Some(setup.lookat),
 			aperture: Some(setup.defocus_angle),
 			focus: Some(setup.lookfrom.distance(setup.lookat)),
 			fov: Some(setup.v_fov),
-			samples: 100,
-			bounces: 10,
+			background_top: None,
+			background_bottom: None,
+			samples: Some(100),
+			samples_auto: None,
+			bounces: Some(10),
+			max_memory: None,
+			tile_index: None,
+			tile_count: None,
+			list_demos: false,
 			help: None,
 			version: None,
 		}
 	}
 }
 
+/// The file format of the output image.
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+	/// Binary portable pixmap, gamma corrected
+	Ppm,
+	/// 24-bit Windows bitmap, gamma corrected
+	Bmp,
+	/// OpenEXR with linear 32-bit float channels
+	#[cfg(feature = "exr-output")]
+	Exr,
+	/// Portable Network Graphics with 8 or 16 bits per channel, gamma corrected
+	#[cfg(feature = "png-output")]
+	Png,
+	/// JPEG with adjustable quality, gamma corrected
+	#[cfg(feature = "jpeg-output")]
+	Jpeg,
+}
+
+/// The amount of bits per color channel of the output image.
+#[cfg(feature = "png-output")]
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum ChannelDepth {
+	/// 8 bits per channel
+	#[value(name = "8")]
+	Eight,
+	/// 16 bits per channel
+	#[value(name = "16")]
+	Sixteen,
+}
+
+/// The source the input JSON is read from.
+#[derive(Clone, Debug, PartialEq)]
+pub enum InputSource {
+	/// A file at the specified path
+	File(PathBuf),
+	/// The standard input, specified as `-`
+	Stdin,
+}
+impl FromStr for InputSource {
+	type Err = Infallible;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"-" => Ok(Self::Stdin),
+			path => Ok(Self::File(PathBuf::from(path))),
+		}
+	}
+}
+
+/// The target the output image is written to.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OutputTarget {
+	/// A file at the specified path
+	File(PathBuf),
+	/// The standard output, specified as `-`
+	Stdout,
+}
+impl FromStr for OutputTarget {
+	type Err = Infallible;
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"-" => Ok(Self::Stdout),
+			path => Ok(Self::File(PathBuf::from(path))),
+		}
+	}
+}
+
 /// Defines the color style of the help message.
 fn help_style() -> Styles {
 	Styles::styled()
@@ -164,3 +425,109 @@ fn help_style() -> Styles {
 		.literal(AnsiColor::Cyan.on_default().bold())
 		.placeholder(AnsiColor::Cyan.on_default())
 }
+
+#[cfg(test)]
+mod tests {
+	use std::path::PathBuf;
+
+	use clap::Parser;
+
+	use super::{Args, InputSource, OutputTarget};
+
+	#[test]
+	fn if_input_is_dash_then_source_is_stdin() {
+		// Both the positional argument and the flag should accept the sentinel:
+		for argv in [
+			vec!["raytracer", "-", "-w=1", "-h=1"],
+			vec!["raytracer", "--input", "-", "-w=1", "-h=1"],
+		] {
+			let args = Args::try_parse_from(&argv).unwrap();
+			assert_eq!(
+				args.input_source(),
+				Some(&InputSource::Stdin),
+				"input should be read from stdin for {:?}",
+				argv
+			);
+		}
+	}
+
+	#[test]
+	fn if_input_is_path_then_source_is_file() {
+		let args = Args::try_parse_from(["raytracer", "-i", "scene.json", "-w=1", "-h=1"]).unwrap();
+		assert_eq!(
+			args.input_source(),
+			Some(&InputSource::File(PathBuf::from("scene.json"))),
+			"input should be read from the file"
+		);
+	}
+
+	#[test]
+	fn if_output_is_dash_then_target_is_stdout() {
+		let args = Args::try_parse_from(["raytracer", "-", "-w=1", "-h=1", "-o", "-"]).unwrap();
+		assert_eq!(
+			args.output,
+			Some(OutputTarget::Stdout),
+			"output should be written to stdout"
+		);
+	}
+
+	#[test]
+	fn if_metadata_used_without_ppm_then_format_options_rejected() {
+		let args = Args::try_parse_from([
+			"raytracer",
+			"-",
+			"-w=1",
+			"-h=1",
+			"--format",
+			"bmp",
+			"--metadata",
+			"author=me",
+		])
+		.unwrap();
+		assert!(
+			args.check_format_options().is_err(),
+			"metadata should be rejected for BMP output, but wasn't"
+		);
+
+		let args =
+			Args::try_parse_from(["raytracer", "-", "-w=1", "-h=1", "--metadata", "author=me"])
+				.unwrap();
+		assert!(
+			args.check_format_options().is_ok(),
+			"metadata should be accepted for PPM output, but wasn't"
+		);
+	}
+
+	#[cfg(feature = "png-output")]
+	#[test]
+	fn if_bits_used_without_png_then_format_options_rejected() {
+		let args =
+			Args::try_parse_from(["raytracer", "-", "-w=1", "-h=1", "--bits", "16"]).unwrap();
+		assert!(
+			args.check_format_options().is_err(),
+			"bits should be rejected for PPM output, but wasn't"
+		);
+
+		let args = Args::try_parse_from([
+			"raytracer",
+			"-",
+			"-w=1",
+			"-h=1",
+			"--format",
+			"png",
+			"--bits",
+			"16",
+		])
+		.unwrap();
+		assert!(
+			args.check_format_options().is_ok(),
+			"bits should be accepted for PNG output, but wasn't"
+		);
+	}
+
+	#[test]
+	fn if_input_specified_twice_then_parsing_fails() {
+		let result = Args::try_parse_from(["raytracer", "a.json", "-i", "b.json", "-w=1", "-h=1"]);
+		assert!(result.is_err(), "parsing should fail, but didn't");
+	}
+}
@@ -1,16 +1,26 @@
 use clap::error::{Error, ErrorKind};
-use raytracer::types::Point;
+use raytracer::types::{Color, Point, Vec3};
 
 /// Represents a type that can be represented as a string in the CLI.
 pub trait ToArgString {
 	/// Converts the type to a string representation for the CLI.
 	fn to_arg_str(&self) -> String;
 }
+impl ToArgString for u8 {
+	fn to_arg_str(&self) -> String {
+		self.to_string()
+	}
+}
 impl ToArgString for u32 {
 	fn to_arg_str(&self) -> String {
 		self.to_string()
 	}
 }
+impl ToArgString for u64 {
+	fn to_arg_str(&self) -> String {
+		self.to_string()
+	}
+}
 impl ToArgString for f64 {
 	fn to_arg_str(&self) -> String {
 		format!("{:.1}", self)
@@ -50,20 +60,113 @@ pub fn arg_desc<T: ToArgString>(desc: &str, format: Option<&str>, default: Optio
 
 /// Parses a string argument into a [`Point`].
 pub fn parse_point(arg: &str) -> Result<Point, Error> {
-	let msg: &str = "format for point type is 'x,y,z', where 'x', 'y', and 'z' are numeric
+	let msg: &str = "format for point type is 'x,y,z', where 'x', 'y', and 'z' are numeric,
+or one of 'up', 'down', 'left', 'right', 'forward', 'back', 'zero'
 example: '1.0,-2.0,3'\n
 hint: try specifying the value like this: '--option=-1.5,2.0,3'";
 	arg.parse::<Point>()
 		.map_err(|e| Error::raw(ErrorKind::ValueValidation, format!("{}\n{}", e, msg)))
 }
 
+/// Parses a string argument of the form `r,g,b` into a [`Color`].
+pub fn parse_color(arg: &str) -> Result<Color, Error> {
+	let msg: &str = "format for color type is 'r,g,b', where 'r', 'g', and 'b' are numeric
+example: '0.5,0.7,1.0'";
+	arg.parse::<Vec3>()
+		.map(Color::from)
+		.map_err(|e| Error::raw(ErrorKind::ValueValidation, format!("{}\n{}", e, msg)))
+}
+
+/// Parses a string argument of the form `key=value` into the key and the value.
+/// The value may contain further `=` signs, while the key must not be empty.
+pub fn parse_key_value(arg: &str) -> Result<(String, String), Error> {
+	let msg = "format for metadata is 'key=value'
+example: 'scene=inputs/test.json'";
+	match arg.split_once('=') {
+		Some((key, value)) if !key.trim().is_empty() => {
+			Ok((key.trim().to_string(), value.to_string()))
+		}
+		_ => Err(Error::raw(ErrorKind::ValueValidation, msg)),
+	}
+}
+
+/// Parses a human-readable amount of memory, such as `512MB` or `4GB`, into bytes.
+///
+/// The units `B`, `KB`, `MB`, `GB` and `TB` (case-insensitive) are multiples of 1024.
+/// Without a unit, the amount is in bytes.
+pub fn parse_byte_size(arg: &str) -> Result<usize, Error> {
+	let msg = "format for sizes is a number followed by a unit (B, KB, MB, GB, TB)
+example: '512MB', '4GB'";
+	let invalid = || Error::raw(ErrorKind::ValueValidation, msg);
+	let arg = arg.trim();
+	let split = arg
+		.find(|c: char| !c.is_ascii_digit() && c != '.')
+		.unwrap_or(arg.len());
+	let (amount, unit) = arg.split_at(split);
+	let exponent = match unit.trim().to_ascii_uppercase().as_str() {
+		"" | "B" => 0,
+		"KB" => 1,
+		"MB" => 2,
+		"GB" => 3,
+		"TB" => 4,
+		_ => return Err(invalid()),
+	};
+	let amount = amount.parse::<f64>().map_err(|_| invalid())?;
+	Ok((amount * 1024f64.powi(exponent)) as usize)
+}
+
 #[cfg(test)]
 mod tests {
 	use raytracer::types::Point;
 
 	use crate::args::helpers::UnquotedArgString;
 
-	use super::{arg_desc, parse_point};
+	use super::{arg_desc, parse_byte_size, parse_key_value, parse_point};
+
+	#[test]
+	fn should_parse_byte_sizes_with_units() {
+		let cases = [
+			("1024", 1024),
+			("2KB", 2048),
+			("512MB", 512 * 1024 * 1024),
+			("4gb", 4 * 1024 * 1024 * 1024),
+			("1.5 GB", 3 * 512 * 1024 * 1024),
+		];
+		for (arg, expected) in cases {
+			let size = parse_byte_size(arg);
+			assert!(
+				matches!(size, Ok(bytes) if bytes == expected),
+				"{} should be parsed to {} bytes, but was {:?}",
+				arg,
+				expected,
+				size.ok()
+			);
+		}
+	}
+
+	#[test]
+	fn if_byte_size_has_unknown_unit_then_error() {
+		let size = parse_byte_size("4 parsecs");
+		assert!(size.is_err(), "unit is unknown, but size was parsed");
+	}
+
+	#[test]
+	fn should_parse_key_value_pairs() {
+		let pair = parse_key_value("note=a=b");
+		assert_eq!(
+			pair.ok(),
+			Some((String::from("note"), String::from("a=b"))),
+			"value should contain everything after the first '='"
+		);
+		assert!(
+			parse_key_value("=value").is_err(),
+			"key is missing, but pair was parsed"
+		);
+		assert!(
+			parse_key_value("novalue").is_err(),
+			"'=' is missing, but pair was parsed"
+		);
+	}
 
 	#[test]
 	fn should_parse_point_with_given_coordinates() {
@@ -80,6 +183,15 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn should_parse_point_from_direction_name() {
+		let point = parse_point("down");
+		assert!(
+			matches!(point, Ok(Point(x, y, z)) if (x, y, z) == (0.0, -1.0, 0.0)),
+			"point should be parsed from name"
+		);
+	}
+
 	#[test]
 	fn if_point_arg_has_less_coordinates_then_error() {
 		let point = parse_point("-1.0,2");
@@ -1,11 +1,20 @@
 use std::f64::consts::PI;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::io::Write;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 
-use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+use rayon::iter::{
+	IndexedParallelIterator, IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
+};
 use rayon::slice::ParallelSliceMut;
+use serde::{Deserialize, Serialize};
 
+use super::error::RaytracerError;
+use super::output::ppm::StreamWriter;
+use super::random;
 use super::scene::Scene;
-use super::types::{Color, Image, Point, Ray, ToVec3, Vec3};
+use super::types::{Color, Image, Point, PostProcessPipeline, Ray, ToVec3, Vec3, vignette_weight};
 
 /// Caret return followed by ANSI erase line command sequence.
 #[cfg(not(feature = "bench"))]
@@ -18,6 +27,33 @@ macro_rules! log {
 	};
 }
 
+mod adaptive;
+mod aov;
+mod checkpoint;
+mod jitter;
+mod json;
+mod memory;
+mod passes;
+mod region;
+mod stereo;
+
+pub use aov::{AlbedoAov, Aov, DepthAov, MaterialIdAov, NormalAov};
+pub use jitter::JitterPattern;
+pub use passes::RenderOutput;
+pub use region::RenderProgress;
+
+/// The factor by which the field of view of [`CameraSetup::auto_place`] is widened
+/// beyond the scene, so that it doesn't touch the edges of the image.
+const AUTO_PLACE_PADDING: f64 = 1.1;
+/// The azimuth and elevation, in degrees, from which [`CameraSetup::auto_place`] looks at a scene.
+const AUTO_PLACE_ANGLES: (f64, f64) = (0.0, 30.0);
+
+/// The maximum amount of rows rendered by [`Camera::estimate_render_time`].
+const ESTIMATE_ROWS: usize = 32;
+
+/// The length of the longer image edge of preview renders, in pixels.
+const PREVIEW_EDGE: usize = 256;
+
 // MARK: - CameraSetup
 
 /// A type that stores mandatory information for a camera.
@@ -56,6 +92,58 @@ impl Default for CameraSetup {
 		}
 	}
 }
+
+// Placement
+impl CameraSetup {
+	/// Creates a setup for a camera that looks at the whole `scene` from the front and slightly
+	/// above, see [`CameraSetup::auto_place_at_angle`].
+	pub fn auto_place(scene: &Scene) -> CameraSetup {
+		Self::auto_place_at_angle(scene, AUTO_PLACE_ANGLES.0, AUTO_PLACE_ANGLES.1)
+	}
+	/// Creates a setup for a camera that looks at the center of the `scene`'s bounding box
+	/// from the specified direction, and fits the whole box into the frame vertically
+	/// (with [`AUTO_PLACE_PADDING`]), so that wider images contain it as well.
+	///
+	/// The `azimuth_deg` is the angle around the y-axis, starting at the `+z` side of the scene and
+	/// turning towards `+x`. The `elevation_deg` is the angle above the horizontal plane, and is
+	/// limited to (-90°; 90°), so that the camera never looks straight up or down.
+	/// The camera is placed outside of the bounding box, at twice the distance from its center to
+	/// its corners, and focuses on its center. Objects without a bounding box (see
+	/// [`Scene::bounding_box`]) aren't framed. If the scene has no bounding box,
+	/// the default setup is returned.
+	pub fn auto_place_at_angle(scene: &Scene, azimuth_deg: f64, elevation_deg: f64) -> CameraSetup {
+		let Some(aabb) = scene.bounding_box() else {
+			return Self::default();
+		};
+		let center = aabb.center();
+		let (x, y, z) = (aabb.x, aabb.y, aabb.z);
+		let radius = Vec3(x.end - x.start, y.end - y.start, z.end - z.start).norm() / 2.0;
+		let radius = if radius > 0.0 { radius } else { 1.0 };
+
+		let (azimuth, elevation) = (
+			azimuth_deg.to_radians(),
+			elevation_deg.clamp(-89.0, 89.0).to_radians(),
+		);
+		let direction = Vec3(
+			elevation.cos() * azimuth.sin(),
+			elevation.sin(),
+			elevation.cos() * azimuth.cos(),
+		);
+		let distance = 2.0 * radius;
+		let lookfrom: Point = (center.to_vec3() + direction.scale(distance)).into();
+		// The bounding sphere of the box appears under this angle
+		let half_fov = f64::asin(f64::min(1.0, AUTO_PLACE_PADDING * radius / distance));
+		Self {
+			v_fov: 2.0 * half_fov.to_degrees(),
+			lookfrom,
+			lookat: center,
+			view_up: Vec3(0.0, 1.0, 0.0),
+			defocus_angle: 0.0,
+			focus_distance: (lookfrom.to_vec3() - center.to_vec3()).norm(),
+			..Self::default()
+		}
+	}
+}
 impl From<CameraSetup> for Camera {
 	fn from(value: CameraSetup) -> Self {
 		Camera::new(value)
@@ -77,10 +165,18 @@ impl From<CameraSetup> for Camera {
 ///
 /// let camera = camera.bounces(10);
 /// ```
-#[derive(Debug, Clone, Copy)]
+/// A camera with all of its settings can be saved with [`Camera::to_json`],
+/// and restored with [`Camera::from_json`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(into = "json::CameraJson", from = "json::CameraJson")]
 pub struct Camera {
-	/// The image dimensions (width, height).
+	/// The setup this camera was created from.
+	setup: CameraSetup,
+	/// The dimensions (width, height) of the image that is rendered, before downsampling.
 	img_size: (usize, usize),
+	/// The factor by which the image is rendered larger than the setup specifies,
+	/// and downsampled after rendering.
+	resolution_scale: usize,
 	/// The center point of the camera (origin of all rays).
 	center: Point,
 	/// Horizontal delta vector between pixels.
@@ -98,6 +194,8 @@ pub struct Camera {
 	/// Amount of samples per pixel.
 	/// A value larger than 1 enables SSAA (supersampling anti-aliasing).
 	samples_per_px: u32,
+	/// The pattern in which the samples are distributed over each pixel.
+	jitter: JitterPattern,
 	/// Amount of bounces off surfaces per ray.
 	bounces: u32,
 	/// An angular measure of aperture, in degrees.
@@ -107,6 +205,27 @@ pub struct Camera {
 	defocus_disk_u: Vec3,
 	/// Vertical aperture offset vector.
 	defocus_disk_v: Vec3,
+	/// Strength of the chromatic aberration applied after rendering.
+	/// A value of 0 disables the effect.
+	chromatic_aberration: f64,
+	/// The radial distortion coefficients `(k1, k2)` of the lens, applied after rendering.
+	/// Coefficients of 0 disable the effect.
+	lens_distortion: (f64, f64),
+	/// How much the corners of the image are darkened by vignetting.
+	/// A value of 0 disables the effect.
+	vignette_strength: f64,
+	/// How quickly vignetting sets in towards the corners of the image.
+	vignette_falloff: f64,
+	/// The times `(open, close)` of the shutter, if rays should be cast at random times in between.
+	shutter_time: Option<(f64, f64)>,
+	/// The seed for random sampling, if renders should be reproducible.
+	seed: Option<u64>,
+	/// The maximum amount of memory a render may use, in bytes, see [`Camera::try_render`].
+	memory_limit: Option<usize>,
+	/// The output variables rendered by [`Camera::render_all_aovs`].
+	aovs: Vec<Arc<dyn Aov>>,
+	/// The pipeline applied to rendered images after all other post-processing.
+	pipeline: Option<Arc<PostProcessPipeline>>,
 }
 
 // Constructors
@@ -143,16 +262,28 @@ impl Camera {
 		let defocus_disk_u = u.scale(defocus_radius);
 		let defocus_disk_v = v.scale(defocus_radius);
 		Self {
+			setup,
 			img_size: (setup.width, setup.height),
+			resolution_scale: 1,
 			center: camera_center,
 			px_d_u,
 			px_d_v,
 			px_00,
 			samples_per_px: 1,
+			jitter: JitterPattern::Random,
 			bounces: 1,
 			defocus_angle: setup.defocus_angle,
 			defocus_disk_u,
 			defocus_disk_v,
+			chromatic_aberration: 0.0,
+			lens_distortion: (0.0, 0.0),
+			vignette_strength: 0.0,
+			vignette_falloff: 1.0,
+			shutter_time: None,
+			seed: None,
+			memory_limit: None,
+			aovs: Vec::new(),
+			pipeline: None,
 		}
 	}
 	/// Calculates the dimensions of the viewport from specified image dimensions.
@@ -177,6 +308,87 @@ impl Camera {
 		let px_00 = vp_00 + (px_d_u + px_d_v) / 2.0;
 		px_00.into()
 	}
+	/// Creates a new camera from the specified setup, keeping the optional features of this camera.
+	fn rebuilt(&self, setup: CameraSetup) -> Self {
+		let scaled = CameraSetup {
+			width: setup.width * self.resolution_scale,
+			height: setup.height * self.resolution_scale,
+			..setup
+		};
+		Camera {
+			setup,
+			resolution_scale: self.resolution_scale,
+			samples_per_px: self.samples_per_px,
+			jitter: self.jitter.clone(),
+			bounces: self.bounces,
+			// Keep depth of field disabled, if it was turned off for a preview
+			defocus_angle: f64::min(self.defocus_angle, setup.defocus_angle),
+			chromatic_aberration: self.chromatic_aberration,
+			lens_distortion: self.lens_distortion,
+			vignette_strength: self.vignette_strength,
+			vignette_falloff: self.vignette_falloff,
+			shutter_time: self.shutter_time,
+			seed: self.seed,
+			memory_limit: self.memory_limit,
+			aovs: self.aovs.clone(),
+			pipeline: self.pipeline.clone(),
+			..Camera::new(scaled)
+		}
+	}
+}
+
+// Getters
+impl Camera {
+	/// The distance between the camera center and the point the camera is looking at.
+	pub fn focal_length(&self) -> f64 {
+		(self.setup.lookfrom.to_vec3() - self.setup.lookat.to_vec3()).norm()
+	}
+	/// The dimensions `(width, height)` of the viewport, in world units.
+	pub fn viewport_size(&self) -> (f64, f64) {
+		Self::viewport_dimensions(&self.setup)
+	}
+	/// The ratio of the image width to the image height.
+	pub fn aspect_ratio(&self) -> f64 {
+		self.img_size.0 as f64 / self.img_size.1 as f64
+	}
+	/// The dimensions `(width, height)` of the rendered image, in pixels.
+	pub fn image_size(&self) -> (usize, usize) {
+		(self.setup.width, self.setup.height)
+	}
+	/// The amount of samples taken per pixel.
+	pub fn samples_per_pixel(&self) -> u32 {
+		self.samples_per_px
+	}
+	/// The maximum amount of bounces per ray.
+	pub fn max_bounces(&self) -> u32 {
+		self.bounces
+	}
+	/// The angular measure of the aperture, in degrees. A value of 0 disables depth of field.
+	pub fn defocus_angle(&self) -> f64 {
+		self.defocus_angle
+	}
+	/// The distance from the camera center to the plane where objects are in focus.
+	pub fn focus_distance(&self) -> f64 {
+		self.setup.focus_distance
+	}
+	/// The area of a pixel on the plane where objects are in focus, in square world units.
+	pub fn pixel_area_at_focus(&self) -> f64 {
+		self.px_d_u.cross(self.px_d_v).norm()
+	}
+	/// The solid angle, in steradians, that the pixel `(px_i, px_j)` (column and row of the
+	/// rendered image, before downsampling) covers as seen from the camera center.
+	///
+	/// Pixels towards the edges of the image are further away and seen at a steeper angle,
+	/// and thus cover a smaller solid angle than pixels at the center.
+	pub fn pixel_solid_angle(&self, px_i: usize, px_j: usize) -> f64 {
+		let px_center =
+			self.px_00.to_vec3() + self.px_d_u.scale(px_i as f64) + self.px_d_v.scale(px_j as f64);
+		let direction = px_center - self.center.to_vec3();
+		let normal = self.px_d_u.cross(self.px_d_v);
+		let distance_sq = direction.norm_sq();
+		// Area projected onto the unit sphere: A * cos(theta) / r^2
+		normal.dot(direction).abs() / (distance_sq * distance_sq.sqrt())
+	}
 }
 
 // Optional features
@@ -190,17 +402,175 @@ impl Camera {
 			..self
 		}
 	}
+	/// Specifies the pattern in which the samples of each pixel are distributed, if anti-aliasing
+	/// is enabled. By default, samples are placed randomly ([`JitterPattern::Random`]).
+	pub fn with_jitter_pattern(self, jitter: JitterPattern) -> Self {
+		Camera { jitter, ..self }
+	}
 	/// Specifies how many times a ray can bounce until the color is determined.
 	/// An amount of 0 means rays do not bounce and only return the color of the surface they land on.
 	pub fn bounces(self, bounces: u32) -> Self {
 		Camera { bounces, ..self }
 	}
+	/// Disables depth of field and supersampling for this camera.
+	///
+	/// This produces a quick pinhole render, which is useful for checking the composition of a scene.
+	pub fn with_dof_preview(self) -> Self {
+		Camera {
+			defocus_angle: 0.0,
+			samples_per_px: 1,
+			..self
+		}
+	}
+	/// Enables chromatic aberration (colour fringing) for this camera.
+	///
+	/// After rendering, the color channels are shifted outwards from the image center
+	/// by an amount proportional to `strength`; see [`Image::chromatic_aberration`].
+	/// A `strength` of 0 means no effect. This is not applied by [`Camera::render_streaming`].
+	pub fn with_chromatic_aberration(self, strength: f64) -> Self {
+		Camera {
+			chromatic_aberration: strength,
+			..self
+		}
+	}
+	/// Enables radial lens distortion for this camera, with the Brown-Conrady coefficients `k1` and `k2`.
+	///
+	/// After rendering, the image is warped radially; see [`Image::lens_distortion`]. A positive `k1`
+	/// gives barrel distortion (the look of a wide-angle lens), a negative `k1` gives pincushion
+	/// distortion. Coefficients of 0 mean no effect. This is not applied by [`Camera::render_streaming`].
+	pub fn with_lens_distortion(self, k1: f64, k2: f64) -> Self {
+		Camera {
+			lens_distortion: (k1, k2),
+			..self
+		}
+	}
+	/// Enables vignetting for this camera, which darkens the image towards its corners.
+	///
+	/// Each pixel is weighted by `1 - strength * smoothstep(r * falloff)`, where `r` is the
+	/// distance from the image center, normalized so that the corners lie at 1.
+	/// A `strength` of 0 means no effect, while a `strength` of 1 turns the corners black
+	/// (given a `falloff` of at least 1). A larger `falloff` darkens more of the image.
+	pub fn with_vignette(self, strength: f64, falloff: f64) -> Self {
+		Camera {
+			vignette_strength: strength,
+			vignette_falloff: falloff,
+			..self
+		}
+	}
+	/// Enables motion blur for this camera, by keeping the shutter open from time `open` to `close`.
+	///
+	/// Each ray is cast at a random time within this interval, so that moving objects
+	/// (such as a [`crate::objects::MovingSphere`]) are smeared along their path.
+	/// Without a shutter time, all rays are cast at time 0.
+	pub fn with_shutter(self, open: f64, close: f64) -> Self {
+		Camera {
+			shutter_time: Some((open, close)),
+			..self
+		}
+	}
+	/// Renders images at `factor` times the configured resolution, and downsamples them afterwards.
+	///
+	/// Each pixel of the final image is the average of a `factor`×`factor` block of rendered pixels
+	/// (see [`Image::downscale`]), which smooths edges like supersampling does.
+	/// A `factor` of less than 1 is treated as 1. This is not applied by [`Camera::render_streaming`],
+	/// which writes the image at the rendered resolution.
+	pub fn with_resolution_scale(self, factor: usize) -> Self {
+		Camera {
+			resolution_scale: usize::max(1, factor),
+			..self
+		}
+		.rebuilt(self.setup)
+	}
+	/// Limits the memory that renders of this camera may use to `bytes`.
+	///
+	/// The limit is checked by [`Camera::try_render`] before rendering, using an estimate
+	/// of the memory needed for the image and the scene (see [`Camera::estimate_memory_bytes`]).
+	pub fn with_memory_limit(self, bytes: usize) -> Self {
+		Camera {
+			memory_limit: Some(bytes),
+			..self
+		}
+	}
+	/// Applies the `pipeline` to rendered images, after downsampling, lens distortion and
+	/// chromatic aberration. This is not applied by [`Camera::render_streaming`].
+	pub fn with_post_process(self, pipeline: PostProcessPipeline) -> Self {
+		Camera {
+			pipeline: Some(Arc::new(pipeline)),
+			..self
+		}
+	}
+	/// Adds an output variable, which is rendered into its own image by [`Camera::render_all_aovs`].
+	pub fn with_aov(mut self, aov: Arc<dyn Aov>) -> Self {
+		self.aovs.push(aov);
+		self
+	}
+	/// Makes renders of this camera reproducible.
+	///
+	/// The random sampling of each pixel is determined by the `seed` and the pixel's position,
+	/// so rendering the same scene with the same seed always produces the same image,
+	/// regardless of the order in which pixels are rendered.
+	pub fn seed(self, seed: u64) -> Self {
+		Camera {
+			seed: Some(seed),
+			..self
+		}
+	}
+	/// Returns a copy of this camera with depth of field and supersampling disabled.
+	/// See [`Camera::with_dof_preview`].
+	pub fn dof_preview(&self) -> Camera {
+		self.clone().with_dof_preview()
+	}
 }
 
 // Rendering
 impl Camera {
 	/// Renders a scene and produces an image.
 	pub fn render(&self, scene: &Scene) -> Image {
+		let cancel = AtomicBool::new(false);
+		self.render_until(scene, &cancel, |_, _, _| {})
+			.expect("render should not be cancelled")
+	}
+	/// Renders a scene and produces an image, calling `callback(row, col, color)` for each pixel
+	/// as soon as it has been computed.
+	///
+	/// Rows are rendered in parallel, so the pixels are not reported in row-major order.
+	/// The callback receives the pixels before post-processing, and at the rendered resolution
+	/// (see [`Camera::with_resolution_scale`]).
+	pub fn render_with_callback<F: Fn(usize, usize, Color) + Sync>(
+		&self,
+		scene: &Scene,
+		callback: F,
+	) -> Image {
+		let cancel = AtomicBool::new(false);
+		self.render_until(scene, &cancel, callback)
+			.expect("render should not be cancelled")
+	}
+	/// Renders a scene, unless the `cancel` flag is set during rendering.
+	///
+	/// The flag is checked before each row is rendered. If it has been set,
+	/// the remaining rows are skipped, and `None` is returned.
+	pub fn render_with_cancel(&self, scene: &Scene, cancel: Arc<AtomicBool>) -> Option<Image> {
+		self.render_until(scene, &cancel, |_, _, _| {})
+	}
+	/// Renders a scene on a blocking thread of the `tokio` runtime, without blocking the caller.
+	///
+	/// # Panics
+	/// Panics if the rendering panics, or if called outside of a `tokio` runtime.
+	#[cfg(feature = "tokio")]
+	pub async fn render_async(&self, scene: Arc<Scene>) -> Image {
+		let camera = self.clone();
+		tokio::task::spawn_blocking(move || camera.render(&scene))
+			.await
+			.expect("render task should complete")
+	}
+	/// Renders a scene row by row in parallel, skipping all remaining rows once `cancel` is set.
+	/// The `on_pixel` callback is called with the row, column and color of each computed pixel.
+	fn render_until<F: Fn(usize, usize, Color) + Sync>(
+		&self,
+		scene: &Scene,
+		cancel: &AtomicBool,
+		on_pixel: F,
+	) -> Option<Image> {
 		let (width, height) = self.img_size;
 
 		let mut image = Image::init(height, width);
@@ -211,29 +581,203 @@ impl Camera {
 			.par_chunks_mut(image.width())
 			.enumerate()
 			.for_each(|(row, pixels)| {
+				if cancel.load(Ordering::Relaxed) {
+					return;
+				}
 				for (col, pixel) in pixels.iter_mut().enumerate() {
 					*pixel = self.sample_pixel(col, row, scene);
+					on_pixel(row, col, *pixel);
 				}
 				remaining.fetch_sub(1, Ordering::Relaxed);
 				log!("{CLEAR}Lines remaining: {:?}", remaining);
 			});
 
+		if cancel.load(Ordering::Relaxed) {
+			log!("{CLEAR}Cancelled.\n");
+			return None;
+		}
 		log!("{CLEAR}Done.\n");
-		image
+		Some(self.post_process(image))
+	}
+	/// Estimates how long rendering a scene with the specified amount of `samples` per pixel takes.
+	///
+	/// A subset of evenly spaced rows (at most [`ESTIMATE_ROWS`]) is rendered with one sample per
+	/// pixel and timed, and the time is extrapolated to the whole image and all samples.
+	pub fn estimate_render_time(&self, scene: &Scene, samples: u32) -> Duration {
+		let (width, height) = self.img_size;
+		if width == 0 || height == 0 {
+			return Duration::ZERO;
+		}
+		let camera = Camera {
+			samples_per_px: 1,
+			..self.clone()
+		};
+		let rows = (0..height)
+			.step_by(height.div_ceil(ESTIMATE_ROWS))
+			.collect::<Vec<_>>();
+
+		let start = Instant::now();
+		rows.par_iter().for_each(|&row| {
+			for col in 0..width {
+				camera.sample_pixel(col, row, scene);
+			}
+		});
+		let elapsed = start.elapsed();
+
+		let factor = (height as f64 / rows.len() as f64) * samples as f64;
+		elapsed.mul_f64(factor)
+	}
+	/// Renders a scene at a low resolution, whose longer edge is 256 pixels long, regardless of
+	/// the configured resolution. The aspect ratio of the image is kept.
+	///
+	/// All other parameters of this camera are kept, which makes this method useful for rapid iteration.
+	pub fn preview_render(&self, scene: &Scene) -> Image {
+		let (width, height) = (self.setup.width.max(1), self.setup.height.max(1));
+		let scale = PREVIEW_EDGE as f64 / usize::max(width, height) as f64;
+		let setup = CameraSetup {
+			width: ((width as f64 * scale).round() as usize).max(1),
+			height: ((height as f64 * scale).round() as usize).max(1),
+			..self.setup
+		};
+		self.rebuilt(setup).render(scene)
+	}
+	/// Renders a scene from several cameras at once, and produces an image for each of them.
+	/// The images are returned in the same order as the cameras, each in its camera's size.
+	///
+	/// The scene is traversed row by row in parallel, and each row is sampled by all cameras
+	/// (that have an image with that row) before moving on, so that the scene is shared (read-only)
+	/// across all cameras.
+	pub fn render_multi(cameras: &[Camera], scene: &Scene) -> Vec<Image> {
+		let height = cameras.iter().map(|c| c.img_size.1).max().unwrap_or(0);
+		let widths = cameras.iter().map(|c| c.img_size.0).collect::<Vec<_>>();
+		let row_len = widths.iter().sum::<usize>();
+		let mut images = cameras
+			.iter()
+			.map(|c| Image::init(c.img_size.1, c.img_size.0))
+			.collect::<Vec<_>>();
+		if row_len == 0 {
+			return images;
+		}
+
+		// Each chunk contains a row of every image: [row][camera][column]
+		let mut rows = vec![Color::black(); height * row_len];
+		let remaining = AtomicUsize::new(height);
+		rows.par_chunks_mut(row_len)
+			.enumerate()
+			.for_each(|(row, mut pixels)| {
+				for (camera, &width) in cameras.iter().zip(&widths) {
+					let (camera_pixels, rest) = pixels.split_at_mut(width);
+					pixels = rest;
+					if row >= camera.img_size.1 {
+						continue;
+					}
+					for (col, pixel) in camera_pixels.iter_mut().enumerate() {
+						*pixel = camera.sample_pixel(col, row, scene);
+					}
+				}
+				remaining.fetch_sub(1, Ordering::Relaxed);
+				log!("{CLEAR}Lines remaining: {:?}", remaining);
+			});
+		log!("{CLEAR}Done.\n");
+
+		for (row, pixels) in rows.chunks(row_len).enumerate() {
+			let mut start = 0;
+			for (image, &width) in images.iter_mut().zip(&widths) {
+				if row < image.height() {
+					for (col, pixel) in pixels[start..start + width].iter().enumerate() {
+						image[(row, col)] = *pixel;
+					}
+				}
+				start += width;
+			}
+		}
+		images
+			.into_iter()
+			.zip(cameras)
+			.map(|(image, camera)| camera.post_process(image))
+			.collect()
+	}
+	/// Renders a scene and writes it to the specified `writer` in raw `.ppm` format.
+	///
+	/// Unlike [`Camera::render`], this method does not store the whole image in memory:
+	/// the image is rendered row by row, and each row is written out as soon as it is complete.
+	/// The `gamma` value is used for gamma correction.
+	pub fn render_streaming<W: Write>(
+		&self,
+		scene: &Scene,
+		gamma: f64,
+		writer: &mut W,
+	) -> Result<(), RaytracerError> {
+		let (width, height) = self.img_size;
+
+		let mut stream = StreamWriter::new(writer);
+		stream.write_header(width, height, gamma)?;
+		for row in 0..height {
+			// Ray trace the pixels of a row in parallel
+			let pixels = (0..width)
+				.into_par_iter()
+				.map(|col| self.sample_pixel(col, row, scene))
+				.collect::<Vec<_>>();
+			for pixel in pixels {
+				stream.write_pixel(pixel)?;
+			}
+			log!("{CLEAR}Lines remaining: {:?}", height - row - 1);
+		}
+		stream.flush()?;
+
+		log!("{CLEAR}Done.\n");
+		Ok(())
+	}
+	/// Applies the post-processing effects enabled on this camera to a rendered image.
+	fn post_process(&self, image: Image) -> Image {
+		let image = image.downscale(self.resolution_scale);
+		let (k1, k2) = self.lens_distortion;
+		let image = image.lens_distortion(k1, k2);
+		let image = match self.chromatic_aberration {
+			0.0 => image,
+			strength => image.chromatic_aberration(strength),
+		};
+		match &self.pipeline {
+			Some(pipeline) => pipeline.run(image),
+			None => image,
+		}
 	}
 	/// Samples a pixel and returns the average color.
 	fn sample_pixel(&self, px_i: usize, px_j: usize, scene: &Scene) -> Color {
+		self.seed_pixel(px_i, px_j);
 		let mut rgb = Vec3::zero();
-		for _ in 0..self.samples_per_px {
-			let ray = self.sampling_ray(px_i, px_j);
+		for sample in 0..self.samples_per_px {
+			let ray = self.sampling_ray(px_i, px_j, sample);
 			rgb += ray.color(scene, self.bounces).to_vec3();
 		}
-		rgb.scale(1.0 / (self.samples_per_px as f64)).into()
+		let weight = self.vignette_weight(px_i, px_j);
+		rgb.scale(weight / (self.samples_per_px as f64)).into()
+	}
+	/// Reseeds the random number generator for the pixel `(px_i, px_j)`, if this camera has a seed.
+	fn seed_pixel(&self, px_i: usize, px_j: usize) {
+		if let Some(seed) = self.seed {
+			// Mix the seed with the pixel position, so that every pixel gets its own sequence
+			let position = ((px_j as u64) << 32) | px_i as u64;
+			random::reseed(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ position);
+		}
+	}
+	/// Calculates the factor by which vignetting scales the color of the pixel `(px_i, px_j)`.
+	fn vignette_weight(&self, px_i: usize, px_j: usize) -> f64 {
+		if self.vignette_strength == 0.0 {
+			return 1.0;
+		}
+		let (width, height) = self.img_size;
+		vignette_weight(
+			(px_j, px_i),
+			(height, width),
+			self.vignette_strength,
+			self.vignette_falloff,
+		)
 	}
 
-	/// Creates a sampling ray for the pixel with index `(px_i, px_j)`.
-	fn sampling_ray(&self, px_i: usize, px_j: usize) -> Ray {
-		let px_offset = self.sampling_offset();
+	/// Creates the `sample`-th sampling ray for the pixel with index `(px_i, px_j)`.
+	fn sampling_ray(&self, px_i: usize, px_j: usize, sample: u32) -> Ray {
+		let px_offset = self.sampling_offset(px_i, px_j, sample);
 		let px_sample = self.px_00.to_vec3()
 			+ (self.px_d_u * ((px_i as f64) + px_offset.x()))
 			+ (self.px_d_v * ((px_j as f64) + px_offset.y()));
@@ -245,22 +789,33 @@ impl Camera {
 		let origin = origin.into();
 
 		let direction = px_sample - origin;
-		Ray::new(origin, direction)
+		debug_assert!(
+			direction.is_finite(),
+			"sampling ray direction should be finite, but was {}",
+			direction
+		);
+		Ray::with_time(origin, direction, self.sampling_time())
 	}
-	/// Calculates a random offset in the `x` and `y` coordinates for supersampling.
+	/// Calculates the offset in the `x` and `y` coordinates of the `sample`-th sample of the pixel
+	/// with index `(px_i, px_j)` for supersampling, according to the jitter pattern.
 	/// Both offsets lie in [-0.5; 0.5).
 	/// If anti-aliasing is disabled for this camera, returns a zero vector.
-	fn sampling_offset(&self) -> Vec3 {
+	fn sampling_offset(&self, px_i: usize, px_j: usize, sample: u32) -> Vec3 {
 		if self.samples_per_px > 1 {
-			Vec3(
-				rand::random_range(-0.5..0.5),
-				rand::random_range(-0.5..0.5),
-				0.0,
-			)
+			self.jitter.offset(px_i, px_j, sample, self.samples_per_px)
 		} else {
 			Vec3::zero()
 		}
 	}
+	/// Calculates a random point in time within the shutter interval, at which a ray is cast.
+	/// If this camera has no shutter time, returns 0.
+	fn sampling_time(&self) -> f64 {
+		match self.shutter_time {
+			Some((open, close)) if open < close => random::random_range(open..close),
+			Some((open, _)) => open,
+			None => 0.0,
+		}
+	}
 	/// Calculates a random offset in the 'x' and 'y' coordinates for defocus blur.
 	/// If the angular aperture (defocus angle) is zero or less, returns a zero vector.
 	fn sampling_disk_offset(&self) -> Vec3 {
@@ -274,7 +829,17 @@ impl Camera {
 
 #[cfg(test)]
 mod tests {
+	use std::collections::HashSet;
+	use std::f64::consts::PI;
+	use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+	use std::sync::{Arc, Mutex};
+	use std::time::{Duration, Instant};
+
 	use super::{Camera, CameraSetup};
+	use crate::core::objects::{Material, Sphere, ToObject};
+	use crate::core::output::ppm;
+	use crate::core::scene::Scene;
+	use crate::core::types::{Color, Image, Point, ToVec3};
 
 	/// Epsilon for f64 equality comparisons.
 	/// Two f64 values are assumed to be equal if their difference is smaller than this value.
@@ -284,6 +849,62 @@ mod tests {
 		f64::abs(a - b) < F64_EQ_EPSILON
 	}
 
+	#[test]
+	fn render_callback_is_called_for_every_pixel() {
+		// This camera produces a 12x7 image:
+		let setup = CameraSetup {
+			width: 12,
+			height: 7,
+			..Default::default()
+		};
+		let camera = Camera::from(setup).bounces(2);
+		let scene = Scene::from([Sphere::new(Point::new(0, 0, -2), 1, Material::Absorbant)]);
+
+		let calls = AtomicUsize::new(0);
+		camera.render_with_callback(&scene, |_, _, _| {
+			calls.fetch_add(1, Ordering::Relaxed);
+		});
+		assert_eq!(
+			calls.load(Ordering::Relaxed),
+			12 * 7,
+			"callback should be called once per pixel"
+		);
+	}
+
+	#[test]
+	fn render_callback_reports_every_pixel_once() {
+		// This seeded camera produces a 12x7 image:
+		let setup = CameraSetup {
+			width: 12,
+			height: 7,
+			..Default::default()
+		};
+		let camera = Camera::from(setup).bounces(2).seed(3);
+		let scene = Scene::from([Sphere::new(Point::new(0, 0, -2), 1, Material::Absorbant)]);
+
+		let reported = Mutex::new(Vec::new());
+		let image = camera.render_with_callback(&scene, |row, col, color| {
+			reported.lock().unwrap().push((row, col, color));
+		});
+		let reported = reported.into_inner().unwrap();
+
+		let indices = reported
+			.iter()
+			.map(|&(row, col, _)| (row, col))
+			.collect::<HashSet<_>>();
+		assert_eq!(reported.len(), 12 * 7, "each pixel should be reported once");
+		assert_eq!(indices.len(), 12 * 7, "each pixel should be reported once");
+		for (row, col, color) in reported {
+			assert_eq!(
+				image[(row, col)],
+				color,
+				"reported color of pixel ({}, {}) should match the image",
+				row,
+				col
+			);
+		}
+	}
+
 	#[test]
 	fn if_pixel_above_center_then_ray_dir_only_z_axis() {
 		// This camera produces a 5x5 image:
@@ -297,7 +918,7 @@ mod tests {
 		let (px_i, px_j) = (2, 2);
 
 		// The ray's direction should only be moving towards the viewport and no other direction:
-		let ray = camera.sampling_ray(px_i, px_j);
+		let ray = camera.sampling_ray(px_i, px_j, 0);
 		assert_eq!(
 			ray.direction.x(),
 			0.0,
@@ -329,8 +950,8 @@ mod tests {
 		// Since supersampling is enabled, all rays intersect the viewport within the 0.5-window of the pixel center.
 		// Thus, we can expect at least one ray's direction to also have a non-zero x- and y-component:
 		let mut has_deviating_rays = false;
-		for _ in 0..samples {
-			let ray = camera.sampling_ray(px_i, px_j);
+		for sample in 0..samples {
+			let ray = camera.sampling_ray(px_i, px_j, sample);
 			// At least x or y of the ray's direction vector should not equal the corresponding camera center's coordinate:
 			let eq_x = f64_approx_eq(ray.direction.x(), camera.center.x());
 			let eq_y = f64_approx_eq(ray.direction.y(), camera.center.y());
@@ -344,4 +965,576 @@ mod tests {
 			"at least one ray should deviate due to anti-aliasing, but all rays hit pixel center"
 		)
 	}
+
+	#[test]
+	fn streaming_render_matches_in_memory_render() {
+		// This camera produces a 20x10 image with one sample per pixel (no random offsets):
+		let setup = CameraSetup {
+			width: 20,
+			height: 10,
+			..Default::default()
+		};
+		let camera = Camera::from(setup).bounces(5);
+		// This scene has a perfectly reflective sphere (no random scattering):
+		let sphere = Sphere::new(
+			Point::new(0, 0, -2),
+			0.5,
+			Material::Metal {
+				color: Color::new(0.8, 0.3, 0.3),
+				fuzz: 0.0,
+			},
+		);
+		let scene = Scene::from([sphere]);
+
+		// Rendering into memory and writing afterwards:
+		let mut expected: Vec<u8> = Vec::new();
+		let image = camera.render(&scene);
+		let write_result = ppm::raw(&image, 2.2, &mut expected);
+		assert!(write_result.is_ok(), "writing should succeed, but didn't");
+
+		// Rendering while writing:
+		let mut actual: Vec<u8> = Vec::new();
+		let stream_result = camera.render_streaming(&scene, 2.2, &mut actual);
+		assert!(
+			stream_result.is_ok(),
+			"streaming should succeed, but error occurred: {:?}",
+			stream_result.err()
+		);
+
+		// The outputs should be byte-identical:
+		assert_eq!(expected, actual, "streamed output should match, but didn't");
+	}
+
+	#[test]
+	fn mirrored_cameras_produce_mirrored_images() {
+		// These cameras look at the origin from opposite sides on the x-axis:
+		let setup = CameraSetup {
+			width: 16,
+			height: 9,
+			lookat: Point::origin(),
+			..Default::default()
+		};
+		let left = Camera::from(CameraSetup {
+			lookfrom: Point::new(-3, 0, 0),
+			focus_distance: 3.0,
+			..setup
+		});
+		let right = Camera::from(CameraSetup {
+			lookfrom: Point::new(3, 0, 0),
+			focus_distance: 3.0,
+			..setup
+		});
+		// This scene is symmetric with respect to the yz-plane, but not left-right symmetric
+		// from the view of each camera (the small sphere is off to the side):
+		let material = Material::Metal {
+			color: Color::new(0.8, 0.3, 0.3),
+			fuzz: 0.0,
+		};
+		let scene = Scene::from([
+			Sphere::new(Point::origin(), 0.5, material.clone()),
+			Sphere::new(Point::new(0, 0.2, 0.7), 0.2, material),
+		]);
+
+		let images = Camera::render_multi(&[left, right], &scene);
+		assert_eq!(images.len(), 2, "there should be an image for each camera");
+
+		// Each image should be the other one flipped horizontally:
+		let (left, right) = (&images[0], &images[1]);
+		let width = left.width();
+		let mut violations = 0;
+		for i in 0..left.height() {
+			for j in 0..width {
+				let a = left[(i, j)];
+				let b = right[(i, width - 1 - j)];
+				if !f64_approx_eq(a.r(), b.r())
+					|| !f64_approx_eq(a.g(), b.g())
+					|| !f64_approx_eq(a.b(), b.b())
+				{
+					violations += 1;
+				}
+			}
+		}
+		assert_eq!(
+			violations, 0,
+			"images should be mirrored, but {} pixels differed",
+			violations
+		);
+	}
+
+	#[test]
+	fn render_multi_renders_each_camera_at_its_own_size() {
+		// These cameras look at the same scene, but produce images of different sizes:
+		let small = Camera::from(CameraSetup {
+			width: 8,
+			height: 12,
+			..Default::default()
+		})
+		.seed(2);
+		let wide = Camera::from(CameraSetup {
+			width: 20,
+			height: 6,
+			..Default::default()
+		})
+		.seed(2);
+		let scene = Scene::from([Sphere::new(
+			Point::new(0, 0, -2),
+			0.5,
+			Material::Matte {
+				color: Color::new(0.5, 0.5, 0.5),
+			},
+		)]);
+
+		let images = Camera::render_multi(&[small.clone(), wide.clone()], &scene);
+		assert_eq!(
+			images[0],
+			small.render(&scene),
+			"small image should match its own render"
+		);
+		assert_eq!(
+			images[1],
+			wide.render(&scene),
+			"wide image should match its own render"
+		);
+	}
+
+	#[test]
+	fn dof_preview_disables_defocus_and_supersampling() {
+		// This camera has a wide aperture and samples each pixel many times:
+		let setup = CameraSetup {
+			defocus_angle: 10.0,
+			..Default::default()
+		};
+		let camera = Camera::from(setup).anti_aliasing(50).bounces(7);
+
+		// The preview should be a pinhole camera with a single sample per pixel:
+		let preview = camera.dof_preview();
+		assert_eq!(preview.defocus_angle, 0.0, "aperture should be disabled");
+		assert_eq!(
+			preview.samples_per_px, 1,
+			"supersampling should be disabled"
+		);
+		assert_eq!(preview.bounces, 7, "bounces should be kept");
+		// ...while the original camera is unchanged:
+		assert_eq!(camera.defocus_angle, 10.0, "original should keep aperture");
+		assert_eq!(camera.samples_per_px, 50, "original should keep samples");
+	}
+
+	#[test]
+	fn preview_render_has_preview_dimensions() {
+		// This camera produces a large image:
+		let setup = CameraSetup {
+			width: 3840,
+			height: 2160,
+			..Default::default()
+		};
+		let camera = Camera::from(setup).bounces(5);
+		// This scene has a single sphere:
+		let sphere = Sphere::new(
+			Point::new(0, 0, -2),
+			0.5,
+			Material::Matte {
+				color: Color::new(0.8, 0.3, 0.3),
+			},
+		);
+		let scene = Scene::from([sphere]);
+
+		// The preview should be downscaled, and should not take long:
+		let start = Instant::now();
+		let image = camera.preview_render(&scene);
+		let elapsed = start.elapsed();
+		assert_eq!(
+			(image.width(), image.height()),
+			(256, 144),
+			"preview should be 256x144"
+		);
+		assert!(
+			elapsed < Duration::from_secs(30),
+			"preview should render quickly, but took {:?}",
+			elapsed
+		);
+	}
+
+	#[test]
+	fn preview_render_keeps_aspect_ratio() {
+		// This camera produces a portrait image:
+		let setup = CameraSetup {
+			width: 600,
+			height: 1200,
+			..Default::default()
+		};
+		let image = Camera::from(setup).bounces(1).preview_render(&Scene::new());
+		assert_eq!(
+			(image.width(), image.height()),
+			(128, 256),
+			"preview should be 128x256"
+		);
+	}
+
+	#[test]
+	fn aspect_ratio_is_width_over_height() {
+		// This is the default camera:
+		let setup = CameraSetup::default();
+		let camera = Camera::from(setup);
+
+		let expected = setup.width as f64 / setup.height as f64;
+		assert!(
+			f64_approx_eq(expected, camera.aspect_ratio()),
+			"aspect ratio should be {}, but was {}",
+			expected,
+			camera.aspect_ratio()
+		);
+		let (vp_width, vp_height) = camera.viewport_size();
+		assert!(
+			f64_approx_eq(expected, vp_width / vp_height),
+			"viewport should have the same aspect ratio {}, but had {}",
+			expected,
+			vp_width / vp_height
+		);
+	}
+
+	#[test]
+	fn getters_reflect_optional_features() {
+		// This camera has custom samples and bounces:
+		let camera = Camera::from(CameraSetup::default())
+			.anti_aliasing(8)
+			.bounces(3);
+
+		assert_eq!(camera.samples_per_pixel(), 8, "samples should be 8");
+		assert_eq!(camera.max_bounces(), 3, "bounces should be 3");
+		assert_eq!(
+			camera.image_size(),
+			(400, 225),
+			"image size should be 400x225"
+		);
+		assert!(
+			f64_approx_eq(camera.focal_length(), 1.0),
+			"focal length should be 1, but was {}",
+			camera.focal_length()
+		);
+	}
+
+	#[test]
+	fn pixel_solid_angles_sum_to_solid_angle_of_view() {
+		let setup = CameraSetup {
+			width: 64,
+			height: 64,
+			v_fov: 90.0,
+			..Default::default()
+		};
+		let camera = Camera::from(setup);
+		let total = (0..64)
+			.flat_map(|row| (0..64).map(move |col| (col, row)))
+			.map(|(col, row)| camera.pixel_solid_angle(col, row))
+			.sum::<f64>();
+
+		// A square view of 90 degrees covers one face of a cube around the camera:
+		let expected = 4.0 * PI / 6.0;
+		assert!(
+			(total - expected).abs() < 0.01 * expected,
+			"pixels should cover {} steradians in total, but covered {}",
+			expected,
+			total
+		);
+		let (vp_width, vp_height) = camera.viewport_size();
+		assert!(
+			f64_approx_eq(
+				camera.pixel_area_at_focus() * 64.0 * 64.0,
+				vp_width * vp_height
+			),
+			"pixels should cover the viewport at the focus plane"
+		);
+	}
+
+	#[test]
+	fn getters_reflect_depth_of_field() {
+		// This camera has a wide aperture, and focuses beyond the point it's looking at:
+		let setup = CameraSetup {
+			defocus_angle: 10.0,
+			focus_distance: 3.5,
+			..Default::default()
+		};
+		let camera = Camera::from(setup).anti_aliasing(50).bounces(0);
+
+		assert_eq!(camera.samples_per_pixel(), 50, "samples should be 50");
+		assert_eq!(camera.max_bounces(), 0, "bounces should be 0");
+		assert_eq!(camera.defocus_angle(), 10.0, "aperture should be 10");
+		assert_eq!(camera.focus_distance(), 3.5, "focus distance should be 3.5");
+		assert_eq!(
+			camera.with_dof_preview().defocus_angle(),
+			0.0,
+			"preview should disable the aperture"
+		);
+	}
+
+	#[test]
+	fn if_shutter_then_rays_cast_within_shutter_time() {
+		let camera = Camera::from(CameraSetup::default());
+		assert_eq!(
+			camera.sampling_ray(0, 0, 0).time,
+			0.0,
+			"without a shutter, rays should be cast at time 0"
+		);
+
+		// This camera keeps the shutter open between times 0.25 and 0.75:
+		let camera = camera.with_shutter(0.25, 0.75);
+		for _ in 0..100 {
+			let time = camera.sampling_ray(0, 0, 0).time;
+			assert!(
+				(0.25..0.75).contains(&time),
+				"ray should be cast within the shutter time, but was cast at {}",
+				time
+			);
+		}
+	}
+
+	/// Returns the average row of the dark pixels in the columns `cols` of the image.
+	fn dark_row(image: &Image, cols: std::ops::Range<usize>) -> f64 {
+		let rows = (0..image.height())
+			.flat_map(|row| cols.clone().map(move |col| (row, col)))
+			.filter(|&index| image[index].luminance() < 0.1)
+			.map(|(row, _)| row as f64)
+			.collect::<Vec<_>>();
+		rows.iter().sum::<f64>() / rows.len() as f64
+	}
+
+	/// Renders a horizontal line of black spheres in the upper half of the image, with the
+	/// specified lens distortion. Returns the average row of the spheres at the left end
+	/// and in the middle of the line.
+	fn distorted_line(k1: f64) -> (f64, f64) {
+		let setup = CameraSetup {
+			width: 90,
+			height: 60,
+			..Default::default()
+		};
+		let camera = Camera::from(setup).with_lens_distortion(k1, 0.0);
+		let spheres = (-4..=4).map(|i| {
+			let x = i as f64 * 0.55;
+			Sphere::new(Point::new(x, 1.0, -4), 0.15, Material::Absorbant).wrap()
+		});
+		let image = camera.render(&Scene::from_objs(spheres));
+		(dark_row(&image, 0..15), dark_row(&image, 38..52))
+	}
+
+	#[test]
+	fn if_lens_distortion_then_lines_curve() {
+		// Without distortion, the line of spheres is straight:
+		let (end, middle) = distorted_line(0.0);
+		assert!(
+			f64::abs(end - middle) < 0.5,
+			"line should be straight, but end was at row {} and middle at {}",
+			end,
+			middle
+		);
+
+		// Barrel distortion moves the ends towards the center (downwards), bowing the line outwards:
+		let (end, middle) = distorted_line(0.3);
+		assert!(
+			end > middle + 1.0,
+			"line should bow outwards, but end was at row {} and middle at {}",
+			end,
+			middle
+		);
+
+		// Pincushion distortion moves the ends away from the center (upwards), bowing the line inwards:
+		let (end, middle) = distorted_line(-0.3);
+		assert!(
+			end < middle - 1.0,
+			"line should bow inwards, but end was at row {} and middle at {}",
+			end,
+			middle
+		);
+	}
+
+	/// Renders an empty scene with a 21x11 camera, with the specified vignette applied.
+	/// Returns the image without vignetting, and the image with vignetting.
+	fn vignette_renders(strength: f64, falloff: f64) -> (Image, Image) {
+		let setup = CameraSetup {
+			width: 21,
+			height: 11,
+			..Default::default()
+		};
+		let camera = Camera::from(setup);
+		let scene = Scene::new();
+		let plain = camera.render(&scene);
+		let vignetted = camera.with_vignette(strength, falloff).render(&scene);
+		(plain, vignetted)
+	}
+
+	#[test]
+	fn if_vignette_then_corner_darker_than_center() {
+		let (plain, vignetted) = vignette_renders(0.5, 1.0);
+
+		// Relative to the image without vignetting, the corner should lose more light:
+		let ratio = |(row, col)| vignetted[(row, col)].luminance() / plain[(row, col)].luminance();
+		let (center, corner) = (ratio((5, 10)), ratio((0, 0)));
+		assert!(
+			f64_approx_eq(center, 1.0),
+			"center should be unchanged, but was scaled by {}",
+			center
+		);
+		assert!(
+			corner < center,
+			"corner should be darker than center, but ratios were {} and {}",
+			corner,
+			center
+		);
+	}
+
+	#[test]
+	fn if_vignette_strength_zero_then_image_unchanged() {
+		let (plain, vignetted) = vignette_renders(0.0, 1.0);
+		assert_eq!(plain, vignetted, "image should be unchanged");
+	}
+
+	#[test]
+	fn if_full_vignette_then_corners_black() {
+		let (_, vignetted) = vignette_renders(1.0, 1.0);
+		for corner in [(0, 0), (0, 20), (10, 0), (10, 20)] {
+			assert_eq!(
+				vignetted[corner],
+				Color::black(),
+				"corner {:?} should be black",
+				corner
+			);
+		}
+	}
+
+	#[test]
+	fn if_resolution_scaled_then_image_close_to_native() {
+		let (camera, scene) = small_render_setup(16);
+		let camera = camera.seed(3);
+		let native = camera.render(&scene);
+
+		// This camera renders at twice the resolution, with as many rays per pixel in total:
+		let scaled_camera = camera.anti_aliasing(4).with_resolution_scale(2);
+		assert_eq!(
+			scaled_camera.image_size(),
+			(40, 20),
+			"image size should not be affected by the scale"
+		);
+		let scaled = scaled_camera.render(&scene);
+		assert_eq!(
+			(scaled.width(), scaled.height()),
+			(40, 20),
+			"scaled render should be downsampled to the configured size"
+		);
+
+		// On average, each channel should be within 5% of the native render:
+		let mean = |image: &Image, channel: fn(&Color) -> f64| {
+			image.into_iter().flatten().map(channel).sum::<f64>()
+				/ (image.width() * image.height()) as f64
+		};
+		let channels: [fn(&Color) -> f64; 3] = [Color::r, Color::g, Color::b];
+		for channel in channels {
+			let (expected, actual) = (mean(&native, channel), mean(&scaled, channel));
+			assert!(
+				(actual - expected).abs() <= 0.05 * expected,
+				"mean channel value should be close to {}, but was {}",
+				expected,
+				actual
+			);
+		}
+	}
+
+	/// Creates a camera with a small image, and a scene with a single sphere in front of it.
+	fn small_render_setup(samples: u32) -> (Camera, Scene) {
+		let setup = CameraSetup {
+			width: 40,
+			height: 20,
+			..Default::default()
+		};
+		let camera = Camera::from(setup).anti_aliasing(samples).bounces(5);
+		let scene = Scene::from([Sphere::new(
+			Point::new(0, 0, -2),
+			1.0,
+			Material::Matte {
+				color: Color::new(0.5, 0.5, 0.5),
+			},
+		)]);
+		(camera, scene)
+	}
+
+	#[test]
+	fn if_cancelled_mid_render_then_no_image() {
+		// This render takes a long time:
+		let (camera, scene) = small_render_setup(10_000);
+		let cancel = Arc::new(AtomicBool::new(false));
+
+		// Cancel it shortly after it has started:
+		let flag = Arc::clone(&cancel);
+		let canceller = std::thread::spawn(move || {
+			std::thread::sleep(Duration::from_millis(20));
+			flag.store(true, Ordering::Relaxed);
+		});
+		let result = camera.render_with_cancel(&scene, cancel);
+		canceller.join().unwrap();
+
+		assert!(result.is_none(), "cancelled render should return no image");
+	}
+
+	#[test]
+	fn if_not_cancelled_then_full_image() {
+		let (camera, scene) = small_render_setup(1);
+		let cancel = Arc::new(AtomicBool::new(false));
+
+		let result = camera.render_with_cancel(&scene, cancel);
+		assert!(result.is_some(), "render should return an image");
+		let image = result.unwrap();
+		assert_eq!(
+			(image.width(), image.height()),
+			(40, 20),
+			"image should have full dimensions"
+		);
+	}
+
+	#[cfg(feature = "tokio")]
+	#[test]
+	fn async_render_produces_image() {
+		let (camera, scene) = small_render_setup(1);
+		let runtime = tokio::runtime::Builder::new_current_thread()
+			.build()
+			.unwrap();
+
+		let image = runtime.block_on(camera.render_async(Arc::new(scene)));
+		assert_eq!(
+			(image.width(), image.height()),
+			(40, 20),
+			"image should have full dimensions"
+		);
+	}
+
+	#[test]
+	fn auto_placed_camera_sees_scene_center_from_outside() {
+		// These spheres are spread around the origin, where the default camera is placed:
+		let scene = Scene::from([
+			Sphere::new(Point::new(-3, 0, 1), 1, Material::Absorbant),
+			Sphere::new(Point::new(2, 4, -5), 0.5, Material::Absorbant),
+		]);
+		let aabb = scene.bounding_box().unwrap();
+		let center = aabb.center();
+
+		let setups = [
+			CameraSetup::auto_place(&scene),
+			CameraSetup::auto_place_at_angle(&scene, 135.0, -20.0),
+		];
+		for setup in setups {
+			let Point(x, y, z) = setup.lookfrom;
+			let inside = aabb.x.surrounds(x) && aabb.y.surrounds(y) && aabb.z.surrounds(z);
+			assert!(!inside, "camera at {} should be outside", setup.lookfrom);
+
+			let forward = setup.lookat.to_vec3() - setup.lookfrom.to_vec3();
+			let to_center = center.to_vec3() - setup.lookfrom.to_vec3();
+			let angle = forward.unit().dot(to_center.unit()).clamp(-1.0, 1.0).acos();
+			assert!(
+				angle.to_degrees() < setup.v_fov / 2.0,
+				"center should be in the field of view {}, but was at {} degrees",
+				setup.v_fov,
+				angle.to_degrees()
+			);
+			assert!(
+				f64_approx_eq(setup.focus_distance, forward.norm()),
+				"camera should focus on the center"
+			);
+		}
+	}
 }
@@ -0,0 +1,210 @@
+use std::ops::Range;
+
+use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+use rayon::slice::ParallelSliceMut;
+
+use crate::core::scene::Scene;
+use crate::core::types::{Color, Image, ToVec3, Vec3};
+
+#[cfg(not(feature = "bench"))]
+use super::CLEAR;
+use super::Camera;
+
+/// The amount of samples per pixel taken between convergence checks of [`Camera::render_adaptive_stop`].
+const SAMPLES_PER_CHECK: u32 = 8;
+/// The fraction of pixels whose variance must lie below the threshold for a render to converge.
+const CONVERGED_FRACTION: f64 = 0.95;
+
+/// The running sums of the samples of a pixel.
+#[derive(Debug, Clone, Copy)]
+struct PixelSums {
+	/// The sum of the sampled colors.
+	color: Vec3,
+	/// The sum of the luminances of the sampled colors.
+	luminance: f64,
+	/// The sum of the squared luminances of the sampled colors.
+	luminance_sq: f64,
+}
+
+// Adaptive rendering
+impl Camera {
+	/// Renders a scene, taking more samples per pixel until the image has converged,
+	/// and returns the image along with the amount of samples per pixel that were taken.
+	///
+	/// Samples are taken in batches of [`SAMPLES_PER_CHECK`]. After each batch, the variance of
+	/// the mean luminance of each pixel is estimated. Once at least `min_samples` have been taken and
+	/// the variance of 95% of all pixels lies below `convergence_threshold`, rendering stops.
+	/// At most `max_samples` (but at least one) samples are taken. The amount of samples configured
+	/// with [`Camera::anti_aliasing`] is ignored; the samples are spread over each pixel as if
+	/// `max_samples` had been configured instead.
+	pub fn render_adaptive_stop(
+		&self,
+		scene: &Scene,
+		min_samples: u32,
+		max_samples: u32,
+		convergence_threshold: f64,
+	) -> (Image, u32) {
+		let (width, height) = self.img_size;
+		let max_samples = u32::max(1, max_samples);
+		let min_samples = u32::min(min_samples, max_samples);
+
+		let empty = PixelSums {
+			color: Vec3::zero(),
+			luminance: 0.0,
+			luminance_sq: 0.0,
+		};
+		let mut sums = vec![empty; width * height];
+		let mut samples = 0;
+		while samples < max_samples {
+			let batch = u32::min(SAMPLES_PER_CHECK, max_samples - samples);
+			// Every batch continues with different random samples of a seeded camera
+			let camera = Camera {
+				seed: self.seed.map(|seed| seed.wrapping_add(samples as u64)),
+				samples_per_px: max_samples,
+				..self.clone()
+			};
+			sums.par_chunks_mut(width.max(1))
+				.enumerate()
+				.for_each(|(row, pixels)| {
+					for (col, pixel) in pixels.iter_mut().enumerate() {
+						camera.accumulate_samples(col, row, scene, samples..samples + batch, pixel);
+					}
+				});
+			samples += batch;
+			log!("{CLEAR}Samples taken: {}", samples);
+
+			if samples >= min_samples && variance_percentile(&sums, samples) < convergence_threshold
+			{
+				break;
+			}
+		}
+		log!("{CLEAR}Done.\n");
+
+		let mut image = Image::init(height, width);
+		for (index, pixel) in sums.iter().enumerate() {
+			let (row, col) = (index / width, index % width);
+			let weight = self.vignette_weight(col, row);
+			image[(row, col)] = pixel.color.scale(weight / samples as f64).into();
+		}
+		(self.post_process(image), samples)
+	}
+	/// Takes the `samples` (by index) of a pixel, and adds them to the pixel's running `sums`.
+	fn accumulate_samples(
+		&self,
+		px_i: usize,
+		px_j: usize,
+		scene: &Scene,
+		samples: Range<u32>,
+		sums: &mut PixelSums,
+	) {
+		self.seed_pixel(px_i, px_j);
+		for sample in samples {
+			let color: Color = self
+				.sampling_ray(px_i, px_j, sample)
+				.color(scene, self.bounces);
+			let luminance = color.luminance();
+			sums.color += color.to_vec3();
+			sums.luminance += luminance;
+			sums.luminance_sq += luminance * luminance;
+		}
+	}
+}
+
+/// Estimates the variance of the mean luminance of each pixel from its running `sums` over `samples`
+/// samples, and returns the variance below which [`CONVERGED_FRACTION`] of all pixels lie.
+fn variance_percentile(sums: &[PixelSums], samples: u32) -> f64 {
+	if sums.is_empty() || samples < 2 {
+		return f64::INFINITY;
+	}
+	let n = samples as f64;
+	let mut variances = sums
+		.iter()
+		.map(|pixel| {
+			let mean = pixel.luminance / n;
+			let sample_variance = (pixel.luminance_sq / n - mean * mean).max(0.0) * n / (n - 1.0);
+			sample_variance / n
+		})
+		.collect::<Vec<_>>();
+	let index = ((variances.len() - 1) as f64 * CONVERGED_FRACTION).ceil() as usize;
+	*variances.select_nth_unstable_by(index, f64::total_cmp).1
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::core::camera::{Camera, CameraSetup};
+	use crate::core::objects::{Material, Sphere};
+	use crate::core::scene::{Background, Scene};
+	use crate::core::types::{Color, Point};
+
+	/// Creates a seeded camera producing a small image.
+	fn camera() -> Camera {
+		let setup = CameraSetup {
+			width: 16,
+			height: 9,
+			..Default::default()
+		};
+		Camera::from(setup).bounces(10).seed(4)
+	}
+
+	#[test]
+	fn if_background_solid_then_converges_immediately() {
+		let gray = Color::new(0.5, 0.5, 0.5);
+		let scene = Scene::new().with_background(Background::Solid(gray));
+
+		let (image, samples) = camera().render_adaptive_stop(&scene, 1, 256, 1e-4);
+		assert_eq!(samples, 8, "render should stop after the first batch");
+		assert_eq!(image[(4, 8)], gray, "image should show the background");
+	}
+
+	#[test]
+	fn if_anti_aliasing_not_configured_then_edges_are_still_smoothed() {
+		// This glowing sphere in front of a black background has hard edges:
+		let scene = Scene::from([Sphere::new(
+			Point::new(0, 0, -2),
+			0.5,
+			Material::Emissive {
+				color: Color::white(),
+				intensity: 1.0,
+			},
+		)])
+		.with_background(Background::Solid(Color::black()));
+
+		// Pixels on the edge should be partly covered by the sphere:
+		let (image, _) = camera().render_adaptive_stop(&scene, 16, 16, 0.0);
+		let edges = image
+			.into_iter()
+			.flatten()
+			.filter(|px| px.r() > 0.0 && px.r() < 1.0)
+			.count();
+		assert!(edges > 0, "edge pixels should be smoothed, but weren't");
+	}
+
+	#[test]
+	fn if_scene_noisy_then_more_samples_taken() {
+		// This glass sphere lies on a large matte sphere, which scatters rays randomly:
+		let scene = Scene::from([
+			Sphere::new(
+				Point::new(0, 0, -2),
+				0.5,
+				Material::Dielectric { ridx: 1.5 },
+			),
+			Sphere::new(
+				Point::new(0, -100.5, -2),
+				100,
+				Material::Matte {
+					color: Color::new(0.5, 0.5, 0.5),
+				},
+			),
+		]);
+		let solid = Scene::new().with_background(Background::Solid(Color::white()));
+
+		let (_, noisy_samples) = camera().render_adaptive_stop(&scene, 1, 64, 1e-5);
+		let (_, solid_samples) = camera().render_adaptive_stop(&solid, 1, 64, 1e-5);
+		assert!(
+			noisy_samples > solid_samples,
+			"noisy scene should take more than {} samples, but took {}",
+			solid_samples,
+			noisy_samples
+		);
+	}
+}
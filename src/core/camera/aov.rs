@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::core::objects::{Hit, Hittable};
+use crate::core::scene::Scene;
+use crate::core::types::{Color, Image, Interval, Ray, ToVec3, Vec3};
+
+#[cfg(not(feature = "bench"))]
+use super::CLEAR;
+use super::Camera;
+
+/// An arbitrary output variable: a quantity that is rendered into its own image,
+/// alongside or instead of the color image, see [`Camera::render_all_aovs`].
+///
+/// The value of a pixel is the average of the values of all of its sampling rays.
+pub trait Aov: Send + Sync {
+	/// The name of this output variable, which identifies its image.
+	fn name(&self) -> &str;
+	/// Calculates the value of a sampling `ray`, which hits the first surface at `hit`
+	/// (or nothing, if [`None`]).
+	fn evaluate(&self, ray: Ray, hit: Option<Hit>, scene: &Scene) -> Color;
+}
+
+impl Debug for dyn Aov {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Aov({})", self.name())
+	}
+}
+
+/// The surface normals in world space, mapped from [-1; 1] to [0; 1] and stored in the red,
+/// green and blue channels. Rays without a surface are black.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NormalAov;
+impl Aov for NormalAov {
+	fn name(&self) -> &str {
+		"normal"
+	}
+	fn evaluate(&self, _ray: Ray, hit: Option<Hit>, _scene: &Scene) -> Color {
+		match hit {
+			Some(hit) => ((hit.normal.unit() + Vec3(1.0, 1.0, 1.0)) / 2.0).into(),
+			None => Color::black(),
+		}
+	}
+}
+
+/// The distance from the camera to the surface, mapped from [`near`; `far`] to [0; 1] and stored
+/// in all three channels. Rays without a surface are white, as if they hit the far plane.
+#[derive(Debug, Clone, Copy)]
+pub struct DepthAov {
+	/// The distance which is mapped to 0 (black); surfaces closer than it are black as well.
+	pub near: f64,
+	/// The distance which is mapped to 1 (white); surfaces further than it are white as well.
+	pub far: f64,
+}
+impl Aov for DepthAov {
+	fn name(&self) -> &str {
+		"depth"
+	}
+	fn evaluate(&self, ray: Ray, hit: Option<Hit>, _scene: &Scene) -> Color {
+		let depth = match hit {
+			Some(hit) if self.far > self.near => {
+				let distance = hit.t * ray.direction.norm();
+				((distance - self.near) / (self.far - self.near)).clamp(0.0, 1.0)
+			}
+			Some(_) => 0.0,
+			None => 1.0,
+		};
+		Color::new(depth, depth, depth)
+	}
+}
+
+/// The base color of the surface materials (see [`crate::objects::Material::albedo`]).
+/// Rays without a surface are black.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AlbedoAov;
+impl Aov for AlbedoAov {
+	fn name(&self) -> &str {
+		"albedo"
+	}
+	fn evaluate(&self, _ray: Ray, hit: Option<Hit>, _scene: &Scene) -> Color {
+		match hit {
+			Some(hit) => hit.material.albedo(),
+			None => Color::black(),
+		}
+	}
+}
+
+/// A color that identifies the material of the surface, so that each kind of material with
+/// a different albedo gets a different (arbitrary) color. Rays without a surface are black.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MaterialIdAov;
+impl Aov for MaterialIdAov {
+	fn name(&self) -> &str {
+		"material-id"
+	}
+	fn evaluate(&self, _ray: Ray, hit: Option<Hit>, _scene: &Scene) -> Color {
+		let Some(hit) = hit else {
+			return Color::black();
+		};
+		let mut hasher = DefaultHasher::new();
+		hit.material.name().hash(&mut hasher);
+		hit.material
+			.albedo()
+			.to_vec3()
+			.to_tuple(f64::to_bits)
+			.hash(&mut hasher);
+		let bits = hasher.finish();
+		let channel = |shift: u32| ((bits >> shift) & 0xff) as f64 / 255.0;
+		Color::new(channel(0), channel(8), channel(16))
+	}
+}
+
+// Arbitrary output variables
+impl Camera {
+	/// Renders an image of each output variable added with [`Camera::with_aov`],
+	/// and returns them by the names of the variables.
+	///
+	/// Unlike [`Camera::render`], rays don't bounce, and no post-processing is applied other than
+	/// downsampling (see [`Camera::with_resolution_scale`]).
+	pub fn render_all_aovs(&self, scene: &Scene) -> HashMap<String, Image> {
+		let (width, height) = self.img_size;
+		let remaining = AtomicUsize::new(height);
+		let rows = (0..height)
+			.into_par_iter()
+			.map(|row| {
+				let pixels = (0..width)
+					.map(|col| self.sample_aovs(col, row, scene))
+					.collect::<Vec<_>>();
+				remaining.fetch_sub(1, Ordering::Relaxed);
+				log!("{CLEAR}Lines remaining: {:?}", remaining);
+				pixels
+			})
+			.collect::<Vec<_>>();
+		log!("{CLEAR}Done.\n");
+
+		let mut images = vec![Image::init(height, width); self.aovs.len()];
+		for (row, pixels) in rows.into_iter().enumerate() {
+			for (col, pixel) in pixels.into_iter().enumerate() {
+				for (image, value) in images.iter_mut().zip(pixel) {
+					image[(row, col)] = value;
+				}
+			}
+		}
+		self.aovs
+			.iter()
+			.zip(images)
+			.map(|(aov, image)| {
+				(
+					aov.name().to_string(),
+					image.downscale(self.resolution_scale),
+				)
+			})
+			.collect()
+	}
+
+	/// Samples a pixel, and returns the average value of each output variable (in their order).
+	fn sample_aovs(&self, px_i: usize, px_j: usize, scene: &Scene) -> Vec<Color> {
+		self.seed_pixel(px_i, px_j);
+		let mut sums = vec![Vec3::zero(); self.aovs.len()];
+		for sample in 0..self.samples_per_px {
+			let ray = self.sampling_ray(px_i, px_j, sample);
+			let hit = scene.hit(ray, Interval::from(0.001));
+			for (sum, aov) in sums.iter_mut().zip(&self.aovs) {
+				*sum += aov.evaluate(ray, hit, scene).to_vec3();
+			}
+		}
+		let samples = self.samples_per_px as f64;
+		sums.into_iter()
+			.map(|sum| sum.scale(1.0 / samples).into())
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+
+	use super::{AlbedoAov, Aov, DepthAov, MaterialIdAov, NormalAov};
+	use crate::core::camera::{Camera, CameraSetup};
+	use crate::core::objects::{Hittable, Material, Sphere};
+	use crate::core::scene::Scene;
+	use crate::core::types::{Color, Interval, Point, Ray, Vec3};
+
+	/// Creates a scene with a single red sphere in front of the origin.
+	fn red_sphere_scene() -> Scene {
+		Scene::from([Sphere::new(
+			Point::new(0, 0, -2),
+			0.5,
+			Material::Matte {
+				color: Color::new(1, 0, 0),
+			},
+		)])
+	}
+
+	#[test]
+	fn aovs_evaluate_sphere_hit() {
+		let scene = red_sphere_scene();
+		// This ray hits the sphere off-center, so that the normal is tilted along every axis:
+		let ray = Ray::new(Point::origin(), Vec3::new(0.1, 0.1, -1));
+		let hit = scene.hit(ray, Interval::from(0.001));
+		assert!(hit.is_some(), "ray should hit the sphere");
+
+		let normal = NormalAov.evaluate(ray, hit, &scene);
+		assert!(
+			normal.r() > 0.0 && normal.g() > 0.0 && normal.b() > 0.0,
+			"normal should have non-zero channels, but was {:?}",
+			normal
+		);
+		assert_eq!(
+			AlbedoAov.evaluate(ray, hit, &scene),
+			Color::new(1, 0, 0),
+			"albedo should be red"
+		);
+	}
+
+	#[test]
+	fn if_no_hit_then_default_color() {
+		let scene = red_sphere_scene();
+		// This ray points away from the sphere:
+		let ray = Ray::new(Point::origin(), Vec3::new(0, 0, 1));
+		let hit = scene.hit(ray, Interval::from(0.001));
+		assert!(hit.is_none(), "ray should miss the sphere");
+
+		let aovs: [&dyn Aov; 3] = [&NormalAov, &AlbedoAov, &MaterialIdAov];
+		for aov in aovs {
+			assert_eq!(
+				aov.evaluate(ray, hit, &scene),
+				Color::black(),
+				"{} should be black without a hit",
+				aov.name()
+			);
+		}
+		let depth = DepthAov {
+			near: 0.0,
+			far: 10.0,
+		};
+		assert_eq!(
+			depth.evaluate(ray, hit, &scene),
+			Color::new(1, 1, 1),
+			"depth should be at the far plane without a hit"
+		);
+	}
+
+	#[test]
+	fn all_aovs_are_rendered_by_name() {
+		let setup = CameraSetup {
+			width: 8,
+			height: 6,
+			..Default::default()
+		};
+		let camera = Camera::from(setup)
+			.with_aov(Arc::new(NormalAov))
+			.with_aov(Arc::new(AlbedoAov));
+
+		let images = camera.render_all_aovs(&red_sphere_scene());
+		let mut names = images.keys().cloned().collect::<Vec<_>>();
+		names.sort();
+		assert_eq!(names, ["albedo", "normal"], "every AOV should be rendered");
+		assert_eq!(
+			images["albedo"][(3, 4)],
+			Color::new(1, 0, 0),
+			"center of the albedo image should be red"
+		);
+	}
+}
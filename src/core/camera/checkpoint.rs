@@ -0,0 +1,359 @@
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+use rayon::slice::ParallelSliceMut;
+use serde::{Deserialize, Serialize};
+
+use crate::core::error::RaytracerError;
+use crate::core::scene::Scene;
+use crate::core::types::{Color, Image};
+
+#[cfg(not(feature = "bench"))]
+use super::CLEAR;
+use super::Camera;
+
+/// The bytes every checkpoint file starts with.
+const MAGIC: &[u8; 8] = b"RTCKPT01";
+
+/// The metadata of a checkpoint, stored as JSON after the magic bytes.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CheckpointHeader {
+	/// The width of the image, in pixels.
+	width: usize,
+	/// The height of the image, in pixels.
+	height: usize,
+	/// The amount of rows that have been rendered completely.
+	rows_completed: usize,
+	/// The amount of rows after which a new checkpoint is saved.
+	checkpoint_every: usize,
+	/// The seed of the camera, which determines the random sampling of the remaining rows.
+	seed: Option<u64>,
+}
+
+// Checkpoints
+impl Camera {
+	/// Renders a scene, and saves a checkpoint to the file at `path` every `checkpoint_every` rows.
+	///
+	/// A checkpoint contains all rows rendered so far, and the camera's seed (see [`Camera::seed`]).
+	/// If the render is interrupted, it can be continued with [`Camera::resume_from_checkpoint`].
+	/// The file is overwritten with each checkpoint, and is kept after rendering has finished.
+	pub fn render_checkpoint(
+		&self,
+		scene: &Scene,
+		path: &Path,
+		checkpoint_every: u32,
+	) -> Result<Image, RaytracerError> {
+		let (width, height) = self.img_size;
+		let header = CheckpointHeader {
+			width,
+			height,
+			rows_completed: 0,
+			checkpoint_every: usize::max(1, checkpoint_every as usize),
+			seed: self.seed,
+		};
+		self.render_from(scene, path, header, Image::init(height, width))
+	}
+	/// Loads the checkpoint saved at `path` by [`Camera::render_checkpoint`], and continues rendering.
+	///
+	/// The seed stored in the checkpoint is used for the remaining rows, so that the result is
+	/// identical to an uninterrupted render. Checkpoints keep being saved as before.
+	///
+	/// Returns an error if the checkpoint can't be read, its dimensions don't match this camera,
+	/// or it saves checkpoints every 0 rows.
+	pub fn resume_from_checkpoint(
+		&self,
+		scene: &Scene,
+		path: &Path,
+	) -> Result<Image, RaytracerError> {
+		let (header, image) = read_checkpoint(path)?;
+		if (header.width, header.height) != self.img_size {
+			return Err(RaytracerError::InvalidCheckpoint(format!(
+				"image size {}x{} does not match camera's {}x{}",
+				header.width, header.height, self.img_size.0, self.img_size.1
+			)));
+		}
+		if header.checkpoint_every == 0 {
+			return Err(RaytracerError::InvalidCheckpoint(String::from(
+				"checkpoints should be saved every 1 or more rows, but are saved every 0",
+			)));
+		}
+		let camera = Camera {
+			seed: header.seed,
+			..self.clone()
+		};
+		camera.render_from(scene, path, header, image)
+	}
+
+	/// Renders the rows of `image` that follow the completed ones in `header`,
+	/// saving a checkpoint after each batch of rows.
+	fn render_from(
+		&self,
+		scene: &Scene,
+		path: &Path,
+		mut header: CheckpointHeader,
+		mut image: Image,
+	) -> Result<Image, RaytracerError> {
+		let width = header.width;
+		header.checkpoint_every = usize::max(1, header.checkpoint_every);
+		while header.rows_completed < header.height {
+			let start = header.rows_completed;
+			let end = usize::min(start + header.checkpoint_every, header.height);
+			image.as_parallel_slice_mut()[start * width..end * width]
+				.par_chunks_mut(width)
+				.enumerate()
+				.for_each(|(row, pixels)| {
+					for (col, pixel) in pixels.iter_mut().enumerate() {
+						*pixel = self.sample_pixel(col, start + row, scene);
+					}
+				});
+			header.rows_completed = end;
+			write_checkpoint(path, &header, &image)?;
+			log!("{CLEAR}Lines remaining: {:?}", header.height - end);
+		}
+		log!("{CLEAR}Done.\n");
+		Ok(self.post_process(image))
+	}
+}
+
+/// Writes the completed rows of the `image` along with the `header` to the file at `path`.
+///
+/// The checkpoint is written to a temporary file next to `path` first, which then replaces the
+/// previous checkpoint, so that an interrupted write doesn't destroy it.
+fn write_checkpoint(
+	path: &Path,
+	header: &CheckpointHeader,
+	image: &Image,
+) -> Result<(), RaytracerError> {
+	let json =
+		serde_json::to_vec(header).map_err(|e| RaytracerError::InvalidCheckpoint(e.to_string()))?;
+
+	let mut tmp = path.as_os_str().to_owned();
+	tmp.push(".tmp");
+	let tmp = Path::new(&tmp);
+
+	let mut writer = BufWriter::new(File::create(tmp)?);
+	writer.write_all(MAGIC)?;
+	writer.write_all(&(json.len() as u64).to_le_bytes())?;
+	writer.write_all(&json)?;
+	for line in image.into_iter().take(header.rows_completed) {
+		for pixel in line {
+			for channel in [pixel.r(), pixel.g(), pixel.b()] {
+				writer.write_all(&channel.to_le_bytes())?;
+			}
+		}
+	}
+	let file = writer.into_inner().map_err(|e| e.into_error())?;
+	file.sync_all()?;
+	fs::rename(tmp, path)?;
+	Ok(())
+}
+
+/// Reads a checkpoint from the file at `path`.
+/// Returns its header, and an image containing the completed rows (the remaining rows are black).
+fn read_checkpoint(path: &Path) -> Result<(CheckpointHeader, Image), RaytracerError> {
+	let file = File::open(path)?;
+	let file_len = file.metadata()?.len();
+	let mut reader = BufReader::new(file);
+
+	let mut magic = [0; MAGIC.len()];
+	reader.read_exact(&mut magic)?;
+	if &magic != MAGIC {
+		return Err(RaytracerError::InvalidCheckpoint(String::from(
+			"file is not a checkpoint",
+		)));
+	}
+	let mut len = [0; 8];
+	reader.read_exact(&mut len)?;
+	let len = u64::from_le_bytes(len);
+	if len > file_len.saturating_sub((MAGIC.len() + 8) as u64) {
+		return Err(RaytracerError::InvalidCheckpoint(format!(
+			"header length {} exceeds the file size of {} bytes",
+			len, file_len
+		)));
+	}
+	let mut json = vec![0; len as usize];
+	reader.read_exact(&mut json)?;
+	let header: CheckpointHeader = serde_json::from_slice(&json)
+		.map_err(|e| RaytracerError::InvalidCheckpoint(e.to_string()))?;
+
+	let mut image = Image::init(header.height, header.width);
+	let mut value = || -> Result<f64, RaytracerError> {
+		let mut bytes = [0; 8];
+		reader.read_exact(&mut bytes)?;
+		Ok(f64::from_le_bytes(bytes))
+	};
+	for row in 0..usize::min(header.rows_completed, header.height) {
+		for col in 0..header.width {
+			image[(row, col)] = Color(value()?, value()?, value()?);
+		}
+	}
+	Ok((header, image))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs;
+	use std::path::PathBuf;
+
+	use super::{CheckpointHeader, MAGIC, read_checkpoint, write_checkpoint};
+	use crate::core::camera::{Camera, CameraSetup};
+	use crate::core::objects::{Material, Sphere};
+	use crate::core::scene::Scene;
+	use crate::core::types::{Color, Image, Point};
+
+	/// Returns a path in the temporary directory, unique to the specified test.
+	fn temp_path(test: &str) -> PathBuf {
+		std::env::temp_dir().join(format!("raytracer-{}-{}.ckpt", test, std::process::id()))
+	}
+
+	/// Creates a seeded camera, and a scene with a single sphere in front of it.
+	fn seeded_setup() -> (Camera, Scene) {
+		let setup = CameraSetup {
+			width: 16,
+			height: 24,
+			..Default::default()
+		};
+		let camera = Camera::from(setup).anti_aliasing(4).bounces(5).seed(7);
+		let scene = Scene::from([Sphere::new(
+			Point::new(0, 0, -2),
+			1.0,
+			Material::Matte {
+				color: Color::new(0.5, 0.5, 0.5),
+			},
+		)]);
+		(camera, scene)
+	}
+
+	#[test]
+	fn checkpointed_render_matches_continuous_render() {
+		let (camera, scene) = seeded_setup();
+		let path = temp_path("checkpointed");
+
+		// Rendering with checkpoints should not alter the result:
+		let expected = camera.render(&scene);
+		let actual = camera.render_checkpoint(&scene, &path, 5);
+		fs::remove_file(&path).ok();
+
+		assert!(actual.is_ok(), "render should succeed, but didn't");
+		assert_eq!(
+			expected,
+			actual.unwrap(),
+			"checkpointed render should match continuous render"
+		);
+	}
+
+	#[test]
+	fn resumed_render_matches_continuous_render() {
+		let (camera, scene) = seeded_setup();
+		let path = temp_path("resumed");
+		let expected = camera.render(&scene);
+
+		// This checkpoint was saved after 10 rows:
+		let mut partial = Image::init(expected.height(), expected.width());
+		for row in 0..10 {
+			for col in 0..expected.width() {
+				partial[(row, col)] = expected[(row, col)];
+			}
+		}
+		let header = CheckpointHeader {
+			width: expected.width(),
+			height: expected.height(),
+			rows_completed: 10,
+			checkpoint_every: 10,
+			seed: Some(7),
+		};
+		write_checkpoint(&path, &header, &partial).unwrap();
+
+		// Resuming should render the remaining rows exactly as before:
+		let actual = camera.resume_from_checkpoint(&scene, &path);
+		fs::remove_file(&path).ok();
+
+		assert!(actual.is_ok(), "resuming should succeed, but didn't");
+		assert_eq!(
+			expected,
+			actual.unwrap(),
+			"resumed render should match continuous render"
+		);
+	}
+
+	#[test]
+	fn if_checkpoint_size_differs_then_resume_fails() {
+		let (camera, scene) = seeded_setup();
+		let path = temp_path("mismatch");
+
+		// This checkpoint belongs to a differently sized image:
+		let header = CheckpointHeader {
+			width: 2,
+			height: 2,
+			rows_completed: 1,
+			checkpoint_every: 1,
+			seed: Some(7),
+		};
+		write_checkpoint(&path, &header, &Image::init(2, 2)).unwrap();
+
+		let result = camera.resume_from_checkpoint(&scene, &path);
+		fs::remove_file(&path).ok();
+		assert!(result.is_err(), "resuming should fail, but didn't");
+	}
+
+	#[test]
+	fn if_header_length_exceeds_file_then_resume_fails() {
+		let (camera, scene) = seeded_setup();
+		let path = temp_path("oversized");
+
+		// This header claims to be far longer than the file:
+		let mut bytes = MAGIC.to_vec();
+		bytes.extend_from_slice(&u64::MAX.to_le_bytes());
+		fs::write(&path, bytes).unwrap();
+
+		let result = camera.resume_from_checkpoint(&scene, &path);
+		fs::remove_file(&path).ok();
+		assert!(result.is_err(), "resuming should fail, but didn't");
+	}
+
+	#[test]
+	fn checkpoint_replaces_previous_without_leftovers() {
+		let path = temp_path("replaced");
+		let header = |rows_completed| CheckpointHeader {
+			width: 2,
+			height: 2,
+			rows_completed,
+			checkpoint_every: 1,
+			seed: Some(7),
+		};
+		write_checkpoint(&path, &header(1), &Image::init(2, 2)).unwrap();
+		write_checkpoint(&path, &header(2), &Image::init(2, 2)).unwrap();
+
+		let mut tmp = path.clone().into_os_string();
+		tmp.push(".tmp");
+		let (read, _) = read_checkpoint(&path).unwrap();
+		let tmp_exists = PathBuf::from(tmp).exists();
+		fs::remove_file(&path).ok();
+
+		assert_eq!(read, header(2), "latest checkpoint should be kept");
+		assert!(!tmp_exists, "temporary file should be renamed, but exists");
+	}
+
+	#[test]
+	fn if_checkpoint_interval_zero_then_resume_fails() {
+		let (camera, scene) = seeded_setup();
+		let path = temp_path("zero-interval");
+
+		// This checkpoint would never advance past row 0:
+		let header = CheckpointHeader {
+			width: 16,
+			height: 24,
+			rows_completed: 0,
+			checkpoint_every: 0,
+			seed: Some(7),
+		};
+		write_checkpoint(&path, &header, &Image::init(24, 16)).unwrap();
+
+		let result = camera.resume_from_checkpoint(&scene, &path);
+		fs::remove_file(&path).ok();
+		assert!(result.is_err(), "resuming should fail, but didn't");
+	}
+}
@@ -0,0 +1,263 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::random;
+use crate::core::types::{Image, Vec3};
+
+/// The additive recurrence constants of the R2 sequence, with which blue noise is shifted between
+/// samples of the same pixel.
+const R2_ALPHA: (f64, f64) = (0.754_877_666_246_692_7, 0.569_840_290_998_053_2);
+
+/// The pattern in which the samples of a pixel are distributed over its area.
+///
+/// Patterns other than [`JitterPattern::Random`] spread the samples more evenly,
+/// which reduces noise at the same amount of samples.
+#[derive(Debug, Clone, Default)]
+pub enum JitterPattern {
+	/// Every sample is placed at a random position.
+	#[default]
+	Random,
+	/// The pixel is divided into a grid of as many cells as there are samples, and every sample is
+	/// placed at a random position within its own cell.
+	Stratified,
+	/// The samples follow the Halton sequence in bases 2 and 3, shifted randomly for every pixel.
+	Halton,
+	/// The samples follow the first two dimensions of the Sobol sequence, scrambled for every pixel.
+	Sobol,
+	/// The samples are taken from a blue noise `texture`, which is tiled over the image.
+	/// The red and green channels are the offsets along the `x` and `y` axes.
+	BlueNoise { texture: Arc<Image> },
+}
+
+impl JitterPattern {
+	/// The name of this pattern.
+	pub fn name(&self) -> &'static str {
+		match self {
+			Self::Random => "random",
+			Self::Stratified => "stratified",
+			Self::Halton => "halton",
+			Self::Sobol => "sobol",
+			Self::BlueNoise { .. } => "blue-noise",
+		}
+	}
+	/// Calculates the offset in the `x` and `y` coordinates of the `sample`-th out of `samples`
+	/// samples of the pixel with index `(px_i, px_j)`. Both offsets lie in [-0.5; 0.5).
+	pub(super) fn offset(&self, px_i: usize, px_j: usize, sample: u32, samples: u32) -> Vec3 {
+		let (x, y) = match self {
+			Self::Random => (random::random(), random::random()),
+			Self::Stratified => {
+				let (rows, cols) = grid(samples);
+				let cell = sample % (rows * cols);
+				let x = ((cell % cols) as f64 + random::random::<f64>()) / cols as f64;
+				let y = ((cell / cols) as f64 + random::random::<f64>()) / rows as f64;
+				(x, y)
+			}
+			Self::Halton => {
+				let shift = pixel_hash(px_i, px_j);
+				(
+					(radical_inverse(sample + 1, 2) + unit(shift as u32)).fract(),
+					(radical_inverse(sample + 1, 3) + unit((shift >> 32) as u32)).fract(),
+				)
+			}
+			Self::Sobol => {
+				let scramble = pixel_hash(px_i, px_j);
+				(
+					unit(sample.reverse_bits() ^ scramble as u32),
+					unit(sobol_second(sample) ^ (scramble >> 32) as u32),
+				)
+			}
+			Self::BlueNoise { texture } if texture.width() > 0 && texture.height() > 0 => {
+				let noise = texture[(px_j % texture.height(), px_i % texture.width())];
+				(
+					(noise.r() + sample as f64 * R2_ALPHA.0).rem_euclid(1.0),
+					(noise.g() + sample as f64 * R2_ALPHA.1).rem_euclid(1.0),
+				)
+			}
+			Self::BlueNoise { .. } => (random::random(), random::random()),
+		};
+		Vec3(x - 0.5, y - 0.5, 0.0)
+	}
+}
+
+/// Calculates the amount of rows and columns of the most square grid with exactly `samples` cells.
+/// There are at least as many columns as rows.
+fn grid(samples: u32) -> (u32, u32) {
+	let samples = samples.max(1);
+	let rows = (1..=samples.isqrt())
+		.rev()
+		.find(|rows| samples.is_multiple_of(*rows))
+		.unwrap_or(1);
+	(rows, samples / rows)
+}
+
+/// The kind of a [`JitterPattern`], by which it is saved to JSON.
+///
+/// Since a texture can't be saved by its kind, [`JitterPattern::BlueNoise`] is restored as
+/// [`JitterPattern::Random`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(super) enum JitterKind {
+	Random,
+	Stratified,
+	Halton,
+	Sobol,
+	BlueNoise,
+}
+impl From<&JitterPattern> for JitterKind {
+	fn from(pattern: &JitterPattern) -> Self {
+		match pattern {
+			JitterPattern::Random => Self::Random,
+			JitterPattern::Stratified => Self::Stratified,
+			JitterPattern::Halton => Self::Halton,
+			JitterPattern::Sobol => Self::Sobol,
+			JitterPattern::BlueNoise { .. } => Self::BlueNoise,
+		}
+	}
+}
+impl From<JitterKind> for JitterPattern {
+	fn from(kind: JitterKind) -> Self {
+		match kind {
+			JitterKind::Random | JitterKind::BlueNoise => Self::Random,
+			JitterKind::Stratified => Self::Stratified,
+			JitterKind::Halton => Self::Halton,
+			JitterKind::Sobol => Self::Sobol,
+		}
+	}
+}
+
+/// Calculates the radical inverse of `index` in the specified `base`, which mirrors its digits
+/// at the decimal point. The result lies in [0; 1).
+fn radical_inverse(mut index: u32, base: u32) -> f64 {
+	let inverse_base = 1.0 / base as f64;
+	let (mut result, mut factor) = (0.0, inverse_base);
+	while index > 0 {
+		result += (index % base) as f64 * factor;
+		index /= base;
+		factor *= inverse_base;
+	}
+	result
+}
+
+/// Calculates the second dimension of the Sobol sequence at `index`, as the bits of a fraction.
+fn sobol_second(mut index: u32) -> u32 {
+	let (mut result, mut direction) = (0, 1 << 31);
+	while index > 0 {
+		if index & 1 == 1 {
+			result ^= direction;
+		}
+		index >>= 1;
+		direction ^= direction >> 1;
+	}
+	result
+}
+
+/// Converts the bits of a fraction to a value in [0; 1).
+fn unit(bits: u32) -> f64 {
+	bits as f64 / (1u64 << 32) as f64
+}
+
+/// Mixes the pixel position into well distributed bits, which decorrelate the sequences
+/// of neighbouring pixels.
+fn pixel_hash(px_i: usize, px_j: usize) -> u64 {
+	let mut hash = (((px_j as u64) << 32) | px_i as u64).wrapping_add(0x9e37_79b9_7f4a_7c15);
+	hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+	hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+	hash ^ (hash >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::core::camera::{Camera, CameraSetup};
+	use crate::core::types::Vec3;
+
+	use super::JitterPattern;
+
+	/// Collects the `samples` offsets of the pixel `(0, 0)`, shifted into [0; 1).
+	fn offsets(camera: &Camera, samples: u32) -> Vec<(f64, f64)> {
+		camera.seed_pixel(0, 0);
+		(0..samples)
+			.map(|sample| camera.sampling_offset(0, 0, sample) + Vec3(0.5, 0.5, 0.0))
+			.map(|offset| (offset.x(), offset.y()))
+			.collect()
+	}
+
+	/// Calculates the largest difference between the share of `points` in a rectangle anchored at
+	/// the origin, and the area of that rectangle, over all rectangles with a point at the corner.
+	fn discrepancy(points: &[(f64, f64)]) -> f64 {
+		let n = points.len() as f64;
+		points
+			.iter()
+			.map(|&(x, y)| {
+				let inside = points.iter().filter(|p| p.0 <= x && p.1 <= y).count();
+				(inside as f64 / n - x * y).abs()
+			})
+			.fold(0.0, f64::max)
+	}
+
+	#[test]
+	fn stratified_samples_are_in_distinct_quadrants() {
+		let camera = Camera::from(CameraSetup::default())
+			.anti_aliasing(4)
+			.with_jitter_pattern(JitterPattern::Stratified);
+
+		let mut quadrants = offsets(&camera, 4)
+			.iter()
+			.map(|&(x, y)| (x >= 0.5, y >= 0.5))
+			.collect::<Vec<_>>();
+		quadrants.sort();
+		quadrants.dedup();
+		assert_eq!(
+			quadrants.len(),
+			4,
+			"every sample should be in its own quadrant, but got {:?}",
+			quadrants
+		);
+	}
+
+	#[test]
+	fn stratified_samples_are_centered_in_pixel() {
+		for samples in [2, 3, 8] {
+			let camera = Camera::from(CameraSetup::default())
+				.anti_aliasing(samples)
+				.seed(11)
+				.with_jitter_pattern(JitterPattern::Stratified);
+
+			// Averaged over many pixels, the samples should cover the whole pixel evenly:
+			let mut sum = Vec3::zero();
+			for px in 0..256 {
+				camera.seed_pixel(px, 0);
+				for sample in 0..samples {
+					sum += camera.sampling_offset(px, 0, sample);
+				}
+			}
+			let mean = sum / (256 * samples) as f64;
+			assert!(
+				mean.x().abs() < 0.03 && mean.y().abs() < 0.03,
+				"mean offset with {} samples should be about 0, but is {:?}",
+				samples,
+				mean
+			);
+		}
+	}
+
+	#[test]
+	fn halton_samples_have_lower_discrepancy_than_random() {
+		// Both cameras are seeded, so that the random samples are always the same:
+		let samples = 64;
+		let camera = Camera::from(CameraSetup::default())
+			.anti_aliasing(samples)
+			.seed(5);
+		let random = discrepancy(&offsets(&camera, samples));
+		let halton = discrepancy(&offsets(
+			&camera.with_jitter_pattern(JitterPattern::Halton),
+			samples,
+		));
+		assert!(
+			halton < random,
+			"Halton discrepancy {} should be lower than random discrepancy {}",
+			halton,
+			random
+		);
+	}
+}
@@ -0,0 +1,222 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::error::RaytracerError;
+use crate::core::types::{Point, Vec3};
+
+use super::jitter::JitterKind;
+use super::{Camera, CameraSetup};
+
+/// The JSON representation of a camera, with all of its settings.
+///
+/// The fields are a superset of those of [`crate::input::CameraInput`]. Missing fields take the
+/// values of a camera created from the default [`CameraSetup`]. Vectors derived from the setup,
+/// such as the pixel deltas and the defocus disk, are calculated when the camera is restored.
+/// Output variables (see [`Camera::with_aov`]) and post-processing pipelines
+/// (see [`Camera::with_post_process`]) aren't saved.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(super) struct CameraJson {
+	/// The width of the image the camera produces, in pixels.
+	width: usize,
+	/// The height of the image the camera produces, in pixels.
+	height: usize,
+	/// The vertical field of view, in degrees.
+	fov: f64,
+	/// The position of the camera.
+	source: Point,
+	/// The point the camera is looking at.
+	target: Point,
+	/// Angular aperture size, in degrees.
+	aperture: f64,
+	/// Distance from camera center to the plane where the objects are in focus.
+	focus_distance: f64,
+	/// The vector pointing from the camera upwards.
+	view_up: Option<Vec3>,
+	/// Samples per pixel.
+	samples: Option<u32>,
+	/// The kind of pattern in which samples are distributed over each pixel.
+	/// The texture of a blue noise pattern isn't saved, so it is restored as a random pattern.
+	jitter: JitterKind,
+	/// Max. amount of bounces per ray.
+	bounces: Option<u32>,
+	/// The factor by which images are rendered larger, and downsampled after rendering.
+	resolution_scale: usize,
+	/// Strength of the chromatic aberration.
+	chromatic_aberration: f64,
+	/// The radial distortion coefficients `(k1, k2)` of the lens.
+	lens_distortion: (f64, f64),
+	/// How much the corners of the image are darkened by vignetting.
+	vignette_strength: f64,
+	/// How quickly vignetting sets in towards the corners of the image.
+	vignette_falloff: f64,
+	/// The times `(open, close)` of the shutter.
+	shutter_time: Option<(f64, f64)>,
+	/// The seed for random sampling.
+	seed: Option<u64>,
+	/// The maximum amount of memory a render may use, in bytes.
+	memory_limit: Option<usize>,
+}
+impl Default for CameraJson {
+	fn default() -> Self {
+		Camera::from(CameraSetup::default()).into()
+	}
+}
+impl From<Camera> for CameraJson {
+	fn from(camera: Camera) -> Self {
+		let setup = camera.setup;
+		Self {
+			width: setup.width,
+			height: setup.height,
+			fov: setup.v_fov,
+			source: setup.lookfrom,
+			target: setup.lookat,
+			aperture: camera.defocus_angle,
+			focus_distance: setup.focus_distance,
+			view_up: Some(setup.view_up),
+			samples: Some(camera.samples_per_px),
+			jitter: JitterKind::from(&camera.jitter),
+			bounces: Some(camera.bounces),
+			resolution_scale: camera.resolution_scale,
+			chromatic_aberration: camera.chromatic_aberration,
+			lens_distortion: camera.lens_distortion,
+			vignette_strength: camera.vignette_strength,
+			vignette_falloff: camera.vignette_falloff,
+			shutter_time: camera.shutter_time,
+			seed: camera.seed,
+			memory_limit: camera.memory_limit,
+		}
+	}
+}
+impl From<CameraJson> for Camera {
+	fn from(json: CameraJson) -> Self {
+		let setup = CameraSetup {
+			width: json.width,
+			height: json.height,
+			v_fov: json.fov,
+			lookfrom: json.source,
+			lookat: json.target,
+			view_up: json.view_up.unwrap_or(CameraSetup::default().view_up),
+			defocus_angle: json.aperture,
+			focus_distance: json.focus_distance,
+		};
+		let camera = Camera {
+			samples_per_px: u32::max(1, json.samples.unwrap_or(1)),
+			jitter: json.jitter.into(),
+			bounces: json.bounces.unwrap_or(1),
+			resolution_scale: usize::max(1, json.resolution_scale),
+			chromatic_aberration: json.chromatic_aberration,
+			lens_distortion: json.lens_distortion,
+			vignette_strength: json.vignette_strength,
+			vignette_falloff: json.vignette_falloff,
+			shutter_time: json.shutter_time,
+			seed: json.seed,
+			memory_limit: json.memory_limit,
+			..Camera::from(setup)
+		};
+		camera.rebuilt(setup)
+	}
+}
+
+// JSON
+impl Camera {
+	/// Restores a camera with all of its settings from JSON, as written by [`Camera::to_json`].
+	///
+	/// The JSON of a camera in the input (see [`crate::input::CameraInput`]) is accepted as well,
+	/// with the remaining settings taking their default values.
+	pub fn from_json(s: &str) -> Result<Self, RaytracerError> {
+		Ok(serde_json::from_str(s)?)
+	}
+	/// Serializes this camera with all of its settings to JSON,
+	/// which can be restored by [`Camera::from_json`].
+	pub fn to_json(&self) -> String {
+		serde_json::to_string(self).expect("cameras should always be serializable")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::core::camera::{Camera, CameraSetup, JitterPattern};
+	use crate::core::types::Point;
+
+	#[test]
+	fn camera_survives_json_round_trip() {
+		let setup = CameraSetup {
+			width: 64,
+			height: 48,
+			v_fov: 30.0,
+			lookfrom: Point::new(1, 2, 3),
+			defocus_angle: 2.0,
+			..Default::default()
+		};
+		let camera = Camera::from(setup)
+			.anti_aliasing(50)
+			.bounces(20)
+			.with_resolution_scale(2)
+			.with_vignette(0.5, 1.5)
+			.seed(7);
+
+		let json = camera.to_json();
+		let restored = Camera::from_json(&json);
+		assert!(
+			restored.is_ok(),
+			"camera {} should be restored, but error occurred: {:?}",
+			json,
+			restored.err()
+		);
+		let restored = restored.unwrap();
+		assert_eq!(restored.samples_per_pixel(), 50, "samples should match");
+		assert_eq!(restored.max_bounces(), 20, "bounces should match");
+		assert_eq!(
+			restored.to_json(),
+			json,
+			"restored camera should serialize identically"
+		);
+		assert_eq!(
+			(restored.img_size, restored.px_00, restored.defocus_disk_u),
+			(camera.img_size, camera.px_00, camera.defocus_disk_u),
+			"derived values should match"
+		);
+	}
+
+	#[test]
+	fn jitter_pattern_survives_json_round_trip() {
+		let camera =
+			Camera::from(CameraSetup::default()).with_jitter_pattern(JitterPattern::Halton);
+		let json = camera.to_json();
+		assert!(
+			json.contains(r#""jitter":"halton""#),
+			"jitter pattern should be saved by name, but JSON was {}",
+			json
+		);
+		let restored = Camera::from_json(&json).unwrap();
+		assert_eq!(
+			restored.to_json(),
+			json,
+			"jitter pattern should be restored"
+		);
+	}
+
+	#[test]
+	fn if_jitter_pattern_unknown_then_parsing_fails() {
+		let json = r#"{ "jitter": "hlaton" }"#;
+		assert!(
+			Camera::from_json(json).is_err(),
+			"misspelled jitter pattern should be rejected"
+		);
+	}
+
+	#[test]
+	fn camera_input_json_is_restored_with_defaults() {
+		let json = r#"{ "fov": 30, "source": [0, 1, 2], "target": [0, 0, 0], "aperture": 0, "focusDistance": 2, "samples": 10 }"#;
+		let restored = Camera::from_json(json);
+		assert!(restored.is_ok(), "camera input should be accepted");
+		let restored = restored.unwrap();
+		assert_eq!(restored.samples_per_pixel(), 10, "samples should match");
+		assert_eq!(restored.max_bounces(), 1, "bounces should be the default");
+		assert_eq!(
+			restored.image_size(),
+			(400, 225),
+			"image size should be the default"
+		);
+	}
+}
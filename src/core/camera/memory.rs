@@ -0,0 +1,83 @@
+use crate::core::error::RaytracerError;
+use crate::core::scene::Scene;
+use crate::core::types::{Color, Image};
+
+use super::Camera;
+
+// Memory
+impl Camera {
+	/// Estimates the memory needed for rendering the `scene`, in bytes: the pixels of the
+	/// image at the rendered resolution, and the objects of the scene (see [`Scene::estimated_memory_bytes`]).
+	pub fn estimate_memory_bytes(&self, scene: &Scene) -> usize {
+		let (width, height) = self.img_size;
+		size_of::<Color>() * width * height + scene.estimated_memory_bytes()
+	}
+	/// Checks whether rendering the `scene` is estimated to stay within the memory limit of
+	/// this camera (see [`Camera::with_memory_limit`]). Without a limit, this always succeeds.
+	pub fn check_memory_limit(&self, scene: &Scene) -> Result<(), RaytracerError> {
+		let Some(limit) = self.memory_limit else {
+			return Ok(());
+		};
+		let estimated = self.estimate_memory_bytes(scene);
+		if estimated > limit {
+			return Err(RaytracerError::MemoryLimitExceeded { estimated, limit });
+		}
+		Ok(())
+	}
+	/// Renders a scene and produces an image, unless rendering is estimated to exceed
+	/// the memory limit of this camera, see [`Camera::check_memory_limit`].
+	pub fn try_render(&self, scene: &Scene) -> Result<Image, RaytracerError> {
+		self.check_memory_limit(scene)?;
+		Ok(self.render(scene))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::core::camera::{Camera, CameraSetup};
+	use crate::core::error::RaytracerError;
+	use crate::core::objects::{Material, Sphere};
+	use crate::core::scene::Scene;
+	use crate::core::types::{Color, Point};
+
+	/// Creates a camera producing a 16x9 image, and a scene with a single sphere.
+	fn setup() -> (Camera, Scene) {
+		let setup = CameraSetup {
+			width: 16,
+			height: 9,
+			..Default::default()
+		};
+		let scene = Scene::from([Sphere::new(Point::new(0, 0, -2), 1, Material::Absorbant)]);
+		(Camera::from(setup), scene)
+	}
+
+	#[test]
+	fn if_estimate_within_limit_then_rendered() {
+		let (camera, scene) = setup();
+		let pixels = size_of::<Color>() * 16 * 9;
+		let camera = camera.with_memory_limit(pixels + scene.estimated_memory_bytes());
+
+		let result = camera.try_render(&scene);
+		assert!(
+			result.is_ok(),
+			"render should succeed, but got {:?}",
+			result.err()
+		);
+	}
+
+	#[test]
+	fn if_estimate_exceeds_limit_then_error() {
+		let (camera, scene) = setup();
+		let estimated = camera.estimate_memory_bytes(&scene);
+		// The limit holds the image, but not the scene:
+		let limit = size_of::<Color>() * 16 * 9;
+		let camera = camera.with_memory_limit(limit);
+
+		let result = camera.try_render(&scene);
+		assert!(
+			matches!(result, Err(RaytracerError::MemoryLimitExceeded { estimated: e, limit: l }) if e == estimated && l == limit),
+			"render should exceed the memory limit, but got {:?}",
+			result.map(|image| image.width())
+		);
+	}
+}
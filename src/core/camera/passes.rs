@@ -0,0 +1,162 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::core::scene::Scene;
+use crate::core::types::{Color, Image, ToVec3, Vec3};
+
+#[cfg(not(feature = "bench"))]
+use super::CLEAR;
+use super::Camera;
+
+/// The passes produced by a single render with [`Camera::render_passes`].
+///
+/// All passes except `color` describe the first surface hit by the rays of each pixel,
+/// averaged over all samples of the pixel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderOutput {
+	/// The rendered image, identical to the result of [`Camera::render`].
+	pub color: Image,
+	/// The surface normals in world space, with the `x`, `y` and `z` components stored
+	/// in the red, green and blue channels. Pixels without a surface are black.
+	pub normals: Image,
+	/// The base color of the surface materials (see [`crate::objects::Material::albedo`]).
+	/// Pixels without a surface are black.
+	pub albedo: Image,
+	/// The distance from the camera to the surface, stored in all three channels.
+	/// Pixels without a surface have an infinite depth.
+	pub depth: Image,
+}
+
+// Render passes
+impl Camera {
+	/// Renders a scene, and produces the color image along with normal, albedo, and depth passes.
+	///
+	/// All passes are computed from the same rays, so the scene is only traversed once.
+	/// The color pass matches [`Camera::render`], including its post-processing.
+	pub fn render_passes(&self, scene: &Scene) -> RenderOutput {
+		let (width, height) = self.img_size;
+		let remaining = AtomicUsize::new(height);
+		let rows = (0..height)
+			.into_par_iter()
+			.map(|row| {
+				let pixels = (0..width)
+					.map(|col| self.sample_passes(col, row, scene))
+					.collect::<Vec<_>>();
+				remaining.fetch_sub(1, Ordering::Relaxed);
+				log!("{CLEAR}Lines remaining: {:?}", remaining);
+				pixels
+			})
+			.collect::<Vec<_>>();
+		log!("{CLEAR}Done.\n");
+
+		let mut passes: [Image; 4] = std::array::from_fn(|_| Image::init(height, width));
+		for (row, pixels) in rows.into_iter().enumerate() {
+			for (col, pixel) in pixels.into_iter().enumerate() {
+				for (pass, value) in passes.iter_mut().zip(pixel) {
+					pass[(row, col)] = value;
+				}
+			}
+		}
+		let [color, normals, albedo, depth] = passes;
+		RenderOutput {
+			color: self.post_process(color),
+			normals: normals.downscale(self.resolution_scale),
+			albedo: albedo.downscale(self.resolution_scale),
+			depth: depth.downscale(self.resolution_scale),
+		}
+	}
+
+	/// Samples a pixel, and returns its average color, normal, albedo and depth (in this order).
+	fn sample_passes(&self, px_i: usize, px_j: usize, scene: &Scene) -> [Color; 4] {
+		self.seed_pixel(px_i, px_j);
+		let (mut rgb, mut normal, mut albedo) = (Vec3::zero(), Vec3::zero(), Vec3::zero());
+		let (mut depth, mut hits) = (0.0, 0);
+		for sample in 0..self.samples_per_px {
+			let ray = self.sampling_ray(px_i, px_j, sample);
+			let (color, hit) = ray.trace(scene, self.bounces);
+			rgb += color.to_vec3();
+			if let Some(hit) = hit {
+				normal += hit.normal;
+				albedo += hit.material.albedo().to_vec3();
+				depth += hit.t * ray.direction.norm();
+				hits += 1;
+			}
+		}
+		let samples = self.samples_per_px as f64;
+		let weight = self.vignette_weight(px_i, px_j);
+		let depth = if hits > 0 {
+			depth / hits as f64
+		} else {
+			f64::INFINITY
+		};
+		[
+			rgb.scale(weight / samples).into(),
+			normal.scale(1.0 / samples).into(),
+			albedo.scale(1.0 / samples).into(),
+			Color::new(depth, depth, depth),
+		]
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::core::camera::{Camera, CameraSetup};
+	use crate::core::objects::{Material, Sphere};
+	use crate::core::scene::Scene;
+	use crate::core::types::{Color, Point};
+
+	/// Creates a seeded camera, and a scene with a single sphere in the middle of the image.
+	fn seeded_setup() -> (Camera, Scene) {
+		let setup = CameraSetup {
+			width: 20,
+			height: 10,
+			..Default::default()
+		};
+		let camera = Camera::from(setup).anti_aliasing(4).bounces(5).seed(3);
+		let scene = Scene::from([Sphere::new(
+			Point::new(0, 0, -2),
+			0.5,
+			Material::Matte {
+				color: Color::new(0.2, 0.4, 0.6),
+			},
+		)]);
+		(camera, scene)
+	}
+
+	#[test]
+	fn color_pass_matches_render() {
+		let (camera, scene) = seeded_setup();
+		let expected = camera.render(&scene);
+		let output = camera.render_passes(&scene);
+		assert_eq!(
+			output.color, expected,
+			"color pass should match the rendered image"
+		);
+	}
+
+	#[test]
+	fn sphere_is_closer_than_background() {
+		let (camera, scene) = seeded_setup();
+		let output = camera.render_passes(&scene);
+
+		// The center pixel shows the sphere, the corner pixel shows the background:
+		let (center, corner) = (output.depth[(5, 10)].r(), output.depth[(0, 0)].r());
+		assert!(
+			center < corner,
+			"sphere should be closer than background, but depths were {} and {}",
+			center,
+			corner
+		);
+		assert_eq!(
+			output.albedo[(5, 10)],
+			Color::new(0.2, 0.4, 0.6),
+			"albedo should be the color of the sphere"
+		);
+		assert!(
+			output.normals[(5, 10)].b() > 0.9,
+			"normal should face the camera, but was {:?}",
+			output.normals[(5, 10)]
+		);
+	}
+}
@@ -0,0 +1,177 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use rayon::slice::ParallelSliceMut;
+
+use crate::core::scene::Scene;
+use crate::core::types::Image;
+
+#[cfg(not(feature = "bench"))]
+use super::CLEAR;
+use super::Camera;
+
+/// A type that is notified about the progress of a render, see [`Camera::render_region_parallel`].
+pub trait RenderProgress: Send + Sync {
+	/// Called whenever a row of pixels has been rendered.
+	fn row_completed(&self);
+}
+/// Ignores all progress.
+impl RenderProgress for () {
+	fn row_completed(&self) {}
+}
+/// Counts the rows that have been rendered.
+impl RenderProgress for AtomicUsize {
+	fn row_completed(&self) {
+		self.fetch_add(1, Ordering::Relaxed);
+	}
+}
+
+// Regions
+impl Camera {
+	/// Renders rectangular regions (tiles) of the images of several cameras in parallel,
+	/// and produces an image for each of them. The images are returned in the same order as the tiles.
+	///
+	/// Each tile is specified as `(camera, start_row, start_col, height, width)`, and renders
+	/// the pixels of the camera's image within these bounds. This way, different machines can
+	/// render different tiles of the same image, which are then composited. Rendering a tile with a
+	/// seeded camera (see [`Camera::seed`]) produces exactly the same pixels as a full render.
+	/// Post-processing effects, which depend on the whole image, are not applied.
+	///
+	/// The `progress` is notified after each completed row of any tile.
+	///
+	/// # Panics
+	/// Panics if a tile exceeds the image of its camera.
+	pub fn render_region_parallel<P: RenderProgress>(
+		cameras: &[(Camera, usize, usize, usize, usize)],
+		scene: Arc<Scene>,
+		progress: Arc<P>,
+	) -> Vec<Image> {
+		for (camera, start_row, start_col, height, width) in cameras {
+			let (img_width, img_height) = camera.img_size;
+			if start_row + height > img_height || start_col + width > img_width {
+				panic!(
+					"tile of size {}x{} at ({}, {}) should lie within the image of size {}x{}",
+					width, height, start_row, start_col, img_width, img_height
+				);
+			}
+		}
+
+		let remaining = AtomicUsize::new(cameras.iter().map(|tile| tile.3).sum());
+		let images = cameras
+			.par_iter()
+			.map(|&(ref camera, start_row, start_col, height, width)| {
+				let mut image = Image::init(height, width);
+				image
+					.par_chunks_mut(width.max(1))
+					.enumerate()
+					.for_each(|(row, pixels)| {
+						for (col, pixel) in pixels.iter_mut().enumerate() {
+							*pixel = camera.sample_pixel(start_col + col, start_row + row, &scene);
+						}
+						progress.row_completed();
+						remaining.fetch_sub(1, Ordering::Relaxed);
+						log!("{CLEAR}Lines remaining: {:?}", remaining);
+					});
+				image
+			})
+			.collect();
+		log!("{CLEAR}Done.\n");
+		images
+	}
+	/// Calculates the bounds `(start_row, start_col, height, width)` of the tile with the specified
+	/// `index`, if this camera's image is split into `count` tiles of (nearly) equal area.
+	///
+	/// The image is split into horizontal strips spanning its full width, whose heights differ
+	/// by at most one row.
+	///
+	/// # Panics
+	/// Panics if `index` is not smaller than `count`.
+	pub fn tile_bounds(&self, index: usize, count: usize) -> (usize, usize, usize, usize) {
+		assert!(
+			index < count,
+			"tile index {} should be smaller than the tile count {}",
+			index,
+			count
+		);
+		let (width, height) = self.img_size;
+		let start_row = index * height / count;
+		let end_row = (index + 1) * height / count;
+		(start_row, 0, end_row - start_row, width)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+	use std::sync::atomic::{AtomicUsize, Ordering};
+
+	use crate::core::camera::{Camera, CameraSetup};
+	use crate::core::objects::{Material, Sphere};
+	use crate::core::scene::Scene;
+	use crate::core::types::{Color, Image, Point};
+
+	/// Creates a seeded camera, and a scene with a single sphere in front of it.
+	fn seeded_setup() -> (Camera, Scene) {
+		let setup = CameraSetup {
+			width: 20,
+			height: 15,
+			..Default::default()
+		};
+		let camera = Camera::from(setup).anti_aliasing(4).bounces(5).seed(11);
+		let scene = Scene::from([Sphere::new(
+			Point::new(0, 0, -2),
+			1.0,
+			Material::Matte {
+				color: Color::new(0.5, 0.5, 0.5),
+			},
+		)]);
+		(camera, scene)
+	}
+
+	#[test]
+	fn composited_tiles_match_full_render() {
+		let (camera, scene) = seeded_setup();
+		let expected = camera.render(&scene);
+		let scene = Arc::new(scene);
+
+		// Each tile is rendered in its own call, as if on different machines:
+		let count = 4;
+		let mut composite = Image::init(15, 20);
+		for index in 0..count {
+			let (start_row, start_col, height, width) = camera.tile_bounds(index, count);
+			let tile = (camera.clone(), start_row, start_col, height, width);
+			let image = Camera::render_region_parallel(&[tile], Arc::clone(&scene), Arc::new(()));
+			for row in 0..height {
+				for col in 0..width {
+					composite[(start_row + row, start_col + col)] = image[0][(row, col)];
+				}
+			}
+		}
+		assert_eq!(
+			composite, expected,
+			"composited tiles should match the full render"
+		);
+	}
+
+	#[test]
+	fn tiles_cover_image_and_report_progress() {
+		let (camera, scene) = seeded_setup();
+		let tiles = (0..3)
+			.map(|index| {
+				let (start_row, start_col, height, width) = camera.tile_bounds(index, 3);
+				(camera.clone(), start_row, start_col, height, width)
+			})
+			.collect::<Vec<_>>();
+		let progress = Arc::new(AtomicUsize::new(0));
+
+		let images = Camera::render_region_parallel(&tiles, Arc::new(scene), Arc::clone(&progress));
+		let heights = images.iter().map(Image::height).collect::<Vec<_>>();
+		assert_eq!(heights, vec![5, 5, 5], "tiles should have equal heights");
+		assert_eq!(
+			progress.load(Ordering::Relaxed),
+			15,
+			"every row should be reported"
+		);
+	}
+}
@@ -0,0 +1,94 @@
+use crate::core::scene::Scene;
+use crate::core::types::{Color, Image, ToVec3};
+
+use super::{Camera, CameraSetup};
+
+// Stereoscopic rendering
+impl Camera {
+	/// Renders a scene as seen by the left and the right eye, e.g. for VR headsets,
+	/// and returns the images in this order.
+	///
+	/// The eyes are placed `eye_separation` apart along the horizontal axis of the image, centered
+	/// at this camera's position, and both look at the same point. The scene is shared by both
+	/// renders, see [`Camera::render_multi`].
+	pub fn render_stereoscopic(&self, scene: &Scene, eye_separation: f64) -> (Image, Image) {
+		let [left, right] = [-0.5, 0.5].map(|side| self.eye(side * eye_separation));
+		let mut images = Camera::render_multi(&[left, right], scene).into_iter();
+		let left = images.next().expect("left eye should be rendered");
+		let right = images.next().expect("right eye should be rendered");
+		(left, right)
+	}
+	/// Renders a red-cyan anaglyph of a scene, which appears three-dimensional through glasses
+	/// with a red filter on the left and a cyan filter on the right eye.
+	///
+	/// The red channel is taken from the left eye, and the green and blue channels from the right
+	/// eye, see [`Camera::render_stereoscopic`].
+	pub fn render_anaglyph(&self, scene: &Scene, eye_separation: f64) -> Image {
+		let (left, right) = self.render_stereoscopic(scene, eye_separation);
+		let mut image = Image::init(left.height(), left.width());
+		for row in 0..image.height() {
+			for col in 0..image.width() {
+				let (l, r) = (left[(row, col)], right[(row, col)]);
+				image[(row, col)] = Color::new(l.r(), r.g(), r.b());
+			}
+		}
+		image
+	}
+
+	/// Returns this camera, moved by `offset` along the horizontal axis of the image,
+	/// but still looking at the same point.
+	fn eye(&self, offset: f64) -> Camera {
+		let shift = self.px_d_u.unit().scale(offset);
+		let setup = CameraSetup {
+			lookfrom: (self.setup.lookfrom.to_vec3() + shift).into(),
+			..self.setup
+		};
+		self.rebuilt(setup)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::core::camera::{Camera, CameraSetup};
+	use crate::core::objects::{Material, Sphere};
+	use crate::core::scene::Scene;
+	use crate::core::types::{Color, Point};
+
+	/// Creates a seeded camera, and a scene with two spheres at different distances.
+	fn stereo_setup() -> (Camera, Scene) {
+		let setup = CameraSetup {
+			width: 32,
+			height: 18,
+			lookfrom: Point::new(0, 0, 2),
+			lookat: Point::origin(),
+			..Default::default()
+		};
+		let matte = |r, g, b| Material::Matte {
+			color: Color::new(r, g, b),
+		};
+		let scene = Scene::from([
+			Sphere::new(Point::origin(), 0.5, matte(1, 0, 0)),
+			Sphere::new(Point::new(0.5, 0, -2), 0.75, matte(0, 0, 1)),
+		]);
+		(Camera::from(setup).seed(3), scene)
+	}
+
+	#[test]
+	fn if_eyes_separated_then_images_differ() {
+		let (camera, scene) = stereo_setup();
+		let (left, right) = camera.render_stereoscopic(&scene, 0.5);
+		assert_ne!(left, right, "both eyes should see the scene differently");
+	}
+
+	#[test]
+	fn if_no_separation_then_images_identical() {
+		let (camera, scene) = stereo_setup();
+		let (left, right) = camera.render_stereoscopic(&scene, 0.0);
+		assert_eq!(left, right, "both eyes should see the same image");
+		assert_eq!(
+			left,
+			camera.render(&scene),
+			"both eyes should see what the camera sees"
+		);
+	}
+}
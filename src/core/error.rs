@@ -0,0 +1,79 @@
+use std::fmt::Display;
+use std::io;
+
+/// A type that represents errors that can occur in the raytracer.
+#[derive(Debug)]
+pub enum RaytracerError {
+	/// Reading or writing failed.
+	Io(io::Error),
+	/// Encoding the output image failed.
+	Encoding(String),
+	/// Parsing the input failed.
+	ParseError(String),
+	/// Images that are combined have different dimensions.
+	DimensionMismatch(String),
+	/// A render checkpoint is malformed or does not match the camera.
+	InvalidCheckpoint(String),
+	/// Rendering is estimated to use more memory than allowed, in bytes.
+	MemoryLimitExceeded { estimated: usize, limit: usize },
+}
+
+impl Display for RaytracerError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Io(e) => write!(f, "i/o error: {}", e),
+			Self::Encoding(msg) => write!(f, "encoding error: {}", msg),
+			Self::ParseError(msg) => write!(f, "parse error: {}", msg),
+			Self::DimensionMismatch(msg) => write!(f, "dimension mismatch: {}", msg),
+			Self::InvalidCheckpoint(msg) => write!(f, "invalid checkpoint: {}", msg),
+			Self::MemoryLimitExceeded { estimated, limit } => write!(
+				f,
+				"memory limit exceeded: rendering needs an estimated {} bytes, but the limit is {} bytes",
+				estimated, limit
+			),
+		}
+	}
+}
+impl std::error::Error for RaytracerError {}
+
+impl From<io::Error> for RaytracerError {
+	fn from(value: io::Error) -> Self {
+		Self::Io(value)
+	}
+}
+
+impl From<serde_json::Error> for RaytracerError {
+	fn from(value: serde_json::Error) -> Self {
+		Self::ParseError(value.to_string())
+	}
+}
+
+#[cfg(feature = "png-output")]
+impl From<png::EncodingError> for RaytracerError {
+	fn from(value: png::EncodingError) -> Self {
+		match value {
+			png::EncodingError::IoError(e) => Self::Io(e),
+			other => Self::Encoding(other.to_string()),
+		}
+	}
+}
+
+#[cfg(feature = "jpeg-output")]
+impl From<jpeg_encoder::EncodingError> for RaytracerError {
+	fn from(value: jpeg_encoder::EncodingError) -> Self {
+		match value {
+			jpeg_encoder::EncodingError::IoError(e) => Self::Io(e),
+			other => Self::Encoding(other.to_string()),
+		}
+	}
+}
+
+#[cfg(feature = "exr-output")]
+impl From<exr::error::Error> for RaytracerError {
+	fn from(value: exr::error::Error) -> Self {
+		match value {
+			exr::error::Error::Io(e) => Self::Io(e),
+			other => Self::Encoding(other.to_string()),
+		}
+	}
+}
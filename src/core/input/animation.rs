@@ -0,0 +1,231 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::types::{Point, ToVec3};
+
+use super::{CameraInput, RaytracerInput};
+
+/// A type that represents the animation settings of an input.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnimationInput {
+	/// The amount of frames rendered per second.
+	pub fps: f64,
+	/// The length of the animation, in seconds.
+	pub duration: f64,
+	/// The keyframes of the animation, in the order of their time.
+	pub keyframes: Vec<Keyframe>,
+}
+
+/// A type that represents the state of the scene at a point in time.
+///
+/// A keyframe only needs to specify what changes: everything else keeps
+/// the state of the previous keyframe (or of the input, for the first keyframe).
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Keyframe {
+	/// The point in time of this keyframe, in seconds.
+	pub time: f64,
+	/// The camera settings at this point in time.
+	#[serde(default)]
+	pub camera: Option<CameraInput>,
+	/// The positions of objects at this point in time.
+	#[serde(default)]
+	pub scene_overrides: Vec<SceneOverride>,
+}
+
+/// A type that represents the position of an object of the scene within a keyframe.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SceneOverride {
+	/// The index of the object in the input's `scene` array.
+	pub object: usize,
+	/// The position of the object (see [`crate::objects::Object::position`]).
+	pub position: Point,
+}
+
+impl AnimationInput {
+	/// The amount of frames in the animation.
+	pub fn frame_count(&self) -> usize {
+		(self.fps * self.duration).round().max(0.0) as usize
+	}
+	/// The point in time at which the frame with the specified index is shown, in seconds.
+	pub fn frame_time(&self, frame: usize) -> f64 {
+		frame as f64 / self.fps
+	}
+}
+
+// Animation
+impl RaytracerInput {
+	/// Returns the input as it is at the point in `time` (in seconds) of its animation.
+	///
+	/// Camera settings and object positions are interpolated linearly between the keyframes
+	/// surrounding `time`. Before the first and after the last keyframe, the state of the
+	/// respective keyframe is kept. If there is no animation, a copy of this input is returned.
+	pub fn at_time(&self, time: f64) -> RaytracerInput {
+		let states = self.keyframe_states();
+		let Some(next) = states.iter().position(|(t, _)| *t > time) else {
+			// After the last keyframe (or without any)
+			return states
+				.last()
+				.map_or_else(|| self.clone(), |(_, s)| s.clone());
+		};
+		if next == 0 {
+			return states[0].1.clone();
+		}
+		let ((t0, from), (t1, to)) = (&states[next - 1], &states[next]);
+		let fraction = (time - t0) / (t1 - t0);
+		interpolate(from, to, fraction)
+	}
+
+	/// Calculates the complete state of this input at each of its keyframes.
+	fn keyframe_states(&self) -> Vec<(f64, RaytracerInput)> {
+		let Some(animation) = &self.animation else {
+			return Vec::new();
+		};
+		let mut state = RaytracerInput {
+			animation: None,
+			..self.clone()
+		};
+		let mut states = Vec::with_capacity(animation.keyframes.len());
+		for keyframe in &animation.keyframes {
+			if let Some(camera) = &keyframe.camera {
+				state.camera = camera.clone();
+			}
+			for SceneOverride { object, position } in &keyframe.scene_overrides {
+				if let Some(object) = state.scene.get_mut(*object) {
+					*object = object.clone().moved_to(*position);
+				}
+			}
+			states.push((keyframe.time, state.clone()));
+		}
+		states
+	}
+}
+
+/// Interpolates linearly between two states of the same input, where a `fraction` of 0
+/// returns `from` and a `fraction` of 1 returns `to`.
+/// Settings that can't be interpolated are taken from `from`.
+fn interpolate(from: &RaytracerInput, to: &RaytracerInput, fraction: f64) -> RaytracerInput {
+	let lerp = |a: f64, b: f64| a + (b - a) * fraction;
+	let lerp_point = |a: Point, b: Point| -> Point {
+		(a.to_vec3() + (b.to_vec3() - a.to_vec3()).scale(fraction)).into()
+	};
+	let (a, b) = (&from.camera, &to.camera);
+	let camera = CameraInput {
+		fov: lerp(a.fov, b.fov),
+		source: lerp_point(a.source, b.source),
+		target: lerp_point(a.target, b.target),
+		aperture: lerp(a.aperture, b.aperture),
+		focus_distance: lerp(a.focus_distance, b.focus_distance),
+		..a.clone()
+	};
+	let scene = from
+		.scene
+		.iter()
+		.zip(&to.scene)
+		.map(|(a, b)| a.clone().moved_to(lerp_point(a.position(), b.position())))
+		.collect();
+	RaytracerInput {
+		camera,
+		scene,
+		..from.clone()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::core::input::RaytracerInput;
+	use crate::core::types::Point;
+
+	/// An input whose camera moves along the x-axis, and whose sphere moves up, within one second.
+	fn animated_input() -> RaytracerInput {
+		let camera = |x: f64| {
+			format!(
+				r#"{{
+					"fov": 40.0,
+					"source": [{}, 0.0, 5.0],
+					"target": [0.0, 0.0, 0.0],
+					"aperture": 0.0,
+					"focusDistance": 5.0
+				}}"#,
+				x
+			)
+		};
+		let json = format!(
+			r#"{{
+				"camera": {},
+				"scene": [
+					{{
+						"type": "sphere",
+						"center": [0.0, 0.0, 0.0],
+						"radius": 1.0,
+						"material": {{ "type": "absorbant" }}
+					}}
+				],
+				"animation": {{
+					"fps": 24.0,
+					"duration": 1.0,
+					"keyframes": [
+						{{ "time": 0.0, "camera": {} }},
+						{{
+							"time": 1.0,
+							"camera": {},
+							"sceneOverrides": [{{ "object": 0, "position": [0.0, 2.0, 0.0] }}]
+						}}
+					]
+				}}
+			}}"#,
+			camera(-2.0),
+			camera(-2.0),
+			camera(4.0)
+		);
+		RaytracerInput::try_from(json.as_str()).unwrap()
+	}
+
+	#[test]
+	fn at_first_keyframe_input_matches_keyframe() {
+		let input = animated_input();
+		let first = input.animation.as_ref().unwrap().keyframes[0].clone();
+
+		let frame = input.at_time(0.0);
+		assert_eq!(
+			Some(frame.camera),
+			first.camera,
+			"camera should match the first keyframe"
+		);
+		assert_eq!(frame.scene, input.scene, "scene should be unchanged");
+	}
+
+	#[test]
+	fn between_keyframes_camera_and_objects_are_interpolated() {
+		let input = animated_input();
+
+		let frame = input.at_time(0.5);
+		assert_eq!(
+			frame.camera.source,
+			Point::new(1, 0, 5),
+			"camera should be at the midpoint"
+		);
+		assert_eq!(
+			frame.scene[0].position(),
+			Point::new(0, 1, 0),
+			"sphere should be at the midpoint"
+		);
+	}
+
+	#[test]
+	fn after_last_keyframe_state_is_kept() {
+		let input = animated_input();
+		let frame = input.at_time(3.0);
+		assert_eq!(
+			frame.camera.source,
+			Point::new(4, 0, 5),
+			"camera should stay at the last keyframe"
+		);
+		assert_eq!(
+			input.animation.unwrap().frame_count(),
+			24,
+			"animation should have 24 frames"
+		);
+	}
+}
@@ -0,0 +1,237 @@
+use std::fs::{self, File};
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::core::error::RaytracerError;
+
+/// The maximum amount of nested `$include` directives.
+const MAX_INCLUDE_DEPTH: usize = 4;
+
+/// Replaces every `{"$include": "path"}` element of the `scene` array by the objects of the
+/// included file. Relative paths are resolved against `base_dir`.
+///
+/// An included file is either an array of objects, or an input whose `"scene"` array is used.
+/// Includes may be nested up to [`MAX_INCLUDE_DEPTH`] levels. If `origin` is specified, it is
+/// the file containing the `scene`, and including it again is reported as a circular include.
+pub(super) fn resolve_includes(
+	scene: &mut Value,
+	base_dir: &Path,
+	origin: Option<&Path>,
+) -> Result<(), RaytracerError> {
+	let Value::Array(objects) = scene else {
+		return Ok(());
+	};
+	let mut chain = Vec::from_iter(origin.map(Path::to_path_buf));
+	*objects = resolve(std::mem::take(objects), base_dir, &mut chain, 0)?;
+	Ok(())
+}
+
+/// Returns an error if any element of the `scene` array is an `{"$include": "path"}` element,
+/// which can't be resolved without the path of the file containing the `scene`.
+pub(super) fn reject_includes(scene: &Value) -> Result<(), String> {
+	let Value::Array(objects) = scene else {
+		return Ok(());
+	};
+	if objects
+		.iter()
+		.any(|object| object.get("$include").is_some())
+	{
+		return Err(String::from(
+			"`$include` is not supported without a base path",
+		));
+	}
+	Ok(())
+}
+
+/// Resolves the includes among the `objects` of a scene, which is nested `depth` levels deep.
+/// The `chain` contains the files that are currently being included (the visited paths).
+fn resolve(
+	objects: Vec<Value>,
+	base_dir: &Path,
+	chain: &mut Vec<PathBuf>,
+	depth: usize,
+) -> Result<Vec<Value>, RaytracerError> {
+	let mut resolved = Vec::with_capacity(objects.len());
+	for object in objects {
+		match object.get("$include").and_then(Value::as_str) {
+			Some(path) => {
+				let included = read_included(&base_dir.join(path), chain, depth + 1)?;
+				resolved.extend(included);
+			}
+			None => resolved.push(object),
+		}
+	}
+	Ok(resolved)
+}
+
+/// Reads the objects of the included file at `path`, resolving its own includes.
+fn read_included(
+	path: &Path,
+	chain: &mut Vec<PathBuf>,
+	depth: usize,
+) -> Result<Vec<Value>, RaytracerError> {
+	let with_path = |e: io::Error| io::Error::new(e.kind(), format!("{}: {}", path.display(), e));
+	let path = fs::canonicalize(path).map_err(with_path)?;
+	if depth > MAX_INCLUDE_DEPTH {
+		return Err(RaytracerError::ParseError(format!(
+			"includes are nested deeper than {} levels at {}",
+			MAX_INCLUDE_DEPTH,
+			path.display()
+		)));
+	}
+	if chain.contains(&path) {
+		return Err(RaytracerError::ParseError(format!(
+			"circular include of {}",
+			path.display()
+		)));
+	}
+
+	let file = File::open(&path).map_err(with_path)?;
+	let scene = match serde_json::from_reader::<_, Value>(BufReader::new(file))? {
+		Value::Object(mut fields) => fields.remove("scene").unwrap_or(Value::Null),
+		value => value,
+	};
+	let Value::Array(objects) = scene else {
+		return Err(RaytracerError::ParseError(format!(
+			"included file {} contains no objects",
+			path.display()
+		)));
+	};
+
+	let base_dir = path.parent().unwrap_or(Path::new("")).to_path_buf();
+	chain.push(path);
+	let objects = resolve(objects, &base_dir, chain, depth)?;
+	chain.pop();
+	Ok(objects)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs;
+	use std::path::PathBuf;
+
+	use crate::core::error::RaytracerError;
+	use crate::core::input::RaytracerInput;
+
+	const CAMERA: &str = r#""camera": { "fov": 45, "source": [0, 0, 0], "target": [0, 0, -1], "aperture": 0, "focusDistance": 1 }"#;
+
+	/// Creates an empty directory in the temporary directory, unique to the specified test.
+	fn temp_dir(test: &str) -> PathBuf {
+		let dir = std::env::temp_dir().join(format!("raytracer-{}-{}", test, std::process::id()));
+		fs::create_dir_all(&dir).unwrap();
+		dir
+	}
+
+	/// Returns the JSON of a matte sphere at the specified x coordinate.
+	fn sphere(x: i32) -> String {
+		format!(
+			r#"{{ "type": "sphere", "center": [{}, 0, 0], "radius": 1, "material": {{ "type": "matte", "color": [1, 1, 1] }} }}"#,
+			x
+		)
+	}
+
+	#[test]
+	fn included_objects_are_inserted_into_scene() {
+		let dir = temp_dir("include");
+		fs::create_dir_all(dir.join("parts")).unwrap();
+
+		// The main file includes a subscene, which itself includes another file next to it:
+		let main = format!(
+			r#"{{ {}, "scene": [{}, {{ "$include": "parts/sub.json" }}] }}"#,
+			CAMERA,
+			sphere(0)
+		);
+		let sub = format!(
+			r#"{{ {}, "scene": [{}, {{ "$include": "nested.json" }}] }}"#,
+			CAMERA,
+			sphere(1)
+		);
+		let nested = format!("[{}, {}]", sphere(2), sphere(3));
+		fs::write(dir.join("main.json"), main).unwrap();
+		fs::write(dir.join("parts/sub.json"), sub).unwrap();
+		fs::write(dir.join("parts/nested.json"), nested).unwrap();
+
+		let result = RaytracerInput::try_from_file_with_includes(&dir.join("main.json"));
+		fs::remove_dir_all(&dir).ok();
+
+		assert!(
+			result.is_ok(),
+			"input should be parsed, but got {:?}",
+			result.err()
+		);
+		let positions = result
+			.unwrap()
+			.scene
+			.iter()
+			.map(|object| object.position().0)
+			.collect::<Vec<_>>();
+		assert_eq!(
+			positions,
+			vec![0.0, 1.0, 2.0, 3.0],
+			"scene should contain the objects of all files in order"
+		);
+	}
+
+	#[test]
+	fn if_input_has_no_path_then_includes_are_rejected() {
+		// This input is not read from a file, so the include can't be resolved:
+		let input = format!(
+			r#"{{ {}, "scene": [{}, {{ "$include": "sub.json" }}] }}"#,
+			CAMERA,
+			sphere(0)
+		);
+
+		let result = RaytracerInput::try_from(input.as_str());
+		assert!(
+			result
+				.as_ref()
+				.is_err_and(|e| e.contains("not supported without a base path")),
+			"include without a base path should be an error, but got {:?}",
+			result
+		);
+	}
+
+	#[test]
+	fn if_includes_are_circular_then_error() {
+		let dir = temp_dir("include-cycle");
+
+		// These files include each other:
+		let a = format!(r#"{{ {}, "scene": [{{ "$include": "b.json" }}] }}"#, CAMERA);
+		let b = r#"[{ "$include": "a.json" }]"#;
+		fs::write(dir.join("a.json"), a).unwrap();
+		fs::write(dir.join("b.json"), b).unwrap();
+
+		let result = RaytracerInput::try_from_file_with_includes(&dir.join("a.json"));
+		fs::remove_dir_all(&dir).ok();
+
+		assert!(
+			matches!(result, Err(RaytracerError::ParseError(_))),
+			"circular include should be an error, but got {:?}",
+			result
+		);
+	}
+
+	#[test]
+	fn if_includes_nested_too_deep_then_error() {
+		let dir = temp_dir("include-depth");
+
+		// Each file includes the next one, five levels deep:
+		for level in 0..5 {
+			let json = format!(r#"[{{ "$include": "{}.json" }}]"#, level + 1);
+			fs::write(dir.join(format!("{}.json", level)), json).unwrap();
+		}
+		fs::write(dir.join("5.json"), format!("[{}]", sphere(0))).unwrap();
+		let main = format!(r#"{{ {}, "scene": [{{ "$include": "0.json" }}] }}"#, CAMERA);
+		fs::write(dir.join("main.json"), main).unwrap();
+
+		let result = RaytracerInput::try_from_file_with_includes(&dir.join("main.json"));
+		fs::remove_dir_all(&dir).ok();
+
+		assert!(
+			result.is_err(),
+			"deeply nested includes should be an error, but weren't"
+		);
+	}
+}
@@ -1,34 +1,210 @@
+mod animation;
+mod include;
+mod validate;
+
+use std::collections::HashMap;
 use std::fs;
-use std::io::BufReader;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use super::error::RaytracerError;
+use super::objects::{Material, Object};
+use super::types::{Point, Vec3};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-use super::objects::Object;
-use super::types::Point;
-use serde::Deserialize;
+pub use animation::{AnimationInput, Keyframe, SceneOverride};
+pub use validate::{
+	NoNanValues, NonDegenerateLookVector, PositiveRadius, SceneValidator, Severity, ValidColors,
+	ValidFov, ValidationError, ValidationReport, ValidationRule,
+};
 
-#[derive(Debug, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 /// A type that represents input to the raytracer.
 pub struct RaytracerInput {
-	/// Camera settings.
+	/// Camera settings. If not specified, the defaults of [`CameraInput`] are used.
+	#[serde(default)]
 	pub camera: CameraInput,
+	/// Named material definitions, which can be referenced from objects in the scene (and from
+	/// within other materials) with `{"$ref": "name"}` instead of an inline material.
+	#[serde(default)]
+	pub materials: HashMap<String, Material>,
 	/// Objects in the scene.
 	pub scene: Vec<Object>,
+	/// Keyframes for rendering an animated sequence of frames, see [`RaytracerInput::at_time`].
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub animation: Option<AnimationInput>,
 }
 impl TryFrom<&str> for RaytracerInput {
 	type Error = String;
 	fn try_from(value: &str) -> Result<Self, Self::Error> {
-		serde_json::from_str::<Self>(value).map_err(|e| e.to_string())
+		let json = serde_json::from_str::<Value>(value).map_err(|e| e.to_string())?;
+		Self::try_from(json)
 	}
 }
 impl TryFrom<fs::File> for RaytracerInput {
 	type Error = String;
 	fn try_from(value: fs::File) -> Result<Self, Self::Error> {
-		let reader = BufReader::new(value);
-		serde_json::from_reader(reader).map_err(|e| e.to_string())
+		Self::from_reader(BufReader::new(value))
+	}
+}
+impl RaytracerInput {
+	/// Reads the input as JSON from the specified `reader`, e.g. the standard input.
+	pub fn from_reader<R: Read>(reader: R) -> Result<Self, String> {
+		let json = serde_json::from_reader::<_, Value>(reader).map_err(|e| e.to_string())?;
+		Self::try_from(json)
 	}
+	/// Reads the input from the JSON file at `path`.
+	///
+	/// Elements of the scene written as `{"$include": "path/to/subscene.json"}` are replaced by
+	/// the objects of the included file, whose path is relative to the directory of `path`.
+	/// Includes can be nested up to 4 levels deep; circular includes are reported as errors.
+	pub fn try_from_file_with_includes(path: &Path) -> Result<Self, RaytracerError> {
+		let file = fs::File::open(path)?;
+		let mut json = serde_json::from_reader::<_, Value>(BufReader::new(file))?;
+		if let Some(scene) = json.get_mut("scene") {
+			let base_dir = path.parent().unwrap_or(Path::new(""));
+			include::resolve_includes(scene, base_dir, Some(&fs::canonicalize(path)?))?;
+		}
+		Self::try_from(json).map_err(RaytracerError::ParseError)
+	}
+	/// Reads the inputs from the JSON files at `paths` (see [`RaytracerInput::try_from_file_with_includes`]),
+	/// and merges them from left to right (see [`RaytracerInput::merge`]).
+	///
+	/// This way, the camera and the objects of a scene can be kept in separate files.
+	/// Returns an error if no paths are specified, or if any file can't be read.
+	pub fn from_files(paths: &[&Path]) -> Result<Self, RaytracerError> {
+		let mut inputs = paths
+			.iter()
+			.map(|path| Self::try_from_file_with_includes(path));
+		let first = inputs.next().ok_or_else(|| {
+			RaytracerError::ParseError(String::from("no input files specified"))
+		})??;
+		inputs.try_fold(first, |base, overlay| Ok(Self::merge(base, overlay?)))
+	}
+	/// Combines two inputs into one.
+	///
+	/// The camera of `overlay` is used, unless it has the default settings (see [`CameraInput`]),
+	/// in which case the camera of `base` is kept. The objects of both scenes are concatenated,
+	/// and named materials and the animation of `overlay` take precedence over those of `base`.
+	pub fn merge(base: RaytracerInput, overlay: RaytracerInput) -> RaytracerInput {
+		let camera = if overlay.camera == CameraInput::default() {
+			base.camera
+		} else {
+			overlay.camera
+		};
+		let mut materials = base.materials;
+		materials.extend(overlay.materials);
+		let mut scene = base.scene;
+		scene.extend(overlay.scene);
+		RaytracerInput {
+			camera,
+			materials,
+			scene,
+			animation: overlay.animation.or(base.animation),
+		}
+	}
+}
+impl TryFrom<Value> for RaytracerInput {
+	type Error = String;
+	/// Parses the input from JSON.
+	/// Returns an error if a material has a color with negative, infinite or NaN channels.
+	///
+	/// Since there is no file to resolve them against, included files
+	/// (see [`RaytracerInput::try_from_file_with_includes`]) are reported as errors.
+	fn try_from(mut value: Value) -> Result<Self, Self::Error> {
+		let materials = value.get("materials").cloned().unwrap_or(Value::Null);
+		if let Some(Value::Object(definitions)) = value.get_mut("materials") {
+			for (name, definition) in definitions.iter_mut() {
+				resolve_material(definition, &materials, &mut vec![name.clone()])?;
+			}
+		}
+		if let Some(scene) = value.get_mut("scene") {
+			include::reject_includes(scene)?;
+			resolve_material_refs(scene, &materials)?;
+		}
+		let input = serde_json::from_value::<Self>(value).map_err(|e| e.to_string())?;
+		// Invalid colors can't be rendered, so they are rejected here instead of while rendering
+		let report = SceneValidator::new().with(ValidColors).run(&input);
+		match report.errors.first() {
+			Some(error) => Err(error.to_string()),
+			None => Ok(input),
+		}
+	}
+}
+
+/// A material as specified in the input: either defined inline, or referencing
+/// a named material from the top-level `"materials"` object.
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum MaterialRef {
+	/// A reference to a named material, written as `{"$ref": "name"}`.
+	Ref {
+		#[serde(rename = "$ref")]
+		name: String,
+	},
+	/// A material defined inline.
+	Inline(Material),
 }
 
-#[derive(Debug, PartialEq, Deserialize)]
+/// The fields of a material that contain other materials (of [`Material::Blend`] and [`Material::TwoSided`]).
+const NESTED_MATERIAL_FIELDS: [&str; 4] = ["a", "b", "front", "back"];
+
+/// Replaces every material reference within `value` by the referenced definition in `materials`.
+/// Returns an error if a reference points to a material that is not defined.
+fn resolve_material_refs(value: &mut Value, materials: &Value) -> Result<(), String> {
+	match value {
+		Value::Array(values) => {
+			for value in values {
+				resolve_material_refs(value, materials)?;
+			}
+		}
+		Value::Object(fields) => {
+			for (key, field) in fields.iter_mut() {
+				match key.as_str() {
+					"material" => resolve_material(field, materials, &mut Vec::new())?,
+					_ => resolve_material_refs(field, materials)?,
+				}
+			}
+		}
+		_ => {}
+	}
+	Ok(())
+}
+
+/// Replaces the material `value` by the referenced definition in `materials`, if it is a reference,
+/// and resolves the references nested within it.
+///
+/// The `chain` contains the names of the references that are currently being resolved.
+/// Returns an error if a reference points to a material that is not defined, or to itself.
+fn resolve_material(
+	value: &mut Value,
+	materials: &Value,
+	chain: &mut Vec<String>,
+) -> Result<(), String> {
+	if let Ok(MaterialRef::Ref { name }) = MaterialRef::deserialize(&*value) {
+		if chain.contains(&name) {
+			return Err(format!("circular material reference '{}'", name));
+		}
+		let definition = materials
+			.get(&name)
+			.ok_or_else(|| format!("unknown material reference '{}'", name))?;
+		*value = definition.clone();
+		chain.push(name);
+		resolve_material(value, materials, chain)?;
+		chain.pop();
+	} else if let Value::Object(fields) = value {
+		for key in NESTED_MATERIAL_FIELDS {
+			if let Some(field) = fields.get_mut(key) {
+				resolve_material(field, materials, chain)?;
+			}
+		}
+	}
+	Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 /// A type that represents a subset of camera settings settable via input.
 pub struct CameraInput {
@@ -42,14 +218,42 @@ pub struct CameraInput {
 	pub aperture: f64,
 	/// Distance from camera center to the plane where the objects are in focus.
 	pub focus_distance: f64,
+	/// The vector pointing from the camera upwards.
+	/// If not specified, `[0, 1, 0]` is assumed.
+	#[serde(default)]
+	pub view_up: Option<Vec3>,
+	/// Samples per pixel.
+	#[serde(default)]
+	pub samples: Option<u32>,
+	/// Max. amount of bounces per ray.
+	#[serde(default)]
+	pub bounces: Option<u32>,
+}
+impl Default for CameraInput {
+	/// Creates camera settings looking from the origin along the negative z-axis,
+	/// with a vertical field of view of 45 degrees and without defocus blur.
+	fn default() -> Self {
+		Self {
+			fov: 45.0,
+			source: Point::origin(),
+			target: Point::new(0, 0, -1),
+			aperture: 0.0,
+			focus_distance: 1.0,
+			view_up: None,
+			samples: None,
+			bounces: None,
+		}
+	}
 }
 
 #[cfg(test)]
 mod tests {
 
+	use std::collections::HashMap;
+
 	use crate::core::input::CameraInput;
-	use crate::objects::{Material, Sphere, ToObject};
-	use crate::types::{Color, Point};
+	use crate::objects::{Group, Material, Object, Sphere, ToObject};
+	use crate::types::{Color, Point, Transform, Vec3};
 
 	use super::RaytracerInput;
 
@@ -62,7 +266,10 @@ mod tests {
 				"source": [0.0, 0.0, -1.0],
 				"target": [0.0, 0.0, 0.0],
 				"aperture": 0.0,
-				"focusDistance": 0.0
+				"focusDistance": 0.0,
+				"viewUp": [1.0, 0.0, 0.0],
+				"samples": 50,
+				"bounces": 20
 			},
 			"scene": [
 				{
@@ -85,7 +292,11 @@ mod tests {
 				target: Point::origin(),
 				aperture: 0.0,
 				focus_distance: 0.0,
+				view_up: Some(Vec3::new(1, 0, 0)),
+				samples: Some(50),
+				bounces: Some(20),
 			},
+			materials: HashMap::new(),
 			scene: vec![
 				Sphere::new(
 					Point::origin(),
@@ -97,6 +308,7 @@ mod tests {
 				)
 				.wrap(),
 			],
+			animation: None,
 		};
 
 		// Parsing should not result in an error, and the values should match:
@@ -130,4 +342,359 @@ mod tests {
 		let parsed = RaytracerInput::try_from(input);
 		assert!(parsed.is_err(), "parsing should fail, but was successful")
 	}
+
+	#[test]
+	fn if_material_referenced_then_spheres_should_share_definition() {
+		// This is the input string, with two spheres referencing the same material:
+		let input = r#"{
+			"camera": {
+				"fov": 27.0,
+				"source": [0.0, 0.0, -1.0],
+				"target": [0.0, 0.0, 0.0],
+				"aperture": 0.0,
+				"focusDistance": 0.0
+			},
+			"materials": {
+				"red_metal": {
+					"type": "metal",
+					"color": [1.0, 0.0, 0.0],
+					"fuzz": 0.1
+				}
+			},
+			"scene": [
+				{
+					"type": "sphere",
+					"center": [-1.0, 0.0, 0.0],
+					"radius": 0.5,
+					"material": { "$ref": "red_metal" }
+				},
+				{
+					"type": "sphere",
+					"center": [1.0, 0.0, 0.0],
+					"radius": 0.5,
+					"material": { "$ref": "red_metal" }
+				}
+			]
+		}"#;
+		// Both spheres should be parsed with this material:
+		let material = Material::Metal {
+			color: Color::new(1, 0, 0),
+			fuzz: 0.1,
+		};
+
+		let result = RaytracerInput::try_from(input);
+		assert!(
+			result.is_ok(),
+			"input should be parsed, but error occurred: {:?}",
+			result.err()
+		);
+
+		let parsed = result.unwrap();
+		let expected = vec![
+			Sphere::new(Point::new(-1, 0, 0), 0.5, material.clone()).wrap(),
+			Sphere::new(Point::new(1, 0, 0), 0.5, material.clone()).wrap(),
+		];
+		assert_eq!(
+			expected, parsed.scene,
+			"both spheres should have the referenced material"
+		);
+		assert_eq!(
+			Some(&material),
+			parsed.materials.get("red_metal"),
+			"named material should be kept in the input"
+		);
+	}
+
+	#[test]
+	fn if_material_reference_unknown_then_parsing_should_error() {
+		// This is the input string, referencing a material that is not defined:
+		let input = r#"{
+			"camera": {
+				"fov": 27.0,
+				"source": [0.0, 0.0, -1.0],
+				"target": [0.0, 0.0, 0.0],
+				"aperture": 0.0,
+				"focusDistance": 0.0
+			},
+			"scene": [
+				{
+					"type": "sphere",
+					"center": [0.0, 0.0, 0.0],
+					"radius": 0.5,
+					"material": { "$ref": "missing" }
+				}
+			]
+		}"#;
+
+		let parsed = RaytracerInput::try_from(input);
+		assert!(parsed.is_err(), "parsing should fail, but was successful")
+	}
+
+	#[test]
+	fn if_material_reference_nested_then_resolved() {
+		// This is the input string, whose blend references two other named materials:
+		let input = r#"{
+			"camera": {
+				"fov": 27.0,
+				"source": [0.0, 0.0, -1.0],
+				"target": [0.0, 0.0, 0.0],
+				"aperture": 0.0,
+				"focusDistance": 0.0
+			},
+			"materials": {
+				"red": { "type": "matte", "color": [1.0, 0.0, 0.0] },
+				"mirror": { "type": "metal", "color": [1.0, 1.0, 1.0], "fuzz": 0.0 },
+				"mix": { "type": "blend", "a": { "$ref": "red" }, "b": { "$ref": "mirror" }, "weight": 0.5 }
+			},
+			"scene": [
+				{
+					"type": "sphere",
+					"center": [0.0, 0.0, 0.0],
+					"radius": 0.5,
+					"material": {
+						"type": "twoSided",
+						"front": { "$ref": "mix" },
+						"back": { "type": "blend", "a": { "$ref": "red" }, "b": { "type": "absorbant" }, "weight": 0.25 }
+					}
+				}
+			]
+		}"#;
+		let red = Material::Matte {
+			color: Color::new(1, 0, 0),
+		};
+		let mirror = Material::Metal {
+			color: Color::white(),
+			fuzz: 0.0,
+		};
+		let mix = Material::mix(red.clone(), mirror, 0.5);
+
+		let result = RaytracerInput::try_from(input);
+		assert!(
+			result.is_ok(),
+			"input should be parsed, but error occurred: {:?}",
+			result.err()
+		);
+		let parsed = result.unwrap();
+		let expected =
+			Material::two_sided(mix.clone(), Material::mix(red, Material::Absorbant, 0.25));
+		assert_eq!(
+			vec![Sphere::new(Point::origin(), 0.5, expected).wrap()],
+			parsed.scene,
+			"sphere should have the nested materials resolved"
+		);
+		assert_eq!(
+			Some(&mix),
+			parsed.materials.get("mix"),
+			"named material should have its references resolved"
+		);
+	}
+
+	#[test]
+	fn if_material_references_circular_then_parsing_should_error() {
+		// This is the input string, whose named materials reference each other:
+		let input = r#"{
+			"camera": {
+				"fov": 27.0,
+				"source": [0.0, 0.0, -1.0],
+				"target": [0.0, 0.0, 0.0],
+				"aperture": 0.0,
+				"focusDistance": 0.0
+			},
+			"materials": {
+				"a": { "type": "blend", "a": { "$ref": "b" }, "b": { "type": "absorbant" }, "weight": 0.5 },
+				"b": { "type": "twoSided", "front": { "$ref": "a" }, "back": { "type": "absorbant" } }
+			},
+			"scene": [
+				{
+					"type": "sphere",
+					"center": [0.0, 0.0, 0.0],
+					"radius": 0.5,
+					"material": { "$ref": "a" }
+				}
+			]
+		}"#;
+
+		let parsed = RaytracerInput::try_from(input);
+		assert!(
+			parsed.as_ref().is_err_and(|e| e.contains("circular")),
+			"parsing should fail with a circular reference, but got {:?}",
+			parsed
+		);
+	}
+
+	#[test]
+	fn if_material_color_negative_then_parsing_should_error() {
+		// This is the input string, with a negative color channel:
+		let input = r#"{
+			"camera": {
+				"fov": 27.0,
+				"source": [0.0, 0.0, -1.0],
+				"target": [0.0, 0.0, 0.0],
+				"aperture": 0.0,
+				"focusDistance": 0.0
+			},
+			"scene": [
+				{
+					"type": "sphere",
+					"center": [0.0, 0.0, 0.0],
+					"radius": 0.5,
+					"material": { "type": "matte", "color": [0.5, -0.2, 0.1] }
+				}
+			]
+		}"#;
+
+		let parsed = RaytracerInput::try_from(input);
+		assert!(
+			parsed
+				.as_ref()
+				.is_err_and(|e| e.contains("scene[0].material.color")),
+			"parsing should fail at the invalid color, but got {:?}",
+			parsed
+		);
+	}
+
+	/// Creates an input with the specified materials and scene, and camera settings with every field set.
+	fn input_with(materials: HashMap<String, Material>, scene: Vec<Object>) -> RaytracerInput {
+		RaytracerInput {
+			camera: CameraInput {
+				fov: 35.5,
+				source: Point::new(1.25, -2, 3),
+				target: Point::new(0, 0.5, -1),
+				aperture: 0.75,
+				focus_distance: 4.2,
+				view_up: Some(Vec3::new(0, 1, 0.1)),
+				samples: Some(64),
+				bounces: None,
+			},
+			materials,
+			scene,
+			animation: None,
+		}
+	}
+
+	#[test]
+	fn serialized_input_parses_to_same_value() {
+		// These are materials of every kind (NaN parameters are avoided, as NaN never compares equal):
+		let materials = [
+			Material::Absorbant,
+			Material::Matte {
+				color: Color::new(0.2, 0.4, 0.6),
+			},
+			Material::Metal {
+				color: Color::new(0.9, 0.1, 0.0),
+				fuzz: 0.35,
+			},
+			Material::Dielectric { ridx: 1.5 },
+		];
+		// Every material is used on every kind of object:
+		let cases = materials.iter().map(|material| {
+			let sphere = Sphere::new(Point::new(-1, 0.5, 2), 0.75, material.clone());
+			let transform = Transform {
+				translation: Vec3::new(1, 2, 3),
+				rotation_y: 45.0,
+				scale: 2.0,
+			};
+			let group = Group::new(vec![sphere.clone().wrap()], transform);
+			let named = HashMap::from([(material.name().to_string(), material.clone())]);
+			input_with(named, vec![sphere.wrap(), group.wrap()])
+		});
+
+		for expected in cases {
+			let json = serde_json::to_string(&expected);
+			assert!(
+				json.is_ok(),
+				"input should be serialized, but error occurred: {:?}",
+				json.err()
+			);
+			let json = json.unwrap();
+
+			let result = RaytracerInput::try_from(json.as_str());
+			assert!(
+				result.is_ok(),
+				"serialized input {} should be parsed, but error occurred: {:?}",
+				json,
+				result.err()
+			);
+			assert_eq!(
+				expected,
+				result.unwrap(),
+				"parsed input should match the serialized one"
+			);
+		}
+	}
+
+	#[test]
+	fn if_inputs_merged_then_overlay_camera_kept() {
+		let sphere = Sphere::new(Point::origin(), 1, Material::Absorbant);
+		let base = input_with(HashMap::new(), vec![sphere.clone().wrap()]);
+		let overlay = RaytracerInput {
+			camera: CameraInput {
+				fov: 60.0,
+				..Default::default()
+			},
+			..input_with(HashMap::new(), vec![])
+		};
+
+		let merged = RaytracerInput::merge(base.clone(), overlay.clone());
+		assert_eq!(
+			merged.camera, overlay.camera,
+			"camera of the overlay should be kept"
+		);
+
+		// If the overlay has the default camera, the base camera should be kept instead:
+		let overlay = RaytracerInput {
+			camera: CameraInput::default(),
+			..overlay
+		};
+		let merged = RaytracerInput::merge(base.clone(), overlay);
+		assert_eq!(
+			merged.camera, base.camera,
+			"camera of the base should be kept"
+		);
+	}
+
+	#[test]
+	fn if_files_merged_then_scene_contains_all_objects() {
+		let dir = std::env::temp_dir().join(format!("raytracer-merge-{}", std::process::id()));
+		std::fs::create_dir_all(&dir).unwrap();
+
+		// The camera is in one file, and the objects are spread across two files without a camera:
+		let camera = r#"{ "camera": { "fov": 30, "source": [0, 1, 5], "target": [0, 0, 0], "aperture": 0, "focusDistance": 5 }, "scene": [] }"#;
+		let sphere = |x: i32| {
+			format!(
+				r#"{{ "type": "sphere", "center": [{}, 0, 0], "radius": 1, "material": {{ "type": "absorbant" }} }}"#,
+				x
+			)
+		};
+		let objects = format!(r#"{{ "scene": [{}, {}] }}"#, sphere(0), sphere(1));
+		let more_objects = format!(r#"{{ "scene": [{}] }}"#, sphere(2));
+		std::fs::write(dir.join("camera.json"), camera).unwrap();
+		std::fs::write(dir.join("objects.json"), objects).unwrap();
+		std::fs::write(dir.join("more.json"), more_objects).unwrap();
+
+		let paths = ["camera.json", "objects.json", "more.json"].map(|name| dir.join(name));
+		let result = RaytracerInput::from_files(&paths.each_ref().map(|path| path.as_path()));
+		std::fs::remove_dir_all(&dir).ok();
+
+		assert!(
+			result.is_ok(),
+			"inputs should be parsed, but got {:?}",
+			result.err()
+		);
+		let input = result.unwrap();
+		assert_eq!(
+			input.camera.fov, 30.0,
+			"camera of the first file should be kept"
+		);
+		let positions = input
+			.scene
+			.iter()
+			.map(|object| object.position().0)
+			.collect::<Vec<_>>();
+		assert_eq!(
+			positions,
+			vec![0.0, 1.0, 2.0],
+			"scene should contain the objects of all files in order"
+		);
+	}
 }
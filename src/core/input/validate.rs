@@ -0,0 +1,396 @@
+use std::fmt::Display;
+
+use crate::core::objects::{Material, Object};
+use crate::core::types::{Color, ToVec3, Vec3};
+
+use super::RaytracerInput;
+
+/// How severe a [`ValidationError`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+	/// The input can't be rendered meaningfully.
+	Error,
+	/// The input can be rendered, but likely not as intended.
+	Warning,
+}
+
+/// A problem found in an input by a [`ValidationRule`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationError {
+	/// How severe the problem is.
+	pub severity: Severity,
+	/// Where in the input the problem is, e.g. `camera.fov` or `scene[2].objects[0]`.
+	pub location: String,
+	/// A description of the problem.
+	pub message: String,
+}
+impl ValidationError {
+	/// Creates an error at the specified location.
+	pub fn error(location: impl Into<String>, message: impl Into<String>) -> Self {
+		Self {
+			severity: Severity::Error,
+			location: location.into(),
+			message: message.into(),
+		}
+	}
+	/// Creates a warning at the specified location.
+	pub fn warning(location: impl Into<String>, message: impl Into<String>) -> Self {
+		Self {
+			severity: Severity::Warning,
+			location: location.into(),
+			message: message.into(),
+		}
+	}
+}
+impl Display for ValidationError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}: {}", self.location, self.message)
+	}
+}
+
+/// A check of an input, which reports the problems it finds.
+///
+/// Custom rules can be added to a [`SceneValidator`] alongside the built-in ones.
+pub trait ValidationRule: Send + Sync {
+	/// Checks the `input`, and returns all problems found (or an empty vector, if there are none).
+	fn check(&self, input: &RaytracerInput) -> Vec<ValidationError>;
+}
+
+/// The problems found in an input by a [`SceneValidator`], split by their [`Severity`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationReport {
+	/// The problems with which the input can't be rendered meaningfully.
+	pub errors: Vec<ValidationError>,
+	/// The problems with which the input can be rendered, but likely not as intended.
+	pub warnings: Vec<ValidationError>,
+}
+impl ValidationReport {
+	/// Whether no errors were found. Warnings are allowed.
+	pub fn is_valid(&self) -> bool {
+		self.errors.is_empty()
+	}
+}
+
+/// Checks inputs with a list of rules.
+///
+/// The default validator contains all built-in rules: [`NoNanValues`], [`PositiveRadius`],
+/// [`ValidFov`], [`ValidColors`] and [`NonDegenerateLookVector`].
+pub struct SceneValidator {
+	/// The rules the input is checked with, in order.
+	pub rules: Vec<Box<dyn ValidationRule>>,
+}
+impl Default for SceneValidator {
+	fn default() -> Self {
+		Self::new()
+			.with(NoNanValues)
+			.with(PositiveRadius)
+			.with(ValidFov)
+			.with(ValidColors)
+			.with(NonDegenerateLookVector)
+	}
+}
+impl SceneValidator {
+	/// Creates a validator without any rules, which finds no problems.
+	pub fn new() -> Self {
+		Self { rules: Vec::new() }
+	}
+	/// Adds a rule to this validator, and returns the validator.
+	pub fn with<R: ValidationRule + 'static>(mut self, rule: R) -> Self {
+		self.rules.push(Box::new(rule));
+		self
+	}
+	/// Checks the `input` with every rule, and collects the problems found.
+	pub fn run(&self, input: &RaytracerInput) -> ValidationReport {
+		let (errors, warnings) = self
+			.rules
+			.iter()
+			.flat_map(|rule| rule.check(input))
+			.partition(|error| error.severity == Severity::Error);
+		ValidationReport { errors, warnings }
+	}
+}
+impl std::fmt::Debug for SceneValidator {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("SceneValidator")
+			.field("rules", &self.rules.len())
+			.finish()
+	}
+}
+
+impl RaytracerInput {
+	/// Checks this input with all built-in rules, see [`SceneValidator::default`].
+	pub fn validate(&self) -> ValidationReport {
+		SceneValidator::default().run(self)
+	}
+}
+
+/// Reports values in the camera settings and the scene that are not a number.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoNanValues;
+impl ValidationRule for NoNanValues {
+	fn check(&self, input: &RaytracerInput) -> Vec<ValidationError> {
+		let camera = &input.camera;
+		let mut values = vec![
+			(String::from("camera.fov"), camera.fov),
+			(String::from("camera.aperture"), camera.aperture),
+			(String::from("camera.focusDistance"), camera.focus_distance),
+		];
+		values.extend(coordinates("camera.source", camera.source.to_vec3()));
+		values.extend(coordinates("camera.target", camera.target.to_vec3()));
+		if let Some(view_up) = camera.view_up {
+			values.extend(coordinates("camera.viewUp", view_up));
+		}
+		for (location, object) in objects(input) {
+			let position = format!("{}.position", location);
+			values.extend(coordinates(&position, object.position().to_vec3()));
+			if let Some(radius) = radius(object) {
+				values.push((format!("{}.radius", location), radius));
+			}
+		}
+		values
+			.into_iter()
+			.filter(|(_, value)| value.is_nan())
+			.map(|(location, _)| ValidationError::error(location, "value is not a number"))
+			.collect()
+	}
+}
+
+/// Reports spheres and cylinders whose radius is not positive, which are invisible.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PositiveRadius;
+impl ValidationRule for PositiveRadius {
+	fn check(&self, input: &RaytracerInput) -> Vec<ValidationError> {
+		objects(input)
+			.into_iter()
+			.filter_map(|(location, object)| match radius(object) {
+				Some(radius) if radius <= 0.0 => Some(ValidationError::error(
+					format!("{}.radius", location),
+					format!("radius should be positive, but is {}", radius),
+				)),
+				_ => None,
+			})
+			.collect()
+	}
+}
+
+/// Reports a vertical field of view outside of the open range (0; 180) degrees.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValidFov;
+impl ValidationRule for ValidFov {
+	fn check(&self, input: &RaytracerInput) -> Vec<ValidationError> {
+		let fov = input.camera.fov;
+		if fov <= 0.0 || fov >= 180.0 {
+			vec![ValidationError::error(
+				"camera.fov",
+				format!(
+					"field of view should be between 0 and 180 degrees, but is {}",
+					fov
+				),
+			)]
+		} else {
+			Vec::new()
+		}
+	}
+}
+
+/// Reports material colors with negative or infinite channels as errors, and colors of surfaces
+/// that reflect more light than they receive (channels above 1) as warnings.
+/// Emissive materials may be brighter than 1.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValidColors;
+impl ValidationRule for ValidColors {
+	fn check(&self, input: &RaytracerInput) -> Vec<ValidationError> {
+		let mut named = input.materials.iter().collect::<Vec<_>>();
+		named.sort_by_key(|(name, _)| *name);
+		let materials = named
+			.into_iter()
+			.map(|(name, material)| (format!("materials.{}", name), material))
+			.chain(objects(input).into_iter().filter_map(|(location, object)| {
+				object
+					.material()
+					.map(|material| (format!("{}.material", location), material))
+			}));
+
+		let mut errors = Vec::new();
+		for (location, material) in materials {
+			for (location, material) in nested_materials(location, material) {
+				let (color, may_exceed_one) = match material {
+					Material::Matte { color }
+					| Material::Metal { color, .. }
+					| Material::Isotropic { color }
+					| Material::HenyeyGreenstein { color, .. }
+					| Material::Subsurface { color, .. } => (*color, false),
+					Material::Emissive { color, .. } => (*color, true),
+					_ => continue,
+				};
+				let location = format!("{}.color", location);
+				if !is_finite(color) || color.r() < 0.0 || color.g() < 0.0 || color.b() < 0.0 {
+					errors.push(ValidationError::error(
+						location,
+						format!(
+							"color channels should be finite and non-negative, but are {:?}",
+							color
+						),
+					));
+				} else if !may_exceed_one && (color.r() > 1.0 || color.g() > 1.0 || color.b() > 1.0)
+				{
+					errors.push(ValidationError::warning(
+						location,
+						format!(
+							"color channels above 1 reflect more light than received: {:?}",
+							color
+						),
+					));
+				}
+			}
+		}
+		errors
+	}
+}
+
+/// Reports a camera that looks at its own position, or whose up direction is zero or parallel to
+/// the viewing direction, since no orientation can be derived from them.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NonDegenerateLookVector;
+impl ValidationRule for NonDegenerateLookVector {
+	fn check(&self, input: &RaytracerInput) -> Vec<ValidationError> {
+		let camera = &input.camera;
+		let look = camera.target.to_vec3() - camera.source.to_vec3();
+		if look.norm_sq() == 0.0 {
+			return vec![ValidationError::error(
+				"camera.target",
+				"camera should look at a point other than its own position",
+			)];
+		}
+		let view_up = camera.view_up.unwrap_or(Vec3::new(0, 1, 0));
+		if look.cross(view_up).norm_sq() == 0.0 {
+			return vec![ValidationError::error(
+				"camera.viewUp",
+				"up direction should be non-zero and not parallel to the viewing direction",
+			)];
+		}
+		Vec::new()
+	}
+}
+
+/// Collects all objects of the input's scene with their location, including those within groups.
+fn objects(input: &RaytracerInput) -> Vec<(String, &Object)> {
+	fn collect<'a>(location: String, object: &'a Object, into: &mut Vec<(String, &'a Object)>) {
+		if let Object::Group(group) = object {
+			for (i, child) in group.objects().iter().enumerate() {
+				collect(format!("{}.objects[{}]", location, i), child, into);
+			}
+		}
+		into.push((location, object));
+	}
+	let mut objects = Vec::new();
+	for (i, object) in input.scene.iter().enumerate() {
+		collect(format!("scene[{}]", i), object, &mut objects);
+	}
+	objects
+}
+
+/// Collects the `material` and the materials it is composed of, with their location.
+fn nested_materials(location: String, material: &Material) -> Vec<(String, &Material)> {
+	let mut materials = match material {
+		Material::Blend { a, b, .. } => [
+			nested_materials(format!("{}.a", location), a),
+			nested_materials(format!("{}.b", location), b),
+		]
+		.concat(),
+		Material::TwoSided { front, back } => [
+			nested_materials(format!("{}.front", location), front),
+			nested_materials(format!("{}.back", location), back),
+		]
+		.concat(),
+		_ => Vec::new(),
+	};
+	materials.push((location, material));
+	materials
+}
+
+/// The radius of a round object, or [`None`] for other objects.
+fn radius(object: &Object) -> Option<f64> {
+	match object {
+		Object::Sphere(sphere) => Some(sphere.radius()),
+		Object::MovingSphere(sphere) => Some(sphere.radius()),
+		Object::InfiniteCylinder(cylinder) => Some(cylinder.radius()),
+		Object::ShellSphere(shell) => Some(shell.outer_radius()),
+		Object::Group(_) | Object::Ground(_) | Object::TriangleMesh(_) => None,
+	}
+}
+
+/// Pairs each coordinate of `vector` with its location below `location`.
+fn coordinates(location: &str, vector: Vec3) -> [(String, f64); 3] {
+	[
+		(format!("{}.x", location), vector.x()),
+		(format!("{}.y", location), vector.y()),
+		(format!("{}.z", location), vector.z()),
+	]
+}
+
+/// Whether all channels of the `color` are finite.
+fn is_finite(color: Color) -> bool {
+	color.r().is_finite() && color.g().is_finite() && color.b().is_finite()
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashMap;
+
+	use super::{NoNanValues, SceneValidator, ValidFov, ValidationRule};
+	use crate::core::input::{CameraInput, RaytracerInput};
+	use crate::core::objects::{Material, Sphere, ToObject};
+	use crate::core::types::Point;
+
+	/// Creates an input with the default camera and the specified sphere.
+	fn input_with_sphere(center: Point) -> RaytracerInput {
+		RaytracerInput {
+			camera: CameraInput::default(),
+			materials: HashMap::new(),
+			scene: vec![Sphere::new(center, 1, Material::Absorbant).wrap()],
+			animation: None,
+		}
+	}
+
+	#[test]
+	fn no_nan_values_detects_nan_in_sphere_center() {
+		let valid = input_with_sphere(Point::new(0, 0, -2));
+		assert!(
+			NoNanValues.check(&valid).is_empty(),
+			"valid input should have no problems"
+		);
+
+		let invalid = input_with_sphere(Point::new(0, f64::NAN, -2));
+		let errors = NoNanValues.check(&invalid);
+		assert_eq!(
+			errors
+				.iter()
+				.map(|e| e.location.as_str())
+				.collect::<Vec<_>>(),
+			["scene[0].position.y"],
+			"NaN coordinate of the sphere should be reported"
+		);
+		let report = SceneValidator::default().run(&invalid);
+		assert!(!report.is_valid(), "input with NaN should be invalid");
+	}
+
+	#[test]
+	fn valid_fov_rejects_0_and_180_degrees() {
+		for fov in [0.0, 180.0] {
+			let mut input = input_with_sphere(Point::new(0, 0, -2));
+			input.camera.fov = fov;
+			assert_eq!(
+				ValidFov.check(&input).len(),
+				1,
+				"field of view of {} degrees should be rejected",
+				fov
+			);
+		}
+		let input = input_with_sphere(Point::new(0, 0, -2));
+		assert!(
+			input.validate().is_valid(),
+			"default field of view should be accepted"
+		);
+	}
+}
@@ -1,6 +1,8 @@
 pub mod camera;
+pub mod error;
 pub mod input;
 pub mod objects;
 pub mod output;
+mod random;
 pub mod scene;
 pub mod types;
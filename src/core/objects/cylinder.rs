@@ -0,0 +1,310 @@
+use std::f64::consts::PI;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::objects::{Hit, HitBuilder, Hittable};
+use crate::core::types::{Aabb, Interval, Point, Ray, ToVec3, Transform, Vec3};
+
+use super::{Material, Object, ToObject};
+
+/// An infinitely long cylinder without caps, such as a pipe or a column.
+///
+/// Since it is unbounded, the cylinder has no bounding box, and is not contained in the bounding
+/// box of a scene (see [`crate::scene::Scene::bounding_box`]).
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(try_from = "CylinderData", into = "CylinderData")]
+pub struct InfiniteCylinder {
+	/// A point on the axis of the cylinder.
+	axis: Point,
+	/// The direction of the axis of the cylinder.
+	direction: Vec3,
+	/// The radius of the cylinder.
+	radius: f64,
+	/// The material of the cylinder's surface.
+	material: Material,
+}
+
+/// The representation of a cylinder in the input.
+#[derive(Deserialize, Serialize)]
+struct CylinderData {
+	axis: Point,
+	direction: Vec3,
+	radius: f64,
+	material: Material,
+}
+
+// Constructor
+impl InfiniteCylinder {
+	/// Creates a new infinite cylinder around the axis through the point `axis` in the specified
+	/// `direction`, which is scaled to unit length and must not be zero.
+	/// If `radius` is negative, a radius of 0 is assumed.
+	pub fn new<F: Into<f64>>(axis: Point, direction: Vec3, radius: F, material: Material) -> Self {
+		debug_assert!(
+			!direction.is_near_zero(),
+			"cylinder direction should not be zero, but was {}",
+			direction
+		);
+		Self {
+			axis,
+			direction: direction.unit(),
+			radius: f64::max(0.0, radius.into()),
+			material,
+		}
+	}
+}
+
+impl TryFrom<CylinderData> for InfiniteCylinder {
+	type Error = String;
+	fn try_from(data: CylinderData) -> Result<Self, Self::Error> {
+		if data.direction.is_near_zero() {
+			return Err(format!(
+				"cylinder direction should not be zero, but was {}",
+				data.direction
+			));
+		}
+		if data.radius.is_nan() || data.radius <= 0.0 {
+			return Err(format!(
+				"cylinder radius should be positive, but was {}",
+				data.radius
+			));
+		}
+		Ok(Self::new(
+			data.axis,
+			data.direction,
+			data.radius,
+			data.material,
+		))
+	}
+}
+impl From<InfiniteCylinder> for CylinderData {
+	fn from(cylinder: InfiniteCylinder) -> Self {
+		Self {
+			axis: cylinder.axis,
+			direction: cylinder.direction,
+			radius: cylinder.radius,
+			material: cylinder.material,
+		}
+	}
+}
+
+// Position
+impl InfiniteCylinder {
+	/// A point on the axis of the cylinder.
+	pub fn axis(&self) -> Point {
+		self.axis
+	}
+	/// The (unit) direction of the axis of the cylinder.
+	pub fn direction(&self) -> Vec3 {
+		self.direction.unit()
+	}
+	/// The radius of the cylinder.
+	pub fn radius(&self) -> f64 {
+		self.radius
+	}
+	/// The material of the cylinder.
+	pub fn material(&self) -> &Material {
+		&self.material
+	}
+	/// The area of the surface of the cylinder, which is infinite.
+	pub fn surface_area(&self) -> f64 {
+		f64::INFINITY
+	}
+	/// Returns this cylinder, with its axis passing through the specified point.
+	pub(crate) fn moved_to(self, axis: Point) -> Self {
+		Self { axis, ..self }
+	}
+	/// Returns this cylinder, with its axis transformed and its radius scaled.
+	pub(crate) fn transformed(self, transform: &Transform) -> Self {
+		Self {
+			axis: transform.apply_point(self.axis),
+			direction: transform.apply_vector(self.direction).unit(),
+			radius: self.radius * transform.scale.abs(),
+			..self
+		}
+	}
+}
+
+// Convert to Object
+impl ToObject for InfiniteCylinder {
+	fn wrap(self) -> Object {
+		Object::InfiniteCylinder(self)
+	}
+}
+
+// Intersection with rays
+impl Hittable for InfiniteCylinder {
+	fn hit(&self, ray: Ray, t_range: Interval) -> Option<Hit<'_>> {
+		// A cylinder without radius has no surface (and no normal)
+		if self.radius <= 0.0 {
+			return None;
+		}
+		// Only the components perpendicular to the axis matter, which leaves a circle in 2D
+		let direction = self.direction.unit();
+		let perpendicular = |v: Vec3| v - direction.scale(v.dot(direction));
+		let d = perpendicular(ray.direction);
+		let o = perpendicular(ray.origin.to_vec3() - self.axis);
+
+		// Solve quadratic equation; rays parallel to the axis never cross the surface
+		let a = d.norm_sq();
+		if a < f64::EPSILON {
+			return None;
+		}
+		let h = o.dot(d);
+		let c = o.norm_sq() - self.radius * self.radius;
+		let discr = h * h - a * c;
+		if discr < 0.0 {
+			return None;
+		}
+
+		let discr_sqrt = discr.sqrt();
+		let t1 = (-h - discr_sqrt) / a;
+		let t2 = (-h + discr_sqrt) / a;
+		let t = if t_range.surrounds(t1) {
+			t1
+		} else if t_range.surrounds(t2) {
+			t2
+		} else {
+			return None;
+		};
+
+		let point = ray.at(t);
+		let offset = point.to_vec3() - self.axis;
+		let outward_normal = perpendicular(offset) / self.radius;
+		let (normal, is_front_face) = Hit::determine_front_face(ray, outward_normal);
+
+		// The texture wraps around the cylinder, and repeats every unit along its axis
+		let helper = if direction.x().abs() > 0.9 {
+			Vec3(0.0, 1.0, 0.0)
+		} else {
+			Vec3(1.0, 0.0, 0.0)
+		};
+		let e1 = direction.cross(helper).unit();
+		let e2 = direction.cross(e1);
+		let angle = f64::atan2(outward_normal.dot(e2), outward_normal.dot(e1));
+		let (u, v) = (
+			(angle + PI) / (2.0 * PI),
+			offset.dot(direction).rem_euclid(1.0),
+		);
+		Some(
+			HitBuilder::new()
+				.t(t)
+				.point(point)
+				.normal(normal)
+				.front_face(is_front_face)
+				.material(&self.material)
+				.uv(u, v)
+				.build()
+				.expect("hit should have all values"),
+		)
+	}
+	fn bounding_box(&self) -> Option<Aabb> {
+		None
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::InfiniteCylinder;
+	use crate::core::objects::{Hittable, Material, Object};
+	use crate::core::types::{Interval, Point, Ray, Vec3};
+
+	/// Creates a vertical cylinder of radius 1 around the y-axis.
+	fn vertical_cylinder() -> InfiniteCylinder {
+		InfiniteCylinder::new(Point::origin(), Vec3::new(0, 1, 0), 1, Material::Absorbant)
+	}
+
+	#[test]
+	fn ray_aimed_at_side_hits_with_perpendicular_normal() {
+		let cylinder = vertical_cylinder();
+		// This ray is aimed at the side of the cylinder, from above:
+		let ray = Ray::new(Point::new(0, 3, 5), Vec3::new(0, -1, -2));
+
+		let hit = cylinder.hit(ray, Interval::new(0.001, f64::INFINITY));
+		assert!(hit.is_some(), "ray should hit the cylinder, but didn't");
+		let hit = hit.unwrap();
+		assert_eq!(hit.point, Point::new(0, 1, 1), "ray should hit the front");
+		assert_eq!(
+			hit.normal,
+			Vec3::new(0, 0, 1),
+			"normal should be perpendicular to the axis"
+		);
+	}
+
+	#[test]
+	fn if_ray_parallel_to_axis_then_no_hit() {
+		let cylinder = vertical_cylinder();
+		// These rays run along the axis, outside and inside of the cylinder:
+		for origin in [Point::new(3, 0, 0), Point::new(0.5, 0, 0)] {
+			let ray = Ray::new(origin, Vec3::new(0, 1, 0));
+			let hit = cylinder.hit(ray, Interval::new(0.001, f64::INFINITY));
+			assert!(hit.is_none(), "ray from {} should miss, but hit", origin);
+		}
+	}
+
+	#[test]
+	fn ray_far_along_axis_still_hits() {
+		let cylinder = vertical_cylinder();
+		// This ray crosses the axis a million units above the point on the axis:
+		let ray = Ray::new(Point::new(-5, 1e6, 0), Vec3::new(1, 0, 0));
+
+		let hit = cylinder.hit(ray, Interval::new(0.001, f64::INFINITY));
+		assert!(hit.is_some(), "ray should hit the cylinder, but didn't");
+		assert_eq!(hit.unwrap().t, 4.0, "ray should hit at x = -1");
+	}
+
+	#[test]
+	fn cylinder_is_deserialized_from_json() {
+		let json = r#"{
+			"type": "infiniteCylinder", "axis": [0, 0, 0], "direction": [0, 1, 0], "radius": 1,
+			"material": { "type": "absorbant" }
+		}"#;
+		let parsed = Object::from_json(json);
+		assert!(
+			matches!(&parsed, Ok(Object::InfiniteCylinder(c)) if *c == vertical_cylinder()),
+			"cylinder should be parsed, but got {:?}",
+			parsed
+		);
+	}
+
+	#[test]
+	fn cylinder_direction_from_json_is_normalized() {
+		let json = r#"{
+			"type": "infiniteCylinder", "axis": [0, 0, 0], "direction": [0, 4, 0], "radius": 1,
+			"material": { "type": "absorbant" }
+		}"#;
+		let parsed = Object::from_json(json);
+		assert!(
+			matches!(&parsed, Ok(Object::InfiniteCylinder(c)) if *c == vertical_cylinder()),
+			"cylinder should be parsed with a unit direction, but got {:?}",
+			parsed
+		);
+	}
+
+	#[test]
+	fn if_direction_zero_or_radius_not_positive_then_json_rejected() {
+		for (direction, radius) in [("[0, 0, 0]", "1"), ("[0, 1, 0]", "0"), ("[0, 1, 0]", "-2")] {
+			let json = format!(
+				r#"{{
+					"type": "infiniteCylinder", "axis": [0, 0, 0], "direction": {}, "radius": {},
+					"material": {{ "type": "absorbant" }}
+				}}"#,
+				direction, radius
+			);
+			assert!(
+				Object::from_json(&json).is_err(),
+				"cylinder with direction {} and radius {} should be rejected",
+				direction,
+				radius
+			);
+		}
+	}
+
+	#[test]
+	fn if_radius_zero_then_no_hit() {
+		let cylinder =
+			InfiniteCylinder::new(Point::origin(), Vec3::new(0, 1, 0), 0, Material::Absorbant);
+		let ray = Ray::new(Point::new(-5, 0, 0), Vec3::new(1, 0, 0));
+		let hit = cylinder.hit(ray, Interval::new(0.001, f64::INFINITY));
+		assert!(hit.is_none(), "cylinder without radius should not be hit");
+	}
+}
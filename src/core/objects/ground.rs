@@ -0,0 +1,446 @@
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::objects::{Hit, HitBuilder, Hittable};
+use crate::core::types::{Aabb, Interval, Point, Ray, ToVec3, Transform, Vec3};
+
+use super::{Material, Object, ToObject};
+
+/// The amount of steps along a ray with which the surface is searched by default.
+const DEFAULT_STEPS: u32 = 128;
+/// The amount of bisections with which an intersection is refined, once it has been found.
+const BISECTIONS: u32 = 32;
+/// The amount of layers of noise, each with double the frequency and half the amplitude.
+const OCTAVES: u32 = 4;
+/// The offset used to approximate the slope of the surface.
+const SLOPE_DELTA: f64 = 1e-4;
+/// The margin by which the region searched for the surface extends above and below its extremes,
+/// so that a flat surface (with an amplitude of 0) is crossed within the region.
+const SLAB_MARGIN: f64 = 1e-6;
+
+/// A round patch of procedurally generated terrain.
+///
+/// The terrain is a disk around `center`, parallel to the xz-plane, whose height is displaced by
+/// Perlin noise. The height varies by at most `amplitude` above and below the center, and
+/// `frequency` determines how many hills there are per unit of distance.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(from = "GroundData", into = "GroundData")]
+pub struct Ground {
+	/// The center of the terrain.
+	center: Point,
+	/// The radius of the disk covered by the terrain.
+	radius: f64,
+	/// The maximum displacement of the surface from the center's height.
+	amplitude: f64,
+	/// The frequency of the noise, in hills per unit of distance.
+	frequency: f64,
+	/// The material of the terrain's surface.
+	material: Material,
+	/// The amount of steps along a ray with which the surface is searched.
+	/// More steps are slower, but miss fewer thin peaks.
+	steps: u32,
+}
+
+/// The representation of a terrain in the input.
+#[derive(Deserialize, Serialize)]
+struct GroundData {
+	center: Point,
+	radius: f64,
+	amplitude: f64,
+	frequency: f64,
+	material: Material,
+	#[serde(default = "default_steps")]
+	steps: u32,
+}
+
+// Constructor
+impl Ground {
+	/// Creates a new patch of terrain around the specified center.
+	/// If `radius` or `amplitude` are negative, a value of 0 is assumed.
+	pub fn new<F: Into<f64>>(
+		center: Point,
+		radius: F,
+		amplitude: F,
+		frequency: F,
+		material: Material,
+	) -> Self {
+		Self {
+			center,
+			radius: f64::max(0.0, radius.into()),
+			amplitude: f64::max(0.0, amplitude.into()),
+			frequency: frequency.into(),
+			material,
+			steps: DEFAULT_STEPS,
+		}
+	}
+	/// Returns this terrain, searched for intersections with the specified amount of steps per ray.
+	/// At least one step is taken.
+	pub fn with_steps(self, steps: u32) -> Self {
+		Self {
+			steps: u32::max(1, steps),
+			..self
+		}
+	}
+}
+
+impl From<GroundData> for Ground {
+	fn from(data: GroundData) -> Self {
+		Self::new(
+			data.center,
+			data.radius,
+			data.amplitude,
+			data.frequency,
+			data.material,
+		)
+		.with_steps(data.steps)
+	}
+}
+impl From<Ground> for GroundData {
+	fn from(ground: Ground) -> Self {
+		Self {
+			center: ground.center,
+			radius: ground.radius,
+			amplitude: ground.amplitude,
+			frequency: ground.frequency,
+			material: ground.material,
+			steps: ground.steps,
+		}
+	}
+}
+
+// Properties
+impl Ground {
+	/// The center of the terrain.
+	pub fn center(&self) -> Point {
+		self.center
+	}
+	/// The material of the terrain.
+	pub fn material(&self) -> &Material {
+		&self.material
+	}
+	/// The area of the disk covered by the terrain, which approximates the area of its surface
+	/// (ignoring the slopes of the hills).
+	pub fn surface_area(&self) -> f64 {
+		PI * self.radius * self.radius
+	}
+	/// Calculates the height (y-coordinate) of the surface above the specified x and z coordinates.
+	/// Coordinates outside of the disk are not rejected, and continue the terrain.
+	pub fn height_at(&self, x: f64, z: f64) -> f64 {
+		let (x, z) = (
+			(x - self.center.x()) * self.frequency,
+			(z - self.center.z()) * self.frequency,
+		);
+		self.center.y() + self.amplitude * fractal_noise(x, z)
+	}
+	/// Returns this terrain, centered at the specified point.
+	pub(crate) fn moved_to(self, center: Point) -> Self {
+		Self { center, ..self }
+	}
+	/// Returns this terrain, with its center transformed and its size scaled.
+	/// The terrain stays parallel to the xz-plane, and its hills are not rotated.
+	pub(crate) fn transformed(self, transform: &Transform) -> Self {
+		let scale = transform.scale.abs();
+		Self {
+			center: transform.apply_point(self.center),
+			radius: self.radius * scale,
+			amplitude: self.amplitude * scale,
+			frequency: self.frequency / scale,
+			..self
+		}
+	}
+}
+
+// Convert to Object
+impl ToObject for Ground {
+	fn wrap(self) -> Object {
+		Object::Ground(self)
+	}
+}
+
+// Intersection with rays
+impl Hittable for Ground {
+	/// Searches the surface by marching along the part of the ray above the disk in equal steps,
+	/// and refines the first crossing of the surface by bisection.
+	fn hit(&self, ray: Ray, t_range: Interval) -> Option<Hit<'_>> {
+		let bounds = self.bounds(ray, t_range)?;
+		let above = |t: f64| {
+			let point = ray.at(t);
+			point.y() - self.height_at(point.x(), point.z())
+		};
+
+		let step = (bounds.end - bounds.start) / self.steps as f64;
+		let (mut t_prev, mut f_prev) = (bounds.start, above(bounds.start));
+		let mut crossing = None;
+		for i in 1..=self.steps {
+			let t = bounds.start + step * i as f64;
+			let f = above(t);
+			if f_prev.signum() != f.signum() {
+				crossing = Some((t_prev, f_prev, t));
+				break;
+			}
+			(t_prev, f_prev) = (t, f);
+		}
+
+		let (mut t_lo, f_lo, mut t_hi) = crossing?;
+		for _ in 0..BISECTIONS {
+			let t_mid = (t_lo + t_hi) / 2.0;
+			if above(t_mid).signum() == f_lo.signum() {
+				t_lo = t_mid;
+			} else {
+				t_hi = t_mid;
+			}
+		}
+		let t = (t_lo + t_hi) / 2.0;
+		if !t_range.surrounds(t) {
+			return None;
+		}
+
+		let point = ray.at(t);
+		let (normal, is_front_face) = Hit::determine_front_face(ray, self.normal_at(point));
+		Some(
+			HitBuilder::new()
+				.t(t)
+				.point(point)
+				.normal(normal)
+				.front_face(is_front_face)
+				.material(&self.material)
+				.uv(
+					(point.x() - self.center.x() + self.radius) / (2.0 * self.radius),
+					(point.z() - self.center.z() + self.radius) / (2.0 * self.radius),
+				)
+				.build()
+				.expect("hit should have all values"),
+		)
+	}
+	fn bounding_box(&self) -> Option<Aabb> {
+		let extent = Vec3::new(self.radius, self.amplitude, self.radius);
+		Some(Aabb::from_corners(
+			(self.center.to_vec3() - extent).into(),
+			(self.center.to_vec3() + extent).into(),
+		))
+	}
+}
+
+impl Ground {
+	/// Calculates the range of `t` within `t_range`, in which the ray lies above the disk
+	/// and between the lowest and highest possible height of the surface.
+	/// Returns [`None`] if the ray doesn't pass this region.
+	fn bounds(&self, ray: Ray, t_range: Interval) -> Option<Interval> {
+		// Slab between the lowest and highest height
+		let (origin, dir) = (ray.origin.to_vec3(), ray.direction);
+		let (y_min, y_max) = (
+			self.center.y() - self.amplitude - SLAB_MARGIN,
+			self.center.y() + self.amplitude + SLAB_MARGIN,
+		);
+		let slab = if dir.y() == 0.0 {
+			if origin.y() < y_min || origin.y() > y_max {
+				return None;
+			}
+			Interval::new(f64::NEG_INFINITY, f64::INFINITY)
+		} else {
+			let (t1, t2) = (
+				(y_min - origin.y()) / dir.y(),
+				(y_max - origin.y()) / dir.y(),
+			);
+			Interval::new(f64::min(t1, t2), f64::max(t1, t2))
+		};
+
+		// Infinite cylinder above the disk
+		let (ox, oz) = (origin.x() - self.center.x(), origin.z() - self.center.z());
+		let a = dir.x() * dir.x() + dir.z() * dir.z();
+		let h = ox * dir.x() + oz * dir.z();
+		let c = ox * ox + oz * oz - self.radius * self.radius;
+		let cylinder = if a == 0.0 {
+			if c > 0.0 {
+				return None;
+			}
+			Interval::new(f64::NEG_INFINITY, f64::INFINITY)
+		} else {
+			let discr = h * h - a * c;
+			if discr < 0.0 {
+				return None;
+			}
+			let discr_sqrt = discr.sqrt();
+			Interval::new((-h - discr_sqrt) / a, (-h + discr_sqrt) / a)
+		};
+
+		let start = f64::max(t_range.start, f64::max(slab.start, cylinder.start));
+		let end = f64::min(t_range.end, f64::min(slab.end, cylinder.end));
+		(start < end && end.is_finite()).then(|| Interval::new(start, end))
+	}
+	/// Approximates the upward-facing unit normal of the surface at the specified point.
+	fn normal_at(&self, point: Point) -> Vec3 {
+		let (x, z) = (point.x(), point.z());
+		let dx = self.height_at(x + SLOPE_DELTA, z) - self.height_at(x - SLOPE_DELTA, z);
+		let dz = self.height_at(x, z + SLOPE_DELTA) - self.height_at(x, z - SLOPE_DELTA);
+		Vec3::new(-dx, 2.0 * SLOPE_DELTA, -dz).unit()
+	}
+}
+
+/// The default amount of steps, used when the input doesn't specify it.
+fn default_steps() -> u32 {
+	DEFAULT_STEPS
+}
+
+/// Sums several octaves of Perlin noise at the specified coordinates. The result lies in `[-1, 1]`.
+fn fractal_noise(x: f64, z: f64) -> f64 {
+	let (mut sum, mut weight, mut total) = (0.0, 1.0, 0.0);
+	for octave in 0..OCTAVES {
+		let scale = (1 << octave) as f64;
+		sum += weight * perlin(x * scale, z * scale);
+		total += weight;
+		weight /= 2.0;
+	}
+	sum / total
+}
+
+/// Calculates two-dimensional Perlin noise at the specified coordinates. The result lies in `[-1, 1]`.
+fn perlin(x: f64, z: f64) -> f64 {
+	let (x0, z0) = (x.floor(), z.floor());
+	let (fx, fz) = (x - x0, z - z0);
+	let (ix, iz) = (x0 as i64, z0 as i64);
+
+	// Dot products of the distance vectors with the gradients at the four corners of the cell
+	let corner = |dx: i64, dz: i64| {
+		let (gx, gz) = gradient(ix + dx, iz + dz);
+		gx * (fx - dx as f64) + gz * (fz - dz as f64)
+	};
+	let fade = |t: f64| t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+	let lerp = |a: f64, b: f64, t: f64| a + (b - a) * t;
+
+	let (u, v) = (fade(fx), fade(fz));
+	let bottom = lerp(corner(0, 0), corner(1, 0), u);
+	let top = lerp(corner(0, 1), corner(1, 1), u);
+	// The raw noise lies in [-1/sqrt(2), 1/sqrt(2)]
+	(lerp(bottom, top, v) / FRAC_1_SQRT_2).clamp(-1.0, 1.0)
+}
+
+/// Picks a pseudo-random unit gradient for the lattice point at the specified coordinates.
+fn gradient(x: i64, z: i64) -> (f64, f64) {
+	// SplitMix64 finalizer, to scramble the coordinates
+	let mut hash = (x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ (z as u64).rotate_left(32);
+	hash = (hash ^ (hash >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+	hash = (hash ^ (hash >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+	hash ^= hash >> 31;
+
+	let d = FRAC_1_SQRT_2;
+	match hash % 8 {
+		0 => (1.0, 0.0),
+		1 => (-1.0, 0.0),
+		2 => (0.0, 1.0),
+		3 => (0.0, -1.0),
+		4 => (d, d),
+		5 => (-d, d),
+		6 => (d, -d),
+		_ => (-d, -d),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Ground;
+	use crate::core::objects::{Hittable, Material, Object};
+	use crate::core::types::{Color, Interval, Point, Ray, Vec3};
+
+	/// Creates hilly terrain around the specified center.
+	fn hills(center: Point) -> Ground {
+		Ground::new(
+			center,
+			10.0,
+			2.0,
+			0.5,
+			Material::Matte {
+				color: Color::new(0.3, 0.5, 0.2),
+			},
+		)
+	}
+
+	#[test]
+	fn if_ray_points_down_above_ground_then_always_hits() {
+		let ground = hills(Point::new(1, -3, 2));
+
+		// These rays start high above different points of the disk, and point straight down:
+		for i in 0..50 {
+			let angle = i as f64 * 0.7;
+			let distance = i as f64 / 50.0 * 9.9;
+			let (x, z) = (1.0 + distance * angle.cos(), 2.0 + distance * angle.sin());
+			let ray = Ray::new(Point::new(x, 20, z), Vec3::new(0, -1, 0));
+
+			// The ray should hit the surface at the height of the terrain:
+			let hit = ground.hit(ray, Interval::from(0));
+			assert!(hit.is_some(), "ray at ({}, {}) should hit the ground", x, z);
+			let hit = hit.unwrap();
+			let expected = ground.height_at(x, z);
+			assert!(
+				f64::abs(hit.point.y() - expected) < 1e-6,
+				"ray at ({}, {}) should hit at height {}, but hit at {}",
+				x,
+				z,
+				expected,
+				hit.point.y()
+			);
+			assert!(hit.is_front_face, "ray should hit from above");
+		}
+	}
+
+	#[test]
+	fn if_ground_flat_then_ray_pointing_down_always_hits() {
+		// This terrain has no hills:
+		let ground = Ground::new(Point::new(1, -3, 2), 10.0, 0.0, 0.5, Material::Absorbant);
+
+		// This ray points straight down onto the center of the terrain:
+		let ray = Ray::new(Point::new(1, 20, 2), Vec3::new(0, -1, 0));
+		let hit = ground.hit(ray, Interval::from(0));
+		assert!(
+			hit.is_some(),
+			"ray should hit the flat ground, but returned None"
+		);
+		let hit = hit.unwrap();
+		assert!(
+			f64::abs(hit.point.y() + 3.0) < 1e-6,
+			"ray should hit at height -3, but hit at {}",
+			hit.point.y()
+		);
+	}
+
+	#[test]
+	fn if_ray_outside_disk_then_none() {
+		let ground = hills(Point::origin());
+
+		// This ray points straight down, but next to the disk:
+		let ray = Ray::new(Point::new(11, 20, 0), Vec3::new(0, -1, 0));
+		assert!(
+			ground.hit(ray, Interval::from(0)).is_none(),
+			"ray should miss the ground, but returned Some"
+		);
+	}
+
+	#[test]
+	fn ground_is_parsed_from_json_with_default_steps() {
+		// This fragment doesn't specify the amount of steps:
+		let json = r#"{"type":"ground","center":[0,0,0],"radius":10,"amplitude":2,"frequency":0.5,"material":{"type":"matte","color":[0.3,0.5,0.2]}}"#;
+		let object = Object::from_json(json);
+		assert!(object.is_ok(), "ground should be parsed, but wasn't");
+		assert_eq!(
+			object.unwrap(),
+			Object::Ground(hills(Point::origin())),
+			"ground should have the fields from the fragment"
+		);
+	}
+
+	#[test]
+	fn if_ground_parsed_with_negative_sizes_then_clamped() {
+		// This fragment has a negative radius and amplitude, and no steps:
+		let json = r#"{"type":"ground","center":[0,0,0],"radius":-10,"amplitude":-2,"frequency":0.5,"material":{"type":"absorbant"},"steps":0}"#;
+		let object = Object::from_json(json);
+		assert!(object.is_ok(), "ground should be parsed, but wasn't");
+		let expected =
+			Ground::new(Point::origin(), 0.0, 0.0, 0.5, Material::Absorbant).with_steps(1);
+		assert_eq!(
+			object.unwrap(),
+			Object::Ground(expected),
+			"ground should be clamped like in its constructor"
+		);
+	}
+}
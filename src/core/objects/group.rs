@@ -0,0 +1,250 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::types::{Aabb, Interval, Point, Ray, ToVec3, Transform};
+
+use super::hit::closest_hit;
+use super::{Hit, Hittable, Object, ToObject};
+
+/// An identifier of a group of objects within a scene.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GroupId(pub(crate) usize);
+
+/// A collection of objects that share a transform.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Group {
+	/// The objects in this group, in local coordinates.
+	objects: Vec<Object>,
+	/// The transform from local to world coordinates.
+	#[serde(default)]
+	transform: Transform,
+	/// The identifier of this group, if it was added to a scene with [`crate::scene::Scene::add_group`].
+	#[serde(skip)]
+	id: Option<GroupId>,
+}
+
+// Constructor
+impl Group {
+	/// Creates a new group of objects, which are transformed by the specified transform.
+	pub fn new(objects: Vec<Object>, transform: Transform) -> Self {
+		Self {
+			objects,
+			transform,
+			id: None,
+		}
+	}
+	/// Assigns an identifier to this group.
+	pub(crate) fn with_id(self, id: GroupId) -> Self {
+		Self {
+			id: Some(id),
+			..self
+		}
+	}
+}
+
+// Getters
+impl Group {
+	/// The objects in this group, in local coordinates.
+	pub fn objects(&self) -> &[Object] {
+		&self.objects
+	}
+	/// The transform from local to world coordinates.
+	pub fn transform(&self) -> Transform {
+		self.transform
+	}
+	/// The identifier of this group, if it was added to a scene with [`crate::scene::Scene::add_group`].
+	pub fn id(&self) -> Option<GroupId> {
+		self.id
+	}
+	/// The total area of the surfaces of the objects in this group, in world coordinates.
+	pub fn surface_area(&self) -> f64 {
+		let scale = self.transform.scale;
+		let area = self.objects.iter().map(Object::surface_area).sum::<f64>();
+		area * scale * scale
+	}
+	/// Returns this group, with its transform translating to the specified position.
+	pub(crate) fn moved_to(self, position: Point) -> Self {
+		let transform = Transform {
+			translation: position.to_vec3(),
+			..self.transform
+		};
+		Self { transform, ..self }
+	}
+	/// Returns this group, with the specified transform applied after its own.
+	pub(crate) fn transformed(self, transform: &Transform) -> Self {
+		Self {
+			transform: self.transform.then(transform),
+			..self
+		}
+	}
+}
+
+// Convert to Object
+impl ToObject for Group {
+	fn wrap(self) -> Object {
+		Object::Group(self)
+	}
+}
+
+// Intersection with rays
+impl Hittable for Group {
+	fn hit(&self, ray: Ray, t_range: Interval) -> Option<Hit<'_>> {
+		// Intersect in local coordinates. The direction is not normalized,
+		// so that the parameter `t` is the same in both coordinate systems.
+		let local_ray = Ray {
+			origin: self.transform.inverse_point(ray.origin),
+			direction: self.transform.inverse_vector(ray.direction),
+			..ray
+		};
+		let hit = closest_hit(&self.objects, local_ray, t_range)?;
+		Some(hit.with_point_normal(
+			ray,
+			self.transform.apply_point(hit.point),
+			self.transform.apply_normal(hit.outward_normal()),
+		))
+	}
+	fn bounding_box(&self) -> Option<Aabb> {
+		self.objects
+			.iter()
+			.filter_map(|obj| obj.bounding_box())
+			.reduce(Aabb::merge)
+			.map(|aabb| aabb.transformed(&self.transform))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Group;
+	use crate::core::objects::{Hittable, Material, Object, Sphere, ToObject};
+	use crate::core::types::{Interval, Point, Ray, Transform, Vec3};
+
+	/// Checks whether two points are approximately equal.
+	fn point_approx_eq(a: Point, b: Point) -> bool {
+		let eq = |a: f64, b: f64| f64::abs(a - b) < 1e-10;
+		eq(a.x(), b.x()) && eq(a.y(), b.y()) && eq(a.z(), b.z())
+	}
+
+	/// A group of two unit spheres on the x-axis, which is moved up by 5 units.
+	fn translated_group() -> Group {
+		let left = Sphere::new(Point::new(-2, 0, 0), 1, Material::Absorbant);
+		let right = Sphere::new(Point::new(2, 0, 0), 1, Material::Absorbant);
+		let transform = Transform::translation(Vec3::new(0, 5, 0));
+		Group::new(vec![left.wrap(), right.wrap()], transform)
+	}
+
+	#[test]
+	fn if_group_translated_then_hit_at_translated_position() {
+		let group = translated_group();
+		// This ray shoots downwards onto the right sphere's translated position:
+		let ray = Ray::new(Point::new(2, 10, 0), Vec3::new(0, -1, 0));
+
+		// The ray should hit the top of the right sphere, moved up by 5:
+		let hit = group.hit(ray, Interval::from(0));
+		assert!(hit.is_some(), "ray should hit the group, but returned None");
+		let hit = hit.unwrap();
+		assert!(
+			point_approx_eq(hit.point, Point::new(2, 6, 0)),
+			"ray should hit at (2, 6, 0), but hit {}",
+			hit.point
+		);
+		assert_eq!(hit.normal, Vec3::new(0, 1, 0), "normal should point up");
+	}
+
+	#[test]
+	fn if_group_translated_then_no_hit_at_original_position() {
+		let group = translated_group();
+		// This ray shoots horizontally through the right sphere's original position:
+		let ray = Ray::new(Point::new(10, 0, 0), Vec3::new(-1, 0, 0));
+
+		let hit = group.hit(ray, Interval::from(0));
+		assert!(
+			hit.is_none(),
+			"ray should miss the group, but returned Some"
+		);
+	}
+
+	#[test]
+	fn group_bounding_box_contains_all_children() {
+		let group = translated_group();
+
+		let aabb = group.bounding_box();
+		assert!(aabb.is_some(), "group should have a bounding box");
+		let aabb = aabb.unwrap();
+		assert_eq!((aabb.x.start, aabb.x.end), (-3.0, 3.0), "x extent is wrong");
+		assert_eq!((aabb.y.start, aabb.y.end), (4.0, 6.0), "y extent is wrong");
+		assert_eq!((aabb.z.start, aabb.z.end), (-1.0, 1.0), "z extent is wrong");
+	}
+
+	#[test]
+	fn group_is_parsed_from_json() {
+		// This is the input string for a group with one sphere:
+		let input = r#"{
+			"type": "group",
+			"transform": { "translation": [0, 5, 0] },
+			"objects": [
+				{
+					"type": "sphere",
+					"center": [2, 0, 0],
+					"radius": 1,
+					"material": { "type": "absorbant" }
+				}
+			]
+		}"#;
+
+		let result = serde_json::from_str::<Object>(input);
+		assert!(
+			result.is_ok(),
+			"input should be parsed, but error occurred: {:?}",
+			result.err()
+		);
+		let sphere = Sphere::new(Point::new(2, 0, 0), 1, Material::Absorbant);
+		let expected = Group::new(
+			vec![sphere.wrap()],
+			Transform::translation(Vec3::new(0, 5, 0)),
+		);
+		assert_eq!(result.unwrap(), expected.wrap(), "group should match");
+	}
+
+	#[test]
+	fn if_group_mirrored_then_normal_points_outwards() {
+		// This group mirrors a unit sphere at the origin through the origin:
+		let sphere = Sphere::new(Point::origin(), 1, Material::Absorbant);
+		let group = Group::new(vec![sphere.wrap()], Transform::scaling(-1.0));
+		// This ray shoots from the front onto the sphere:
+		let ray = Ray::new(Point::new(0, 0, 5), Vec3::new(0, 0, -1));
+
+		let hit = group.hit(ray, Interval::from(0));
+		assert!(hit.is_some(), "ray should hit the group, but returned None");
+		let hit = hit.unwrap();
+		assert!(
+			point_approx_eq(hit.point, Point::new(0, 0, 1)),
+			"ray should hit at (0, 0, 1), but hit {}",
+			hit.point
+		);
+		assert_eq!(
+			hit.normal,
+			Vec3::new(0, 0, 1),
+			"normal should point outwards, but was {}",
+			hit.normal
+		);
+		assert!(hit.is_front_face, "ray should hit the front face");
+	}
+
+	#[test]
+	fn if_ray_starts_inside_mirrored_group_then_back_face() {
+		let sphere = Sphere::new(Point::origin(), 1, Material::Absorbant);
+		let group = Group::new(vec![sphere.wrap()], Transform::scaling(-2.0));
+		// This ray starts at the center and shoots upwards:
+		let ray = Ray::new(Point::origin(), Vec3::new(0, 1, 0));
+
+		let hit = group.hit(ray, Interval::from(0));
+		assert!(hit.is_some(), "ray should hit the group, but returned None");
+		let hit = hit.unwrap();
+		assert!(!hit.is_front_face, "ray should hit the back face");
+		assert_eq!(
+			hit.normal,
+			Vec3::new(0, -1, 0),
+			"normal should face the ray, but was {}",
+			hit.normal
+		);
+	}
+}
@@ -1,17 +1,34 @@
-use serde::Deserialize;
+use std::any::Any;
 
-use crate::core::types::{Interval, Point, Ray, Vec3};
+use serde::{Deserialize, Serialize};
 
-use super::{Material, Sphere};
+use crate::core::error::RaytracerError;
+use crate::core::types::{Aabb, Interval, Point, Ray, Transform, Vec3};
+
+use super::{
+	Ground, Group, InfiniteCylinder, Material, MovingSphere, ShellSphere, Sphere, TriangleMesh,
+};
 
 /// A type that wraps hittable objects.
 /// This is done for performance improvements (static dispatch).
 // -Also we can avoid messing with Box<dyn Hittable> :)
-#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub enum Object {
 	/// A sphere.
 	Sphere(Sphere),
+	/// A sphere that moves over time.
+	MovingSphere(MovingSphere),
+	/// A group of objects sharing a transform.
+	Group(Group),
+	/// A patch of procedurally generated terrain.
+	Ground(Ground),
+	/// A mesh of triangles.
+	TriangleMesh(TriangleMesh),
+	/// An infinitely long cylinder without caps.
+	InfiniteCylinder(InfiniteCylinder),
+	/// A hollow sphere.
+	ShellSphere(ShellSphere),
 }
 
 /// Represents an object hittable/intersectable by a ray.
@@ -19,7 +36,10 @@ pub trait Hittable {
 	/// Calculates the intersection point of the specified ray with this object.
 	/// Additionally, validates if the parameter `t` lies within the specified (plausible) range.
 	/// If `t` lies outside the range, returns [`None`]; otherwise a [`Hit`] object.
-	fn hit(&self, ray: Ray, t_range: Interval) -> Option<Hit>;
+	fn hit(&self, ray: Ray, t_range: Interval) -> Option<Hit<'_>>;
+	/// Calculates the smallest axis-aligned box that encloses this object.
+	/// Returns [`None`] if the object has no bounding box (for example, if it is empty).
+	fn bounding_box(&self) -> Option<Aabb>;
 }
 
 /// A trait to wrap objects into an [`Object`] enum.
@@ -30,16 +50,145 @@ pub trait ToObject {
 
 // Dispatch methods
 impl Hittable for Object {
-	fn hit(&self, ray: Ray, t_range: Interval) -> Option<Hit> {
+	fn hit(&self, ray: Ray, t_range: Interval) -> Option<Hit<'_>> {
 		match self {
 			Self::Sphere(sphere) => sphere.hit(ray, t_range),
+			Self::MovingSphere(sphere) => sphere.hit(ray, t_range),
+			Self::Group(group) => group.hit(ray, t_range),
+			Self::Ground(ground) => ground.hit(ray, t_range),
+			Self::TriangleMesh(mesh) => mesh.hit(ray, t_range),
+			Self::InfiniteCylinder(cylinder) => cylinder.hit(ray, t_range),
+			Self::ShellSphere(shell) => shell.hit(ray, t_range),
+		}
+	}
+	fn bounding_box(&self) -> Option<Aabb> {
+		match self {
+			Self::Sphere(sphere) => sphere.bounding_box(),
+			Self::MovingSphere(sphere) => sphere.bounding_box(),
+			Self::Group(group) => group.bounding_box(),
+			Self::Ground(ground) => ground.bounding_box(),
+			Self::TriangleMesh(mesh) => mesh.bounding_box(),
+			Self::InfiniteCylinder(cylinder) => cylinder.bounding_box(),
+			Self::ShellSphere(shell) => shell.bounding_box(),
+		}
+	}
+}
+
+// JSON
+impl Object {
+	/// Parses a single object from a JSON fragment, such as one element of the input's `scene` array.
+	/// The fragment must contain the `type` of the object.
+	pub fn from_json(s: &str) -> Result<Self, RaytracerError> {
+		Ok(serde_json::from_str(s)?)
+	}
+	/// Serializes this object to a JSON fragment, which can be parsed by [`Object::from_json`].
+	pub fn to_json(&self) -> String {
+		serde_json::to_string(self).expect("objects should always be serializable")
+	}
+}
+
+// Position
+impl Object {
+	/// Returns the position of this object: the center of a (hollow) sphere (at time 0, if it's
+	/// moving) or of terrain, the center of a mesh's bounding box, the translation of a group,
+	/// or the point on a cylinder's axis.
+	pub fn position(&self) -> Point {
+		match self {
+			Self::Sphere(sphere) => sphere.center(),
+			Self::MovingSphere(sphere) => sphere.center_at(0.0),
+			Self::Group(group) => group.transform().translation.into(),
+			Self::Ground(ground) => ground.center(),
+			Self::TriangleMesh(mesh) => mesh.center(),
+			Self::InfiniteCylinder(cylinder) => cylinder.axis(),
+			Self::ShellSphere(shell) => shell.center(),
+		}
+	}
+	/// Moves this object, so that its [`Object::position`] is the specified one.
+	/// A moving sphere keeps its movement relative to the new position.
+	pub fn moved_to(self, position: Point) -> Self {
+		match self {
+			Self::Sphere(sphere) => Self::Sphere(sphere.moved_to(position)),
+			Self::MovingSphere(sphere) => Self::MovingSphere(sphere.moved_to(position)),
+			Self::Group(group) => Self::Group(group.moved_to(position)),
+			Self::Ground(ground) => Self::Ground(ground.moved_to(position)),
+			Self::TriangleMesh(mesh) => Self::TriangleMesh(mesh.moved_to(position)),
+			Self::InfiniteCylinder(cylinder) => Self::InfiniteCylinder(cylinder.moved_to(position)),
+			Self::ShellSphere(shell) => Self::ShellSphere(shell.moved_to(position)),
+		}
+	}
+	/// Applies the specified transform to this object: its position is transformed, and its size
+	/// is scaled. A group's transform is combined with the specified one (see [`Transform::then`]).
+	pub fn apply_transform(self, transform: &Transform) -> Self {
+		match self {
+			Self::Sphere(sphere) => Self::Sphere(sphere.transformed(transform)),
+			Self::MovingSphere(sphere) => Self::MovingSphere(sphere.transformed(transform)),
+			Self::Group(group) => Self::Group(group.transformed(transform)),
+			Self::Ground(ground) => Self::Ground(ground.transformed(transform)),
+			Self::TriangleMesh(mesh) => Self::TriangleMesh(mesh.transformed(transform)),
+			Self::InfiniteCylinder(cylinder) => {
+				Self::InfiniteCylinder(cylinder.transformed(transform))
+			}
+			Self::ShellSphere(shell) => Self::ShellSphere(shell.transformed(transform)),
+		}
+	}
+}
+
+impl Object {
+	/// Returns the material of this object, or [`None`] if it has none (such as a group).
+	pub fn material(&self) -> Option<&Material> {
+		match self {
+			Self::Sphere(sphere) => Some(sphere.material()),
+			Self::MovingSphere(sphere) => Some(sphere.material()),
+			Self::Group(_) => None,
+			Self::Ground(ground) => Some(ground.material()),
+			Self::TriangleMesh(mesh) => Some(mesh.material()),
+			Self::InfiniteCylinder(cylinder) => Some(cylinder.material()),
+			Self::ShellSphere(shell) => Some(shell.material()),
+		}
+	}
+	/// Returns the area of the surface of this object, or of all objects in a group.
+	pub fn surface_area(&self) -> f64 {
+		match self {
+			Self::Sphere(sphere) => sphere.surface_area(),
+			Self::MovingSphere(sphere) => sphere.surface_area(),
+			Self::Group(group) => group.surface_area(),
+			Self::Ground(ground) => ground.surface_area(),
+			Self::TriangleMesh(mesh) => mesh.surface_area(),
+			Self::InfiniteCylinder(cylinder) => cylinder.surface_area(),
+			Self::ShellSphere(shell) => shell.surface_area(),
+		}
+	}
+	/// Returns the wrapped object, to be downcast to its concrete type.
+	pub(crate) fn as_any(&self) -> &dyn Any {
+		match self {
+			Self::Sphere(sphere) => sphere,
+			Self::MovingSphere(sphere) => sphere,
+			Self::Group(group) => group,
+			Self::Ground(ground) => ground,
+			Self::TriangleMesh(mesh) => mesh,
+			Self::InfiniteCylinder(cylinder) => cylinder,
+			Self::ShellSphere(shell) => shell,
+		}
+	}
+}
+
+/// Calculates the intersection of the specified ray with the nearest of the objects.
+pub(crate) fn closest_hit(objects: &[Object], ray: Ray, t_range: Interval) -> Option<Hit<'_>> {
+	let mut t_max = t_range.end;
+	let mut closest_hit: Option<Hit> = None;
+	for obj in objects {
+		let hit = obj.hit(ray, Interval::new(t_range.start, t_max));
+		if let Some(_hit) = hit {
+			t_max = _hit.t;
+			closest_hit = hit;
 		}
 	}
+	closest_hit
 }
 
 /// Represents an intersection between a ray and an object in the scene.
 #[derive(Debug, Clone, Copy)]
-pub struct Hit {
+pub struct Hit<'a> {
 	/// The time parameter along the the ray vector axis.
 	pub t: f64,
 	/// The intersection point.
@@ -49,10 +198,14 @@ pub struct Hit {
 	/// Determines if the ray hits from outside the object (`true`) or inside (`false`).
 	pub is_front_face: bool,
 	/// The material of the surface that was hit.
-	pub material: Material,
+	pub material: &'a Material,
+	/// The horizontal texture coordinate of the intersection point, in `[0, 1]`.
+	pub u: f64,
+	/// The vertical texture coordinate of the intersection point, in `[0, 1]`.
+	pub v: f64,
 }
 
-impl Hit {
+impl Hit<'_> {
 	/// Calculates the orientation between the ray and the outward normal.
 	///
 	/// The parameter `outward_normal` **must** be a unit, normal vector.
@@ -71,11 +224,110 @@ impl Hit {
 		};
 		(normal, is_front_face)
 	}
+	/// Returns this hit, with the specified texture coordinates.
+	pub fn with_uv(self, u: f64, v: f64) -> Self {
+		Self { u, v, ..self }
+	}
+	/// Returns this hit, moved to the specified point and outward normal vector.
+	/// The face orientation is determined again with respect to `ray`.
+	pub fn with_point_normal(self, ray: Ray, point: Point, outward_normal: Vec3) -> Self {
+		let (normal, is_front_face) = Self::determine_front_face(ray, outward_normal);
+		Self {
+			point,
+			normal,
+			is_front_face,
+			..self
+		}
+	}
+	/// The normal vector at the intersection point, pointing outwards the object.
+	pub fn outward_normal(&self) -> Vec3 {
+		if self.is_front_face {
+			self.normal
+		} else {
+			-self.normal
+		}
+	}
+}
+
+/// A builder for a [`Hit`], which checks that all required values are set.
+///
+/// The texture coordinates are optional, and are 0 if not specified:
+/// ```
+/// let hit = HitBuilder::new()
+///     .t(t)
+///     .point(point)
+///     .normal(normal)
+///     .front_face(is_front_face)
+///     .material(&material)
+///     .build()?;
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HitBuilder<'a> {
+	t: Option<f64>,
+	point: Option<Point>,
+	normal: Option<Vec3>,
+	is_front_face: Option<bool>,
+	material: Option<&'a Material>,
+	uv: (f64, f64),
+}
+
+impl<'a> HitBuilder<'a> {
+	/// Creates a new builder, without any values.
+	pub fn new() -> Self {
+		Self::default()
+	}
+	/// Specifies the time parameter along the ray.
+	pub fn t(mut self, t: f64) -> Self {
+		self.t = Some(t);
+		self
+	}
+	/// Specifies the intersection point.
+	pub fn point(mut self, p: Point) -> Self {
+		self.point = Some(p);
+		self
+	}
+	/// Specifies the normal vector at the intersection point.
+	pub fn normal(mut self, n: Vec3) -> Self {
+		self.normal = Some(n);
+		self
+	}
+	/// Specifies if the ray hits from outside the object (`true`) or inside (`false`).
+	pub fn front_face(mut self, b: bool) -> Self {
+		self.is_front_face = Some(b);
+		self
+	}
+	/// Specifies the material of the surface that was hit.
+	pub fn material(mut self, m: &'a Material) -> Self {
+		self.material = Some(m);
+		self
+	}
+	/// Specifies the texture coordinates of the intersection point.
+	pub fn uv(mut self, u: f64, v: f64) -> Self {
+		self.uv = (u, v);
+		self
+	}
+	/// Creates the hit from the specified values.
+	/// Returns an error naming the first missing value, if any required value wasn't specified.
+	pub fn build(self) -> Result<Hit<'a>, String> {
+		let missing = |name: &str| format!("hit should have a {}, but none was specified", name);
+		Ok(Hit {
+			t: self.t.ok_or_else(|| missing("time parameter"))?,
+			point: self.point.ok_or_else(|| missing("point"))?,
+			normal: self.normal.ok_or_else(|| missing("normal"))?,
+			is_front_face: self
+				.is_front_face
+				.ok_or_else(|| missing("face orientation"))?,
+			material: self.material.ok_or_else(|| missing("material"))?,
+			u: self.uv.0,
+			v: self.uv.1,
+		})
+	}
 }
 
 #[cfg(test)]
 mod tests {
-	use super::Hit;
+	use super::{Hit, HitBuilder};
+	use crate::core::objects::Material;
 	use crate::core::types::{Point, Ray, Vec3};
 
 	#[test]
@@ -107,4 +359,50 @@ mod tests {
 			"is_front_face should be false, was `{is_front_face}`"
 		);
 	}
+
+	#[test]
+	fn if_all_values_specified_then_hit_is_built() {
+		let material = Material::Absorbant;
+		let hit = HitBuilder::new()
+			.t(2.0)
+			.point(Point::new(0, 0, -2))
+			.normal(Vec3::new(0, 0, 1))
+			.front_face(true)
+			.material(&material)
+			.uv(0.25, 0.75)
+			.build();
+		assert!(hit.is_ok(), "hit should be built, but got {:?}", hit.err());
+		let hit = hit.unwrap();
+		assert_eq!(
+			(hit.t, hit.u, hit.v),
+			(2.0, 0.25, 0.75),
+			"values should match"
+		);
+	}
+
+	#[test]
+	fn if_values_missing_then_build_fails() {
+		let material = Material::Absorbant;
+		// This builder has everything but the normal:
+		let builder = HitBuilder::new()
+			.t(2.0)
+			.point(Point::new(0, 0, -2))
+			.front_face(true)
+			.material(&material);
+		let hit = builder.build();
+		assert!(
+			hit.as_ref().is_err_and(|e| e.contains("normal")),
+			"build should fail due to the missing normal, but got {:?}",
+			hit
+		);
+		// Texture coordinates are optional, but nothing else is:
+		assert!(
+			HitBuilder::new().uv(0.5, 0.5).build().is_err(),
+			"build should fail without any required values"
+		);
+		assert!(
+			builder.normal(Vec3::new(0, 0, 1)).build().is_ok(),
+			"build should succeed once the normal is specified"
+		);
+	}
 }
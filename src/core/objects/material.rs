@@ -1,45 +1,216 @@
-use serde::Deserialize;
+use std::f64::consts::PI;
+use std::fmt;
 
-use crate::core::objects::Hit;
-use crate::core::types::{Color, Ray, Vec3};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::core::error::RaytracerError;
+use crate::core::objects::{Hit, Hittable};
+use crate::core::random;
+use crate::core::scene::Scene;
+use crate::core::types::{Color, Interval, Ray, ToVec3, Vec3};
 
 /// A type that describes a material of a surface.
+///
+/// Materials are compared by their exact parameters. Note that parameters with a value of
+/// `f64::NAN` never compare equal, so neither do materials containing them.
 //
 // This is used to mimic dynamic dispatch to simplify handling of different materials
 // (so that we do not have to use `Box<dyn Material>` and deal with its consequences).
-#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[derive(Clone, PartialEq, Deserialize, Serialize)]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub enum Material {
 	/// A material which absorbs all light.
 	Absorbant,
 	/// A matte material with Lambertian reflectance.
-	Matte { color: Color },
+	///
+	/// If the `color` is missing from JSON, grey is assumed.
+	Matte {
+		#[serde(default = "Material::default_color")]
+		color: Color,
+	},
 	/// A metallic, reflective material.
 	///
 	/// The `fuzz` parameter describes how imperfect the surface is.
 	/// A value of 0 describes a perfectly reflective metal,
 	/// while a value of 1 describes a rough/brushed surface.
 	/// Values outside the `0..=1` range are clamped.
-	Metal { color: Color, fuzz: f64 },
+	///
+	/// If missing from JSON, the `color` is silver and the `fuzz` is 0.
+	Metal {
+		#[serde(default = "Material::default_metal_color")]
+		color: Color,
+		#[serde(default = "Material::default_fuzz")]
+		fuzz: f64,
+	},
 	/// A transparent, dielectric material.
 	///
 	/// The `ridx` parameter is the refractive index of the material.
 	/// For glass, use a value of 1.5-1.7; for diamonds 2.4.
 	Dielectric { ridx: f64 },
-}
-// Keep the list in sync (used in tests)
-#[allow(dead_code)]
-const ALL_MATERIALS: &[Material] = &[
-	Material::Absorbant,
-	Material::Matte {
-		color: Color::black(),
+	/// A transparent, dielectric material with a separate refractive index for each color channel.
+	///
+	/// Since light of different wavelengths is refracted differently, white light is split into
+	/// its colors (dispersion), which causes colored fringes at the edges of objects.
+	/// For glass, use indices that increase slightly from red to blue, e.g. 1.50, 1.52, 1.54.
+	#[serde(rename_all = "camelCase")]
+	DispersiveDielectric {
+		ridx_r: f64,
+		ridx_g: f64,
+		ridx_b: f64,
 	},
-	Material::Metal {
-		color: Color::black(),
-		fuzz: 0.0,
+	/// A probabilistic mix of two materials.
+	///
+	/// Each time a ray hits the surface, it scatters off material `a` with a probability of `weight`,
+	/// and off material `b` otherwise. On average, the surface appears as a blend of both.
+	Blend {
+		a: Box<Material>,
+		b: Box<Material>,
+		weight: f64,
+	},
+	/// A material that looks different from either side of the surface.
+	///
+	/// Rays hitting the front face of the surface (from outside of the object) scatter off the
+	/// `front` material, and rays hitting the back face scatter off the `back` material.
+	TwoSided {
+		front: Box<Material>,
+		back: Box<Material>,
+	},
+	/// A material that scatters light equally in all directions, independent of the incoming ray,
+	/// as particles of fog or smoke do.
+	Isotropic { color: Color },
+	/// A material that scatters light around the incoming direction according to the
+	/// Henyey-Greenstein phase function, as particles of fog or smoke do.
+	///
+	/// The `g` parameter is the average cosine of the scattering angle, within `-1..1`:
+	/// positive values scatter forward, negative values backward, and 0 in all directions
+	/// equally (like [`Material::Isotropic`]).
+	HenyeyGreenstein { color: Color, g: f64 },
+	/// A material which emits light of the `color`, scaled by the `intensity`, such as a lamp.
+	///
+	/// Emissive surfaces don't scatter any light. If missing from JSON, the `intensity` is 1.
+	Emissive {
+		color: Color,
+		#[serde(default = "Material::default_intensity")]
+		intensity: f64,
+	},
+	/// A translucent material, such as skin, wax or marble, into which light enters and scatters
+	/// around before leaving the surface at a nearby point.
+	///
+	/// This is a simplified model: rays leave the surface at a distance from the hit point that is
+	/// normally distributed with a standard deviation of `scattering_radius`, into a random
+	/// (Lambertian) direction. Along the way, each channel is absorbed at a rate of `absorption`
+	/// per unit of distance, scaled by how much of the channel the `color` doesn't reflect, so that
+	/// light of the dominant color travels further (and bleeds into shadows).
+	#[serde(rename_all = "camelCase")]
+	Subsurface {
+		color: Color,
+		scattering_radius: f64,
+		absorption: f64,
 	},
-	Material::Dielectric { ridx: 1.0 },
-];
+}
+// Keep the list in sync (used in tests)
+#[cfg(test)]
+fn all_materials() -> [Material; 11] {
+	[
+		Material::Absorbant,
+		Material::Matte {
+			color: Color::black(),
+		},
+		Material::Metal {
+			color: Color::black(),
+			fuzz: 0.0,
+		},
+		Material::Dielectric { ridx: 1.0 },
+		Material::DispersiveDielectric {
+			ridx_r: 1.0,
+			ridx_g: 1.0,
+			ridx_b: 1.0,
+		},
+		Material::mix(Material::Absorbant, Material::Dielectric { ridx: 1.0 }, 0.5),
+		Material::two_sided(Material::Absorbant, Material::Dielectric { ridx: 1.0 }),
+		Material::Isotropic {
+			color: Color::black(),
+		},
+		Material::HenyeyGreenstein {
+			color: Color::black(),
+			g: 0.0,
+		},
+		Material::Emissive {
+			color: Color::black(),
+			intensity: 1.0,
+		},
+		Material::Subsurface {
+			color: Color::black(),
+			scattering_radius: 0.0,
+			absorption: 0.0,
+		},
+	]
+}
+
+// Constructors
+impl Material {
+	/// Creates a material that scatters rays off material `a` with a probability of `weight`,
+	/// and off material `b` otherwise. See [`Material::Blend`].
+	pub fn mix(a: Material, b: Material, weight: f64) -> Self {
+		Self::Blend {
+			a: Box::new(a),
+			b: Box::new(b),
+			weight,
+		}
+	}
+	/// Creates a material that scatters rays hitting the front face off the `front` material,
+	/// and rays hitting the back face off the `back` material. See [`Material::TwoSided`].
+	pub fn two_sided(front: Material, back: Material) -> Self {
+		Self::TwoSided {
+			front: Box::new(front),
+			back: Box::new(back),
+		}
+	}
+	/// Returns the material that applies to the side of the surface that was hit.
+	/// For materials other than [`Material::TwoSided`], this is the material itself.
+	fn side(&self, hit: &Hit) -> &Material {
+		match self {
+			Self::TwoSided { front, back } => {
+				if hit.is_front_face {
+					front.side(hit)
+				} else {
+					back.side(hit)
+				}
+			}
+			_ => self,
+		}
+	}
+}
+
+// JSON
+impl Material {
+	/// The color of matte materials whose color is missing from JSON (grey).
+	fn default_color() -> Color {
+		Color::new(0.5, 0.5, 0.5)
+	}
+	/// The color of metals whose color is missing from JSON (silver).
+	fn default_metal_color() -> Color {
+		Color::new(0.8, 0.8, 0.8)
+	}
+	/// The fuzz of metals whose fuzz is missing from JSON (perfectly reflective).
+	fn default_fuzz() -> f64 {
+		0.0
+	}
+	/// The intensity of emissive materials whose intensity is missing from JSON.
+	fn default_intensity() -> f64 {
+		1.0
+	}
+	/// Parses a material from a JSON fragment, such as the `material` of an object in the input.
+	/// The fragment must contain the `type` of the material.
+	pub fn from_json(s: &str) -> Result<Self, RaytracerError> {
+		Ok(serde_json::from_str(s)?)
+	}
+	/// Serializes this material to a JSON fragment, which can be parsed by [`Material::from_json`].
+	pub fn to_json(&self) -> String {
+		serde_json::to_string(self).expect("materials should always be serializable")
+	}
+}
 
 impl Material {
 	/// Calculates the scattered (bouncing) ray, depending on the material.
@@ -47,39 +218,410 @@ impl Material {
 	/// Accepts the incoming `ray` onto the surface, and the `hit` from which the ray should scatter.
 	///
 	/// Returns either a scattered ray, or `None` if the ray was completely absorbed.
+	/// The scattered ray is cast at the same time as the incoming ray.
 	pub fn scatter(&self, ray: Ray, hit: Hit) -> Option<Ray> {
-		match self {
+		let scattered = match self {
 			Self::Absorbant => None,
 			Self::Matte { color } => scatter_matte(hit, *color),
 			Self::Metal { color, fuzz } => scatter_metal(ray, hit, *color, *fuzz),
 			Self::Dielectric { ridx } => scatter_dielectric(ray, hit, *ridx),
+			Self::DispersiveDielectric {
+				ridx_r,
+				ridx_g,
+				ridx_b,
+			} => scatter_dispersive(ray, hit, [*ridx_r, *ridx_g, *ridx_b]),
+			Self::Blend { a, b, weight } => {
+				if random::random::<f64>() < *weight {
+					a.scatter(ray, hit)
+				} else {
+					b.scatter(ray, hit)
+				}
+			}
+			Self::TwoSided { .. } => self.side(&hit).scatter(ray, hit),
+			Self::Isotropic { color } => Some(Ray::newc(hit.point, Vec3::random_unit(), *color)),
+			Self::HenyeyGreenstein { color, g } => {
+				let (r1, r2) = (random::random::<f64>(), random::random::<f64>());
+				let direction = henyey_greenstein_dir(ray.direction, *g, r1, r2);
+				Some(Ray::newc(hit.point, direction, *color))
+			}
+			Self::Emissive { .. } => None,
+			Self::Subsurface {
+				color,
+				scattering_radius,
+				absorption,
+			} => scatter_subsurface(hit, *color, *scattering_radius, *absorption),
+		};
+		scattered.map(|scattered| Ray {
+			time: ray.time,
+			..scattered
+		})
+	}
+	/// Calculates the scattered ray off this material in the `scene`, see [`Material::scatter`].
+	///
+	/// Light scattered below the surface (see [`Material::Subsurface`]) leaves on the tangent plane
+	/// at the hit. Here, that exit point is projected back onto the surface of the object that was
+	/// hit, and the scattered direction is drawn anew around the normal there. If the projection
+	/// misses the object, the light leaves at the hit point instead.
+	pub(crate) fn scatter_in(&self, ray: Ray, hit: Hit, scene: &Scene) -> Option<Ray> {
+		let scattered = self.scatter(ray, hit)?;
+		if scattered.origin == hit.point {
+			return Some(scattered);
+		}
+		let height = (scattered.origin.to_vec3() - hit.point.to_vec3()).norm();
+		let probe = Ray::with_time(
+			(scattered.origin.to_vec3() + hit.normal.scale(height)).into(),
+			-hit.normal,
+			ray.time,
+		);
+		let exit = scene
+			.hit(probe, Interval::new(0.0, 2.0 * height))
+			.filter(|exit| exit.is_front_face && std::ptr::eq(exit.material, hit.material));
+		let (origin, normal) = match exit {
+			Some(exit) => (exit.point, exit.normal),
+			None => (hit.point, hit.normal),
+		};
+		Some(Ray {
+			origin,
+			direction: Vec3::random_cosine_direction().orient(normal),
+			..scattered
+		})
+	}
+	/// Calculates the probability density of this material scattering the incoming ray `ray_in`
+	/// at the `hit` into the direction of `ray_out`.
+	///
+	/// Only diffuse and volumetric materials scatter according to a density; for all other
+	/// materials, returns zero.
+	pub fn scattering_pdf(&self, ray_in: Ray, hit: Hit, ray_out: Ray) -> f64 {
+		match self {
+			Self::Matte { .. } | Self::Subsurface { .. } => {
+				let cos_theta = hit.normal.dot(ray_out.direction.unit());
+				f64::max(0.0, cos_theta / PI)
+			}
+			Self::Blend { a, b, weight } => {
+				weight * a.scattering_pdf(ray_in, hit, ray_out)
+					+ (1.0 - weight) * b.scattering_pdf(ray_in, hit, ray_out)
+			}
+			Self::TwoSided { .. } => self.side(&hit).scattering_pdf(ray_in, hit, ray_out),
+			Self::Isotropic { .. } => 1.0 / (4.0 * PI),
+			Self::HenyeyGreenstein { g, .. } => {
+				let cos_theta = ray_in.direction.unit().dot(ray_out.direction.unit());
+				henyey_greenstein_pdf(cos_theta, *g)
+			}
+			Self::Absorbant
+			| Self::Metal { .. }
+			| Self::Dielectric { .. }
+			| Self::DispersiveDielectric { .. }
+			| Self::Emissive { .. } => 0.0,
+		}
+	}
+	/// Samples the direction into which the incoming ray `ray_in` scatters at the `hit`,
+	/// drawing all random values from `rng`.
+	///
+	/// Works like [`Material::scatter`], without constructing a ray.
+	/// If the ray is absorbed, a zero vector is returned.
+	pub fn generate_scattered(&self, ray_in: Ray, hit: Hit, rng: &mut impl Rng) -> Vec3 {
+		match self {
+			Self::Absorbant | Self::Emissive { .. } => Vec3::zero(),
+			Self::Matte { .. } | Self::Subsurface { .. } => {
+				Vec3::cosine_direction(rng.random(), rng.random()).orient(hit.normal)
+			}
+			Self::Metal { fuzz, .. } => {
+				metal_dir(ray_in, hit, *fuzz, random_unit(rng)).unwrap_or(Vec3::zero())
+			}
+			Self::Dielectric { ridx } => dielectric_dir(ray_in, hit, *ridx, rng.random()),
+			Self::DispersiveDielectric {
+				ridx_r,
+				ridx_g,
+				ridx_b,
+			} => {
+				let ridx = [*ridx_r, *ridx_g, *ridx_b][rng.random_range(0..3)];
+				dielectric_dir(ray_in, hit, ridx, rng.random())
+			}
+			Self::Blend { a, b, weight } => {
+				if rng.random::<f64>() < *weight {
+					a.generate_scattered(ray_in, hit, rng)
+				} else {
+					b.generate_scattered(ray_in, hit, rng)
+				}
+			}
+			Self::TwoSided { .. } => self.side(&hit).generate_scattered(ray_in, hit, rng),
+			Self::Isotropic { .. } => random_unit(rng),
+			Self::HenyeyGreenstein { g, .. } => {
+				henyey_greenstein_dir(ray_in.direction, *g, rng.random(), rng.random())
+			}
+		}
+	}
+	/// Returns the light emitted by this material, which is black for all materials other than
+	/// [`Material::Emissive`].
+	///
+	/// Blends emit a mix of the light of both materials, two-sided materials the light of their
+	/// front material.
+	pub fn emittance(&self) -> Color {
+		match self {
+			Self::Emissive { color, intensity } => color.to_vec3().scale(*intensity).into(),
+			Self::Blend { a, b, weight } => Color::mix(b.emittance(), a.emittance(), *weight),
+			Self::TwoSided { front, .. } => front.emittance(),
+			_ => Color::black(),
+		}
+	}
+	/// Checks if this material emits any light, see [`Material::emittance`].
+	pub fn is_emissive(&self) -> bool {
+		self.emittance() != Color::black()
+	}
+}
+
+// Identification & formatting
+impl Material {
+	/// Returns the base color of this material, independent of lighting.
+	///
+	/// Dielectrics are treated as white, since they don't tint light; absorbant materials are black.
+	/// Volumetric materials are described by the color of the light they scatter,
+	/// and emissive materials by the color of the light they emit.
+	/// Two-sided materials are described by their front material.
+	pub fn albedo(&self) -> Color {
+		match self {
+			Self::Absorbant => Color::black(),
+			Self::Matte { color }
+			| Self::Metal { color, .. }
+			| Self::Isotropic { color }
+			| Self::HenyeyGreenstein { color, .. }
+			| Self::Emissive { color, .. }
+			| Self::Subsurface { color, .. } => *color,
+			Self::Dielectric { .. } | Self::DispersiveDielectric { .. } => Color::white(),
+			Self::Blend { a, b, weight } => Color::mix(b.albedo(), a.albedo(), *weight),
+			Self::TwoSided { front, .. } => front.albedo(),
+		}
+	}
+	/// Returns how rough the surface of this material is, between 0 (smooth) and 1 (rough).
+	///
+	/// Matte surfaces and volumetric materials are fully rough, metals are as rough as their
+	/// (clamped) `fuzz`, and absorbant, dielectric and emissive materials are smooth.
+	pub fn roughness(&self) -> f64 {
+		match self {
+			Self::Absorbant
+			| Self::Dielectric { .. }
+			| Self::DispersiveDielectric { .. }
+			| Self::Emissive { .. } => 0.0,
+			Self::Matte { .. }
+			| Self::Isotropic { .. }
+			| Self::HenyeyGreenstein { .. }
+			| Self::Subsurface { .. } => 1.0,
+			Self::Metal { fuzz, .. } => fuzz.clamp(0.0, 1.0),
+			Self::Blend { a, b, weight } => b.roughness() * (1.0 - weight) + a.roughness() * weight,
+			Self::TwoSided { front, .. } => front.roughness(),
+		}
+	}
+	/// Returns the index of refraction of this material, if it is dielectric.
+	///
+	/// For dispersive dielectrics, the index of the green channel is returned as a representative.
+	/// Blends have no single index of refraction, and also return [`None`].
+	pub fn ior(&self) -> Option<f64> {
+		match self {
+			Self::Dielectric { ridx } => Some(*ridx),
+			Self::DispersiveDielectric { ridx_g, .. } => Some(*ridx_g),
+			Self::TwoSided { front, .. } => front.ior(),
+			_ => None,
+		}
+	}
+	/// Returns a short identifier of this material's kind, suitable for filenames or logs.
+	pub fn name(&self) -> &'static str {
+		match self {
+			Self::Absorbant => "absorbant",
+			Self::Matte { .. } => "matte",
+			Self::Metal { .. } => "metal",
+			Self::Dielectric { .. } => "dielectric",
+			Self::DispersiveDielectric { .. } => "dispersive",
+			Self::Blend { .. } => "blend",
+			Self::TwoSided { .. } => "two-sided",
+			Self::Isotropic { .. } => "isotropic",
+			Self::HenyeyGreenstein { .. } => "henyey-greenstein",
+			Self::Emissive { .. } => "emissive",
+			Self::Subsurface { .. } => "subsurface",
+		}
+	}
+}
+impl fmt::Display for Material {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Absorbant => write!(f, "Absorbant"),
+			Self::Matte { color } => write!(f, "Matte({})", color.to_hex()),
+			Self::Metal { color, fuzz } => write!(f, "Metal({}, fuzz={:.2})", color.to_hex(), fuzz),
+			Self::Dielectric { ridx } => write!(f, "Dielectric(ior={:.2})", ridx),
+			Self::DispersiveDielectric {
+				ridx_r,
+				ridx_g,
+				ridx_b,
+			} => write!(
+				f,
+				"DispersiveDielectric(ior={:.2}/{:.2}/{:.2})",
+				ridx_r, ridx_g, ridx_b
+			),
+			Self::Blend { a, b, weight } => write!(f, "Blend({}, {}, weight={:.2})", a, b, weight),
+			Self::TwoSided { front, back } => write!(f, "TwoSided({}, {})", front, back),
+			Self::Isotropic { color } => write!(f, "Isotropic({})", color.to_hex()),
+			Self::HenyeyGreenstein { color, g } => {
+				write!(f, "HenyeyGreenstein({}, g={:.2})", color.to_hex(), g)
+			}
+			Self::Emissive { color, intensity } => {
+				write!(
+					f,
+					"Emissive({}, intensity={:.2})",
+					color.to_hex(),
+					intensity
+				)
+			}
+			Self::Subsurface {
+				color,
+				scattering_radius,
+				absorption,
+			} => write!(
+				f,
+				"Subsurface({}, radius={:.2}, absorption={:.2})",
+				color.to_hex(),
+				scattering_radius,
+				absorption
+			),
+		}
+	}
+}
+impl fmt::Debug for Material {
+	/// Formats all parameters without rounding.
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Absorbant => f.write_str("Absorbant"),
+			Self::Matte { color } => f.debug_struct("Matte").field("color", color).finish(),
+			Self::Metal { color, fuzz } => f
+				.debug_struct("Metal")
+				.field("color", color)
+				.field("fuzz", fuzz)
+				.finish(),
+			Self::Dielectric { ridx } => f.debug_struct("Dielectric").field("ridx", ridx).finish(),
+			Self::DispersiveDielectric {
+				ridx_r,
+				ridx_g,
+				ridx_b,
+			} => f
+				.debug_struct("DispersiveDielectric")
+				.field("ridx_r", ridx_r)
+				.field("ridx_g", ridx_g)
+				.field("ridx_b", ridx_b)
+				.finish(),
+			Self::Blend { a, b, weight } => f
+				.debug_struct("Blend")
+				.field("a", a)
+				.field("b", b)
+				.field("weight", weight)
+				.finish(),
+			Self::TwoSided { front, back } => f
+				.debug_struct("TwoSided")
+				.field("front", front)
+				.field("back", back)
+				.finish(),
+			Self::Isotropic { color } => f.debug_struct("Isotropic").field("color", color).finish(),
+			Self::HenyeyGreenstein { color, g } => f
+				.debug_struct("HenyeyGreenstein")
+				.field("color", color)
+				.field("g", g)
+				.finish(),
+			Self::Emissive { color, intensity } => f
+				.debug_struct("Emissive")
+				.field("color", color)
+				.field("intensity", intensity)
+				.finish(),
+			Self::Subsurface {
+				color,
+				scattering_radius,
+				absorption,
+			} => f
+				.debug_struct("Subsurface")
+				.field("color", color)
+				.field("scattering_radius", scattering_radius)
+				.field("absorption", absorption)
+				.finish(),
 		}
 	}
 }
 
 /// Calculates the scattered ray off a matte material.
 fn scatter_matte(hit: Hit, color: Color) -> Option<Ray> {
-	let mut direction = hit.normal + Vec3::random_unit();
-	if direction.is_near_zero() {
-		direction = hit.normal
-	}
+	let direction = Vec3::random_cosine_direction().orient(hit.normal);
 	Some(Ray::newc(hit.point, direction, color))
 }
 
+/// Calculates the scattered ray off a subsurface scattering material, which leaves on the tangent
+/// plane at a normally distributed distance from the hit point (see [`Material::scatter_in`] for
+/// its projection onto the surface). Hits from within the object are scattered like a matte material.
+fn scatter_subsurface(
+	hit: Hit,
+	color: Color,
+	scattering_radius: f64,
+	absorption: f64,
+) -> Option<Ray> {
+	if !hit.is_front_face {
+		return scatter_matte(hit, color);
+	}
+	// Distance from the hit point of a two-dimensional normal distribution (Box-Muller)
+	let (r1, r2) = (random::random::<f64>(), random::random::<f64>());
+	let distance = scattering_radius * f64::sqrt(-2.0 * f64::ln(1.0 - r1));
+	let phi = 2.0 * PI * r2;
+	let offset = Vec3(phi.cos(), phi.sin(), 0.0).orient(hit.normal);
+	let origin = hit.point.to_vec3() + offset.scale(distance);
+
+	// Instead of absorbing every channel equally (as `exp(-absorption * distance)` would, which only
+	// darkens the light), the rate is scaled by how much of each channel the color absorbs,
+	// so that the dominant color travels further, and tints the light bleeding out of the shadows.
+	let transmittance = |c: f64| c * f64::exp(-absorption * (1.0 - c).max(0.0) * distance);
+	let attenuation = Color::new(
+		transmittance(color.r()),
+		transmittance(color.g()),
+		transmittance(color.b()),
+	);
+	let direction = Vec3::random_cosine_direction().orient(hit.normal);
+	Some(Ray::newc(origin.into(), direction, attenuation))
+}
+
 /// Calculates the scattered ray off a metallic material.
 fn scatter_metal(ray: Ray, hit: Hit, color: Color, fuzz: f64) -> Option<Ray> {
+	let direction = metal_dir(ray, hit, fuzz, Vec3::random_unit())?;
+	Some(Ray::newc(hit.point, direction, color))
+}
+
+/// Calculates the direction of a ray reflected off a metallic material, perturbed by the
+/// unit vector `offset` depending on `fuzz`.
+/// Returns `None` if the direction lands below the surface.
+fn metal_dir(ray: Ray, hit: Hit, fuzz: f64, offset: Vec3) -> Option<Vec3> {
 	let fuzz = fuzz.clamp(0.0, 1.0);
-	let direction = reflect_dir(ray.direction, hit.normal) + Vec3::random_unit().scale(fuzz);
+	let direction = reflect_dir(ray.direction, hit.normal) + offset.scale(fuzz);
 	// if direction vector lands below the surface, absorb
-	if direction.dot(hit.normal) > 0.0 {
-		Some(Ray::newc(hit.point, direction, color))
-	} else {
-		None
-	}
+	(direction.dot(hit.normal) > 0.0).then_some(direction)
 }
 
 /// Calculates the scattered ray off a dielectric material.
 fn scatter_dielectric(ray: Ray, hit: Hit, ridx: f64) -> Option<Ray> {
+	let direction = dielectric_dir(ray, hit, ridx, random::random_range(0.0..1.0));
+	Some(Ray::new(hit.point, direction))
+}
+
+/// Calculates the scattered ray off a dispersive dielectric material.
+///
+/// One of the color channels is picked at random, and the ray is refracted with its index.
+/// The scattered ray only carries that channel, tripled since each channel is picked a third
+/// of the time; on average, all channels are transmitted fully.
+fn scatter_dispersive(ray: Ray, hit: Hit, ridx: [f64; 3]) -> Option<Ray> {
+	let channel = random::random_range(0..3);
+	let direction = dielectric_dir(ray, hit, ridx[channel], random::random_range(0.0..1.0));
+	let mut color = [0.0; 3];
+	color[channel] = 3.0;
+	Some(Ray::newc(
+		hit.point,
+		direction,
+		Color(color[0], color[1], color[2]),
+	))
+}
+
+/// Calculates the direction of a ray refracted or reflected by a dielectric material.
+/// The uniformly distributed value `sample` in `[0, 1)` decides between both if the ray can be refracted.
+fn dielectric_dir(ray: Ray, hit: Hit, ridx: f64, sample: f64) -> Vec3 {
 	let ri = if hit.is_front_face { 1.0 / ridx } else { ridx };
 
 	let unit_dir = ray.direction.unit();
@@ -87,15 +629,44 @@ fn scatter_dielectric(ray: Ray, hit: Hit, ridx: f64) -> Option<Ray> {
 	let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
 	let can_refract = ri * sin_theta <= 1.0;
 
-	if can_refract || reflectance(cos_theta, 1.0, ridx) > rand::random_range(0.0..1.0) {
-		let direction = refract_dir(unit_dir, hit.normal, ri);
-		Some(Ray::new(hit.point, direction))
+	if can_refract || reflectance(cos_theta, 1.0, ridx) > sample {
+		refract_dir(unit_dir, hit.normal, ri)
 	} else {
-		let direction = reflect_dir(ray.direction, hit.normal);
-		Some(Ray::new(hit.point, direction))
+		reflect_dir(ray.direction, hit.normal)
 	}
 }
 
+/// Calculates the direction of a ray scattered according to the Henyey-Greenstein phase function
+/// with the asymmetry `g`, around the `incoming` direction.
+/// The uniformly distributed values `r1`, `r2` in `[0, 1)` determine the direction.
+fn henyey_greenstein_dir(incoming: Vec3, g: f64, r1: f64, r2: f64) -> Vec3 {
+	let cos_theta = if g.abs() < 1e-3 {
+		1.0 - 2.0 * r1
+	} else {
+		let term = (1.0 - g * g) / (1.0 - g + 2.0 * g * r1);
+		((1.0 + g * g - term * term) / (2.0 * g)).clamp(-1.0, 1.0)
+	};
+	let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+	let phi = 2.0 * PI * r2;
+	Vec3(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta).orient(incoming.unit())
+}
+
+/// Calculates the density of the Henyey-Greenstein phase function with the asymmetry `g`,
+/// for a scattering angle with the cosine `cos_theta`.
+fn henyey_greenstein_pdf(cos_theta: f64, g: f64) -> f64 {
+	let denominator = 1.0 + g * g - 2.0 * g * cos_theta;
+	(1.0 - g * g) / (4.0 * PI * denominator * denominator.sqrt())
+}
+
+/// Creates a random unit vector, uniformly distributed across the unit sphere,
+/// from the random values drawn from `rng`.
+fn random_unit(rng: &mut impl Rng) -> Vec3 {
+	let z: f64 = rng.random_range(-1.0..1.0);
+	let phi = 2.0 * PI * rng.random::<f64>();
+	let radius = (1.0 - z * z).sqrt();
+	Vec3(radius * phi.cos(), radius * phi.sin(), z)
+}
+
 /// Calculates the specular reflection coefficient using Schlick's approximation.
 ///
 /// The `cos` parameter denotes the cosine of the angle between the incoming ray direction and the normal;
@@ -131,11 +702,292 @@ fn refract_dir(incoming: Vec3, normal: Vec3, ridx_ratio: f64) -> Vec3 {
 
 #[cfg(test)]
 mod tests {
-	use crate::core::objects::Hit;
-	use crate::core::objects::material::ALL_MATERIALS;
-	use crate::core::types::{Point, Ray, Vec3};
+	use crate::core::camera::{Camera, CameraSetup};
+	use crate::core::error::RaytracerError;
+	use crate::core::objects::{HitBuilder, Hittable, Sphere};
+	use crate::core::scene::{Background, Scene};
+	use crate::core::types::{Color, Image, Interval, Point, Ray, ToVec3, Vec3};
+
+	use std::f64::consts::PI;
+	use std::ops::Range;
+
+	use super::{Material, all_materials, reflect_dir, refract_dir};
 
-	use super::{reflect_dir, refract_dir};
+	#[test]
+	fn display_is_human_readable() {
+		// These are materials of every kind, with their expected representations:
+		let cases = [
+			(Material::Absorbant, "Absorbant"),
+			(
+				Material::Matte {
+					color: Color::new(0.2, 0.4, 0.6),
+				},
+				"Matte(#336699)",
+			),
+			(
+				Material::Metal {
+					color: Color::new(2.0 / 3.0, 0.4, 0.2),
+					fuzz: 0.1,
+				},
+				"Metal(#aa6633, fuzz=0.10)",
+			),
+			(Material::Dielectric { ridx: 1.5 }, "Dielectric(ior=1.50)"),
+			(
+				Material::DispersiveDielectric {
+					ridx_r: 1.5,
+					ridx_g: 1.52,
+					ridx_b: 1.54,
+				},
+				"DispersiveDielectric(ior=1.50/1.52/1.54)",
+			),
+			(
+				Material::mix(Material::Absorbant, Material::Dielectric { ridx: 1.5 }, 0.3),
+				"Blend(Absorbant, Dielectric(ior=1.50), weight=0.30)",
+			),
+			(
+				Material::two_sided(Material::Absorbant, Material::Dielectric { ridx: 1.5 }),
+				"TwoSided(Absorbant, Dielectric(ior=1.50))",
+			),
+			(
+				Material::Isotropic {
+					color: Color::new(0.2, 0.4, 0.6),
+				},
+				"Isotropic(#336699)",
+			),
+			(
+				Material::HenyeyGreenstein {
+					color: Color::new(0.2, 0.4, 0.6),
+					g: -0.25,
+				},
+				"HenyeyGreenstein(#336699, g=-0.25)",
+			),
+			(
+				Material::Emissive {
+					color: Color::new(0.2, 0.4, 0.6),
+					intensity: 5.0,
+				},
+				"Emissive(#336699, intensity=5.00)",
+			),
+			(
+				Material::Subsurface {
+					color: Color::new(0.2, 0.4, 0.6),
+					scattering_radius: 0.25,
+					absorption: 2.0,
+				},
+				"Subsurface(#336699, radius=0.25, absorption=2.00)",
+			),
+		];
+		assert_eq!(
+			cases.len(),
+			all_materials().len(),
+			"every kind of material should be covered"
+		);
+		for (material, expected) in cases {
+			let actual = material.to_string();
+			assert_eq!(
+				actual, expected,
+				"material should be displayed as {}, but was {}",
+				expected, actual
+			);
+		}
+	}
+
+	#[test]
+	fn debug_formats_colors_without_rounding() {
+		// This color is brighter than 1, and can't be represented by 8 bits per channel:
+		let material = Material::Emissive {
+			color: Color::new(2.5, 0.125, 0.0),
+			intensity: 1.0,
+		};
+		let expected = "Emissive { color: Color(2.5, 0.125, 0.0), intensity: 1.0 }";
+		let actual = format!("{:?}", material);
+		assert_eq!(
+			actual, expected,
+			"material should be formatted as {}, but was {}",
+			expected, actual
+		);
+	}
+
+	#[test]
+	fn getters_describe_every_material() {
+		let color = Color::new(0.2, 0.4, 0.6);
+		// These are materials of every kind, with their expected albedo, roughness, and ior:
+		let cases = [
+			(Material::Absorbant, Color::black(), 0.0, None),
+			(Material::Matte { color }, color, 1.0, None),
+			(Material::Metal { color, fuzz: 0.3 }, color, 0.3, None),
+			(
+				Material::Dielectric { ridx: 1.5 },
+				Color::white(),
+				0.0,
+				Some(1.5),
+			),
+			(
+				Material::DispersiveDielectric {
+					ridx_r: 1.5,
+					ridx_g: 1.52,
+					ridx_b: 1.54,
+				},
+				Color::white(),
+				0.0,
+				Some(1.52),
+			),
+			(
+				Material::mix(
+					Material::Matte {
+						color: Color::white(),
+					},
+					Material::Absorbant,
+					0.5,
+				),
+				Color::new(0.5, 0.5, 0.5),
+				0.5,
+				None,
+			),
+			(
+				Material::two_sided(
+					Material::Dielectric { ridx: 1.5 },
+					Material::Matte { color },
+				),
+				Color::white(),
+				0.0,
+				Some(1.5),
+			),
+			(Material::Isotropic { color }, color, 1.0, None),
+			(
+				Material::HenyeyGreenstein { color, g: 0.5 },
+				color,
+				1.0,
+				None,
+			),
+			(
+				Material::Emissive {
+					color,
+					intensity: 4.0,
+				},
+				color,
+				0.0,
+				None,
+			),
+			(
+				Material::Subsurface {
+					color,
+					scattering_radius: 0.1,
+					absorption: 2.0,
+				},
+				color,
+				1.0,
+				None,
+			),
+		];
+		assert_eq!(
+			cases.len(),
+			all_materials().len(),
+			"every kind of material should be covered"
+		);
+		for (material, albedo, roughness, ior) in cases {
+			assert_eq!(
+				material.albedo(),
+				albedo,
+				"albedo of {} should match",
+				material
+			);
+			assert_eq!(
+				material.roughness(),
+				roughness,
+				"roughness of {} should match",
+				material
+			);
+			assert_eq!(material.ior(), ior, "ior of {} should match", material);
+		}
+	}
+
+	#[test]
+	fn metal_roughness_follows_clamped_fuzz() {
+		let metal = |fuzz| Material::Metal {
+			color: Color::white(),
+			fuzz,
+		};
+		assert!(
+			metal(0.1).roughness() < metal(0.8).roughness(),
+			"more fuzz should make the metal rougher"
+		);
+		assert_eq!(metal(5.0).roughness(), 1.0, "fuzz should be clamped");
+	}
+
+	#[test]
+	fn every_material_has_a_distinct_name() {
+		let materials = all_materials();
+		let mut names = materials.iter().map(|m| m.name()).collect::<Vec<_>>();
+		names.sort();
+		names.dedup();
+		assert_eq!(
+			names.len(),
+			materials.len(),
+			"every material should have a distinct name, but names were {:?}",
+			names
+		);
+	}
+
+	#[test]
+	fn lambertian_pdf_integrates_to_one_over_hemisphere() {
+		let material = Material::Matte {
+			color: Color::black(),
+		};
+		let normal = Vec3::new(0, 1, 0);
+		let hit = HitBuilder::new()
+			.t(1.0)
+			.point(Point::origin())
+			.normal(normal)
+			.front_face(true)
+			.material(&material)
+			.build()
+			.unwrap();
+		let ray_in = Ray::new(Point::new(0, 1, 0), Vec3::new(0, -1, 0));
+
+		// Monte Carlo integration with directions uniformly distributed across the hemisphere:
+		let samples = 100_000;
+		let sum: f64 = (0..samples)
+			.map(|_| {
+				let direction = Vec3::random_in_unit_hemisphere(normal).unit();
+				let ray_out = Ray::new(hit.point, direction);
+				material.scattering_pdf(ray_in, hit, ray_out) * 2.0 * PI
+			})
+			.sum();
+		let integral = sum / samples as f64;
+		assert!(
+			(integral - 1.0).abs() < 0.02,
+			"pdf should integrate to 1, but integrated to {}",
+			integral
+		);
+	}
+
+	#[test]
+	fn generated_matte_directions_point_away_from_surface() {
+		let material = Material::Matte {
+			color: Color::black(),
+		};
+		let normal = Vec3::new(0, 0, 1);
+		let hit = HitBuilder::new()
+			.t(1.0)
+			.point(Point::origin())
+			.normal(normal)
+			.front_face(true)
+			.material(&material)
+			.build()
+			.unwrap();
+		let ray_in = Ray::new(Point::new(0, 0, 1), Vec3::new(0, 0, -1));
+
+		let mut rng = rand::rng();
+		for _ in 0..100 {
+			let direction = material.generate_scattered(ray_in, hit, &mut rng);
+			assert!(
+				direction.dot(normal) >= 0.0,
+				"generated direction {} should point away from the surface",
+				direction
+			);
+		}
+	}
 
 	#[test]
 	fn bouncing_ray_always_originates_at_hit_point() {
@@ -147,19 +999,20 @@ mod tests {
 
 		// For every material, if the ray is scattered, the bouncing one should originate at the hit point:
 		let mut violations = vec![];
-		for mat in ALL_MATERIALS {
-			let hit = Hit {
-				t: 5.0,
-				point,
-				normal,
-				is_front_face: true,
-				material: *mat,
-			};
+		for mat in &all_materials() {
+			let hit = HitBuilder::new()
+				.t(5.0)
+				.point(point)
+				.normal(normal)
+				.front_face(true)
+				.material(mat)
+				.build()
+				.unwrap();
 			let Some(ray_out) = mat.scatter(ray_in, hit) else {
 				continue;
 			};
 			if ray_out.origin != point {
-				violations.push((mat, ray_out.origin));
+				violations.push((mat.clone(), ray_out.origin));
 			}
 		}
 
@@ -175,6 +1028,483 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn blend_scatters_with_intermediate_color() {
+		// This material is 30% red and 70% blue:
+		let material = Material::mix(
+			Material::Matte {
+				color: Color::new(1, 0, 0),
+			},
+			Material::Matte {
+				color: Color::new(0, 0, 1),
+			},
+			0.3,
+		);
+		let hit = HitBuilder::new()
+			.t(1.0)
+			.point(Point::origin())
+			.normal(Vec3::new(0, 1, 0))
+			.front_face(true)
+			.material(&material)
+			.build()
+			.unwrap();
+		let ray_in = Ray::new(Point::new(0, 1, 0), Vec3::new(0, -1, 0));
+
+		// On average, the scattered rays should carry a mix of both colors:
+		let samples = 10_000;
+		let sum = (0..samples)
+			.filter_map(|_| material.scatter(ray_in, hit))
+			.fold(Vec3::zero(), |sum, ray| sum + ray.attenuation.to_vec3());
+		let average = sum / samples as f64;
+		assert!(
+			(average.x() - 0.3).abs() < 0.03 && (average.z() - 0.7).abs() < 0.03,
+			"average color should be about (0.3, 0, 0.7), but was {}",
+			average
+		);
+	}
+
+	/// Scatters many rays coming from above onto a surface of the `material` facing upwards,
+	/// and returns the average cosine between the incoming and scattered directions, along with
+	/// the fraction of rays scattered back above the surface.
+	fn scattering_statistics(material: &Material) -> (f64, f64) {
+		let normal = Vec3::new(0, 1, 0);
+		let hit = HitBuilder::new()
+			.t(1.0)
+			.point(Point::origin())
+			.normal(normal)
+			.front_face(true)
+			.material(material)
+			.build()
+			.unwrap();
+		let ray_in = Ray::new(Point::new(0, 1, 0), Vec3::new(0, -1, 0));
+
+		let samples = 20_000;
+		let directions = (0..samples)
+			.filter_map(|_| material.scatter(ray_in, hit))
+			.map(|ray| ray.direction.unit())
+			.collect::<Vec<_>>();
+		assert_eq!(directions.len(), samples, "every ray should scatter");
+		let mean_cos = directions
+			.iter()
+			.map(|dir| dir.dot(ray_in.direction))
+			.sum::<f64>()
+			/ samples as f64;
+		let above = directions
+			.iter()
+			.filter(|dir| dir.dot(normal) > 0.0)
+			.count();
+		(mean_cos, above as f64 / samples as f64)
+	}
+
+	#[test]
+	fn isotropic_scatters_equally_in_all_directions() {
+		let material = Material::Isotropic {
+			color: Color::white(),
+		};
+		// Scattered directions should be spread evenly, so they cancel out on average:
+		let (mean_cos, above) = scattering_statistics(&material);
+		assert!(
+			mean_cos.abs() < 0.03,
+			"average direction should be near zero, but cosine was {}",
+			mean_cos
+		);
+		assert!(
+			(above - 0.5).abs() < 0.03,
+			"half of the rays should scatter above the surface, but {} did",
+			above
+		);
+	}
+
+	#[test]
+	fn henyey_greenstein_average_cosine_is_asymmetry() {
+		// The average cosine of the scattering angle should be g, for forward and backward scattering:
+		for g in [-0.6, 0.0, 0.6] {
+			let material = Material::HenyeyGreenstein {
+				color: Color::white(),
+				g,
+			};
+			let (mean_cos, _) = scattering_statistics(&material);
+			assert!(
+				(mean_cos - g).abs() < 0.03,
+				"average cosine should be {}, but was {}",
+				g,
+				mean_cos
+			);
+		}
+	}
+
+	#[test]
+	fn every_material_survives_json_round_trip() {
+		// These are materials of every kind, with parameters that are exactly representable:
+		let materials = [
+			Material::Absorbant,
+			Material::Matte {
+				color: Color::new(0.25, 0.5, 0.75),
+			},
+			Material::Metal {
+				color: Color::new(0.125, 1, 0),
+				fuzz: 0.375,
+			},
+			Material::Dielectric { ridx: 1.5 },
+			Material::DispersiveDielectric {
+				ridx_r: 1.5,
+				ridx_g: 1.5625,
+				ridx_b: 1.625,
+			},
+			Material::mix(
+				Material::Absorbant,
+				Material::Dielectric { ridx: 2.5 },
+				0.25,
+			),
+			Material::two_sided(
+				Material::Matte {
+					color: Color::new(1, 0.5, 0),
+				},
+				Material::Metal {
+					color: Color::white(),
+					fuzz: 0.0,
+				},
+			),
+			Material::Isotropic {
+				color: Color::new(0.5, 0.5, 0.5),
+			},
+			Material::HenyeyGreenstein {
+				color: Color::new(1, 1, 0.75),
+				g: -0.625,
+			},
+			Material::Emissive {
+				color: Color::new(1, 0.75, 0.5),
+				intensity: 5.0,
+			},
+			Material::Subsurface {
+				color: Color::new(0.875, 0.5, 0.375),
+				scattering_radius: 0.25,
+				absorption: 2.5,
+			},
+		];
+		assert_eq!(
+			materials.each_ref().map(|m| m.name()),
+			all_materials().map(|m| m.name()),
+			"every kind of material should be tested"
+		);
+
+		for material in materials {
+			let json = material.to_json();
+			let parsed = Material::from_json(&json);
+			assert!(
+				parsed.is_ok(),
+				"material {} should be parsed, but error occurred: {:?}",
+				json,
+				parsed.err()
+			);
+			let parsed = parsed.unwrap();
+			assert_eq!(parsed, material, "parsed material should match");
+			// Serializing again should produce the same text, so no value was rounded:
+			assert_eq!(
+				parsed.to_json(),
+				json,
+				"material should serialize identically"
+			);
+		}
+	}
+
+	#[test]
+	fn dielectric_is_deserialized_with_refractive_index() {
+		let parsed = Material::from_json(r#"{"type":"dielectric","ridx":1.5}"#);
+		assert!(
+			matches!(parsed, Ok(Material::Dielectric { ridx }) if ridx.to_bits() == 1.5f64.to_bits()),
+			"dielectric should be parsed with index 1.5, but got {:?}",
+			parsed
+		);
+	}
+
+	#[test]
+	fn if_color_missing_then_default_is_used() {
+		let parsed = Material::from_json(r#"{"type":"matte"}"#);
+		assert_eq!(
+			parsed.ok(),
+			Some(Material::Matte {
+				color: Color::new(0.5, 0.5, 0.5)
+			}),
+			"matte material should be grey"
+		);
+		let parsed = Material::from_json(r#"{"type":"metal"}"#);
+		assert_eq!(
+			parsed.ok(),
+			Some(Material::Metal {
+				color: Color::new(0.8, 0.8, 0.8),
+				fuzz: 0.0
+			}),
+			"metal should be silver and perfectly reflective"
+		);
+
+		// An explicit color should override the default:
+		let parsed = Material::from_json(r#"{"type":"matte","color":[1,0,0]}"#);
+		assert_eq!(
+			parsed.ok(),
+			Some(Material::Matte {
+				color: Color::new(1, 0, 0)
+			}),
+			"matte material should be red"
+		);
+	}
+
+	#[test]
+	fn emissive_material_is_parsed_and_emits_light() {
+		let parsed = Material::from_json(r#"{"type":"emissive","color":[1,1,1],"intensity":5.0}"#);
+		assert_eq!(
+			parsed.ok(),
+			Some(Material::Emissive {
+				color: Color::white(),
+				intensity: 5.0
+			}),
+			"emissive material should be parsed"
+		);
+		let material = Material::from_json(r#"{"type":"emissive","color":[1,0.5,0]}"#).unwrap();
+		assert_eq!(
+			material.emittance(),
+			Color::new(1, 0.5, 0),
+			"intensity should be 1 by default"
+		);
+		assert!(
+			all_materials()
+				.iter()
+				.filter(|m| !matches!(m, Material::Emissive { .. }))
+				.all(|m| !m.is_emissive()),
+			"other materials should not emit light"
+		);
+	}
+
+	#[test]
+	fn if_material_type_unknown_then_parse_error() {
+		let parsed = Material::from_json(r#"{"type":"plasma","color":[1,0,0]}"#);
+		assert!(
+			matches!(&parsed, Err(RaytracerError::ParseError(msg)) if msg.contains("plasma")),
+			"unknown type should be a parse error naming it, but got {:?}",
+			parsed
+		);
+	}
+
+	#[test]
+	fn blend_is_deserialized_with_nested_materials() {
+		let json = r#"{
+			"type": "blend",
+			"a": { "type": "matte", "color": [1, 0, 0] },
+			"b": { "type": "dielectric", "ridx": 1.5 },
+			"weight": 0.3
+		}"#;
+		let expected = Material::mix(
+			Material::Matte {
+				color: Color::new(1, 0, 0),
+			},
+			Material::Dielectric { ridx: 1.5 },
+			0.3,
+		);
+		let parsed = serde_json::from_str::<Material>(json);
+		assert!(parsed.is_ok(), "blend should be parsed, but wasn't");
+		assert_eq!(parsed.unwrap(), expected, "nested materials should match");
+	}
+
+	#[test]
+	fn two_sided_scatters_off_material_of_hit_side() {
+		// This material is red matte on the front, and absorbs everything on the back:
+		let red = Color::new(1, 0, 0);
+		let material = Material::two_sided(Material::Matte { color: red }, Material::Absorbant);
+		let hit = |is_front_face| {
+			HitBuilder::new()
+				.t(1.0)
+				.point(Point::origin())
+				.normal(Vec3::new(0, 1, 0))
+				.front_face(is_front_face)
+				.material(&material)
+				.build()
+				.unwrap()
+		};
+		let ray_in = Ray::new(Point::new(0, 1, 0), Vec3::new(0, -1, 0));
+
+		// A ray from the front should scatter off the red matte material:
+		let front = material.scatter(ray_in, hit(true));
+		assert!(front.is_some(), "ray from the front should scatter");
+		assert_eq!(
+			front.unwrap().attenuation,
+			red,
+			"scattered ray should be red"
+		);
+		// A ray from the back should be absorbed:
+		assert!(
+			material.scatter(ray_in, hit(false)).is_none(),
+			"ray from the back should be absorbed"
+		);
+	}
+
+	#[test]
+	fn two_sided_is_deserialized_with_nested_materials() {
+		let json = r#"{
+			"type": "twoSided",
+			"front": { "type": "metal", "color": [1, 1, 1], "fuzz": 0 },
+			"back": { "type": "absorbant" }
+		}"#;
+		let expected = Material::two_sided(
+			Material::Metal {
+				color: Color::white(),
+				fuzz: 0.0,
+			},
+			Material::Absorbant,
+		);
+		let parsed = serde_json::from_str::<Material>(json);
+		assert!(
+			parsed.is_ok(),
+			"two-sided material should be parsed, but wasn't"
+		);
+		assert_eq!(parsed.unwrap(), expected, "nested materials should match");
+	}
+
+	/// Renders a sphere of the specified material in front of the sky, and returns the largest
+	/// deviation of any pixel from the colors the sky can have (white blended with light blue).
+	fn max_sky_deviation(material: Material) -> f64 {
+		let setup = CameraSetup {
+			width: 24,
+			height: 24,
+			v_fov: 60.0,
+			..Default::default()
+		};
+		let camera = Camera::from(setup).anti_aliasing(30).bounces(50).seed(5);
+		let scene = Scene::from([Sphere::new(Point::new(0, 0, -2), 1.0, material)]);
+		let image = camera.render(&scene);
+
+		// A blend of white and light blue (0.5, 0.7, 1.0) has a full blue channel, and its
+		// red channel is reduced by 5/3 as much as its green channel:
+		image
+			.into_iter()
+			.flatten()
+			.map(|pixel| {
+				let blue = (pixel.b() - 1.0).abs();
+				let red_green = ((1.0 - pixel.r()) - (1.0 - pixel.g()) * 5.0 / 3.0).abs();
+				f64::max(blue, red_green)
+			})
+			.fold(0.0, f64::max)
+	}
+
+	#[test]
+	fn dispersive_dielectric_splits_channels() {
+		// Without dispersion, every ray through the glass ends up in the sky, untinted:
+		let plain = max_sky_deviation(Material::Dielectric { ridx: 1.52 });
+		assert!(
+			plain < 1e-9,
+			"plain glass should only show sky colors, but deviated by {}",
+			plain
+		);
+
+		// With dispersion, the channels are refracted towards different parts of the sky:
+		let dispersive = max_sky_deviation(Material::DispersiveDielectric {
+			ridx_r: 1.50,
+			ridx_g: 1.52,
+			ridx_b: 1.54,
+		});
+		assert!(
+			dispersive > 0.1,
+			"dispersive glass should show color fringes, but deviated by only {}",
+			dispersive
+		);
+	}
+
+	/// Renders a sphere of the specified material, lit by a lamp from the right, whose terminator
+	/// lies around column 12.
+	fn render_lit_sphere(material: Material) -> Image {
+		let setup = CameraSetup {
+			width: 24,
+			height: 24,
+			v_fov: 60.0,
+			..Default::default()
+		};
+		let camera = Camera::from(setup).anti_aliasing(512).bounces(4).seed(7);
+		let lamp = Material::Emissive {
+			color: Color::white(),
+			intensity: 16.0,
+		};
+		let scene = Scene::from([
+			Sphere::new(Point::new(0, 0, -2), 1.0, material),
+			Sphere::new(Point::new(6, 0, -2), 1.5, lamp),
+		])
+		.with_background(Background::Solid(Color::black()));
+		camera.render(&scene)
+	}
+
+	/// Sums the colors of the pixels of `image` in the middle rows and the specified columns.
+	fn band_color(image: &Image, cols: Range<usize>) -> Color {
+		(8..16)
+			.flat_map(|row| cols.clone().map(move |col| (row, col)))
+			.fold(Color::black(), |sum, index| sum + image[index])
+	}
+
+	#[test]
+	fn subsurface_sphere_bleeds_light_past_terminator() {
+		// Without absorption, both materials attenuate light equally:
+		let color = Color::new(0.9, 0.4, 0.3);
+		let brightness = |material: Material| {
+			// These columns lie just left of the terminator, where the lamp is hidden behind the sphere:
+			let band = band_color(&render_lit_sphere(material), 7..11);
+			band.r() + band.g() + band.b()
+		};
+		let matte = brightness(Material::Matte { color });
+		let subsurface = brightness(Material::Subsurface {
+			color,
+			scattering_radius: 0.2,
+			absorption: 0.0,
+		});
+		assert!(
+			subsurface > 0.2 && subsurface > 10.0 * matte,
+			"subsurface scattering should bleed light into the shadow (brightness {}), \
+			 unlike matte (brightness {})",
+			subsurface,
+			matte
+		);
+	}
+
+	#[test]
+	fn subsurface_sphere_is_redder_than_matte_at_terminator() {
+		// With absorption, the dominant red channel should travel further than the others:
+		let color = Color::new(0.9, 0.4, 0.3);
+		let redness = |material: Material| {
+			// These columns lie just right of the terminator, where both materials are lit:
+			let band = band_color(&render_lit_sphere(material), 12..16);
+			band.r() / (band.g() + band.b())
+		};
+		let matte = redness(Material::Matte { color });
+		let subsurface = redness(Material::Subsurface {
+			color,
+			scattering_radius: 0.2,
+			absorption: 4.0,
+		});
+		assert!(
+			subsurface > 1.5 * matte,
+			"subsurface sphere should be redder at the terminator (ratio {}) than matte (ratio {})",
+			subsurface,
+			matte
+		);
+	}
+
+	#[test]
+	fn subsurface_light_leaves_on_surface() {
+		let material = Material::Subsurface {
+			color: Color::white(),
+			scattering_radius: 0.3,
+			absorption: 0.0,
+		};
+		let scene = Scene::from([Sphere::new(Point::origin(), 1.0, material.clone())]);
+		let ray_in = Ray::new(Point::new(0, 0, 5), Vec3::new(0, 0, -1));
+		let hit = scene.hit(ray_in, Interval::from(0.001)).unwrap();
+		for _ in 0..100 {
+			let ray_out = material.scatter_in(ray_in, hit, &scene).unwrap();
+			let radius = ray_out.origin.to_vec3().norm();
+			assert!(
+				(radius - 1.0).abs() < 1e-6,
+				"light should leave on the surface, but left at distance {} from the center",
+				radius
+			);
+		}
+	}
+
 	#[test]
 	fn reflected_ray_has_same_angle() {
 		// This incoming ray hits the surface at an angle:
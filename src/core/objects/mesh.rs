@@ -0,0 +1,418 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::objects::{Hit, HitBuilder, Hittable};
+use crate::core::types::{Aabb, Interval, Point, Ray, ToVec3, Transform, Vec3};
+
+use super::{Material, Object, ToObject};
+
+/// The thickness added to each axis of the bounding box.
+const BOUNDS_PADDING: f64 = 1e-6;
+
+/// A collection of triangles, which share their vertices and material.
+///
+/// Optionally, a mesh has a normal vector and texture coordinates for each vertex, which are
+/// interpolated across the triangles. Without normals, each triangle is shaded flat.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(try_from = "MeshData", into = "MeshData")]
+pub struct TriangleMesh {
+	/// The corner points of the triangles.
+	vertices: Vec<Point>,
+	/// The unit normal vector at each vertex, or empty for flat shading.
+	normals: Vec<Vec3>,
+	/// The texture coordinates `(u, v)` of each vertex, or empty.
+	uvs: Vec<(f64, f64)>,
+	/// The triangles, as indices of their three vertices.
+	faces: Vec<[usize; 3]>,
+	/// The material of the mesh's surface.
+	material: Material,
+	/// The box enclosing all vertices, or [`None`] if there are none.
+	bounds: Option<Aabb>,
+}
+
+/// The representation of a mesh in the input.
+#[derive(Deserialize, Serialize)]
+struct MeshData {
+	vertices: Vec<Point>,
+	#[serde(default)]
+	normals: Vec<Vec3>,
+	#[serde(default)]
+	uvs: Vec<(f64, f64)>,
+	faces: Vec<[usize; 3]>,
+	material: Material,
+}
+
+// Constructor
+impl TriangleMesh {
+	/// Creates a new mesh of triangles, each specified by the indices of its corners in `vertices`.
+	///
+	/// # Panics
+	/// Panics if a face refers to a vertex that doesn't exist.
+	pub fn new(vertices: Vec<Point>, faces: Vec<[usize; 3]>, material: Material) -> Self {
+		let data = MeshData {
+			vertices,
+			normals: vec![],
+			uvs: vec![],
+			faces,
+			material,
+		};
+		Self::try_from(data).unwrap_or_else(|e| panic!("{}", e))
+	}
+	/// Returns this mesh with the specified normal vectors, one for each vertex.
+	/// The normals are interpolated across each triangle for smooth shading.
+	///
+	/// # Panics
+	/// Panics if the amount of normals differs from the amount of vertices.
+	pub fn with_normals(self, normals: Vec<Vec3>) -> Self {
+		assert_eq!(
+			normals.len(),
+			self.vertices.len(),
+			"mesh should have a normal for each vertex"
+		);
+		let normals = normals.into_iter().map(Vec3::unit).collect();
+		Self { normals, ..self }
+	}
+	/// Returns this mesh with the specified texture coordinates `(u, v)`, one pair for each vertex.
+	///
+	/// # Panics
+	/// Panics if the amount of coordinates differs from the amount of vertices.
+	pub fn with_uvs(self, uvs: Vec<(f64, f64)>) -> Self {
+		assert_eq!(
+			uvs.len(),
+			self.vertices.len(),
+			"mesh should have texture coordinates for each vertex"
+		);
+		Self { uvs, ..self }
+	}
+}
+
+// Properties
+impl TriangleMesh {
+	/// The amount of triangles in this mesh.
+	pub fn len(&self) -> usize {
+		self.faces.len()
+	}
+	/// Checks whether this mesh contains no triangles.
+	pub fn is_empty(&self) -> bool {
+		self.faces.is_empty()
+	}
+	/// The amount of memory allocated for the vertices, normals, texture coordinates and faces, in bytes.
+	pub(crate) fn heap_bytes(&self) -> usize {
+		size_of_val(self.vertices.as_slice())
+			+ size_of_val(self.normals.as_slice())
+			+ size_of_val(self.uvs.as_slice())
+			+ size_of_val(self.faces.as_slice())
+	}
+	/// The material of the mesh.
+	pub fn material(&self) -> &Material {
+		&self.material
+	}
+	/// The total area of the triangles of the mesh.
+	pub fn surface_area(&self) -> f64 {
+		self.faces
+			.iter()
+			.map(|&[a, b, c]| {
+				let (a, b, c) = (self.vertices[a], self.vertices[b], self.vertices[c]);
+				let (ab, ac) = (b.to_vec3() - a.to_vec3(), c.to_vec3() - a.to_vec3());
+				ab.cross(ac).norm() / 2.0
+			})
+			.sum()
+	}
+	/// The center of the box enclosing this mesh, or the origin if it has no vertices.
+	pub fn center(&self) -> Point {
+		match self.bounds {
+			Some(aabb) => Point::new(
+				(aabb.x.start + aabb.x.end) / 2.0,
+				(aabb.y.start + aabb.y.end) / 2.0,
+				(aabb.z.start + aabb.z.end) / 2.0,
+			),
+			None => Point::origin(),
+		}
+	}
+	/// Returns this mesh, moved so that its [`TriangleMesh::center`] is the specified point.
+	pub(crate) fn moved_to(self, center: Point) -> Self {
+		let offset = center.to_vec3() - self.center().to_vec3();
+		let vertices = self
+			.vertices
+			.iter()
+			.map(|vertex| (vertex.to_vec3() + offset).into())
+			.collect::<Vec<_>>();
+		Self {
+			bounds: bounds_of(&vertices),
+			vertices,
+			..self
+		}
+	}
+	/// Returns this mesh, with each of its vertices and normals transformed.
+	///
+	/// A negative scale mirrors the mesh, so the winding of each face is reversed and the normals
+	/// are flipped, to keep them pointing outwards.
+	pub(crate) fn transformed(self, transform: &Transform) -> Self {
+		let mirrored = transform.scale < 0.0;
+		let vertices = self
+			.vertices
+			.iter()
+			.map(|&vertex| transform.apply_point(vertex))
+			.collect::<Vec<_>>();
+		let normals = self
+			.normals
+			.iter()
+			.map(|&normal| transform.apply_normal(normal))
+			.collect();
+		let faces = if mirrored {
+			self.faces.iter().map(|&[a, b, c]| [a, c, b]).collect()
+		} else {
+			self.faces
+		};
+		Self {
+			bounds: bounds_of(&vertices),
+			vertices,
+			normals,
+			faces,
+			..self
+		}
+	}
+}
+
+impl TryFrom<MeshData> for TriangleMesh {
+	type Error = String;
+	fn try_from(data: MeshData) -> Result<Self, Self::Error> {
+		let count = data.vertices.len();
+		if let Some(face) = data
+			.faces
+			.iter()
+			.find(|face| face.iter().any(|&i| i >= count))
+		{
+			return Err(format!(
+				"face {:?} refers to a vertex that doesn't exist (mesh has {} vertices)",
+				face, count
+			));
+		}
+		if !data.normals.is_empty() && data.normals.len() != count {
+			return Err(format!(
+				"mesh has {} vertices, but {} normals",
+				count,
+				data.normals.len()
+			));
+		}
+		if !data.uvs.is_empty() && data.uvs.len() != count {
+			return Err(format!(
+				"mesh has {} vertices, but {} uvs",
+				count,
+				data.uvs.len()
+			));
+		}
+		Ok(Self {
+			bounds: bounds_of(&data.vertices),
+			vertices: data.vertices,
+			normals: data.normals.into_iter().map(Vec3::unit).collect(),
+			uvs: data.uvs,
+			faces: data.faces,
+			material: data.material,
+		})
+	}
+}
+impl From<TriangleMesh> for MeshData {
+	fn from(mesh: TriangleMesh) -> Self {
+		Self {
+			vertices: mesh.vertices,
+			normals: mesh.normals,
+			uvs: mesh.uvs,
+			faces: mesh.faces,
+			material: mesh.material,
+		}
+	}
+}
+
+// Convert to Object
+impl ToObject for TriangleMesh {
+	fn wrap(self) -> Object {
+		Object::TriangleMesh(self)
+	}
+}
+
+// Intersection with rays
+impl Hittable for TriangleMesh {
+	/// Intersects the ray with every triangle (Möller–Trumbore algorithm), if it passes through the
+	/// bounding box of the mesh, and returns the closest intersection.
+	fn hit(&self, ray: Ray, t_range: Interval) -> Option<Hit<'_>> {
+		if !self.bounds?.ray_hit(ray, t_range) {
+			return None;
+		}
+		let mut t_max = t_range.end;
+		let mut closest = None;
+		for (index, face) in self.faces.iter().enumerate() {
+			if let Some((t, u, v)) = self.hit_face(face, ray, Interval::new(t_range.start, t_max)) {
+				t_max = t;
+				closest = Some((index, t, u, v));
+			}
+		}
+		let (index, t, u, v) = closest?;
+
+		// Interpolate with barycentric coordinates
+		let [a, b, c] = self.faces[index];
+		let w = 1.0 - u - v;
+		let outward_normal = if self.normals.is_empty() {
+			let (p0, p1, p2) = (
+				self.vertices[a].to_vec3(),
+				self.vertices[b].to_vec3(),
+				self.vertices[c].to_vec3(),
+			);
+			(p1 - p0).cross(p2 - p0).unit()
+		} else {
+			(self.normals[a].scale(w) + self.normals[b].scale(u) + self.normals[c].scale(v)).unit()
+		};
+		let (tex_u, tex_v) = if self.uvs.is_empty() {
+			(u, v)
+		} else {
+			let (uv_a, uv_b, uv_c) = (self.uvs[a], self.uvs[b], self.uvs[c]);
+			(
+				w * uv_a.0 + u * uv_b.0 + v * uv_c.0,
+				w * uv_a.1 + u * uv_b.1 + v * uv_c.1,
+			)
+		};
+
+		let (normal, is_front_face) = Hit::determine_front_face(ray, outward_normal);
+		Some(
+			HitBuilder::new()
+				.t(t)
+				.point(ray.at(t))
+				.normal(normal)
+				.front_face(is_front_face)
+				.material(&self.material)
+				.uv(tex_u, tex_v)
+				.build()
+				.expect("hit should have all values"),
+		)
+	}
+	fn bounding_box(&self) -> Option<Aabb> {
+		self.bounds
+	}
+}
+
+impl TriangleMesh {
+	/// Calculates the intersection of the ray with a single triangle.
+	/// Returns the parameter `t` and the barycentric coordinates `(u, v)` of the intersection point,
+	/// or [`None`] if the ray misses the triangle or `t` lies outside of `t_range`.
+	fn hit_face(&self, face: &[usize; 3], ray: Ray, t_range: Interval) -> Option<(f64, f64, f64)> {
+		let p0 = self.vertices[face[0]].to_vec3();
+		let edge_1 = self.vertices[face[1]].to_vec3() - p0;
+		let edge_2 = self.vertices[face[2]].to_vec3() - p0;
+
+		let p = ray.direction.cross(edge_2);
+		let det = edge_1.dot(p);
+		if det.abs() < f64::EPSILON {
+			return None; // ray is parallel to the triangle
+		}
+		let inv_det = 1.0 / det;
+		let s = ray.origin.to_vec3() - p0;
+		let u = s.dot(p) * inv_det;
+		if !(0.0..=1.0).contains(&u) {
+			return None;
+		}
+		let q = s.cross(edge_1);
+		let v = ray.direction.dot(q) * inv_det;
+		if v < 0.0 || u + v > 1.0 {
+			return None;
+		}
+		let t = edge_2.dot(q) * inv_det;
+		t_range.surrounds(t).then_some((t, u, v))
+	}
+}
+
+/// Calculates the smallest box enclosing all of the specified points.
+/// The box is padded slightly, so that flat meshes have a box with some thickness.
+fn bounds_of(vertices: &[Point]) -> Option<Aabb> {
+	vertices
+		.iter()
+		.map(|&vertex| Aabb::from_corners(vertex, vertex))
+		.reduce(Aabb::merge)
+		.map(|aabb| {
+			Aabb::new(
+				aabb.x.pad(BOUNDS_PADDING),
+				aabb.y.pad(BOUNDS_PADDING),
+				aabb.z.pad(BOUNDS_PADDING),
+			)
+		})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::TriangleMesh;
+	use crate::core::objects::{Hittable, Material, Object};
+	use crate::core::types::{Interval, Point, Ray, Transform, Vec3};
+
+	/// A unit square in the xy-plane, made of two triangles.
+	fn square() -> TriangleMesh {
+		let vertices = vec![
+			Point::new(0, 0, 0),
+			Point::new(1, 0, 0),
+			Point::new(1, 1, 0),
+			Point::new(0, 1, 0),
+		];
+		TriangleMesh::new(vertices, vec![[0, 1, 2], [0, 2, 3]], Material::Absorbant)
+	}
+
+	#[test]
+	fn if_ray_hits_triangle_then_some_and_correct_intersect() {
+		let mesh = square();
+		// This ray shoots along the z-axis onto the second triangle:
+		let ray = Ray::new(Point::new(0.25, 0.75, 5), Vec3::new(0, 0, -1));
+
+		// The ray should hit the square at z = 0, with the normal facing the ray:
+		let hit = mesh.hit(ray, Interval::from(0));
+		assert!(hit.is_some(), "ray should hit the mesh, but returned None");
+		let hit = hit.unwrap();
+		assert_eq!(
+			hit.point,
+			Point::new(0.25, 0.75, 0),
+			"ray should hit the square"
+		);
+		assert_eq!(hit.normal, Vec3::new(0, 0, 1), "normal should face the ray");
+	}
+
+	#[test]
+	fn if_mesh_mirrored_then_normals_still_point_outwards() {
+		// These squares face +z, once with flat and once with smooth shading:
+		let smooth = square().with_normals(vec![Vec3::new(0, 0, 1); 4]);
+		let transform = Transform {
+			scale: -1.0,
+			..Transform::identity()
+		};
+		for mesh in [square(), smooth] {
+			// Mirrored through the origin, the squares should face -z:
+			let mesh = mesh.transformed(&transform);
+			let ray = Ray::new(Point::new(-0.25, -0.75, -5), Vec3::new(0, 0, 1));
+
+			let hit = mesh.hit(ray, Interval::from(0));
+			assert!(hit.is_some(), "ray should hit the mesh, but returned None");
+			let hit = hit.unwrap();
+			assert!(hit.is_front_face, "ray should hit the front face");
+			assert_eq!(
+				hit.normal,
+				Vec3::new(0, 0, -1),
+				"normal should face the ray"
+			);
+		}
+	}
+
+	#[test]
+	fn if_ray_misses_triangles_then_none() {
+		let mesh = square();
+		// This ray shoots along the z-axis, next to the square:
+		let ray = Ray::new(Point::new(1.5, 0.5, 5), Vec3::new(0, 0, -1));
+		assert!(
+			mesh.hit(ray, Interval::from(0)).is_none(),
+			"ray should miss the mesh, but returned Some"
+		);
+	}
+
+	#[test]
+	fn if_face_refers_to_missing_vertex_then_parsing_fails() {
+		// This fragment describes a triangle with a corner index out of bounds:
+		let json = r#"{"type":"triangleMesh","vertices":[[0,0,0],[1,0,0],[0,1,0]],"faces":[[0,1,3]],"material":{"type":"absorbant"}}"#;
+		assert!(
+			Object::from_json(json).is_err(),
+			"mesh parsing should fail, but didn't"
+		);
+	}
+}
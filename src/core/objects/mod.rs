@@ -1,7 +1,20 @@
+mod cylinder;
+mod ground;
+mod group;
 mod hit;
 mod material;
+mod mesh;
+mod moving_sphere;
+mod shell;
 mod sphere;
 
-pub use hit::{Hit, Hittable, Object, ToObject};
+pub use cylinder::InfiniteCylinder;
+pub use ground::Ground;
+pub use group::{Group, GroupId};
+pub(crate) use hit::closest_hit;
+pub use hit::{Hit, HitBuilder, Hittable, Object, ToObject};
 pub use material::Material;
+pub use mesh::TriangleMesh;
+pub use moving_sphere::MovingSphere;
+pub use shell::ShellSphere;
 pub use sphere::Sphere;
@@ -0,0 +1,155 @@
+use std::f64::consts::PI;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::objects::{Hit, Hittable};
+use crate::core::types::{Aabb, Interval, Point, Ray, ToVec3, Transform};
+
+use super::sphere::hit_sphere;
+use super::{Material, Object, Sphere, ToObject};
+
+/// A 3D sphere that moves in a straight line over time.
+///
+/// The sphere is centered at `start` at time 0, and at `end` at time 1.
+/// At other times, its center is interpolated (or extrapolated) linearly.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct MovingSphere {
+	/// The center of the sphere at time 0.
+	start: Point,
+	/// The center of the sphere at time 1.
+	end: Point,
+	/// The radius of the sphere.
+	radius: f64,
+	/// The material of the sphere's surface.
+	material: Material,
+}
+
+// Constructor
+impl MovingSphere {
+	/// Creates a new 3D sphere which moves from `start` (at time 0) to `end` (at time 1).
+	/// If `radius` is negative, a radius of 0 is assumed.
+	pub fn new<F: Into<f64>>(start: Point, end: Point, radius: F, material: Material) -> Self {
+		Self {
+			start,
+			end,
+			radius: f64::max(0.0, radius.into()),
+			material,
+		}
+	}
+}
+
+// Motion
+impl MovingSphere {
+	/// The radius of the sphere.
+	pub fn radius(&self) -> f64 {
+		self.radius
+	}
+	/// The material of the sphere.
+	pub fn material(&self) -> &Material {
+		&self.material
+	}
+	/// The area of the surface of the sphere.
+	pub fn surface_area(&self) -> f64 {
+		4.0 * PI * self.radius * self.radius
+	}
+	/// Calculates the center of the sphere at the specified point in `time`.
+	pub fn center_at(&self, time: f64) -> Point {
+		let offset = (self.end.to_vec3() - self.start.to_vec3()).scale(time);
+		(self.start.to_vec3() + offset).into()
+	}
+	/// Returns this sphere, moved so that its center at time 0 is the specified point.
+	pub(crate) fn moved_to(self, start: Point) -> Self {
+		let offset = start.to_vec3() - self.start.to_vec3();
+		Self {
+			start,
+			end: (self.end.to_vec3() + offset).into(),
+			..self
+		}
+	}
+	/// Returns this sphere, with both of its centers transformed and its radius scaled.
+	pub(crate) fn transformed(self, transform: &Transform) -> Self {
+		Self {
+			start: transform.apply_point(self.start),
+			end: transform.apply_point(self.end),
+			radius: self.radius * transform.scale.abs(),
+			..self
+		}
+	}
+}
+
+// Convert to Object
+impl ToObject for MovingSphere {
+	fn wrap(self) -> Object {
+		Object::MovingSphere(self)
+	}
+}
+
+// Intersection with rays
+impl Hittable for MovingSphere {
+	fn hit(&self, ray: Ray, t_range: Interval) -> Option<Hit<'_>> {
+		let center = self.center_at(ray.time);
+		hit_sphere(center, self.radius, &self.material, ray, t_range)
+	}
+	/// Calculates the box enclosing the sphere during its movement from time 0 to time 1.
+	fn bounding_box(&self) -> Option<Aabb> {
+		let bounds_at =
+			|center| Sphere::new(center, self.radius, Material::Absorbant).bounding_box();
+		Some(Aabb::merge(bounds_at(self.start)?, bounds_at(self.end)?))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::MovingSphere;
+	use crate::core::objects::{Hittable, Material};
+	use crate::core::types::{Interval, Point, Ray, Vec3};
+
+	#[test]
+	fn if_hit_at_different_times_then_different_points() {
+		// This sphere moves from left to right, and is hit by rays looking along the x-axis:
+		let sphere = MovingSphere::new(
+			Point::new(-1, 0, 0),
+			Point::new(1, 0, 0),
+			0.5,
+			Material::Absorbant,
+		);
+		let ray_at = |time| Ray::with_time(Point::new(-5, 0, 0), Vec3::new(1, 0, 0), time);
+
+		let early = sphere.hit(ray_at(0.0), Interval::from(0.001));
+		let late = sphere.hit(ray_at(1.0), Interval::from(0.001));
+		assert!(
+			early.is_some() && late.is_some(),
+			"sphere should be hit at both times"
+		);
+		let (early, late) = (early.unwrap().point, late.unwrap().point);
+		assert_eq!(
+			early,
+			Point::new(-1.5, 0, 0),
+			"sphere should be hit at its start"
+		);
+		assert_eq!(
+			late,
+			Point::new(0.5, 0, 0),
+			"sphere should be hit at its end"
+		);
+	}
+
+	#[test]
+	fn bounding_box_encloses_whole_path() {
+		let sphere = MovingSphere::new(
+			Point::new(0, 0, 0),
+			Point::new(0, 4, 0),
+			1,
+			Material::Absorbant,
+		);
+		let aabb = sphere.bounding_box().unwrap();
+		for time in [0.0, 0.25, 0.5, 1.0] {
+			let center = sphere.center_at(time);
+			assert!(
+				aabb.y.start <= center.y() - 1.0 && center.y() + 1.0 <= aabb.y.end,
+				"box should enclose the sphere at time {}",
+				time
+			);
+		}
+	}
+}
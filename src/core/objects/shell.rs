@@ -0,0 +1,221 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::objects::{Hit, Hittable};
+use crate::core::types::{Aabb, Interval, Point, Ray, Transform};
+
+use super::{Material, Object, Sphere, ToObject};
+
+/// A hollow sphere, such as a glass bubble, whose wall lies between two concentric spheres.
+///
+/// Rays can hit both the outer and the inner surface. The inner surface has the same material as
+/// the outer one, with inverted refractive indices, so that rays leave the wall into the air inside.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(from = "ShellJson", into = "ShellJson")]
+pub struct ShellSphere {
+	/// The outer surface of the shell.
+	outer: Sphere,
+	/// The inner surface of the shell, with the inverted material.
+	inner: Sphere,
+}
+
+// Constructor
+impl ShellSphere {
+	/// Creates a new hollow sphere with the specified center point, outer and inner radius.
+	/// See [`Sphere::shell`].
+	pub fn new<F: Into<f64>>(
+		center: Point,
+		outer_radius: F,
+		inner_radius: F,
+		material: Material,
+	) -> Self {
+		let (outer, inner) = Sphere::shell(center, outer_radius, inner_radius, material);
+		Self { outer, inner }
+	}
+}
+
+// Position
+impl ShellSphere {
+	/// The coordinates of the center of the shell.
+	pub fn center(&self) -> Point {
+		self.outer.center()
+	}
+	/// The radius of the outer surface of the shell.
+	pub fn outer_radius(&self) -> f64 {
+		self.outer.radius()
+	}
+	/// The radius of the inner surface of the shell.
+	pub fn inner_radius(&self) -> f64 {
+		self.inner.radius()
+	}
+	/// The material of the shell (of its outer surface).
+	pub fn material(&self) -> &Material {
+		self.outer.material()
+	}
+	/// The area of both surfaces of the shell.
+	pub fn surface_area(&self) -> f64 {
+		self.outer.surface_area() + self.inner.surface_area()
+	}
+	/// Returns this shell, centered at the specified point.
+	pub(crate) fn moved_to(self, center: Point) -> Self {
+		Self {
+			outer: self.outer.moved_to(center),
+			inner: self.inner.moved_to(center),
+		}
+	}
+	/// Returns this shell, with its center transformed and both radii scaled.
+	pub(crate) fn transformed(self, transform: &Transform) -> Self {
+		Self {
+			outer: self.outer.transformed(transform),
+			inner: self.inner.transformed(transform),
+		}
+	}
+}
+
+// Convert to Object
+impl ToObject for ShellSphere {
+	fn wrap(self) -> Object {
+		Object::ShellSphere(self)
+	}
+}
+
+// Intersection with rays
+impl Hittable for ShellSphere {
+	fn hit(&self, ray: Ray, t_range: Interval) -> Option<Hit<'_>> {
+		let outer = self.outer.hit(ray, t_range);
+		let t_max = outer.map_or(t_range.end, |hit| hit.t);
+		self.inner
+			.hit(ray, Interval::new(t_range.start, t_max))
+			.or(outer)
+	}
+	fn bounding_box(&self) -> Option<Aabb> {
+		self.outer.bounding_box()
+	}
+}
+
+/// Returns the material of the inner surface of a shell made of `material`:
+/// refractive indices are inverted, other materials stay the same.
+pub(super) fn inner_material(material: Material) -> Material {
+	match material {
+		Material::Dielectric { ridx } => Material::Dielectric { ridx: 1.0 / ridx },
+		Material::DispersiveDielectric {
+			ridx_r,
+			ridx_g,
+			ridx_b,
+		} => Material::DispersiveDielectric {
+			ridx_r: 1.0 / ridx_r,
+			ridx_g: 1.0 / ridx_g,
+			ridx_b: 1.0 / ridx_b,
+		},
+		other => other,
+	}
+}
+
+/// The JSON representation of a shell, which describes both surfaces at once.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ShellJson {
+	/// The coordinates of the center of the shell.
+	center: Point,
+	/// The radius of the outer surface of the shell.
+	outer_radius: f64,
+	/// The radius of the inner surface of the shell.
+	inner_radius: f64,
+	/// The material of the shell.
+	material: Material,
+}
+impl From<ShellJson> for ShellSphere {
+	fn from(json: ShellJson) -> Self {
+		Self::new(
+			json.center,
+			json.outer_radius,
+			json.inner_radius,
+			json.material,
+		)
+	}
+}
+impl From<ShellSphere> for ShellJson {
+	fn from(shell: ShellSphere) -> Self {
+		Self {
+			center: shell.center(),
+			outer_radius: shell.outer_radius(),
+			inner_radius: shell.inner_radius(),
+			material: shell.outer.material().clone(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::ShellSphere;
+	use crate::core::objects::{Hittable, Material, Object};
+	use crate::core::types::{Interval, Point, Ray, Vec3};
+
+	/// Creates a glass shell of outer radius 1 and inner radius 0.8, centered at the origin.
+	fn glass_shell() -> ShellSphere {
+		ShellSphere::new(
+			Point::origin(),
+			1.0,
+			0.8,
+			Material::Dielectric { ridx: 1.5 },
+		)
+	}
+
+	#[test]
+	fn ray_hits_outer_then_inner_surface() {
+		let shell = glass_shell();
+		let ray = Ray::new(Point::new(-5, 0, 0), Vec3::new(1, 0, 0));
+
+		// Each search starts just behind the previous surface:
+		let xs = [0.001, 4.001, 4.201, 5.801].map(|start| {
+			shell
+				.hit(ray, Interval::new(start, f64::INFINITY))
+				.map_or(f64::NAN, |hit| hit.point.x())
+		});
+		let expected = [-1.0, -0.8, 0.8, 1.0];
+		assert!(
+			xs.iter().zip(expected).all(|(x, e)| (x - e).abs() < 1e-9),
+			"ray should cross all four surfaces at {:?}, but crossed at {:?}",
+			expected,
+			xs
+		);
+	}
+
+	#[test]
+	fn ray_through_glass_shell_exits_without_deflection() {
+		let shell = glass_shell();
+		let initial = Ray::new(Point::new(-5, 0, 0), Vec3::new(1, 0, 0));
+
+		// Rays are sometimes reflected, so only those leaving on the far side are checked:
+		let mut transmitted = 0;
+		for _ in 0..100 {
+			let mut ray = initial;
+			while let Some(hit) = shell.hit(ray, Interval::new(0.001, f64::INFINITY)) {
+				ray = hit.material.scatter(ray, hit).unwrap();
+			}
+			if ray.origin.x() > 0.0 {
+				transmitted += 1;
+				let direction = ray.direction.unit();
+				assert!(
+					(direction - initial.direction).norm() < 1e-9,
+					"ray should exit in its initial direction, but exited in {}",
+					direction
+				);
+			}
+		}
+		assert!(transmitted > 50, "most rays should pass through the shell");
+	}
+
+	#[test]
+	fn shell_is_deserialized_from_json() {
+		let json = r#"{
+			"type": "shellSphere", "center": [0, 0, 0], "outerRadius": 1, "innerRadius": 0.8,
+			"material": { "type": "dielectric", "ridx": 1.5 }
+		}"#;
+		let parsed = Object::from_json(json);
+		assert!(
+			matches!(&parsed, Ok(Object::ShellSphere(s)) if *s == glass_shell()),
+			"shell should be parsed, but got {:?}",
+			parsed
+		);
+	}
+}
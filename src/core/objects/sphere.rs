@@ -1,12 +1,16 @@
-use serde::Deserialize;
+use std::f64::consts::PI;
 
-use crate::core::objects::{Hit, Hittable};
-use crate::core::types::{Interval, Point, Ray, ToVec3};
+use serde::{Deserialize, Serialize};
 
-use super::{Material, ToObject};
+use crate::core::error::RaytracerError;
+use crate::core::objects::{Hit, HitBuilder, Hittable, Object, TriangleMesh};
+use crate::core::random;
+use crate::core::types::{Aabb, Interval, Point, Ray, ToVec3, Transform, Vec3};
+
+use super::{Material, ToObject, shell};
 
 /// A 3D sphere.
-#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct Sphere {
 	/// The coordinates of the center of the sphere.
 	center: Point,
@@ -27,6 +31,214 @@ impl Sphere {
 			material,
 		}
 	}
+	/// Creates the two surfaces of a hollow sphere with the specified center point: an outer sphere
+	/// of the `material`, and an inner sphere whose material has inverted refractive indices
+	/// (if it is a dielectric), so that rays pass from the wall into the air inside.
+	///
+	/// The inner radius is at most as large as the outer radius.
+	/// See [`crate::objects::ShellSphere`] for a single object with both surfaces.
+	pub fn shell<F: Into<f64>>(
+		center: Point,
+		outer_radius: F,
+		inner_radius: F,
+		material: Material,
+	) -> (Sphere, Sphere) {
+		let outer = Self::new(center, outer_radius, material.clone());
+		let inner_radius = f64::min(inner_radius.into(), outer.radius);
+		let inner = Self::new(center, inner_radius, shell::inner_material(material));
+		(outer, inner)
+	}
+}
+
+// Position
+impl Sphere {
+	/// The coordinates of the center of the sphere.
+	pub fn center(&self) -> Point {
+		self.center
+	}
+	/// The radius of the sphere.
+	pub fn radius(&self) -> f64 {
+		self.radius
+	}
+	/// The material of the sphere.
+	pub fn material(&self) -> &Material {
+		&self.material
+	}
+	/// The area of the surface of the sphere.
+	pub fn surface_area(&self) -> f64 {
+		4.0 * PI * self.radius * self.radius
+	}
+	/// Returns this sphere, centered at the specified point.
+	pub(crate) fn moved_to(self, center: Point) -> Self {
+		Self { center, ..self }
+	}
+	/// Returns this sphere, with its center transformed and its radius scaled.
+	pub(crate) fn transformed(self, transform: &Transform) -> Self {
+		Self {
+			center: transform.apply_point(self.center),
+			radius: self.radius * transform.scale.abs(),
+			..self
+		}
+	}
+}
+
+// JSON
+impl Sphere {
+	/// Parses a sphere from a JSON fragment with `"type": "sphere"`.
+	/// Returns an error if the fragment is malformed or describes a different type of object.
+	pub fn from_json(s: &str) -> Result<Self, RaytracerError> {
+		match Object::from_json(s)? {
+			Object::Sphere(sphere) => Ok(sphere),
+			other => {
+				let found = serde_json::to_value(&other).map(|json| json["type"].clone());
+				Err(RaytracerError::ParseError(format!(
+					"expected a sphere, but found {}",
+					found.unwrap_or_default()
+				)))
+			}
+		}
+	}
+}
+
+// Texture coordinates
+impl Sphere {
+	/// Calculates the texture coordinates `(u, v)` of a point on the surface of a sphere
+	/// with the specified center and radius. Both coordinates lie in `[0, 1]`.
+	///
+	/// The coordinate `u` is the angle around the y-axis, starting at `-x`;
+	/// the coordinate `v` is the angle from the bottom (`-y`) to the top (`+y`) pole.
+	pub fn uv_at(center: Point, point: Point, radius: f64) -> (f64, f64) {
+		let p = (point.to_vec3() - center) / radius;
+		let theta = f64::acos(-p.y());
+		let phi = f64::atan2(-p.z(), p.x()) + PI;
+		(phi / (2.0 * PI), theta / PI)
+	}
+	/// Calculates the texture coordinates `(u, v)` of a point on the surface of this sphere.
+	/// See [`Sphere::uv_at`].
+	pub fn uv_at_surface(&self, surface_point: Point) -> (f64, f64) {
+		Self::uv_at(self.center, surface_point, self.radius)
+	}
+}
+
+// Tessellation
+impl Sphere {
+	/// Approximates this sphere with a mesh of triangles (a UV sphere), which has the same material.
+	///
+	/// The surface is divided into `subdivisions` bands of latitude and of longitude (at least 3 each),
+	/// and each resulting patch is split into two triangles. Patches at the poles are triangles already.
+	/// The vertices have the same normals and texture coordinates as the sphere at their position.
+	pub fn to_mesh(&self, subdivisions: u32) -> TriangleMesh {
+		let bands = u32::max(3, subdivisions) as usize;
+		let mut vertices = Vec::with_capacity((bands + 1) * (bands + 1));
+		let mut normals = Vec::with_capacity(vertices.capacity());
+		let mut uvs = Vec::with_capacity(vertices.capacity());
+		for row in 0..=bands {
+			for col in 0..=bands {
+				// Inverse of `Sphere::uv_at`
+				let (u, v) = (col as f64 / bands as f64, row as f64 / bands as f64);
+				let (theta, phi) = (v * PI, u * 2.0 * PI - PI);
+				let normal = Vec3::new(
+					theta.sin() * phi.cos(),
+					-theta.cos(),
+					-theta.sin() * phi.sin(),
+				);
+				vertices.push((self.center.to_vec3() + normal.scale(self.radius)).into());
+				normals.push(normal);
+				uvs.push((u, v));
+			}
+		}
+
+		let mut faces = Vec::with_capacity(2 * bands * bands);
+		for row in 0..bands {
+			for col in 0..bands {
+				let (a, c) = (row * (bands + 1) + col, (row + 1) * (bands + 1) + col);
+				let (b, d) = (a + 1, c + 1);
+				// The vertices of the bottom and top row coincide at the poles
+				if row != 0 {
+					faces.push([a, b, d]);
+				}
+				if row != bands - 1 {
+					faces.push([a, d, c]);
+				}
+			}
+		}
+		TriangleMesh::new(vertices, faces, self.material.clone())
+			.with_normals(normals)
+			.with_uvs(uvs)
+	}
+}
+
+// Geometry queries
+impl Sphere {
+	/// Calculates where the line segment from `p0` to `p1` first crosses the surface of this sphere.
+	///
+	/// Returns the parameter `t` in `[0, 1]` of the intersection point `p0 + t * (p1 - p0)`,
+	/// or [`None`] if the segment doesn't cross the surface (for example, if it lies completely
+	/// inside or outside of the sphere). This is useful for collision detection.
+	pub fn intersect_segment(&self, p0: Point, p1: Point) -> Option<f64> {
+		let ray = Ray::new(p0, p1.to_vec3() - p0.to_vec3());
+		let t_range = Interval::new(-f64::EPSILON, 1.0 + f64::EPSILON);
+		hit_sphere(self.center, self.radius, &self.material, ray, t_range)
+			.map(|hit| hit.t.clamp(0.0, 1.0))
+	}
+	/// Calculates the point on the surface of this sphere that is closest to `p`.
+	/// If `p` is the center of the sphere, the topmost point of the sphere is returned.
+	pub fn closest_point_to(&self, p: Point) -> Point {
+		let offset = p.to_vec3() - self.center.to_vec3();
+		let direction = if offset.norm_sq() == 0.0 {
+			Vec3::new(0, 1, 0)
+		} else {
+			offset.unit()
+		};
+		(self.center.to_vec3() + direction.scale(self.radius)).into()
+	}
+}
+
+// Light sampling
+impl Sphere {
+	/// Generates a random point on the surface of this sphere, uniformly distributed over its area.
+	pub fn random_surface_point(&self) -> Point {
+		(self.center.to_vec3() + Vec3::random_unit().scale(self.radius)).into()
+	}
+	/// Calculates the probability density (over solid angle) with which
+	/// [`Sphere::random_direction_from`] generates `direction` from `origin`.
+	///
+	/// Outside of the sphere, this is the inverse of the solid angle the sphere covers, or 0 if
+	/// `direction` misses the sphere. Within the sphere, all directions are equally likely.
+	pub fn pdf_value(&self, origin: Point, direction: Vec3) -> f64 {
+		let distance_sq = (self.center.to_vec3() - origin.to_vec3()).norm_sq();
+		let radius_sq = self.radius * self.radius;
+		if distance_sq <= radius_sq {
+			return 1.0 / (4.0 * PI);
+		}
+		if self
+			.hit(Ray::new(origin, direction), Interval::from(0.001))
+			.is_none()
+		{
+			return 0.0;
+		}
+		let cos_theta_max = f64::sqrt(1.0 - radius_sq / distance_sq);
+		1.0 / (2.0 * PI * (1.0 - cos_theta_max))
+	}
+	/// Generates a random unit vector from `origin` towards a point on the surface of this sphere.
+	///
+	/// Outside of the sphere, the directions are uniformly distributed over the cone of directions
+	/// that hit the sphere (which only reaches the visible side of its surface), so that
+	/// [`Sphere::pdf_value`] is their density. Within the sphere, any direction is returned.
+	pub fn random_direction_from(&self, origin: Point) -> Vec3 {
+		let offset = self.center.to_vec3() - origin.to_vec3();
+		let distance_sq = offset.norm_sq();
+		let radius_sq = self.radius * self.radius;
+		if distance_sq <= radius_sq {
+			return Vec3::random_unit();
+		}
+		let cos_theta_max = f64::sqrt(1.0 - radius_sq / distance_sq);
+		let (r1, r2) = (random::random::<f64>(), random::random::<f64>());
+		let z = 1.0 + r2 * (cos_theta_max - 1.0);
+		let phi = 2.0 * PI * r1;
+		let sin_theta = f64::sqrt(1.0 - z * z);
+		Vec3(phi.cos() * sin_theta, phi.sin() * sin_theta, z).orient(offset.unit())
+	}
 }
 
 // Convert to Object
@@ -38,50 +250,160 @@ impl ToObject for Sphere {
 
 // Intersection with rays
 impl Hittable for Sphere {
-	fn hit(&self, ray: Ray, t_range: Interval) -> Option<Hit> {
-		// Solve quadratic equation
-		let cq = self.center.to_vec3() - ray.origin;
-		let a = ray.direction.norm_sq();
-		let h = ray.direction.dot(cq);
-		let c = cq.norm_sq() - self.radius * self.radius;
-
-		let discr = h * h - a * c;
-		if discr < 0.0 {
-			return None;
-		}
+	fn hit(&self, ray: Ray, t_range: Interval) -> Option<Hit<'_>> {
+		hit_sphere(self.center, self.radius, &self.material, ray, t_range)
+	}
+	fn bounding_box(&self) -> Option<Aabb> {
+		let extent = Vec3::diagonal(self.radius);
+		Some(Aabb::from_corners(
+			(self.center.to_vec3() - extent).into(),
+			(self.center.to_vec3() + extent).into(),
+		))
+	}
+}
 
-		let discr_sqrt = discr.sqrt();
-		let t1 = (h - discr_sqrt) / a;
-		let t2 = (h + discr_sqrt) / a;
+/// Calculates the intersection of the ray with a sphere of the specified center and radius.
+/// See [`Hittable::hit`].
+pub(super) fn hit_sphere(
+	center: Point,
+	radius: f64,
+	material: &Material,
+	ray: Ray,
+	t_range: Interval,
+) -> Option<Hit<'_>> {
+	// Solve quadratic equation
+	let cq = center.to_vec3() - ray.origin;
+	let a = ray.direction.norm_sq();
+	let h = ray.direction.dot(cq);
+	let c = cq.norm_sq() - radius * radius;
 
-		// Choose a plausible root
-		let t = if t_range.surrounds(t1) {
-			t1
-		} else if t_range.surrounds(t2) {
-			t2
-		} else {
-			return None;
-		};
+	let discr = h * h - a * c;
+	if discr < 0.0 {
+		return None;
+	}
 
-		let point = ray.at(t);
-		let outward_normal = (point.to_vec3() - self.center) / self.radius;
+	let discr_sqrt = discr.sqrt();
+	let t1 = (h - discr_sqrt) / a;
+	let t2 = (h + discr_sqrt) / a;
 
-		let (normal, is_front_face) = Hit::determine_front_face(ray, outward_normal);
-		Some(Hit {
-			t,
-			point,
-			normal,
-			is_front_face,
-			material: self.material,
-		})
-	}
+	// Choose a plausible root
+	let t = if t_range.surrounds(t1) {
+		t1
+	} else if t_range.surrounds(t2) {
+		t2
+	} else {
+		return None;
+	};
+
+	let point = ray.at(t);
+	let outward_normal = (point.to_vec3() - center) / radius;
+
+	let (normal, is_front_face) = Hit::determine_front_face(ray, outward_normal);
+	let (u, v) = Sphere::uv_at(center, point, radius);
+	Some(
+		HitBuilder::new()
+			.t(t)
+			.point(point)
+			.normal(normal)
+			.front_face(is_front_face)
+			.material(material)
+			.uv(u, v)
+			.build()
+			.expect("hit should have all values"),
+	)
 }
 
 #[cfg(test)]
 mod tests {
 	use super::Sphere;
-	use crate::core::objects::{Hittable, Material};
-	use crate::core::types::{Interval, Point, Ray, Vec3};
+	use crate::core::camera::{Camera, CameraSetup};
+	use crate::core::error::RaytracerError;
+	use crate::core::objects::{Hittable, Material, Object};
+	use crate::core::scene::Scene;
+	use crate::core::types::{Color, Interval, Point, Ray, ToVec3, Vec3};
+
+	/// Checks whether two `(u, v)` pairs are approximately equal.
+	fn uv_approx_eq(a: (f64, f64), b: (f64, f64)) -> bool {
+		f64::abs(a.0 - b.0) < 1e-10 && f64::abs(a.1 - b.1) < 1e-10
+	}
+
+	#[test]
+	fn segment_through_sphere_hits_at_entry() {
+		let sphere = Sphere::new(Point::origin(), 1, Material::Absorbant);
+
+		// This segment of length 4 enters the unit sphere at x = -1, a quarter along the way:
+		let t = sphere.intersect_segment(Point::new(-2, 0, 0), Point::new(2, 0, 0));
+		assert_eq!(t, Some(0.25), "segment should hit the sphere at t = 0.25");
+
+		// This segment stops before reaching the sphere:
+		let t = sphere.intersect_segment(Point::new(-3, 0, 0), Point::new(-1.5, 0, 0));
+		assert_eq!(t, None, "segment should not hit the sphere");
+	}
+
+	#[test]
+	fn closest_point_lies_towards_external_point() {
+		let sphere = Sphere::new(Point::new(1, 0, 0), 2, Material::Absorbant);
+		let closest = sphere.closest_point_to(Point::new(1, 0, 5));
+		assert_eq!(
+			closest,
+			Point::new(1, 0, 2),
+			"closest point should lie between the center and the external point"
+		);
+	}
+
+	#[test]
+	fn uv_at_cardinal_directions_is_correct() {
+		// These are the points in all six cardinal directions on the unit sphere, and their (u, v):
+		let cases = [
+			(Point::new(0, 1, 0), (0.5, 1.0)),
+			(Point::new(0, -1, 0), (0.5, 0.0)),
+			(Point::new(-1, 0, 0), (0.0, 0.5)),
+			(Point::new(0, 0, 1), (0.25, 0.5)),
+			(Point::new(1, 0, 0), (0.5, 0.5)),
+			(Point::new(0, 0, -1), (0.75, 0.5)),
+		];
+
+		for (point, expected) in cases {
+			let actual = Sphere::uv_at(Point::origin(), point, 1.0);
+			assert!(
+				uv_approx_eq(expected, actual),
+				"uv at {} should be {:?}, but was {:?}",
+				point,
+				expected,
+				actual
+			);
+		}
+	}
+
+	#[test]
+	fn uv_at_surface_accounts_for_center_and_radius() {
+		// This sphere is positioned away from origin and has radius 2:
+		let sphere = Sphere::new(Point::new(5, 5, 5), 2, Material::Absorbant);
+
+		// The top of the sphere should have the same coordinates as the north pole of the unit sphere:
+		let actual = sphere.uv_at_surface(Point::new(5, 7, 5));
+		assert!(
+			uv_approx_eq((0.5, 1.0), actual),
+			"uv at the top should be (0.5, 1.0), but was {:?}",
+			actual
+		);
+	}
+
+	#[test]
+	fn if_ray_hits_sphere_then_hit_has_uv() {
+		// This sphere is positioned at origin and has radius 1:
+		let sphere = Sphere::new(Point::origin(), 1, Material::Absorbant);
+		// This ray starts above the sphere, and points downwards (y-axis) towards it:
+		let ray = Ray::new(Point::new(0, 10, 0), Vec3::new(0, -1, 0));
+
+		// The ray should hit the north pole:
+		let hit = sphere.hit(ray, Interval::from(0)).unwrap();
+		assert!(
+			uv_approx_eq((0.5, 1.0), (hit.u, hit.v)),
+			"uv of the hit should be (0.5, 1.0), but was {:?}",
+			(hit.u, hit.v)
+		);
+	}
 
 	#[test]
 	fn if_ray_hits_sphere_then_some_and_correct_intersect() {
@@ -174,4 +496,132 @@ mod tests {
 			"hit should be on the back face, but was front face"
 		);
 	}
+
+	#[test]
+	fn if_ray_hits_mesh_sphere_then_uv_matches_sphere() {
+		let sphere = Sphere::new(Point::new(1, 2, 3), 2, Material::Absorbant);
+		let mesh = sphere.to_mesh(32);
+		// This ray shoots diagonally towards the center of the sphere:
+		let ray = Ray::new(Point::new(6, 5, 8), Vec3::new(-5, -3, -5));
+
+		// The texture coordinates should nearly match the analytic sphere:
+		let expected = sphere.hit(ray, Interval::from(0)).unwrap();
+		let actual = mesh.hit(ray, Interval::from(0)).unwrap();
+		assert!(
+			f64::abs(expected.u - actual.u) < 1e-3 && f64::abs(expected.v - actual.v) < 1e-3,
+			"uv of the mesh should be {:?}, but was {:?}",
+			(expected.u, expected.v),
+			(actual.u, actual.v)
+		);
+	}
+
+	#[test]
+	fn mesh_sphere_render_matches_sphere_render() {
+		// This sphere covers a circle with a radius of about 10 pixels:
+		let sphere = Sphere::new(
+			Point::new(0, 0, -12),
+			1,
+			Material::Matte {
+				color: Color::new(0.8, 0.3, 0.3),
+			},
+		);
+		let setup = CameraSetup {
+			width: 100,
+			height: 100,
+			..Default::default()
+		};
+		let camera = Camera::from(setup).anti_aliasing(1).bounces(4).seed(5);
+
+		// A finely tessellated sphere should look like the analytic one:
+		let expected = camera.render(&Scene::from([sphere.clone()]));
+		let actual = camera.render(&Scene::from([sphere.to_mesh(32)]));
+		assert!(
+			actual.approx_eq(&expected, 0.001),
+			"rendered mesh should match the sphere within 1 pixel per 1000"
+		);
+	}
+
+	#[test]
+	fn sphere_is_parsed_from_json_fragment() {
+		// This fragment describes a single red sphere:
+		let json = r#"{"type":"sphere","center":[0,0,0],"radius":1,"material":{"type":"matte","color":[1,0,0]}}"#;
+		let sphere = Sphere::from_json(json);
+		assert!(sphere.is_ok(), "sphere should be parsed, but wasn't");
+		assert_eq!(
+			sphere.unwrap(),
+			Sphere::new(
+				Point::new(0, 0, 0),
+				1,
+				Material::Matte {
+					color: Color::new(1, 0, 0)
+				}
+			),
+			"sphere should have the fields from the fragment"
+		);
+	}
+
+	#[test]
+	fn if_fragment_is_group_then_sphere_parsing_fails() {
+		// This fragment describes an empty group:
+		let json = r#"{"type":"group","objects":[]}"#;
+		assert!(
+			Object::from_json(json).is_ok(),
+			"object should be parsed, but wasn't"
+		);
+		let result = Sphere::from_json(json);
+		assert!(
+			matches!(&result, Err(RaytracerError::ParseError(msg)) if msg.contains("group")),
+			"sphere parsing should fail naming the group, but got {:?}",
+			result
+		);
+	}
+
+	#[test]
+	fn object_json_round_trips() {
+		let object = Object::Sphere(Sphere::new(
+			Point::new(1, 2, 3),
+			0.5,
+			Material::Dielectric { ridx: 1.5 },
+		));
+		let parsed = Object::from_json(&object.to_json());
+		assert!(parsed.is_ok(), "object should be parsed, but wasn't");
+		assert_eq!(parsed.unwrap(), object, "parsed object should be equal");
+	}
+
+	#[test]
+	fn average_of_random_surface_points_converges_to_center() {
+		let center = Point::new(1, -2, 3);
+		let sphere = Sphere::new(center, 2, Material::Absorbant);
+
+		let samples = 20_000;
+		let sum = (0..samples).fold(Vec3::zero(), |sum, _| {
+			sum + sphere.random_surface_point().to_vec3()
+		});
+		let average = sum / samples as f64;
+		assert!(
+			(average - center.to_vec3()).norm() < 0.1,
+			"average point {} should be close to the center {}",
+			average,
+			center
+		);
+	}
+
+	#[test]
+	fn random_directions_from_outside_hit_sphere() {
+		let sphere = Sphere::new(Point::new(0, 0, -5), 1, Material::Absorbant);
+		let origin = Point::origin();
+		for _ in 0..100 {
+			let direction = sphere.random_direction_from(origin);
+			assert!(
+				sphere.pdf_value(origin, direction) > 0.0,
+				"direction {} should hit the sphere",
+				direction
+			);
+		}
+		assert_eq!(
+			sphere.pdf_value(origin, Vec3::new(0, 0, 1)),
+			0.0,
+			"direction away from the sphere should have zero density"
+		);
+	}
 }
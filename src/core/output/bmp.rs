@@ -0,0 +1,89 @@
+use std::io::{BufWriter, Write};
+
+use crate::core::error::RaytracerError;
+use crate::core::types::Image;
+
+use super::ppm::calc_colors;
+
+/// The combined size of the file header and the info header, in bytes.
+const HEADER_SIZE: u32 = 14 + 40;
+
+/// Outputs the image to the specified `writer` in 24-bit BMP format.
+///
+/// Rows are stored bottom-up, with each row padded to a multiple of 4 bytes.
+/// The `gamma` value is used for gamma correction.
+pub fn write<W: Write>(image: &Image, gamma: f64, writer: &mut W) -> Result<(), RaytracerError> {
+	let (width, height) = (image.width(), image.height());
+	let row_size = (3 * width).next_multiple_of(4);
+	let data_size = (row_size * height) as u32;
+
+	let mut writer = BufWriter::new(writer);
+	// File header
+	writer.write_all(b"BM")?;
+	writer.write_all(&(HEADER_SIZE + data_size).to_le_bytes())?;
+	writer.write_all(&[0; 4])?; // reserved
+	writer.write_all(&HEADER_SIZE.to_le_bytes())?;
+	// Info header (BITMAPINFOHEADER)
+	writer.write_all(&40u32.to_le_bytes())?;
+	writer.write_all(&(width as i32).to_le_bytes())?;
+	writer.write_all(&(height as i32).to_le_bytes())?; // positive: bottom-up
+	writer.write_all(&1u16.to_le_bytes())?; // color planes
+	writer.write_all(&24u16.to_le_bytes())?; // bits per pixel
+	writer.write_all(&0u32.to_le_bytes())?; // no compression
+	writer.write_all(&data_size.to_le_bytes())?;
+	writer.write_all(&2835i32.to_le_bytes())?; // 72 DPI, horizontally
+	writer.write_all(&2835i32.to_le_bytes())?; // 72 DPI, vertically
+	writer.write_all(&0u32.to_le_bytes())?; // colors in palette
+	writer.write_all(&0u32.to_le_bytes())?; // important colors
+	// Pixel data
+	let padding = vec![0; row_size - 3 * width];
+	for row in (0..height).rev() {
+		for col in 0..width {
+			let (r, g, b) = calc_colors(&image[(row, col)], gamma);
+			writer.write_all(&[b, g, r])?;
+		}
+		writer.write_all(&padding)?;
+	}
+	writer.flush()?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::core::types::{Color, Image};
+
+	/// Reads a little-endian `u32` from the buffer at the specified offset.
+	fn read_u32(buf: &[u8], offset: usize) -> u32 {
+		u32::from_le_bytes(buf[offset..offset + 4].try_into().unwrap())
+	}
+
+	#[test]
+	fn correct_bmp() {
+		// This is a 2x2 image:
+		let mut image = Image::init(2, 2);
+		// The top left pixel is red, the bottom right pixel is blue:
+		image[(0, 0)] = Color::new(1, 0, 0);
+		image[(1, 1)] = Color::new(0, 0, 1);
+
+		// Write image to buf:
+		let mut buf: Vec<u8> = Vec::new();
+		let write_result = super::write(&image, 2.2, &mut buf);
+		assert!(write_result.is_ok(), "writing should succeed, but didn't");
+
+		// Each row has 6 bytes of pixels, padded to 8 bytes:
+		assert_eq!(&buf[0..2], b"BM", "file should start with signature");
+		assert_eq!(read_u32(&buf, 2), 54 + 16, "bfSize should be 70");
+		assert_eq!(read_u32(&buf, 10), 54, "pixel data should follow headers");
+		assert_eq!(read_u32(&buf, 18), 2, "biWidth should be 2");
+		assert_eq!(read_u32(&buf, 22), 2, "biHeight should be 2 (bottom-up)");
+		assert_eq!(buf.len(), 70, "file should be 70 bytes long");
+
+		// The bottom row comes first, with pixels in BGR order:
+		#[rustfmt::skip]
+		let expected = [
+			0, 0, 0,   255, 0, 0,   0, 0,
+			0, 0, 255, 0,   0, 0,   0, 0,
+		];
+		assert_eq!(&buf[54..], &expected, "pixel data should match, but didn't");
+	}
+}
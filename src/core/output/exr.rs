@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+use std::io::{Seek, Write};
+
+use exr::prelude::{
+	Encoding, ImageAttributes, IntegerBounds, Layer, LayerAttributes, RgbChannels,
+	SpecificChannels, Vec2, WritableImage,
+};
+
+use crate::core::error::RaytracerError;
+use crate::core::types::Image;
+
+/// Outputs the image to the specified `writer` in OpenEXR format.
+///
+/// The image is written as a single RGB layer with 32-bit float precision.
+/// No gamma correction is performed, since EXR stores linear color values.
+pub fn write<W: Write + Seek>(image: &Image, writer: &mut W) -> Result<(), RaytracerError> {
+	let layer = rgb_layer(image, LayerAttributes::default());
+	exr::prelude::Image::from_layer(layer)
+		.write()
+		.to_buffered(writer)?;
+	Ok(())
+}
+
+/// Outputs multiple images to the specified `writer` as named layers of one OpenEXR file.
+///
+/// Useful for storing several render passes (e.g. color, normals, depth) together.
+/// Layers are written in the alphabetical order of their names.
+pub fn write_multilayer<W: Write + Seek>(
+	layers: &HashMap<String, Image>,
+	writer: &mut W,
+) -> Result<(), RaytracerError> {
+	let mut names: Vec<&String> = layers.keys().collect();
+	names.sort();
+
+	let (width, height) = layers.values().fold((0, 0), |(w, h), image| {
+		(w.max(image.width()), h.max(image.height()))
+	});
+	let attributes = ImageAttributes::new(IntegerBounds::from_dimensions((width, height)));
+
+	let layers: Vec<_> = names
+		.into_iter()
+		.map(|name| rgb_layer(&layers[name], LayerAttributes::named(name.as_str())))
+		.collect();
+	exr::prelude::Image::from_layers(attributes, layers)
+		.write()
+		.to_buffered(writer)?;
+	Ok(())
+}
+
+/// A layer with red, green and blue channels, whose samples are provided by `F`.
+type RgbLayer<F> = Layer<SpecificChannels<F, RgbChannels>>;
+
+/// Creates a scanline RGB layer that reads its pixels from the `image`.
+fn rgb_layer(
+	image: &Image,
+	attributes: LayerAttributes,
+) -> RgbLayer<impl Fn(Vec2<usize>) -> (f32, f32, f32) + Sync> {
+	let channels = SpecificChannels::rgb(|pos: Vec2<usize>| {
+		let pixel = image[(pos.y(), pos.x())];
+		(pixel.r() as f32, pixel.g() as f32, pixel.b() as f32)
+	});
+	Layer::new(
+		(image.width(), image.height()),
+		attributes,
+		Encoding::UNCOMPRESSED,
+		channels,
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashMap;
+	use std::io::Cursor;
+
+	use exr::prelude::{AnyChannels, FlatSamples, Layer, Layers, ReadChannels, ReadLayers};
+
+	use crate::core::types::{Color, Image, ToVec3};
+
+	/// Reads all layers of an EXR file from the buffer.
+	fn read_layers(buf: Vec<u8>) -> exr::prelude::Image<Layers<AnyChannels<FlatSamples>>> {
+		exr::prelude::read()
+			.no_deep_data()
+			.largest_resolution_level()
+			.all_channels()
+			.all_layers()
+			.all_attributes()
+			.from_buffered(Cursor::new(buf))
+			.expect("reading should succeed")
+	}
+
+	/// Returns the samples of the channel with the specified `name` as floats.
+	fn channel(layer: &Layer<AnyChannels<FlatSamples>>, name: &str) -> Vec<f32> {
+		layer
+			.channel_data
+			.list
+			.iter()
+			.find(|channel| channel.name.to_string() == name)
+			.map(|channel| channel.sample_data.values_as_f32().collect())
+			.unwrap_or_else(|| panic!("channel {} should exist, but didn't", name))
+	}
+
+	#[test]
+	fn written_exr_preserves_linear_values() {
+		// This is a 3x2 image with distinct values, including ones above 1:
+		let mut image = Image::init(2, 3);
+		image[(0, 0)] = Color::new(0.1, 0.2, 0.3);
+		image[(0, 2)] = Color::new(4.5, 0.0, 0.25);
+		image[(1, 1)] = Color::new(0.333, 0.666, 0.999);
+
+		// Write image to buf:
+		let mut buf = Cursor::new(Vec::new());
+		let write_result = super::write(&image, &mut buf);
+		assert!(write_result.is_ok(), "writing should succeed, but didn't");
+
+		// Read back the single layer:
+		let read = read_layers(buf.into_inner());
+		assert_eq!(
+			read.layer_data.len(),
+			1,
+			"there should be exactly one layer"
+		);
+		let layer = &read.layer_data[0];
+		let (r, g, b) = (
+			channel(layer, "R"),
+			channel(layer, "G"),
+			channel(layer, "B"),
+		);
+
+		// Every pixel should match the source within f32 precision:
+		for row in 0..image.height() {
+			for col in 0..image.width() {
+				let i = row * image.width() + col;
+				let expected = image[(row, col)];
+				let actual = Color::new(r[i], g[i], b[i]);
+				let diff = (expected.to_vec3() - actual.to_vec3()).norm();
+				assert!(
+					diff < 1e-6,
+					"pixel ({}, {}) should be {:?}, but was {:?}",
+					row,
+					col,
+					expected,
+					actual
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn multilayer_exr_contains_named_layers() {
+		// These are two passes of a 2x2 image:
+		let mut color = Image::init(2, 2);
+		color[(0, 1)] = Color::new(1, 0, 0);
+		let mut normals = Image::init(2, 2);
+		normals[(1, 0)] = Color::new(0, 0, 1);
+		let layers = HashMap::from([
+			(String::from("normals"), normals),
+			(String::from("color"), color),
+		]);
+
+		// Write layers to buf:
+		let mut buf = Cursor::new(Vec::new());
+		let write_result = super::write_multilayer(&layers, &mut buf);
+		assert!(write_result.is_ok(), "writing should succeed, but didn't");
+
+		// Both layers should be present with their names and values:
+		let read = read_layers(buf.into_inner());
+		assert_eq!(read.layer_data.len(), 2, "there should be two layers");
+		for (name, index, channel_name) in [("color", 1, "R"), ("normals", 2, "B")] {
+			let layer = read
+				.layer_data
+				.iter()
+				.find(|layer| layer.attributes.layer_name == Some(name.into()))
+				.unwrap_or_else(|| panic!("layer {} should exist, but didn't", name));
+			let samples = channel(layer, channel_name);
+			assert_eq!(
+				samples[index], 1.0,
+				"layer {} should have {} = 1 at index {}",
+				name, channel_name, index
+			);
+		}
+	}
+}
@@ -0,0 +1,96 @@
+use std::io::Write;
+
+use jpeg_encoder::{ColorType, Encoder};
+
+use crate::core::error::RaytracerError;
+use crate::core::types::Image;
+
+use super::ppm::calc_colors;
+
+/// Outputs the image to the specified `writer` in JPEG format (as YCbCr).
+/// The `gamma` value is used for gamma correction.
+///
+/// The `quality` ranges from 1 (smallest file) to 100 (least compression artifacts).
+/// Returns an error if the quality lies outside of this range, or if the image is
+/// wider or taller than 65535 pixels.
+pub fn write<W: Write>(
+	image: &Image,
+	gamma: f64,
+	quality: u8,
+	writer: &mut W,
+) -> Result<(), RaytracerError> {
+	if !(1..=100).contains(&quality) {
+		return Err(RaytracerError::Encoding(format!(
+			"JPEG quality should be between 1 and 100, but was {}",
+			quality
+		)));
+	}
+	let (Ok(width), Ok(height)) = (u16::try_from(image.width()), u16::try_from(image.height()))
+	else {
+		return Err(RaytracerError::Encoding(format!(
+			"image of size {}x{} is too large for JPEG",
+			image.width(),
+			image.height()
+		)));
+	};
+
+	let mut data = Vec::with_capacity(3 * image.width() * image.height());
+	for line in image {
+		for pixel in line {
+			let (r, g, b) = calc_colors(pixel, gamma);
+			data.extend([r, g, b]);
+		}
+	}
+	Encoder::new(writer, quality).encode(&data, width, height, ColorType::Rgb)?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::core::error::RaytracerError;
+	use crate::core::output::ppm::calc_colors;
+	use crate::core::types::{Color, Image};
+
+	#[test]
+	fn decoded_jpeg_is_close_to_source() {
+		// This 4x4 image has a smooth gradient:
+		let mut image = Image::init(4, 4);
+		for row in 0..4 {
+			for col in 0..4 {
+				image[(row, col)] = Color::new(0.2 + 0.1 * col as f64, 0.5, 0.8 - 0.1 * row as f64);
+			}
+		}
+
+		let mut buf = Vec::new();
+		let write_result = super::write(&image, 2.2, 90, &mut buf);
+		assert!(write_result.is_ok(), "writing should succeed, but didn't");
+
+		let mut decoder = jpeg_decoder::Decoder::new(buf.as_slice());
+		let data = decoder.decode().expect("image should be valid");
+		let info = decoder.info().expect("header should be valid");
+		assert_eq!((info.width, info.height), (4, 4), "image should be 4x4");
+		for (index, decoded) in data.chunks(3).enumerate() {
+			let (r, g, b) = calc_colors(&image[(index / 4, index % 4)], 2.2);
+			for (expected, actual) in [r, g, b].into_iter().zip(decoded) {
+				assert!(
+					expected.abs_diff(*actual) <= 10,
+					"pixel {} should be close to {:?}, but was {:?}",
+					index,
+					(r, g, b),
+					decoded
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn if_quality_out_of_range_then_error() {
+		let image = Image::init(1, 1);
+		let result = super::write(&image, 2.2, 0, &mut Vec::new());
+		assert!(
+			matches!(result, Err(RaytracerError::Encoding(_))),
+			"quality 0 should be an error, but got {:?}",
+			result
+		);
+	}
+}
@@ -1 +1,8 @@
+pub mod bmp;
+#[cfg(feature = "exr-output")]
+pub mod exr;
+#[cfg(feature = "jpeg-output")]
+pub mod jpeg;
+#[cfg(feature = "png-output")]
+pub mod png;
 pub mod ppm;
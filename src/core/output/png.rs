@@ -0,0 +1,118 @@
+use std::io::Write;
+
+use png::{BitDepth, ColorType, Encoder};
+
+use crate::core::error::RaytracerError;
+use crate::core::types::{Color, Image, ToVec3};
+
+use super::ppm::calc_colors;
+
+/// Outputs the image to the specified `writer` in PNG format, with 8 bits per channel.
+/// The `gamma` value is used for gamma correction.
+pub fn write<W: Write>(image: &Image, gamma: f64, writer: &mut W) -> Result<(), RaytracerError> {
+	let mut data = Vec::with_capacity(3 * image.width() * image.height());
+	for line in image {
+		for pixel in line {
+			let (r, g, b) = calc_colors(pixel, gamma);
+			data.extend([r, g, b]);
+		}
+	}
+	encode(image, BitDepth::Eight, &data, writer)
+}
+
+/// Outputs the image to the specified `writer` in PNG format, with 16 bits per channel.
+/// The `gamma` value is used for gamma correction.
+///
+/// Compared to [`write`], the higher precision preserves detail in dark areas,
+/// and avoids banding in smooth gradients.
+pub fn write_16bit<W: Write>(
+	image: &Image,
+	gamma: f64,
+	writer: &mut W,
+) -> Result<(), RaytracerError> {
+	let mut data = Vec::with_capacity(6 * image.width() * image.height());
+	for line in image {
+		for pixel in line {
+			let (r, g, b) = calc_colors_16bit(pixel, gamma);
+			// PNG stores samples in big-endian byte order
+			for channel in [r, g, b] {
+				data.extend(channel.to_be_bytes());
+			}
+		}
+	}
+	encode(image, BitDepth::Sixteen, &data, writer)
+}
+
+/// Encodes the RGB samples in `data` as a PNG image with the dimensions of `image`.
+fn encode<W: Write>(
+	image: &Image,
+	depth: BitDepth,
+	data: &[u8],
+	writer: &mut W,
+) -> Result<(), RaytracerError> {
+	let mut encoder = Encoder::new(writer, image.width() as u32, image.height() as u32);
+	encoder.set_color(ColorType::Rgb);
+	encoder.set_depth(depth);
+	let mut png_writer = encoder.write_header()?;
+	png_writer.write_image_data(data)?;
+	png_writer.finish()?;
+	Ok(())
+}
+
+/// Performs gamma correction and translation from internal to output color space.
+/// Returns a tuple `(red, green, blue)` with each value corresponding to the respective channel's 16-bit value.
+fn calc_colors_16bit(pixel: &Color, gamma: f64) -> (u16, u16, u16) {
+	let rgb = pixel.to_vec3().exp(1.0 / gamma);
+	rgb.to_tuple(|x| (65536.0 * x.clamp(0.0, 0.99999)) as u16)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::core::types::{Color, Image};
+
+	/// Decodes a PNG image from the buffer, and returns its bit depth and samples.
+	fn decode(buf: &[u8]) -> (png::BitDepth, Vec<u8>) {
+		let decoder = png::Decoder::new(buf);
+		let mut reader = decoder.read_info().expect("header should be valid");
+		let mut data = vec![0; reader.output_buffer_size()];
+		let info = reader.next_frame(&mut data).expect("image should be valid");
+		data.truncate(info.buffer_size());
+		(info.bit_depth, data)
+	}
+
+	#[test]
+	fn correct_8bit_png() {
+		// This is a 2x1 image with a red and a blue pixel:
+		let mut image = Image::init(1, 2);
+		image[(0, 0)] = Color::new(1, 0, 0);
+		image[(0, 1)] = Color::new(0, 0, 1);
+
+		let mut buf = Vec::new();
+		let write_result = super::write(&image, 2.2, &mut buf);
+		assert!(write_result.is_ok(), "writing should succeed, but didn't");
+
+		// The samples should be the 8-bit colors in RGB order:
+		let (depth, data) = decode(&buf);
+		assert_eq!(depth, png::BitDepth::Eight, "bit depth should be 8");
+		assert_eq!(data, vec![255, 0, 0, 0, 0, 255], "samples should match");
+	}
+
+	#[test]
+	fn if_16bit_then_dark_pixels_keep_detail() {
+		// This image has a single near-black pixel, which is too dark for 8 bits without gamma correction:
+		let mut image = Image::init(1, 1);
+		image[(0, 0)] = Color::new(0.001, 0.001, 0.001);
+
+		let (mut buf_8, mut buf_16) = (Vec::new(), Vec::new());
+		super::write(&image, 1.0, &mut buf_8).unwrap();
+		super::write_16bit(&image, 1.0, &mut buf_16).unwrap();
+
+		// The 8-bit output is black, while the 16-bit output isn't:
+		let (_, data_8) = decode(&buf_8);
+		assert_eq!(data_8, vec![0, 0, 0], "8-bit pixel should round to black");
+		let (depth, data_16) = decode(&buf_16);
+		assert_eq!(depth, png::BitDepth::Sixteen, "bit depth should be 16");
+		let red = u16::from_be_bytes([data_16[0], data_16[1]]);
+		assert_eq!(red, 65, "16-bit pixel should be 65, but was {}", red);
+	}
+}
@@ -4,36 +4,167 @@ use crate::core::types::{Image, ToVec3};
 use crate::types::Color;
 
 /// Outputs the image to the specified `writer` in plain (ASCII) format.
+/// See [`PlainWriter`] for embedding comments.
 pub fn plain<W: Write>(image: &Image, gamma: f64, writer: &mut W) -> Result<(), io::Error> {
-	let mut writer = BufWriter::new(writer);
-	writeln!(writer, "P3\n{} {}\n255", image.width(), image.height())?;
-	for line in image {
-		for pixel in line {
-			let (r, g, b) = calc_colors(pixel, gamma);
-			writeln!(writer, "{} {} {}", r, g, b)?;
-		}
-	}
-	writer.flush()?;
-	Ok(())
+	PlainWriter::new(gamma).write(image, writer)
 }
 
 /// Outputs the image to the specified `writer` in raw (binary) format.
+/// See [`RawWriter`] for embedding comments.
 pub fn raw<W: Write>(image: &Image, gamma: f64, writer: &mut W) -> Result<(), io::Error> {
-	let mut writer = BufWriter::new(writer);
-	writeln!(writer, "P6\n{} {}\n255", image.width(), image.height())?;
-	for line in image {
-		for pixel in line {
-			let (r, g, b) = calc_colors(pixel, gamma);
-			writer.write_all(&[r, g, b])?;
+	RawWriter::new(gamma).write(image, writer)
+}
+
+/// A type that outputs images in plain (ASCII) format, with comments in the header:
+/// ```
+/// PlainWriter::new(gamma)
+///   .with_comment("samples", "100")
+///   .write(&image, &mut writer)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct PlainWriter {
+	gamma: f64,
+	comments: Vec<String>,
+}
+
+impl PlainWriter {
+	/// Creates a new writer, which uses the `gamma` value for gamma correction.
+	pub fn new(gamma: f64) -> Self {
+		Self {
+			gamma,
+			comments: Vec::new(),
 		}
 	}
-	writer.flush()?;
-	Ok(())
+	/// Adds a `# key: value` comment line to the header.
+	/// Line breaks in the key or value are replaced by spaces.
+	pub fn with_comment(mut self, key: &str, value: &str) -> Self {
+		self.comments.push(comment(key, value));
+		self
+	}
+	/// Outputs the image to the specified `writer`.
+	pub fn write<W: Write>(&self, image: &Image, writer: &mut W) -> Result<(), io::Error> {
+		let mut writer = BufWriter::new(writer);
+		write_header(&mut writer, "P3", image, &self.comments)?;
+		for line in image {
+			for pixel in line {
+				let (r, g, b) = calc_colors(pixel, self.gamma);
+				writeln!(writer, "{} {} {}", r, g, b)?;
+			}
+		}
+		writer.flush()
+	}
+}
+
+/// A type that outputs images in raw (binary) format, with comments in the header:
+/// ```
+/// RawWriter::new(gamma)
+///   .with_comment("samples", "100")
+///   .write(&image, &mut writer)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct RawWriter {
+	gamma: f64,
+	comments: Vec<String>,
+}
+
+impl RawWriter {
+	/// Creates a new writer, which uses the `gamma` value for gamma correction.
+	pub fn new(gamma: f64) -> Self {
+		Self {
+			gamma,
+			comments: Vec::new(),
+		}
+	}
+	/// Adds a `# key: value` comment line to the header.
+	/// Line breaks in the key or value are replaced by spaces.
+	pub fn with_comment(mut self, key: &str, value: &str) -> Self {
+		self.comments.push(comment(key, value));
+		self
+	}
+	/// Outputs the image to the specified `writer`.
+	pub fn write<W: Write>(&self, image: &Image, writer: &mut W) -> Result<(), io::Error> {
+		let mut writer = BufWriter::new(writer);
+		write_header(&mut writer, "P6", image, &self.comments)?;
+		for line in image {
+			for pixel in line {
+				let (r, g, b) = calc_colors(pixel, self.gamma);
+				writer.write_all(&[r, g, b])?;
+			}
+		}
+		writer.flush()
+	}
+}
+
+/// Formats a header comment line (without the line break).
+fn comment(key: &str, value: &str) -> String {
+	let single_line = |s: &str| s.replace(['\r', '\n'], " ");
+	format!("# {}: {}", single_line(key), single_line(value))
+}
+
+/// Writes the header of the format with the `magic` number for the image, with the `comments`
+/// after the magic number.
+fn write_header<W: Write>(
+	writer: &mut W,
+	magic: &str,
+	image: &Image,
+	comments: &[String],
+) -> Result<(), io::Error> {
+	writeln!(writer, "{}", magic)?;
+	for comment in comments {
+		writeln!(writer, "{}", comment)?;
+	}
+	writeln!(writer, "{} {}\n255", image.width(), image.height())
+}
+
+/// A type that outputs an image in raw (binary) format pixel by pixel,
+/// without requiring the whole image to be stored in memory.
+///
+/// The header has to be written first, followed by all pixels in row-major order:
+/// ```
+/// let mut stream = StreamWriter::new(writer);
+/// stream.write_header(width, height, gamma)?;
+/// stream.write_pixel(color)?;
+/// // ...
+/// stream.flush()?;
+/// ```
+pub struct StreamWriter<W: Write> {
+	writer: BufWriter<W>,
+	gamma: f64,
+}
+
+impl<W: Write> StreamWriter<W> {
+	/// Creates a new stream writer that outputs to the specified `writer`.
+	pub fn new(writer: W) -> Self {
+		Self {
+			writer: BufWriter::new(writer),
+			gamma: 1.0,
+		}
+	}
+	/// Writes the header for an image of the specified dimensions.
+	/// The `gamma` value is used for gamma correction of all pixels written afterwards.
+	pub fn write_header(
+		&mut self,
+		width: usize,
+		height: usize,
+		gamma: f64,
+	) -> Result<(), io::Error> {
+		self.gamma = gamma;
+		writeln!(self.writer, "P6\n{} {}\n255", width, height)
+	}
+	/// Writes the next pixel.
+	pub fn write_pixel(&mut self, color: Color) -> Result<(), io::Error> {
+		let (r, g, b) = calc_colors(&color, self.gamma);
+		self.writer.write_all(&[r, g, b])
+	}
+	/// Flushes all buffered pixels to the underlying writer.
+	pub fn flush(&mut self) -> Result<(), io::Error> {
+		self.writer.flush()
+	}
 }
 
 /// Performs gamma correction and translation from internal to output color space.
 /// Returns a tuple `(red, green, blue)` with each value corresponding to the respective channel's 8-bit value.
-fn calc_colors(pixel: &Color, gamma: f64) -> (u8, u8, u8) {
+pub(super) fn calc_colors(pixel: &Color, gamma: f64) -> (u8, u8, u8) {
 	let rgb = pixel.to_vec3().exp(1.0 / gamma);
 	rgb.to_tuple(|x| (256.0 * x.clamp(0.0, 0.999)) as u8)
 }
@@ -42,7 +173,7 @@ fn calc_colors(pixel: &Color, gamma: f64) -> (u8, u8, u8) {
 mod tests {
 	use crate::core::types::{Color, Image};
 
-	use super::calc_colors;
+	use super::{PlainWriter, RawWriter, StreamWriter, calc_colors};
 
 	#[test]
 	fn transforms_color_to_output_rgb() {
@@ -120,4 +251,65 @@ mod tests {
 		assert!(write_result.is_ok(), "writing should succeed, but didn't");
 		assert_eq!(expected, buf, ".ppm output should match, but didn't");
 	}
+
+	#[test]
+	fn comments_are_written_before_pixels() {
+		let image = Image::init(1, 1);
+
+		// The plain format should contain the comments verbatim after the magic number:
+		let mut buf: Vec<u8> = Vec::new();
+		let write_result = PlainWriter::new(2.2)
+			.with_comment("samples", "100")
+			.with_comment("scene", "inputs/test.json")
+			.write(&image, &mut buf);
+		assert!(write_result.is_ok(), "writing should succeed, but didn't");
+		let expected = "P3\n# samples: 100\n# scene: inputs/test.json\n1 1\n255\n0 0 0\n";
+		assert_eq!(
+			String::from_utf8(buf).unwrap(),
+			expected,
+			"comments should be part of the header"
+		);
+
+		// The raw format should contain the same header, followed by the pixel bytes:
+		let mut buf: Vec<u8> = Vec::new();
+		let write_result = RawWriter::new(2.2)
+			.with_comment("samples", "100")
+			.with_comment("seed", "line\nbreak")
+			.write(&image, &mut buf);
+		assert!(write_result.is_ok(), "writing should succeed, but didn't");
+		let mut expected = b"P6\n# samples: 100\n# seed: line break\n1 1\n255\n".to_vec();
+		expected.extend([0, 0, 0]);
+		assert_eq!(buf, expected, "comments should be part of the header");
+	}
+
+	#[test]
+	fn stream_writer_produces_raw_ppm() {
+		// This is a 2x2 image:
+		let mut image = Image::init(2, 2);
+		// The bottom right pixel is red:
+		image[(1, 1)] = Color::new(1, 0, 0);
+
+		// Write image to buf:
+		let mut expected: Vec<u8> = Vec::new();
+		let write_result = super::raw(&image, 2.2, &mut expected);
+		assert!(write_result.is_ok(), "writing should succeed, but didn't");
+
+		// Stream the same pixels to another buf:
+		let mut actual: Vec<u8> = Vec::new();
+		let mut stream = StreamWriter::new(&mut actual);
+		let stream_result = stream
+			.write_header(2, 2, 2.2)
+			.and_then(|_| stream.write_pixel(Color::black()))
+			.and_then(|_| stream.write_pixel(Color::black()))
+			.and_then(|_| stream.write_pixel(Color::black()))
+			.and_then(|_| stream.write_pixel(Color::new(1, 0, 0)))
+			.and_then(|_| stream.flush());
+		assert!(
+			stream_result.is_ok(),
+			"streaming should succeed, but didn't"
+		);
+		drop(stream);
+
+		assert_eq!(expected, actual, "streamed output should match, but didn't");
+	}
 }
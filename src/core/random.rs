@@ -0,0 +1,55 @@
+//! A source of random numbers for rendering.
+//!
+//! Every thread has its own generator, which can be reseeded to make renders reproducible.
+
+use std::cell::RefCell;
+
+use rand::distr::uniform::{SampleRange, SampleUniform};
+use rand::distr::{Distribution, StandardUniform};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+thread_local! {
+	/// The random number generator of the current thread.
+	static RNG: RefCell<StdRng> = RefCell::new(StdRng::from_os_rng());
+}
+
+/// Generates a random value in the specified range.
+pub(crate) fn random_range<T, R>(range: R) -> T
+where
+	T: SampleUniform,
+	R: SampleRange<T>,
+{
+	RNG.with_borrow_mut(|rng| rng.random_range(range))
+}
+
+/// Generates a random value of the standard distribution of the type
+/// (for floating point values, uniformly distributed in `[0, 1)`).
+pub(crate) fn random<T>() -> T
+where
+	StandardUniform: Distribution<T>,
+{
+	RNG.with_borrow_mut(|rng| rng.random())
+}
+
+/// Reseeds the random number generator of the current thread.
+/// All values generated afterwards on this thread are determined by the `seed`.
+pub(crate) fn reseed(seed: u64) {
+	RNG.with_borrow_mut(|rng| *rng = StdRng::seed_from_u64(seed));
+}
+
+#[cfg(test)]
+mod tests {
+	#[test]
+	fn if_reseeded_then_same_values_are_generated() {
+		super::reseed(42);
+		let first = (0..10).map(|_| super::random::<f64>()).collect::<Vec<_>>();
+		super::reseed(42);
+		let second = (0..10).map(|_| super::random::<f64>()).collect::<Vec<_>>();
+
+		assert_eq!(
+			first, second,
+			"same seed should produce the same values, but didn't"
+		);
+	}
+}
@@ -1,27 +1,78 @@
+mod background;
+mod builder;
+mod graph;
+
+use std::collections::HashMap;
+
 use serde::Deserialize;
 
-use super::objects::{Hit, Hittable, Object, ToObject};
-use super::types::Interval;
+use super::objects::{Group, GroupId, Hit, Hittable, Object, Sphere, ToObject, closest_hit};
+use super::types::{Aabb, Interval, Point, Ray, Transform, Vec3};
+
+pub use background::{Background, HitOrBackground};
+pub use builder::SceneBuilder;
+pub use graph::{Node, NodeId, SceneGraph};
+
+/// The amount of objects of each type in a scene, see [`Scene::object_stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SceneStats {
+	/// The amount of all objects.
+	pub total: usize,
+	/// The amount of (static) spheres.
+	pub spheres: usize,
+	/// The amount of moving spheres.
+	pub moving_spheres: usize,
+	/// The amount of groups.
+	pub groups: usize,
+	/// The amount of procedurally generated terrains.
+	pub grounds: usize,
+	/// The amount of triangle meshes.
+	pub meshes: usize,
+	/// The amount of infinite cylinders.
+	pub cylinders: usize,
+	/// The amount of hollow spheres.
+	pub shells: usize,
+}
 
 /// A collection of objects to be rendered.
 #[derive(Debug, Default, PartialEq, Deserialize)]
 pub struct Scene {
 	list: Vec<Object>,
+	/// The identifier assigned to the next group added with [`Scene::add_group`].
+	#[serde(skip)]
+	next_group_id: usize,
+	/// The background seen by rays that don't hit any object.
+	#[serde(skip)]
+	background: Background,
 }
 
 impl Scene {
 	/// Creates a new empty scene, without any objects.
 	pub fn new() -> Self {
-		Self { list: Vec::new() }
+		Self::from_objs(Vec::new())
 	}
 	/// Creates a new scene by moving a collection of wrapped objects into this type.
-	pub fn from_objs(objs: Vec<Object>) -> Self {
-		Self { list: objs }
+	pub fn from_objs<I: IntoIterator<Item = Object>>(objs: I) -> Self {
+		Self {
+			list: objs.into_iter().collect(),
+			next_group_id: 0,
+			background: Background::default(),
+		}
 	}
 	/// Adds an object to this scene.
 	pub fn add<T: Hittable + ToObject>(&mut self, obj: T) {
 		self.list.push(obj.wrap());
 	}
+	/// Adds an object to this scene, and returns the scene.
+	///
+	/// Unlike [`Scene::append`], this allows chaining objects of different types:
+	/// ```
+	/// let scene = Scene::new().with(sphere).with(group);
+	/// ```
+	pub fn with<T: Hittable + ToObject>(mut self, obj: T) -> Self {
+		self.add(obj);
+		self
+	}
 	/// Appends a collection of objects to this scene.
 	///
 	/// You can chain this method multiple times to append objects of different types:
@@ -40,12 +91,215 @@ impl Scene {
 		self.list.append(&mut wrapped_objs);
 		self
 	}
+	/// Adds a group of objects to this scene, which share the specified transform.
+	/// Returns an identifier, with which the group can be removed later.
+	pub fn add_group(&mut self, objs: Vec<Object>, transform: Transform) -> GroupId {
+		let id = GroupId(self.next_group_id);
+		self.next_group_id += 1;
+		self.list
+			.push(Group::new(objs, transform).with_id(id).wrap());
+		id
+	}
+	/// Removes the group with the specified identifier, along with all of its objects, from this scene.
+	pub fn remove_group(&mut self, id: GroupId) {
+		self.list
+			.retain(|obj| !matches!(obj, Object::Group(group) if group.id() == Some(id)));
+	}
 	/// Removes all objects from this scene.
 	pub fn clear(&mut self) {
 		self.list.clear();
 	}
 }
 
+// Transforms
+impl Scene {
+	/// Applies the specified transform to every object in this scene, see [`Object::apply_transform`].
+	pub fn transform_all(&mut self, transform: &Transform) {
+		self.list = std::mem::take(&mut self.list)
+			.into_iter()
+			.map(|obj| obj.apply_transform(transform))
+			.collect();
+	}
+	/// Moves every object in this scene by the specified vector, and returns the scene.
+	pub fn translated_by(mut self, v: Vec3) -> Self {
+		self.transform_all(&Transform::translation(v));
+		self
+	}
+	/// Scales every object in this scene (and the distances between them) by the specified factor,
+	/// with the origin as the fixed point, and returns the scene.
+	pub fn scaled_by(mut self, f: f64) -> Self {
+		self.transform_all(&Transform::scaling(f));
+		self
+	}
+}
+
+// Ordering
+impl Scene {
+	/// Creates a new scene with the objects of this scene, sorted ascending by the distance from
+	/// `camera_position` to the center of their bounding box.
+	///
+	/// Since the nearest hit is searched for, rays that hit near objects first can skip far objects
+	/// early. Objects without a bounding box are placed last, in their original order.
+	pub fn sort_front_to_back(&self, camera_position: Point) -> Scene {
+		let distance = |obj: &Object| match obj.bounding_box() {
+			Some(aabb) => camera_position.distance(aabb.center()),
+			None => f64::INFINITY,
+		};
+		let mut list = self.list.clone();
+		list.sort_by(|a, b| distance(a).total_cmp(&distance(b)));
+		self.with_list(list)
+	}
+	/// Creates a new scene with the objects of this scene, grouped by the name of their material
+	/// (see [`crate::objects::Material::name`]), which improves cache locality when rendering.
+	///
+	/// Objects without a single material, such as groups, are placed last. Otherwise the
+	/// original order of the objects is kept.
+	pub fn sort_by_material(&self) -> Scene {
+		let mut list = self.list.clone();
+		list.sort_by_key(|obj| match obj.material() {
+			Some(material) => (false, material.name()),
+			None => (true, ""),
+		});
+		self.with_list(list)
+	}
+
+	/// Creates a new scene with the specified objects, and the background of this scene.
+	fn with_list(&self, list: Vec<Object>) -> Scene {
+		Scene {
+			list,
+			next_group_id: self.next_group_id,
+			background: self.background,
+		}
+	}
+}
+
+// Background
+impl Scene {
+	/// Returns this scene with the specified background.
+	pub fn with_background(self, background: Background) -> Self {
+		Self { background, ..self }
+	}
+	/// Sets the background of this scene.
+	pub fn set_background(&mut self, background: Background) {
+		self.background = background;
+	}
+	/// The background seen by rays that don't hit any object.
+	pub fn background(&self) -> Background {
+		self.background
+	}
+	/// Calculates the closest intersection of the `ray` with the objects of this scene.
+	/// If there is none within `t_range`, returns the color of the background in the ray's direction.
+	pub fn hit_with_background(&self, ray: Ray, t_range: Interval) -> HitOrBackground<'_> {
+		match self.hit(ray, t_range) {
+			Some(hit) => HitOrBackground::Hit(hit),
+			None => HitOrBackground::Background(self.background.color(ray)),
+		}
+	}
+}
+
+// Introspection
+impl Scene {
+	/// Returns the amount of objects in this scene.
+	/// A group counts as a single object.
+	pub fn len(&self) -> usize {
+		self.list.len()
+	}
+	/// Checks whether this scene contains no objects.
+	pub fn is_empty(&self) -> bool {
+		self.list.is_empty()
+	}
+	/// Returns the amount of objects in this scene (same as [`Scene::len`]).
+	pub fn object_count(&self) -> usize {
+		self.len()
+	}
+	/// Returns the amount of spheres in this scene, not counting the ones inside groups.
+	pub fn count_spheres(&self) -> usize {
+		self.objects_of_type::<Sphere>().len()
+	}
+	/// Counts the objects of this scene by type, not counting the ones inside groups.
+	pub fn object_stats(&self) -> SceneStats {
+		let mut stats = SceneStats {
+			total: self.len(),
+			..Default::default()
+		};
+		for obj in &self.list {
+			match obj {
+				Object::Sphere(_) => stats.spheres += 1,
+				Object::MovingSphere(_) => stats.moving_spheres += 1,
+				Object::Group(_) => stats.groups += 1,
+				Object::Ground(_) => stats.grounds += 1,
+				Object::TriangleMesh(_) => stats.meshes += 1,
+				Object::InfiniteCylinder(_) => stats.cylinders += 1,
+				Object::ShellSphere(_) => stats.shells += 1,
+			}
+		}
+		stats
+	}
+	/// Counts the objects of this scene by the name of their material (see [`crate::objects::Material::name`]),
+	/// not counting the ones inside groups.
+	pub fn material_stats(&self) -> HashMap<&'static str, usize> {
+		let mut stats = HashMap::new();
+		for material in self.list.iter().filter_map(Object::material) {
+			*stats.entry(material.name()).or_insert(0) += 1;
+		}
+		stats
+	}
+	/// Returns all objects of type `T` in this scene, not counting the ones inside groups.
+	///
+	/// ```
+	/// let spheres: Vec<&Sphere> = scene.objects_of_type::<Sphere>();
+	/// ```
+	pub fn objects_of_type<T: 'static>(&self) -> Vec<&T> {
+		self.list
+			.iter()
+			.filter_map(|obj| obj.as_any().downcast_ref::<T>())
+			.collect()
+	}
+	/// Returns the objects of this scene with an emissive material (see
+	/// [`crate::objects::Material::is_emissive`]), not counting the ones inside groups.
+	pub fn emissive_objects(&self) -> Vec<&Object> {
+		self.list
+			.iter()
+			.filter(|obj| obj.material().is_some_and(|m| m.is_emissive()))
+			.collect()
+	}
+	/// Calculates the total power emitted by the objects of this scene, as the luminance of the
+	/// light each emissive object emits, times its surface area (see [`Scene::emissive_objects`]).
+	pub fn total_emitted_power(&self) -> f64 {
+		self.emissive_objects()
+			.iter()
+			.filter_map(|obj| Some(obj.material()?.emittance().luminance() * obj.surface_area()))
+			.sum()
+	}
+	/// Calculates the smallest axis-aligned box that encloses all objects of this scene.
+	/// Objects without a bounding box, such as [`InfiniteCylinder`](super::objects::InfiniteCylinder)s, are silently skipped, so
+	/// the box doesn't contain them; returns [`None`] if there are no other objects.
+	pub fn bounding_box(&self) -> Option<Aabb> {
+		Hittable::bounding_box(self)
+	}
+	/// Estimates the memory used by the objects of this scene, in bytes.
+	///
+	/// This includes the objects themselves, as well as the vertices and faces of meshes
+	/// and the objects within groups, but not small allocations such as nested materials.
+	pub fn estimated_memory_bytes(&self) -> usize {
+		self.list.iter().map(object_memory_bytes).sum()
+	}
+}
+
+/// Estimates the memory used by an object, in bytes, see [`Scene::estimated_memory_bytes`].
+fn object_memory_bytes(obj: &Object) -> usize {
+	let heap_bytes = match obj {
+		Object::Group(group) => group.objects().iter().map(object_memory_bytes).sum(),
+		Object::TriangleMesh(mesh) => mesh.heap_bytes(),
+		Object::Sphere(_)
+		| Object::MovingSphere(_)
+		| Object::Ground(_)
+		| Object::InfiniteCylinder(_)
+		| Object::ShellSphere(_) => 0,
+	};
+	size_of::<Object>() + heap_bytes
+}
+
 // ::from constructor
 impl<I, O> From<I> for Scene
 where
@@ -54,31 +308,30 @@ where
 {
 	fn from(value: I) -> Self {
 		let objects = value.into_iter().map(|obj| obj.wrap()).collect::<Vec<_>>();
-		Self { list: objects }
+		Self::from_objs(objects)
 	}
 }
 
 // Handle as collection of hittables
 impl Hittable for Scene {
-	fn hit(&self, ray: super::types::Ray, t_range: super::types::Interval) -> Option<Hit> {
-		let mut t_max = t_range.end;
-		let mut closest_hit: Option<Hit> = None;
-		for obj in &self.list {
-			let hit = obj.hit(ray, Interval::new(t_range.start, t_max));
-			if let Some(_hit) = hit {
-				t_max = _hit.t;
-				closest_hit = hit;
-			}
-		}
-		closest_hit
+	fn hit(&self, ray: Ray, t_range: Interval) -> Option<Hit<'_>> {
+		closest_hit(&self.list, ray, t_range)
+	}
+	fn bounding_box(&self) -> Option<Aabb> {
+		self.list
+			.iter()
+			.filter_map(|obj| obj.bounding_box())
+			.reduce(Aabb::merge)
 	}
 }
 
 #[cfg(test)]
 mod tests {
-	use super::Scene;
-	use crate::core::objects::{Hittable, Material, Sphere};
-	use crate::core::types::{Color, Interval, Point, Ray, Vec3};
+	use std::f64::consts::PI;
+
+	use super::{Background, HitOrBackground, Scene, SceneStats};
+	use crate::core::objects::{Group, Hittable, Material, MovingSphere, Sphere, ToObject};
+	use crate::core::types::{Color, Interval, Point, Ray, Transform, Vec3};
 	use crate::objects::Object;
 
 	#[test]
@@ -158,13 +411,13 @@ mod tests {
 
 		// Appending all of them in multiple .append calls should contain all of them in the end:
 		let scene = Scene::new()
-			.append([spheres[0], spheres[1]])
-			.append([spheres[2], spheres[3]])
-			.append([spheres[4]]);
+			.append(spheres[0..2].to_vec())
+			.append(spheres[2..4].to_vec())
+			.append(spheres[4..].to_vec());
 
 		let mut missing_objects: Vec<Sphere> = Vec::new();
 		for sphere in spheres {
-			if !scene.list.contains(&Object::Sphere(sphere)) {
+			if !scene.list.contains(&Object::Sphere(sphere.clone())) {
 				missing_objects.push(sphere);
 			}
 		}
@@ -174,4 +427,236 @@ mod tests {
 			missing_objects
 		)
 	}
+
+	#[test]
+	fn removing_group_removes_all_its_objects() {
+		// This scene has a sphere, and two groups of spheres:
+		let sphere = Sphere::new(Point::origin(), 1.0, Material::Absorbant);
+		let mut scene = Scene::from([sphere.clone()]);
+		let group1 = scene.add_group(
+			vec![sphere.clone().wrap(), sphere.clone().wrap()],
+			Transform::translation(Vec3::new(5, 0, 0)),
+		);
+		let group2 = scene.add_group(
+			vec![sphere.wrap()],
+			Transform::translation(Vec3::new(-5, 0, 0)),
+		);
+		assert_ne!(group1, group2, "groups should have distinct identifiers");
+
+		// Removing the first group should only keep the single sphere and the second group:
+		scene.remove_group(group1);
+		assert_eq!(scene.list.len(), 2, "scene should have two objects left");
+		assert!(
+			matches!(&scene.list[1], Object::Group(group) if group.id() == Some(group2)),
+			"second group should be kept"
+		);
+	}
+
+	#[test]
+	fn if_scene_has_spheres_then_count_and_bounds_reflect_them() {
+		// This scene has five spheres along the x-axis, and a group:
+		let spheres = (0..5).map(|i| Sphere::new(Point::new(i, 0, 0), 0.5, Material::Absorbant));
+		let mut scene = Scene::from(spheres);
+		scene.add_group(Vec::new(), Transform::default());
+
+		assert_eq!(scene.object_count(), 6, "scene should contain 6 objects");
+		assert_eq!(scene.count_spheres(), 5, "scene should contain 5 spheres");
+		assert_eq!(
+			scene.objects_of_type::<Group>().len(),
+			1,
+			"scene should contain 1 group"
+		);
+
+		// The bounding box should enclose all spheres:
+		let bbox = scene.bounding_box();
+		assert!(bbox.is_some(), "scene should have a bounding box");
+		let bbox = bbox.unwrap();
+		assert_eq!(
+			(bbox.x.start, bbox.x.end),
+			(-0.5, 4.5),
+			"bounding box should span the spheres on the x-axis"
+		);
+	}
+
+	#[test]
+	fn from_objs_accepts_wrapped_objects() {
+		let sphere1 = Sphere::new(Point::new(0, 0, 0), 1, Material::Absorbant);
+		let sphere2 = Sphere::new(Point::new(2, 0, 0), 1, Material::Absorbant);
+
+		let scene = Scene::from_objs(vec![Object::Sphere(sphere1), Object::Sphere(sphere2)]);
+		assert_eq!(scene.len(), 2, "scene should contain 2 objects");
+	}
+
+	#[test]
+	fn with_chain_accumulates_objects() {
+		let sphere = Sphere::new(Point::new(0, 0, 0), 1, Material::Absorbant);
+		let group = Group::new(vec![sphere.clone().wrap()], Transform::default());
+
+		// Objects of different types can be chained:
+		let scene = Scene::new().with(sphere.clone()).with(group).with(sphere);
+		assert_eq!(scene.len(), 3, "scene should contain 3 objects");
+		assert_eq!(scene.count_spheres(), 2, "scene should contain 2 spheres");
+	}
+
+	#[test]
+	fn object_stats_count_objects_by_type() {
+		let sphere = Sphere::new(Point::new(0, 0, 0), 1, Material::Absorbant);
+		let moving = MovingSphere::new(
+			Point::new(0, 0, 0),
+			Point::new(1, 0, 0),
+			1,
+			Material::Absorbant,
+		);
+		let group = Group::new(vec![sphere.clone().wrap()], Transform::default());
+
+		// This scene has 3 spheres, 1 moving sphere, and 2 groups:
+		let scene = Scene::from([sphere.clone(), sphere.clone(), sphere])
+			.with(moving)
+			.with(group.clone())
+			.with(group);
+		assert_eq!(
+			scene.object_stats(),
+			SceneStats {
+				total: 6,
+				spheres: 3,
+				moving_spheres: 1,
+				groups: 2,
+				grounds: 0,
+				meshes: 0,
+				cylinders: 0,
+				shells: 0,
+			},
+			"objects should be counted by type"
+		);
+	}
+
+	#[test]
+	fn material_stats_count_objects_by_material() {
+		let matte = Material::Matte {
+			color: Color::white(),
+		};
+		let spheres = (0..4).map(|i| Sphere::new(Point::new(i, 0, 0), 0.5, matte.clone()));
+
+		// This scene only contains matte spheres:
+		let stats = Scene::from(spheres).material_stats();
+		assert_eq!(stats.len(), 1, "there should be a single material");
+		assert_eq!(stats.get("matte"), Some(&4), "all spheres should be matte");
+	}
+
+	#[test]
+	fn if_ray_misses_then_background_is_returned() {
+		let blue = Color::new(0, 0, 1);
+		let sphere = Sphere::new(Point::new(0, 0, -2), 1, Material::Absorbant);
+		let scene = Scene::from([sphere]).with_background(Background::Solid(blue));
+
+		// This ray points away from the sphere:
+		let ray = Ray::new(Point::origin(), Vec3::new(0, 0, 1));
+		let result = scene.hit_with_background(ray, Interval::from(0));
+		assert!(
+			matches!(result, HitOrBackground::Background(color) if color == blue),
+			"ray should see the blue background, but got {:?}",
+			result
+		);
+	}
+
+	#[test]
+	fn if_ray_hits_then_hit_is_returned() {
+		let material = Material::Matte {
+			color: Color::new(0.2, 0.4, 0.6),
+		};
+		let sphere = Sphere::new(Point::new(0, 0, -2), 1, material.clone());
+		let scene = Scene::from([sphere]).with_background(Background::Solid(Color::black()));
+
+		// This ray points at the sphere:
+		let ray = Ray::new(Point::origin(), Vec3::new(0, 0, -1));
+		let result = scene.hit_with_background(ray, Interval::from(0));
+		assert!(
+			matches!(result, HitOrBackground::Hit(hit) if *hit.material == material),
+			"ray should hit the sphere with its material, but got {:?}",
+			result
+		);
+	}
+
+	#[test]
+	fn if_scene_scaled_then_sphere_radius_scaled() {
+		// This sphere is centered at the origin:
+		let sphere = Sphere::new(Point::origin(), 1.5, Material::Absorbant);
+		let scene = Scene::from([sphere]).scaled_by(2.0);
+
+		let spheres = scene.objects_of_type::<Sphere>();
+		assert_eq!(spheres[0].center(), Point::origin(), "center should stay");
+		assert_eq!(spheres[0].radius(), 3.0, "radius should be doubled");
+	}
+
+	#[test]
+	fn if_scene_translated_then_all_objects_moved() {
+		// This scene has a sphere, and a group translated along the x-axis:
+		let sphere = Sphere::new(Point::new(1, 0, 0), 1, Material::Absorbant);
+		let group = Group::new(
+			vec![sphere.clone().wrap()],
+			Transform::translation(Vec3::new(5, 0, 0)),
+		);
+		let scene = Scene::new()
+			.with(sphere)
+			.with(group)
+			.translated_by(Vec3::new(0, 2, 0));
+
+		let positions = scene.list.iter().map(Object::position).collect::<Vec<_>>();
+		assert_eq!(
+			positions,
+			vec![Point::new(1, 2, 0), Point::new(5, 2, 0)],
+			"objects should be moved up"
+		);
+	}
+
+	#[test]
+	fn if_sorted_front_to_back_then_nearest_object_first() {
+		// These spheres are placed along the z-axis, in front of the camera in reverse order:
+		let scene = Scene::from([
+			Sphere::new(Point::new(0, 0, -9), 1, Material::Absorbant),
+			Sphere::new(Point::new(0, 0, -3), 1, Material::Absorbant),
+			Sphere::new(Point::new(0, 0, -6), 1, Material::Absorbant),
+		]);
+		let camera = Point::origin();
+
+		let sorted = scene.sort_front_to_back(camera);
+		let distance = |obj: &Object| camera.distance(obj.bounding_box().unwrap().center());
+		let (first, last) = (&sorted.list[0], &sorted.list[sorted.len() - 1]);
+		assert!(
+			distance(first) < distance(last),
+			"first object at {} should be closer than last object at {}",
+			first.position(),
+			last.position()
+		);
+		assert_eq!(sorted.len(), 3, "no objects should be lost");
+	}
+
+	#[test]
+	fn emissive_spheres_contribute_emitted_power() {
+		// Two of these unit spheres emit light, the third one doesn't:
+		let color = Color::new(1, 0.5, 0.25);
+		let emissive = Material::Emissive {
+			color,
+			intensity: 5.0,
+		};
+		let scene = Scene::from([
+			Sphere::new(Point::new(-2, 0, 0), 1, emissive.clone()),
+			Sphere::new(Point::origin(), 1, Material::Absorbant),
+			Sphere::new(Point::new(2, 0, 0), 1, emissive),
+		]);
+
+		assert_eq!(
+			scene.emissive_objects().len(),
+			2,
+			"both emissive spheres should be listed"
+		);
+		let expected = 2.0 * 4.0 * PI * 5.0 * color.luminance();
+		let power = scene.total_emitted_power();
+		assert!(
+			(power - expected).abs() < 1e-9,
+			"power should be {}, but was {}",
+			expected,
+			power
+		);
+	}
 }
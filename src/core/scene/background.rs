@@ -0,0 +1,75 @@
+use crate::core::objects::Hit;
+use crate::core::types::{Color, Ray, ToVec3};
+
+/// The color of rays that don't hit any object in a scene.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Background {
+	/// A gradient that blends from the `bottom` color (straight down) to the `top` color
+	/// (straight up), based on the vertical component of the ray's direction.
+	/// Horizontal rays see an even mix of both colors.
+	Gradient { top: Color, bottom: Color },
+	/// A single color in every direction.
+	Solid(Color),
+}
+impl Default for Background {
+	/// Creates a sky that blends from white below to light blue above.
+	fn default() -> Self {
+		Self::Gradient {
+			top: Color(0.5, 0.7, 1.0),
+			bottom: Color::white(),
+		}
+	}
+}
+
+impl Background {
+	/// Calculates the color of the background in the direction of the `ray`.
+	pub fn color(&self, ray: Ray) -> Color {
+		match self {
+			Self::Gradient { top, bottom } => {
+				let a = 0.5 * (ray.direction.unit().y() + 1.0);
+				let bottom = bottom.to_vec3().scale(1.0 - a);
+				let top = top.to_vec3().scale(a);
+				(bottom + top).into()
+			}
+			Self::Solid(color) => *color,
+		}
+	}
+}
+
+/// The result of casting a ray into a scene, see [`crate::scene::Scene::hit_with_background`].
+#[derive(Debug, Clone, Copy)]
+pub enum HitOrBackground<'a> {
+	/// The ray hit an object.
+	Hit(Hit<'a>),
+	/// The ray missed all objects, and sees the background of the specified color.
+	Background(Color),
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Background;
+	use crate::core::types::{Color, Point, Ray, ToVec3, Vec3};
+
+	#[test]
+	fn gradient_blends_from_bottom_to_top() {
+		let background = Background::Gradient {
+			top: Color::new(1, 0, 0),
+			bottom: Color::new(0, 0, 1),
+		};
+		let color = |y| background.color(Ray::new(Point::origin(), Vec3::new(1, y, 0)));
+		let cases = [
+			(1e9, Color::new(1, 0, 0), "straight up should be red"),
+			(0.0, Color::new(0.5, 0, 0.5), "horizontal should be purple"),
+			(-1e9, Color::new(0, 0, 1), "straight down should be blue"),
+		];
+		for (y, expected, message) in cases {
+			let actual = color(y);
+			assert!(
+				(actual.to_vec3() - expected.to_vec3()).norm() < 1e-6,
+				"{}, but was {:?}",
+				message,
+				actual
+			);
+		}
+	}
+}
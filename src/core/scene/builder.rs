@@ -0,0 +1,75 @@
+use crate::core::objects::{Hittable, Material, Sphere, ToObject};
+use crate::core::types::Point;
+
+use super::{Background, Scene};
+
+/// A builder, with which a scene can be constructed in a single expression:
+/// ```
+/// let scene = SceneBuilder::new()
+///   .sphere(Point::new(0, 0, -1), 0.5, material)
+///   .object(group)
+///   .background(Background::Solid(color))
+///   .build();
+/// ```
+#[derive(Debug, Default)]
+pub struct SceneBuilder {
+	/// The scene that is being built.
+	scene: Scene,
+}
+
+impl SceneBuilder {
+	/// Creates a new builder of an empty scene.
+	pub fn new() -> Self {
+		Self::default()
+	}
+	/// Adds a sphere with the specified center, radius and material to the scene.
+	/// See [`Sphere::new`].
+	pub fn sphere<F: Into<f64>>(self, center: Point, radius: F, material: Material) -> Self {
+		self.object(Sphere::new(center, radius, material))
+	}
+	/// Adds an object of any type to the scene.
+	pub fn object<T: Hittable + ToObject>(mut self, obj: T) -> Self {
+		self.scene.add(obj);
+		self
+	}
+	/// Sets the background of the scene, see [`Scene::with_background`].
+	pub fn background(mut self, background: Background) -> Self {
+		self.scene.set_background(background);
+		self
+	}
+	/// Returns the scene that has been built.
+	pub fn build(self) -> Scene {
+		self.scene
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::SceneBuilder;
+	use crate::core::objects::{Material, MovingSphere};
+	use crate::core::scene::Background;
+	use crate::core::types::{Color, Point};
+
+	#[test]
+	fn built_scene_contains_all_objects() {
+		let blue = Color::new(0.1, 0.2, 0.9);
+		let scene = SceneBuilder::new()
+			.sphere(Point::new(-1, 0, -2), 0.5, Material::Absorbant)
+			.sphere(Point::new(1, 0, -2), 0.5, Material::Absorbant)
+			.object(MovingSphere::new(
+				Point::new(0, 1, -2),
+				Point::new(0, 2, -2),
+				0.25,
+				Material::Absorbant,
+			))
+			.background(Background::Solid(blue))
+			.build();
+
+		assert_eq!(scene.len(), 3, "scene should contain 3 objects");
+		assert_eq!(
+			scene.background(),
+			Background::Solid(blue),
+			"scene should have the specified background"
+		);
+	}
+}
@@ -0,0 +1,184 @@
+use crate::core::objects::Object;
+use crate::core::types::Transform;
+
+use super::Scene;
+
+/// An identifier of a node within a [`SceneGraph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
+/// A node of a [`SceneGraph`].
+#[derive(Debug, Clone)]
+pub struct Node {
+	/// The transform of this node, relative to its parent.
+	pub transform: Transform,
+	/// The object at this node, if there is one. Nodes without an object only group their children.
+	pub object: Option<Object>,
+	/// The children of this node, which inherit its transform.
+	pub children: Vec<NodeId>,
+}
+
+/// A hierarchy of objects, in which each node's transform applies to all of its descendants.
+///
+/// Complex models can be built from parts that are positioned relative to each other:
+/// ```
+/// let mut graph = SceneGraph::new();
+/// let robot = graph.add_root(Transform::translation(position), None);
+/// let head = graph.add_child(robot, Transform::translation(Vec3::new(0, 2, 0)), Some(head));
+/// let scene = graph.flatten();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SceneGraph {
+	/// All nodes of the graph, indexed by their identifiers.
+	nodes: Vec<Node>,
+	/// The nodes without a parent, in the order they were added.
+	roots: Vec<NodeId>,
+}
+
+impl SceneGraph {
+	/// Creates a new empty scene graph.
+	pub fn new() -> Self {
+		Self::default()
+	}
+	/// Adds a node without a parent, whose `transform` is relative to the world.
+	pub fn add_root(&mut self, transform: Transform, object: Option<Object>) -> NodeId {
+		let id = self.push(transform, object);
+		self.roots.push(id);
+		id
+	}
+	/// Adds a node as the last child of the `parent` node, whose `transform` is relative to
+	/// the parent's.
+	///
+	/// # Panics
+	/// Panics if the `parent` is not a node of this graph.
+	pub fn add_child(
+		&mut self,
+		parent: NodeId,
+		transform: Transform,
+		object: Option<Object>,
+	) -> NodeId {
+		assert!(
+			parent.0 < self.nodes.len(),
+			"parent {:?} should be a node of this graph",
+			parent
+		);
+		let id = self.push(transform, object);
+		self.nodes[parent.0].children.push(id);
+		id
+	}
+	/// Returns the node with the specified identifier, if it belongs to this graph.
+	pub fn node(&self, id: NodeId) -> Option<&Node> {
+		self.nodes.get(id.0)
+	}
+	/// Returns the amount of nodes in this graph.
+	pub fn len(&self) -> usize {
+		self.nodes.len()
+	}
+	/// Checks if this graph has no nodes.
+	pub fn is_empty(&self) -> bool {
+		self.nodes.is_empty()
+	}
+	/// Creates a flat scene for rendering, in which the transforms of each object's node and all of
+	/// its ancestors are applied to the object (see [`Object::apply_transform`]).
+	///
+	/// Objects are added to the scene depth-first, in the order their nodes were added.
+	pub fn flatten(&self) -> Scene {
+		let mut objects = Vec::new();
+		for root in &self.roots {
+			self.flatten_into(*root, &Transform::default(), &mut objects);
+		}
+		Scene::from_objs(objects)
+	}
+
+	/// Adds a new node without any children, and returns its identifier.
+	fn push(&mut self, transform: Transform, object: Option<Object>) -> NodeId {
+		self.nodes.push(Node {
+			transform,
+			object,
+			children: Vec::new(),
+		});
+		NodeId(self.nodes.len() - 1)
+	}
+	/// Adds the transformed objects of the node `id` and its descendants to `objects`,
+	/// where `parent` is the combined transform of the node's ancestors.
+	fn flatten_into(&self, id: NodeId, parent: &Transform, objects: &mut Vec<Object>) {
+		let node = &self.nodes[id.0];
+		let transform = node.transform.then(parent);
+		if let Some(object) = &node.object {
+			objects.push(object.clone().apply_transform(&transform));
+		}
+		for child in &node.children {
+			self.flatten_into(*child, &transform, objects);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::core::objects::{Material, Sphere, ToObject};
+	use crate::core::types::{Color, Point, Transform, Vec3};
+
+	use super::SceneGraph;
+
+	/// Creates a matte sphere of radius 1 at the origin.
+	fn sphere() -> Sphere {
+		Sphere::new(
+			Point::origin(),
+			1.0,
+			Material::Matte {
+				color: Color::white(),
+			},
+		)
+	}
+
+	#[test]
+	fn child_inherits_parent_translation() {
+		// This child is at the origin of its parent, which is moved by 5 along the x-axis:
+		let mut graph = SceneGraph::new();
+		let parent = graph.add_root(Transform::translation(Vec3::new(5, 0, 0)), None);
+		graph.add_child(parent, Transform::default(), Some(sphere().wrap()));
+
+		let scene = graph.flatten();
+		assert_eq!(scene.len(), 1, "scene should contain the child");
+		assert_eq!(
+			scene.list[0].position(),
+			Point::new(5, 0, 0),
+			"child should be moved with its parent"
+		);
+	}
+
+	#[test]
+	fn nested_transforms_are_composed() {
+		// The root doubles the size and moves by (1, 0, 0); its child moves by (0, 2, 0)
+		// in the root's (scaled) space, and the grandchild moves by (0, 0, 3) in the child's space:
+		let mut graph = SceneGraph::new();
+		let root = Transform {
+			translation: Vec3::new(1, 0, 0),
+			scale: 2.0,
+			..Default::default()
+		};
+		let root = graph.add_root(root, Some(sphere().wrap()));
+		let child = graph.add_child(
+			root,
+			Transform::translation(Vec3::new(0, 2, 0)),
+			Some(sphere().wrap()),
+		);
+		graph.add_child(
+			child,
+			Transform::translation(Vec3::new(0, 0, 3)),
+			Some(sphere().wrap()),
+		);
+
+		let scene = graph.flatten();
+		let positions = scene.list.iter().map(|o| o.position()).collect::<Vec<_>>();
+		assert_eq!(
+			positions,
+			vec![
+				Point::new(1, 0, 0),
+				Point::new(1, 4, 0),
+				Point::new(1, 4, 6)
+			],
+			"translations should be scaled by the root, and add up"
+		);
+	}
+}
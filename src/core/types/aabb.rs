@@ -0,0 +1,155 @@
+use super::{Interval, Point, Ray, ToVec3, Transform};
+
+/// An axis-aligned bounding box, described by an interval on each axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+	/// The extent on the X axis.
+	pub x: Interval,
+	/// The extent on the Y axis.
+	pub y: Interval,
+	/// The extent on the Z axis.
+	pub z: Interval,
+}
+
+// Constructors
+impl Aabb {
+	/// Creates a new bounding box from the specified intervals on each axis.
+	pub fn new(x: Interval, y: Interval, z: Interval) -> Self {
+		Self { x, y, z }
+	}
+	/// Creates the smallest bounding box that contains both specified (opposite) corner points.
+	pub fn from_corners(a: Point, b: Point) -> Self {
+		let axis = |a: f64, b: f64| Interval::new(f64::min(a, b), f64::max(a, b));
+		Self {
+			x: axis(a.x(), b.x()),
+			y: axis(a.y(), b.y()),
+			z: axis(a.z(), b.z()),
+		}
+	}
+}
+
+// Operations
+impl Aabb {
+	/// Returns the smallest bounding box that contains both this and another bounding box.
+	pub fn merge(self, other: Self) -> Self {
+		Self {
+			x: self.x.merge(other.x),
+			y: self.y.merge(other.y),
+			z: self.z.merge(other.z),
+		}
+	}
+	/// Returns the interval on the specified axis (0 for X, 1 for Y, 2 for Z).
+	pub fn axis(&self, index: usize) -> Interval {
+		match index {
+			0 => self.x,
+			1 => self.y,
+			2 => self.z,
+			_ => panic!("axis index out of bounds {}", index),
+		}
+	}
+	/// Checks whether the specified ray passes through this bounding box within `t_range`.
+	///
+	/// Uses the slab method: on each axis, the range of `t` for which the ray lies between
+	/// both planes of the box is calculated, and these ranges are intersected.
+	pub fn ray_hit(&self, ray: Ray, t_range: Interval) -> bool {
+		let origin = ray.origin.to_vec3();
+		let mut t_range = t_range;
+		for axis in 0..3 {
+			let slab = self.axis(axis);
+			let (origin, direction) = (origin[axis], ray.direction[axis]);
+			if direction.abs() < f64::EPSILON {
+				// The ray is parallel to the slab, and either always or never inside of it
+				if origin < slab.start || origin > slab.end {
+					return false;
+				}
+				continue;
+			}
+			let t0 = (slab.start - origin) / direction;
+			let t1 = (slab.end - origin) / direction;
+			let (t0, t1) = if t0 < t1 { (t0, t1) } else { (t1, t0) };
+			t_range = Interval::new(f64::max(t_range.start, t0), f64::min(t_range.end, t1));
+			if t_range.end <= t_range.start {
+				return false;
+			}
+		}
+		true
+	}
+	/// Returns the center point of this bounding box.
+	pub fn center(&self) -> Point {
+		let mid = |axis: Interval| (axis.start + axis.end) / 2.0;
+		Point(mid(self.x), mid(self.y), mid(self.z))
+	}
+	/// Returns the eight corner points of this bounding box.
+	pub fn corners(&self) -> [Point; 8] {
+		let (x, y, z) = (self.x, self.y, self.z);
+		[
+			Point(x.start, y.start, z.start),
+			Point(x.start, y.start, z.end),
+			Point(x.start, y.end, z.start),
+			Point(x.start, y.end, z.end),
+			Point(x.end, y.start, z.start),
+			Point(x.end, y.start, z.end),
+			Point(x.end, y.end, z.start),
+			Point(x.end, y.end, z.end),
+		]
+	}
+	/// Returns the smallest bounding box that contains this bounding box after applying a transform.
+	pub fn transformed(&self, transform: &Transform) -> Self {
+		let corners = self.corners().map(|corner| transform.apply_point(corner));
+		corners.iter().skip(1).fold(
+			Self::from_corners(corners[0], corners[0]),
+			|aabb, corner| aabb.merge(Self::from_corners(*corner, *corner)),
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Aabb;
+	use crate::core::types::{Interval, Point, Ray, Vec3};
+
+	/// Creates a cube with side length 2, centered at the origin.
+	fn cube() -> Aabb {
+		Aabb::from_corners(Point::new(-1, -1, -1), Point::new(1, 1, 1))
+	}
+
+	#[test]
+	fn if_ray_through_center_then_hit() {
+		// This ray points at the center of the box from outside:
+		let ray = Ray::new(Point::new(0, 0, 5), Vec3::new(0.1, 0.1, -1));
+		assert!(
+			cube().ray_hit(ray, Interval::from(0)),
+			"ray through the center should hit the box, but didn't"
+		);
+	}
+
+	#[test]
+	fn if_ray_parallel_outside_slab_then_no_hit() {
+		// This ray runs along the z-axis, above the box:
+		let ray = Ray::new(Point::new(0, 2, 5), Vec3::new(0, 0, -1));
+		assert!(
+			!cube().ray_hit(ray, Interval::from(0)),
+			"ray parallel to the slab should miss the box, but hit"
+		);
+	}
+
+	#[test]
+	fn if_ray_starts_inside_then_hit() {
+		// This ray starts inside the box:
+		let ray = Ray::new(Point::new(0.5, 0, 0), Vec3::new(1, 1, 0));
+		assert!(
+			cube().ray_hit(ray, Interval::from(0)),
+			"ray starting inside should hit the box, but didn't"
+		);
+	}
+
+	#[test]
+	fn if_box_behind_ray_then_no_hit() {
+		// This ray points away from the box:
+		let ray = Ray::new(Point::new(0, 0, 5), Vec3::new(0, 0, 1));
+		assert!(
+			!cube().ray_hit(ray, Interval::from(0)),
+			"ray pointing away should miss the box, but hit"
+		);
+	}
+}
@@ -1,13 +1,37 @@
+mod aberration;
+mod bloom;
+mod channels;
+mod denoise;
+mod distortion;
+mod histogram;
+mod metrics;
+mod pipeline;
+mod scale;
+mod tone;
+mod vignette;
+
 use std::ops;
 
-use super::Color;
+pub use channels::Channel;
+pub use pipeline::{
+	BloomPass, ChromaticAberrationPass, GammaCorrectionPass, PostProcess, PostProcessPipeline,
+	ToneMapPass, VignettePass,
+};
+pub use tone::ToneMap;
+pub(crate) use vignette::vignette_weight;
+
+use super::{Color, ToVec3};
 
 // MARK: - Image
 
 type ImageIdx = (usize, usize);
 
+/// The amount by which the channels of two pixels may differ, while still being considered equal.
+/// This is one step of an 8-bit color channel.
+const PIXEL_TOLERANCE: f64 = 1.0 / 255.0;
+
 /// A type that represents an image.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Image {
 	pixels: Vec<Color>,
 	height: usize,
@@ -24,6 +48,15 @@ impl Image {
 			width,
 		}
 	}
+	/// Creates a new image with the specified height and width, filled with the `color`.
+	#[cfg(test)]
+	pub(crate) fn filled(height: usize, width: usize, color: Color) -> Self {
+		Self {
+			pixels: vec![color; height * width],
+			height,
+			width,
+		}
+	}
 	/// Returns the height of this image, in pixels.
 	pub fn height(&self) -> usize {
 		self.height
@@ -32,6 +65,32 @@ impl Image {
 	pub fn width(&self) -> usize {
 		self.width
 	}
+	/// Checks whether this image is approximately equal to `other`: both have the same size, and at
+	/// most the fraction `tolerance` of their pixels differ noticeably (by more than one 8-bit step
+	/// in any channel).
+	pub fn approx_eq(&self, other: &Image, tolerance: f64) -> bool {
+		if (self.height, self.width) != (other.height, other.width) {
+			return false;
+		}
+		let differing = self
+			.pixels
+			.iter()
+			.zip(&other.pixels)
+			.filter(|(a, b)| {
+				let diff = (a.to_vec3() - b.to_vec3()).to_tuple(f64::abs);
+				f64::max(diff.0, f64::max(diff.1, diff.2)) > PIXEL_TOLERANCE
+			})
+			.count();
+		differing as f64 <= tolerance * self.pixels.len() as f64
+	}
+	/// Checks if any pixel of this image has a negative or NaN channel, see [`Color::is_valid`].
+	pub fn has_invalid_pixels(&self) -> bool {
+		self.pixels.iter().any(|pixel| !pixel.is_valid())
+	}
+	/// Counts the pixels of this image that have a NaN channel, see [`Color::has_nan`].
+	pub fn count_nan_pixels(&self) -> usize {
+		self.pixels.iter().filter(|pixel| pixel.has_nan()).count()
+	}
 	/// Checks if the specified index is valid for this image.
 	/// Panics if either the row or column index is out of bounds.
 	#[cfg(debug_assertions)]
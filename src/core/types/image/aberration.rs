@@ -0,0 +1,136 @@
+use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+use rayon::slice::ParallelSliceMut;
+
+use crate::core::types::{Color, ToVec3};
+
+use super::Image;
+
+/// Relative amount of radial shift applied to the red, green and blue channels.
+/// Longer wavelengths are refracted less by a lens, and are therefore shifted further outwards.
+const CHANNEL_SHIFTS: [f64; 3] = [1.0, 0.6, 0.3];
+
+// Chromatic aberration
+impl Image {
+	/// Simulates lateral chromatic aberration (colour fringing), and returns the result.
+	///
+	/// Each color channel is shifted outwards from the center of the image independently,
+	/// by an amount proportional to `strength` and to the distance from the center.
+	/// At a `strength` of `0.01`, the red channel is magnified by 1%; the green and blue
+	/// channels are magnified less. A `strength` of `0` leaves the image unchanged.
+	pub fn chromatic_aberration(&self, strength: f64) -> Image {
+		if strength == 0.0 {
+			return self.clone();
+		}
+		let center_row = (self.height as f64 - 1.0) / 2.0;
+		let center_col = (self.width as f64 - 1.0) / 2.0;
+		let scales = CHANNEL_SHIFTS.map(|shift| 1.0 + strength * shift);
+
+		let mut output = Image::init(self.height, self.width);
+		output
+			.par_chunks_mut(self.width)
+			.enumerate()
+			.for_each(|(row, pixels)| {
+				for (col, pixel) in pixels.iter_mut().enumerate() {
+					// Each channel is sampled closer to the center, which shifts it outwards
+					let [r, g, b] = scales.map(|scale| {
+						let src_row = center_row + (row as f64 - center_row) / scale;
+						let src_col = center_col + (col as f64 - center_col) / scale;
+						self.bilinear(src_row, src_col)
+					});
+					*pixel = Color::new(r.r(), g.g(), b.b());
+				}
+			});
+		output
+	}
+
+	/// Samples this image at the specified fractional position with bilinear interpolation.
+	/// Positions outside of the image are clamped to the nearest edge.
+	pub(super) fn bilinear(&self, row: f64, col: f64) -> Color {
+		let row = row.clamp(0.0, self.height as f64 - 1.0);
+		let col = col.clamp(0.0, self.width as f64 - 1.0);
+		let (row_0, col_0) = (row.floor() as usize, col.floor() as usize);
+		let (row_1, col_1) = (
+			usize::min(row_0 + 1, self.height - 1),
+			usize::min(col_0 + 1, self.width - 1),
+		);
+		let (t_row, t_col) = (row - row_0 as f64, col - col_0 as f64);
+
+		let lerp = |a: Color, b: Color, t: f64| a.to_vec3().scale(1.0 - t) + b.to_vec3().scale(t);
+		let top = lerp(self[(row_0, col_0)], self[(row_0, col_1)], t_col);
+		let bottom = lerp(self[(row_1, col_0)], self[(row_1, col_1)], t_col);
+		(top.scale(1.0 - t_row) + bottom.scale(t_row)).into()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::core::types::{Color, Image};
+
+	/// Returns the column with the largest value in the specified row, for each channel.
+	fn brightest_cols(image: &Image, row: usize) -> [usize; 3] {
+		let channels: [fn(&Color) -> f64; 3] = [Color::r, Color::g, Color::b];
+		channels.map(|channel| {
+			(0..image.width())
+				.max_by(|&a, &b| channel(&image[(row, a)]).total_cmp(&channel(&image[(row, b)])))
+				.unwrap()
+		})
+	}
+
+	#[test]
+	fn if_pixel_near_edge_then_channels_are_offset() {
+		// This image has a single white pixel near its right edge:
+		let mut image = Image::init(21, 101);
+		image[(10, 90)] = Color::white();
+
+		// With chromatic aberration, the channels should drift apart:
+		let result = image.chromatic_aberration(0.1);
+		let [r, g, b] = brightest_cols(&result, 10);
+		assert!(
+			r > g && g > b,
+			"red should be shifted furthest and blue the least, but columns were {:?}",
+			[r, g, b]
+		);
+		assert!(
+			b >= 90,
+			"all channels should shift outwards, but blue was at {}",
+			b
+		);
+	}
+
+	#[test]
+	fn if_pixel_at_center_then_unaffected() {
+		// This image has a single white pixel at its exact center:
+		let mut image = Image::init(21, 21);
+		image[(10, 10)] = Color::white();
+
+		// The center pixel should not move:
+		let result = image.chromatic_aberration(0.05);
+		assert_eq!(
+			result[(10, 10)],
+			Color::white(),
+			"center pixel should be unaffected, but was {:?}",
+			result[(10, 10)]
+		);
+	}
+
+	#[test]
+	fn if_strength_zero_then_image_unchanged() {
+		// This image has a single white pixel near its corner:
+		let mut image = Image::init(9, 9);
+		image[(1, 7)] = Color::white();
+
+		// No strength should produce the same image:
+		let result = image.chromatic_aberration(0.0);
+		for row in 0..image.height() {
+			for col in 0..image.width() {
+				assert_eq!(
+					result[(row, col)],
+					image[(row, col)],
+					"pixel ({}, {}) should be unchanged",
+					row,
+					col
+				);
+			}
+		}
+	}
+}
@@ -0,0 +1,148 @@
+use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+use rayon::slice::ParallelSliceMut;
+
+use crate::core::types::{Color, ToVec3, Vec3};
+
+use super::Image;
+
+// Bloom
+impl Image {
+	/// Lets bright highlights bleed into their surroundings, and returns the result.
+	///
+	/// Pixels with a luminance of at least `threshold` are blurred with a Gaussian kernel
+	/// that reaches `radius` pixels in every direction. The blurred highlights are scaled by
+	/// `intensity` and added to this image.
+	pub fn bloom(&self, threshold: f64, radius: usize, intensity: f64) -> Image {
+		let mut bright = self.clone();
+		for pixel in bright.pixels.iter_mut() {
+			if pixel.luminance() < threshold {
+				*pixel = Color::black();
+			}
+		}
+		let blurred = bright.gaussian_blur(radius);
+
+		let mut output = self.clone();
+		for (pixel, glow) in output.pixels.iter_mut().zip(blurred.pixels) {
+			*pixel = (pixel.to_vec3() + glow.to_vec3().scale(intensity)).into();
+		}
+		output
+	}
+
+	/// Blurs this image with a normalized Gaussian kernel of the specified `radius`,
+	/// and returns the result. The kernel's standard deviation is a third of the radius.
+	fn gaussian_blur(&self, radius: usize) -> Image {
+		let sigma = f64::max(radius as f64 / 3.0, f64::EPSILON);
+		let kernel: Vec<f64> = (-(radius as isize)..=radius as isize)
+			.map(|k| f64::exp(-((k * k) as f64) / (2.0 * sigma * sigma)))
+			.collect();
+		let total: f64 = kernel.iter().sum();
+		let kernel: Vec<f64> = kernel.into_iter().map(|w| w / total).collect();
+
+		let horizontal = self.convolution_pass(&kernel, (0, 1));
+		horizontal.convolution_pass(&kernel, (1, 0))
+	}
+	/// Performs a one-dimensional convolution pass with the `kernel` in the direction `(d_row, d_col)`.
+	/// Pixels outside of the image are treated as black.
+	fn convolution_pass(&self, kernel: &[f64], direction: (isize, isize)) -> Image {
+		let radius = (kernel.len() / 2) as isize;
+
+		let mut output = Image::init(self.height, self.width);
+		output
+			.par_chunks_mut(self.width)
+			.enumerate()
+			.for_each(|(row, pixels)| {
+				for (col, pixel) in pixels.iter_mut().enumerate() {
+					let mut sum = Vec3::zero();
+					for (k, weight) in (-radius..=radius).zip(kernel) {
+						let (i, j) = (
+							row as isize + k * direction.0,
+							col as isize + k * direction.1,
+						);
+						if let Some(neighbour) = self.get(i, j) {
+							sum += neighbour.to_vec3().scale(*weight);
+						}
+					}
+					*pixel = sum.into();
+				}
+			});
+		output
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::core::types::{Color, Image};
+
+	/// Creates a black image with a single very bright pixel at its center.
+	fn bright_spot() -> Image {
+		let mut image = Image::init(21, 21);
+		image[(10, 10)] = Color::new(10, 10, 10);
+		image
+	}
+
+	#[test]
+	fn if_bright_pixel_then_bloom_surrounds_it() {
+		// This image has a very bright pixel at its center:
+		let image = bright_spot();
+
+		// After bloom, the pixels around it should be lit, with decreasing brightness:
+		let result = image.bloom(1.0, 3, 1.0);
+		let ring = (1..=3)
+			.map(|d| result[(10, 10 + d)].luminance())
+			.collect::<Vec<_>>();
+		assert!(
+			ring.iter().all(|&l| l > 0.0),
+			"pixels within the radius should be lit, but were {:?}",
+			ring
+		);
+		assert!(
+			ring.windows(2).all(|w| w[0] > w[1]),
+			"brightness should fall off with distance, but was {:?}",
+			ring
+		);
+		assert_eq!(
+			result[(10, 14)],
+			Color::black(),
+			"pixels beyond the radius should stay black"
+		);
+	}
+
+	#[test]
+	fn if_radius_larger_then_bloom_reaches_further() {
+		// This image has a very bright pixel at its center:
+		let image = bright_spot();
+
+		// A pixel 5 pixels away should only be reached by a larger radius:
+		let small = image.bloom(1.0, 3, 1.0);
+		let large = image.bloom(1.0, 6, 1.0);
+		assert_eq!(
+			small[(15, 10)],
+			Color::black(),
+			"small radius should not reach the pixel"
+		);
+		assert!(
+			large[(15, 10)].luminance() > 0.0,
+			"large radius should reach the pixel, but didn't"
+		);
+	}
+
+	#[test]
+	fn if_below_threshold_then_image_unchanged() {
+		// This image has a dim pixel:
+		let mut image = Image::init(5, 5);
+		image[(2, 2)] = Color::new(0.5, 0.5, 0.5);
+
+		// Bloom should not affect it:
+		let result = image.bloom(1.0, 2, 1.0);
+		assert_eq!(
+			result[(2, 1)],
+			Color::black(),
+			"dim pixels should not bloom"
+		);
+		assert_eq!(
+			result[(2, 2)],
+			image[(2, 2)],
+			"dim pixel should be unchanged"
+		);
+	}
+}
@@ -0,0 +1,211 @@
+use crate::core::error::RaytracerError;
+use crate::core::types::Color;
+
+use super::Image;
+
+/// A color channel of an image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+	/// The red channel.
+	R,
+	/// The green channel.
+	G,
+	/// The blue channel.
+	B,
+}
+impl Channel {
+	/// The index of this channel within [`Color::to_array`].
+	fn index(self) -> usize {
+		match self {
+			Self::R => 0,
+			Self::G => 1,
+			Self::B => 2,
+		}
+	}
+}
+
+// Channels
+impl Image {
+	/// Returns a channel of this image as a greyscale image, i.e. every pixel has the value of the
+	/// channel in all three components.
+	pub fn channel(&self, ch: Channel) -> Image {
+		Image {
+			pixels: self
+				.pixels
+				.iter()
+				.map(|pixel| {
+					let value = pixel.to_array()[ch.index()];
+					Color::new(value, value, value)
+				})
+				.collect(),
+			height: self.height,
+			width: self.width,
+		}
+	}
+	/// Returns this image with a channel replaced by the same channel of the greyscale image
+	/// `replacement` (see [`Image::channel`]). The other channels are unchanged.
+	///
+	/// Returns an error if the images have different dimensions.
+	pub fn with_channel(&self, ch: Channel, replacement: &Image) -> Result<Image, RaytracerError> {
+		if (replacement.height, replacement.width) != (self.height, self.width) {
+			return Err(RaytracerError::DimensionMismatch(format!(
+				"image has size {}x{}, but replacement channel has size {}x{}",
+				self.width, self.height, replacement.width, replacement.height
+			)));
+		}
+		let pixels = self
+			.pixels
+			.iter()
+			.zip(&replacement.pixels)
+			.map(|(pixel, replacement)| {
+				let mut channels = pixel.to_array();
+				channels[ch.index()] = replacement.to_array()[ch.index()];
+				Color::from_array(channels)
+			})
+			.collect();
+		Ok(Image {
+			pixels,
+			height: self.height,
+			width: self.width,
+		})
+	}
+	/// Splits this image into its red, green and blue channels, see [`Image::channel`].
+	pub fn split_channels(&self) -> (Image, Image, Image) {
+		(
+			self.channel(Channel::R),
+			self.channel(Channel::G),
+			self.channel(Channel::B),
+		)
+	}
+	/// Combines three greyscale images into one RGB image, taking the red component of `r`,
+	/// the green component of `g`, and the blue component of `b`.
+	/// This is the inverse of [`Image::split_channels`].
+	///
+	/// Returns an error if the images don't all have the same dimensions.
+	pub fn from_channels(r: &Image, g: &Image, b: &Image) -> Result<Image, RaytracerError> {
+		let size = (r.height, r.width);
+		if (g.height, g.width) != size || (b.height, b.width) != size {
+			return Err(RaytracerError::DimensionMismatch(format!(
+				"channels have sizes {}x{}, {}x{} and {}x{}",
+				r.width, r.height, g.width, g.height, b.width, b.height
+			)));
+		}
+		let pixels = r
+			.pixels
+			.iter()
+			.zip(&g.pixels)
+			.zip(&b.pixels)
+			.map(|((r, g), b)| Color::new(r.r(), g.g(), b.b()))
+			.collect();
+		Ok(Image {
+			pixels,
+			height: r.height,
+			width: r.width,
+		})
+	}
+	/// Applies the row-major color `matrix` to every pixel of this image, for example to change
+	/// its saturation or color temperature (see [`Color::apply_matrix`]).
+	pub fn color_grade(&self, matrix: [[f64; 3]; 3]) -> Image {
+		Image {
+			pixels: self
+				.pixels
+				.iter()
+				.map(|pixel| pixel.apply_matrix(matrix))
+				.collect(),
+			height: self.height,
+			width: self.width,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Channel;
+	use crate::core::types::{Color, Image};
+
+	#[test]
+	fn split_and_recombined_image_is_unchanged() {
+		let image = Image::filled(3, 4, Color::new(0.2, 0.5, 0.8));
+
+		let (r, g, b) = image.split_channels();
+		assert_eq!(
+			g[(1, 1)],
+			Color::new(0.5, 0.5, 0.5),
+			"channel should be greyscale"
+		);
+		let result = Image::from_channels(&r, &g, &b);
+		assert!(result.is_ok(), "combining should succeed, but didn't");
+		assert_eq!(result.unwrap(), image, "image should be unchanged");
+	}
+
+	#[test]
+	fn if_image_is_red_then_other_channels_are_black() {
+		let image = Image::filled(2, 2, Color::new(1, 0, 0));
+
+		let (r, g, b) = image.split_channels();
+		assert_eq!(
+			r,
+			Image::filled(2, 2, Color::white()),
+			"red channel should be white"
+		);
+		assert_eq!(g, Image::init(2, 2), "green channel should be black");
+		assert_eq!(b, Image::init(2, 2), "blue channel should be black");
+	}
+
+	#[test]
+	fn color_grade_applies_matrix_to_every_pixel() {
+		let image = Image::filled(2, 3, Color::new(0.2, 0.5, 0.8));
+		// This matrix swaps the red and blue channels:
+		let swap = [[0.0, 0.0, 1.0], [0.0, 1.0, 0.0], [1.0, 0.0, 0.0]];
+		assert_eq!(
+			image.color_grade(swap),
+			Image::filled(2, 3, Color::new(0.8, 0.5, 0.2)),
+			"every pixel should have its channels swapped"
+		);
+	}
+
+	#[test]
+	fn replaced_channel_changes_only_that_channel() {
+		let image = Image::filled(2, 3, Color::new(0.2, 0.5, 0.8));
+		let replacement = Image::filled(2, 3, Color::new(0.9, 0.9, 0.9));
+
+		let result = image.with_channel(Channel::R, &replacement);
+		assert!(result.is_ok(), "replacing should succeed, but didn't");
+		let result = result.unwrap();
+		assert_eq!(
+			result,
+			Image::filled(2, 3, Color::new(0.9, 0.5, 0.8)),
+			"only the red channel should change"
+		);
+		assert_eq!(
+			result.channel(Channel::R),
+			replacement,
+			"red channel should be the replacement"
+		);
+	}
+
+	#[test]
+	fn if_green_replaced_with_black_then_green_is_zero() {
+		let image = Image::filled(2, 2, Color::new(0.2, 0.5, 0.8));
+		let result = image.with_channel(Channel::G, &Image::init(2, 2)).unwrap();
+		assert_eq!(
+			result,
+			Image::filled(2, 2, Color::new(0.2, 0, 0.8)),
+			"green channel should be zero"
+		);
+		assert!(
+			image.with_channel(Channel::G, &Image::init(3, 2)).is_err(),
+			"replacing with a different size should fail, but didn't"
+		);
+	}
+
+	#[test]
+	fn if_dimensions_differ_then_combining_fails() {
+		let (r, g) = (Image::init(2, 2), Image::init(2, 2));
+		let b = Image::init(2, 3);
+		assert!(
+			Image::from_channels(&r, &g, &b).is_err(),
+			"combining should fail, but didn't"
+		);
+	}
+}
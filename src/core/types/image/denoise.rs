@@ -0,0 +1,233 @@
+use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+use rayon::slice::ParallelSliceMut;
+
+use crate::core::types::{Color, ToVec3, Vec3};
+
+use super::Image;
+
+// Denoising
+impl Image {
+	/// Reduces noise in this image with a bilateral filter, and returns the result.
+	///
+	/// Each pixel is replaced by a weighted average of its neighbours, where the weight
+	/// decreases exponentially both with the spatial distance (controlled by `spatial_sigma`),
+	/// and with the color distance (controlled by `range_sigma`) to the pixel.
+	/// This smooths out noise while preserving edges.
+	///
+	/// The filter is separated into a horizontal and a vertical pass;
+	/// the kernel radius is approximately `3 * spatial_sigma`.
+	/// If either sigma is not positive, the image is returned unchanged.
+	pub fn denoise(&self, spatial_sigma: f64, range_sigma: f64) -> Image {
+		if spatial_sigma <= 0.0 || range_sigma <= 0.0 {
+			return self.clone();
+		}
+		let radius = (3.0 * spatial_sigma).ceil() as isize;
+		let horizontal = self.bilateral_pass(radius, spatial_sigma, range_sigma, (0, 1));
+		horizontal.bilateral_pass(radius, spatial_sigma, range_sigma, (1, 0))
+	}
+	/// Reduces noise in this image with a non-local means filter, and returns the result.
+	///
+	/// Each pixel is replaced by a weighted average of the pixels within a `search_window`
+	/// around it, where the weight depends on how similar the patches (of size `patch_size`)
+	/// around both pixels are. The parameter `h` controls the strength of the filter;
+	/// if it is not positive, the image is returned unchanged.
+	///
+	/// This produces better results than [`Image::denoise`], but is substantially slower.
+	pub fn denoise_nlm(&self, patch_size: usize, search_window: usize, h: f64) -> Image {
+		if h <= 0.0 {
+			return self.clone();
+		}
+		let patch_radius = (patch_size / 2) as isize;
+		let search_radius = (search_window / 2) as isize;
+		let h_sq = h * h;
+
+		let mut output = Image::init(self.height, self.width);
+		output
+			.par_chunks_mut(self.width)
+			.enumerate()
+			.for_each(|(row, pixels)| {
+				for (col, pixel) in pixels.iter_mut().enumerate() {
+					let (row, col) = (row as isize, col as isize);
+					let mut sum = Vec3::zero();
+					let mut total_weight = 0.0;
+					for di in -search_radius..=search_radius {
+						for dj in -search_radius..=search_radius {
+							let Some(neighbour) = self.get(row + di, col + dj) else {
+								continue;
+							};
+							let distance = self.patch_distance((row, col), (di, dj), patch_radius);
+							let weight = f64::exp(-distance / h_sq);
+							sum += neighbour.to_vec3().scale(weight);
+							total_weight += weight;
+						}
+					}
+					*pixel = (sum / total_weight).into();
+				}
+			});
+		output
+	}
+
+	/// Performs a one-dimensional bilateral filter pass in the direction `(d_row, d_col)`.
+	fn bilateral_pass(
+		&self,
+		radius: isize,
+		spatial_sigma: f64,
+		range_sigma: f64,
+		direction: (isize, isize),
+	) -> Image {
+		let spatial_denom = 2.0 * spatial_sigma * spatial_sigma;
+		let range_denom = 2.0 * range_sigma * range_sigma;
+
+		let mut output = Image::init(self.height, self.width);
+		output
+			.par_chunks_mut(self.width)
+			.enumerate()
+			.for_each(|(row, pixels)| {
+				for (col, pixel) in pixels.iter_mut().enumerate() {
+					let center = self[(row, col)].to_vec3();
+					let mut sum = Vec3::zero();
+					let mut total_weight = 0.0;
+					for k in -radius..=radius {
+						let (i, j) = (
+							row as isize + k * direction.0,
+							col as isize + k * direction.1,
+						);
+						let Some(neighbour) = self.get(i, j) else {
+							continue;
+						};
+						let neighbour = neighbour.to_vec3();
+						let spatial = (k * k) as f64 / spatial_denom;
+						let range = (neighbour - center).norm_sq() / range_denom;
+						let weight = f64::exp(-spatial - range);
+						sum += neighbour.scale(weight);
+						total_weight += weight;
+					}
+					*pixel = (sum / total_weight).into();
+				}
+			});
+		output
+	}
+	/// Calculates the mean squared color distance between the patch around `center`,
+	/// and the patch around the pixel at `offset` from it.
+	/// Pixels of either patch that lie outside the image are ignored.
+	fn patch_distance(&self, center: (isize, isize), offset: (isize, isize), radius: isize) -> f64 {
+		let mut distance = 0.0;
+		let mut count = 0;
+		for i in -radius..=radius {
+			for j in -radius..=radius {
+				let (row, col) = (center.0 + i, center.1 + j);
+				let a = self.get(row, col);
+				let b = self.get(row + offset.0, col + offset.1);
+				if let (Some(a), Some(b)) = (a, b) {
+					distance += (a.to_vec3() - b.to_vec3()).norm_sq();
+					count += 1;
+				}
+			}
+		}
+		if count > 0 {
+			distance / count as f64
+		} else {
+			0.0
+		}
+	}
+	/// Returns the pixel at the specified (possibly negative or out of bounds) index,
+	/// or `None` if the index lies outside this image.
+	pub(super) fn get(&self, row: isize, col: isize) -> Option<Color> {
+		let in_bounds =
+			(0..self.height as isize).contains(&row) && (0..self.width as isize).contains(&col);
+		in_bounds.then(|| self[(row as usize, col as usize)])
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::core::types::{Color, Image};
+
+	/// Checks whether every pixel of the image has approximately the specified color.
+	fn is_constant(image: &Image, color: Color) -> bool {
+		let eq = |a: f64, b: f64| f64::abs(a - b) < 1e-10;
+		image
+			.into_iter()
+			.flatten()
+			.all(|px| eq(px.r(), color.r()) && eq(px.g(), color.g()) && eq(px.b(), color.b()))
+	}
+
+	/// Creates an image of the specified dimensions, filled with the specified color.
+	fn constant_image(height: usize, width: usize, color: Color) -> Image {
+		let mut image = Image::init(height, width);
+		for i in 0..height {
+			for j in 0..width {
+				image[(i, j)] = color;
+			}
+		}
+		image
+	}
+
+	#[test]
+	fn if_constant_image_then_bilateral_denoise_leaves_it_unchanged() {
+		// This image has the same color everywhere:
+		let color = Color::new(0.2, 0.5, 0.8);
+		let image = constant_image(8, 12, color);
+
+		let denoised = image.denoise(1.5, 0.1);
+		assert!(
+			is_constant(&denoised, color),
+			"denoised constant image should be unchanged, but wasn't"
+		);
+	}
+
+	#[test]
+	fn if_constant_image_then_nlm_denoise_leaves_it_unchanged() {
+		// This image has the same color everywhere:
+		let color = Color::new(0.2, 0.5, 0.8);
+		let image = constant_image(8, 12, color);
+
+		let denoised = image.denoise_nlm(3, 7, 0.1);
+		assert!(
+			is_constant(&denoised, color),
+			"denoised constant image should be unchanged, but wasn't"
+		);
+	}
+
+	#[test]
+	fn if_sigma_or_strength_zero_then_denoise_leaves_image_unchanged() {
+		// This image is grey, except for one bright pixel in the middle:
+		let mut image = constant_image(9, 9, Color::new(0.5, 0.5, 0.5));
+		image[(4, 4)] = Color::new(0.6, 0.6, 0.6);
+
+		assert_eq!(
+			image.denoise(0.0, 0.5),
+			image,
+			"zero spatial sigma should leave image unchanged"
+		);
+		assert_eq!(
+			image.denoise(1.0, 0.0),
+			image,
+			"zero range sigma should leave image unchanged"
+		);
+		assert_eq!(
+			image.denoise_nlm(3, 7, 0.0),
+			image,
+			"zero strength should leave image unchanged"
+		);
+	}
+
+	#[test]
+	fn bilateral_denoise_smooths_outlier() {
+		// This image is grey, except for one bright pixel in the middle:
+		let grey = Color::new(0.5, 0.5, 0.5);
+		let mut image = constant_image(9, 9, grey);
+		image[(4, 4)] = Color::new(0.6, 0.6, 0.6);
+
+		// The bright pixel should be closer to its neighbours after denoising:
+		let denoised = image.denoise(1.0, 0.5);
+		let before = image[(4, 4)].r() - grey.r();
+		let after = denoised[(4, 4)].r() - grey.r();
+		assert!(
+			after < before,
+			"outlier should be smoothed, but difference went from {} to {}",
+			before,
+			after
+		);
+	}
+}
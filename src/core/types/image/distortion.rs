@@ -0,0 +1,114 @@
+use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+use rayon::slice::ParallelSliceMut;
+
+use super::Image;
+
+// Lens distortion
+impl Image {
+	/// Simulates radial lens distortion (Brown-Conrady model), and returns the result.
+	///
+	/// Each pixel at distance `r` from the center of the image is sampled from the distance
+	/// `r * (1 + k1 * r² + k2 * r⁴)` in this image, where `r` is normalized so that the corners
+	/// lie at 1. A positive `k1` produces barrel distortion, which bows straight lines outwards;
+	/// a negative `k1` produces pincushion distortion, which bows them inwards. Areas sampled from
+	/// outside of the image are filled with its edge pixels. Coefficients of `0` leave the image unchanged.
+	pub fn lens_distortion(&self, k1: f64, k2: f64) -> Image {
+		if k1 == 0.0 && k2 == 0.0 {
+			return self.clone();
+		}
+		let center_row = (self.height as f64 - 1.0) / 2.0;
+		let center_col = (self.width as f64 - 1.0) / 2.0;
+		let max_distance = f64::hypot(center_row, center_col);
+		if max_distance == 0.0 {
+			return self.clone();
+		}
+
+		let mut output = Image::init(self.height, self.width);
+		output
+			.par_chunks_mut(self.width)
+			.enumerate()
+			.for_each(|(row, pixels)| {
+				for (col, pixel) in pixels.iter_mut().enumerate() {
+					let (d_row, d_col) = (row as f64 - center_row, col as f64 - center_col);
+					let r_sq = (d_row * d_row + d_col * d_col) / (max_distance * max_distance);
+					let scale = 1.0 + k1 * r_sq + k2 * r_sq * r_sq;
+					*pixel = self.bilinear(center_row + d_row * scale, center_col + d_col * scale);
+				}
+			});
+		output
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::core::types::{Color, Image};
+
+	#[test]
+	fn if_pixel_at_center_then_unaffected() {
+		// This image has a single white pixel at its exact center:
+		let mut image = Image::init(21, 21);
+		image[(10, 10)] = Color::white();
+
+		// The center pixel should not move, regardless of the distortion:
+		for k1 in [-0.3, 0.3] {
+			let result = image.lens_distortion(k1, 0.1);
+			assert_eq!(
+				result[(10, 10)],
+				Color::white(),
+				"center pixel should be unaffected for k1 = {}",
+				k1
+			);
+		}
+	}
+
+	#[test]
+	fn if_coefficients_zero_then_image_unchanged() {
+		// This image has a single white pixel near its corner:
+		let mut image = Image::init(9, 9);
+		image[(1, 7)] = Color::white();
+
+		let result = image.lens_distortion(0.0, 0.0);
+		assert_eq!(result, image, "image should be unchanged");
+	}
+
+	/// Finds the column of a bright vertical line in the specified row, weighted by brightness.
+	fn line_column(image: &Image, row: usize) -> f64 {
+		let (weighted, total) = (0..image.width())
+			.map(|col| (col as f64, image[(row, col)].luminance()))
+			.fold((0.0, 0.0), |(weighted, total), (col, lum)| {
+				(weighted + col * lum, total + lum)
+			});
+		weighted / total
+	}
+
+	#[test]
+	fn if_k1_positive_then_lines_bow_outwards() {
+		// This image has a vertical white line to the right of its center:
+		let mut image = Image::init(41, 41);
+		for row in 0..41 {
+			image[(row, 30)] = Color::white();
+		}
+
+		// The bowing is how much further the line is from the center in the middle than near the top:
+		let bowing = |k1: f64| {
+			let result = image.lens_distortion(k1, 0.0);
+			line_column(&result, 20) - line_column(&result, 4)
+		};
+		let (straight, barrel, pincushion) = (bowing(0.0), bowing(0.4), bowing(-0.4));
+		assert!(
+			straight.abs() < 1e-10,
+			"line should stay straight without distortion, but bowed by {}",
+			straight
+		);
+		assert!(
+			barrel > 0.5,
+			"line should bow outwards for k1 > 0, but bowed by {}",
+			barrel
+		);
+		assert!(
+			pincushion < -0.5,
+			"line should bow inwards for k1 < 0, but bowed by {}",
+			pincushion
+		);
+	}
+}
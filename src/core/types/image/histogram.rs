@@ -0,0 +1,102 @@
+use crate::core::types::Color;
+
+use super::Image;
+
+// Histograms
+impl Image {
+	/// Counts the pixels of this image by luminance (see [`Color::luminance`]).
+	///
+	/// The range `[0, 1]` is divided into `buckets` equally sized buckets, and each pixel is counted
+	/// in the bucket its luminance falls into. Values outside of the range are clamped, so that
+	/// overexposed pixels are counted in the last bucket.
+	pub fn histogram(&self, buckets: usize) -> Vec<u32> {
+		self.histogram_of(buckets, Color::luminance)
+	}
+	/// Counts the pixels of this image by the values of their red, green, and blue channels,
+	/// in the same way as [`Image::histogram`]. Returns the histograms of the channels in this order.
+	pub fn histogram_rgb(&self, buckets: usize) -> (Vec<u32>, Vec<u32>, Vec<u32>) {
+		(
+			self.histogram_of(buckets, Color::r),
+			self.histogram_of(buckets, Color::g),
+			self.histogram_of(buckets, Color::b),
+		)
+	}
+	/// Returns the fraction of pixels with at least one channel exceeding 1, which are clipped
+	/// when the image is written. A large fraction indicates an overexposed image.
+	pub fn clipping_fraction(&self) -> f64 {
+		if self.pixels.is_empty() {
+			return 0.0;
+		}
+		let clipped = self
+			.pixels
+			.iter()
+			.filter(|pixel| pixel.r() > 1.0 || pixel.g() > 1.0 || pixel.b() > 1.0)
+			.count();
+		clipped as f64 / self.pixels.len() as f64
+	}
+
+	/// Counts the pixels of this image in `buckets` buckets over `[0, 1]`, by the result of `value`.
+	fn histogram_of(&self, buckets: usize, value: fn(&Color) -> f64) -> Vec<u32> {
+		let mut histogram = vec![0; buckets];
+		if buckets == 0 {
+			return histogram;
+		}
+		for pixel in &self.pixels {
+			let scaled = value(pixel).clamp(0.0, 1.0) * buckets as f64;
+			let bucket = usize::min(scaled as usize, buckets - 1);
+			histogram[bucket] += 1;
+		}
+		histogram
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::core::types::{Color, Image};
+
+	#[test]
+	fn if_image_white_then_all_pixels_in_last_bucket() {
+		let mut image = Image::init(4, 5);
+		for row in 0..4 {
+			for col in 0..5 {
+				image[(row, col)] = Color::white();
+			}
+		}
+
+		let histogram = image.histogram(8);
+		assert_eq!(
+			histogram,
+			vec![0, 0, 0, 0, 0, 0, 0, 20],
+			"all pixels should be in the last bucket"
+		);
+		assert_eq!(
+			image.clipping_fraction(),
+			0.0,
+			"white pixels should not be clipped"
+		);
+	}
+
+	#[test]
+	fn if_image_mixed_then_pixels_spread_across_buckets() {
+		// This image has a black, a grey, a red, and an overexposed pixel:
+		let mut image = Image::init(2, 2);
+		image[(0, 1)] = Color::new(0.5, 0.5, 0.5);
+		image[(1, 0)] = Color::new(1.0, 0.0, 0.0);
+		image[(1, 1)] = Color::new(3.0, 3.0, 3.0);
+
+		assert_eq!(
+			image.histogram(4),
+			vec![2, 0, 1, 1],
+			"pixels should be counted by luminance"
+		);
+		let (r, g, b) = image.histogram_rgb(2);
+		assert_eq!(r, vec![1, 3], "red channel should be counted");
+		assert_eq!(g, vec![2, 2], "green channel should be counted");
+		assert_eq!(b, vec![2, 2], "blue channel should be counted");
+		assert_eq!(
+			image.clipping_fraction(),
+			0.25,
+			"one of four pixels should be clipped"
+		);
+	}
+}
@@ -0,0 +1,157 @@
+use crate::core::types::ToVec3;
+
+use super::Image;
+
+/// The size of the square windows over which the structural similarity is calculated, in pixels.
+const SSIM_WINDOW: usize = 8;
+/// Stabilizes the luminance term of the structural similarity for dark windows.
+const SSIM_C1: f64 = 0.01 * 0.01;
+/// Stabilizes the contrast term of the structural similarity for flat windows.
+const SSIM_C2: f64 = 0.03 * 0.03;
+
+// Error metrics
+// Channel values are compared as they are, with a peak value of 1
+impl Image {
+	/// Calculates the mean squared error of the channels of this image, compared to `reference`.
+	///
+	/// # Panics
+	/// Panics if the images have different sizes.
+	pub fn mse(&self, reference: &Image) -> f64 {
+		self.check_same_size(reference);
+		if self.pixels.is_empty() {
+			return 0.0;
+		}
+		let sum = self
+			.pixels
+			.iter()
+			.zip(&reference.pixels)
+			.map(|(a, b)| (a.to_vec3() - b.to_vec3()).norm_sq())
+			.sum::<f64>();
+		sum / (3 * self.pixels.len()) as f64
+	}
+	/// Calculates the peak signal-to-noise ratio of this image compared to `reference`, in decibels.
+	/// Identical images have an infinite ratio.
+	///
+	/// # Panics
+	/// Panics if the images have different sizes.
+	pub fn psnr(&self, reference: &Image) -> f64 {
+		10.0 * f64::log10(1.0 / self.mse(reference))
+	}
+	/// Calculates the structural similarity index of this image compared to `reference`, which is 1
+	/// for identical images, and lower the more their structure differs.
+	///
+	/// The index is calculated on the luminance of [`SSIM_WINDOW`] × [`SSIM_WINDOW`] windows (smaller
+	/// at the edges), and averaged over all windows.
+	///
+	/// # Panics
+	/// Panics if the images have different sizes.
+	pub fn ssim(&self, reference: &Image) -> f64 {
+		self.check_same_size(reference);
+		let mut sum = 0.0;
+		let mut windows = 0;
+		for row in (0..self.height).step_by(SSIM_WINDOW) {
+			for col in (0..self.width).step_by(SSIM_WINDOW) {
+				let rows = row..usize::min(row + SSIM_WINDOW, self.height);
+				let cols = col..usize::min(col + SSIM_WINDOW, self.width);
+				let window = rows.flat_map(|i| cols.clone().map(move |j| (i, j)));
+				sum += window_ssim(
+					window.map(|idx| (self[idx].luminance(), reference[idx].luminance())),
+				);
+				windows += 1;
+			}
+		}
+		if windows == 0 {
+			1.0
+		} else {
+			sum / windows as f64
+		}
+	}
+	/// Calculates the largest absolute difference of any channel of any pixel of this image,
+	/// compared to `reference`.
+	///
+	/// # Panics
+	/// Panics if the images have different sizes.
+	pub fn max_channel_error(&self, reference: &Image) -> f64 {
+		self.check_same_size(reference);
+		self.pixels
+			.iter()
+			.zip(&reference.pixels)
+			.map(|(a, b)| {
+				let diff = (a.to_vec3() - b.to_vec3()).to_tuple(f64::abs);
+				f64::max(diff.0, f64::max(diff.1, diff.2))
+			})
+			.fold(0.0, f64::max)
+	}
+
+	/// Panics if the `reference` image has a different size than this image.
+	fn check_same_size(&self, reference: &Image) {
+		assert_eq!(
+			(self.height, self.width),
+			(reference.height, reference.width),
+			"images should have the same size (height, width)"
+		);
+	}
+}
+
+/// Calculates the structural similarity of a window, given as pairs of luminance values.
+fn window_ssim<I: Iterator<Item = (f64, f64)>>(values: I) -> f64 {
+	let values = values.collect::<Vec<_>>();
+	let n = values.len() as f64;
+	let mean_a = values.iter().map(|v| v.0).sum::<f64>() / n;
+	let mean_b = values.iter().map(|v| v.1).sum::<f64>() / n;
+	let (mut var_a, mut var_b, mut cov) = (0.0, 0.0, 0.0);
+	for (a, b) in &values {
+		var_a += (a - mean_a) * (a - mean_a);
+		var_b += (b - mean_b) * (b - mean_b);
+		cov += (a - mean_a) * (b - mean_b);
+	}
+	let (var_a, var_b, cov) = (var_a / n, var_b / n, cov / n);
+	((2.0 * mean_a * mean_b + SSIM_C1) * (2.0 * cov + SSIM_C2))
+		/ ((mean_a * mean_a + mean_b * mean_b + SSIM_C1) * (var_a + var_b + SSIM_C2))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::core::types::{Color, Image};
+
+	#[test]
+	fn if_compared_to_itself_then_no_error() {
+		// This image has a gradient, so that it isn't uniform:
+		let mut image = Image::init(10, 12);
+		for row in 0..10 {
+			for col in 0..12 {
+				image[(row, col)] = Color::new(row as f64 / 10.0, col as f64 / 12.0, 0.5);
+			}
+		}
+		assert_eq!(image.mse(&image), 0.0, "MSE should be 0");
+		assert_eq!(image.psnr(&image), f64::INFINITY, "PSNR should be infinite");
+		assert!(
+			(image.ssim(&image) - 1.0).abs() < 1e-9,
+			"SSIM should be 1, but was {}",
+			image.ssim(&image)
+		);
+		assert_eq!(
+			image.max_channel_error(&image),
+			0.0,
+			"max error should be 0"
+		);
+	}
+
+	#[test]
+	fn if_black_compared_to_white_then_maximum_error() {
+		let black = Image::filled(6, 4, Color::black());
+		let white = Image::filled(6, 4, Color::new(1, 1, 1));
+		assert_eq!(black.mse(&white), 1.0, "MSE should be 1");
+		assert_eq!(black.psnr(&white), 0.0, "PSNR should be 0 dB");
+		assert_eq!(
+			black.max_channel_error(&white),
+			1.0,
+			"max error should be 1"
+		);
+		assert!(
+			black.ssim(&white) < 0.01,
+			"SSIM should be close to 0, but was {}",
+			black.ssim(&white)
+		);
+	}
+}
@@ -0,0 +1,161 @@
+use std::fmt;
+
+use super::{Image, ToneMap};
+
+/// A step of post-processing, which transforms a rendered image.
+pub trait PostProcess: Send + Sync {
+	/// Applies this step to the `image`, and returns the result.
+	fn apply(&self, image: Image) -> Image;
+}
+
+/// Applies gamma correction, see [`Image::gamma_correct_in_place`].
+#[derive(Debug, Clone, Copy)]
+pub struct GammaCorrectionPass {
+	/// The gamma value, by whose inverse each channel is raised.
+	pub gamma: f64,
+}
+impl PostProcess for GammaCorrectionPass {
+	fn apply(&self, mut image: Image) -> Image {
+		image.gamma_correct_in_place(self.gamma);
+		image
+	}
+}
+
+/// Maps colors into the displayable range, see [`Image::tone_map_in_place`].
+#[derive(Debug, Clone, Copy)]
+pub struct ToneMapPass {
+	/// The operator with which colors are mapped.
+	pub mode: ToneMap,
+}
+impl PostProcess for ToneMapPass {
+	fn apply(&self, mut image: Image) -> Image {
+		image.tone_map_in_place(self.mode);
+		image
+	}
+}
+
+/// Makes bright areas glow, see [`Image::bloom`].
+#[derive(Debug, Clone, Copy)]
+pub struct BloomPass {
+	/// The luminance above which pixels glow.
+	pub threshold: f64,
+	/// The radius of the glow, in pixels.
+	pub radius: usize,
+	/// The factor by which the glow is added to the image.
+	pub intensity: f64,
+}
+impl PostProcess for BloomPass {
+	fn apply(&self, image: Image) -> Image {
+		image.bloom(self.threshold, self.radius, self.intensity)
+	}
+}
+
+/// Darkens the image towards its corners, see [`Image::vignette`].
+#[derive(Debug, Clone, Copy)]
+pub struct VignettePass {
+	/// How much the corners are darkened.
+	pub strength: f64,
+	/// How quickly darkening sets in towards the corners.
+	pub falloff: f64,
+}
+impl PostProcess for VignettePass {
+	fn apply(&self, image: Image) -> Image {
+		image.vignette(self.strength, self.falloff)
+	}
+}
+
+/// Shifts the color channels apart towards the edges, see [`Image::chromatic_aberration`].
+#[derive(Debug, Clone, Copy)]
+pub struct ChromaticAberrationPass {
+	/// The relative amount by which the red channel is magnified.
+	pub strength: f64,
+}
+impl PostProcess for ChromaticAberrationPass {
+	fn apply(&self, image: Image) -> Image {
+		image.chromatic_aberration(self.strength)
+	}
+}
+
+/// A sequence of post-processing steps, which are applied to an image in order.
+///
+/// ```
+/// let pipeline = PostProcessPipeline::new()
+///     .with(ToneMapPass { mode: ToneMap::Aces })
+///     .with(VignettePass { strength: 0.5, falloff: 1.0 });
+/// let image = pipeline.run(image);
+/// ```
+#[derive(Default)]
+pub struct PostProcessPipeline {
+	/// The steps of this pipeline, in the order they are applied.
+	pub stages: Vec<Box<dyn PostProcess>>,
+}
+
+impl PostProcessPipeline {
+	/// Creates a new pipeline without any steps, which leaves images unchanged.
+	pub fn new() -> Self {
+		Self::default()
+	}
+	/// Adds a step to the end of this pipeline, and returns the pipeline.
+	pub fn with<P: PostProcess + 'static>(mut self, stage: P) -> Self {
+		self.stages.push(Box::new(stage));
+		self
+	}
+	/// Applies all steps of this pipeline to the `image` in order, and returns the result.
+	pub fn run(&self, image: Image) -> Image {
+		self.stages
+			.iter()
+			.fold(image, |image, stage| stage.apply(image))
+	}
+}
+impl fmt::Debug for PostProcessPipeline {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("PostProcessPipeline")
+			.field("stages", &self.stages.len())
+			.finish()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{GammaCorrectionPass, PostProcessPipeline, ToneMapPass};
+	use crate::core::types::{Color, Image, ToneMap};
+
+	/// Creates an image with a gradient from black to very bright.
+	fn gradient() -> Image {
+		let mut image = Image::init(4, 8);
+		for row in 0..4 {
+			for col in 0..8 {
+				image[(row, col)] = Color::new(col as f64, row as f64 / 4.0, 0.5);
+			}
+		}
+		image
+	}
+
+	#[test]
+	fn empty_pipeline_leaves_image_unchanged() {
+		let image = gradient();
+		assert_eq!(
+			PostProcessPipeline::new().run(image.clone()),
+			image,
+			"image should be unchanged"
+		);
+	}
+
+	#[test]
+	fn pipeline_matches_manual_application() {
+		let pipeline = PostProcessPipeline::new()
+			.with(ToneMapPass {
+				mode: ToneMap::Reinhard,
+			})
+			.with(GammaCorrectionPass { gamma: 2.2 });
+
+		let mut expected = gradient();
+		expected.tone_map_in_place(ToneMap::Reinhard);
+		expected.gamma_correct_in_place(2.2);
+		assert_eq!(
+			pipeline.run(gradient()),
+			expected,
+			"pipeline should apply the steps in order"
+		);
+	}
+}
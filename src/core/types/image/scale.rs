@@ -0,0 +1,226 @@
+use std::f64::consts::PI;
+
+use crate::core::types::{ToVec3, Vec3};
+
+use super::Image;
+
+/// The amount of lobes of the Lanczos kernel on each side of its center.
+const LANCZOS_LOBES: f64 = 3.0;
+
+// Scaling
+impl Image {
+	/// Shrinks this image by the specified `factor` in both dimensions, and returns the result.
+	///
+	/// Each pixel of the result is the average of a `factor`×`factor` block of this image
+	/// (box filtering). If a dimension isn't divisible by `factor`, the remaining pixels at the
+	/// bottom or right edge are dropped. A `factor` of 0 or 1 leaves the image unchanged.
+	pub fn downscale(&self, factor: usize) -> Image {
+		if factor <= 1 {
+			return self.clone();
+		}
+		let mut output = Image::init(self.height / factor, self.width / factor);
+		let block_size = (factor * factor) as f64;
+		for row in 0..output.height {
+			for col in 0..output.width {
+				let mut sum = Vec3::zero();
+				for src_row in row * factor..(row + 1) * factor {
+					for src_col in col * factor..(col + 1) * factor {
+						sum += self[(src_row, src_col)].to_vec3();
+					}
+				}
+				output[(row, col)] = (sum / block_size).into();
+			}
+		}
+		output
+	}
+	/// Shrinks this image by the specified `factor` in both dimensions, and returns the result.
+	///
+	/// Each pixel of the result is the average of a `factor`×`factor` block of this image
+	/// (box filtering), see [`Image::downscale`].
+	///
+	/// # Panics
+	/// Panics if `factor` is 0, or if a dimension of this image is not divisible by `factor`.
+	pub fn box_downsample(&self, factor: usize) -> Image {
+		assert!(
+			factor > 0 && self.width.is_multiple_of(factor) && self.height.is_multiple_of(factor),
+			"image of size {}x{} should be divisible by the factor {}",
+			self.width,
+			self.height,
+			factor
+		);
+		self.downscale(factor)
+	}
+	/// Shrinks this image to the specified size, and returns the result.
+	///
+	/// The image is resampled with a Lanczos-3 filter, separately along each dimension. This
+	/// preserves more detail than box filtering, but may produce slight ringing at sharp edges.
+	/// Pixels beyond the edges of this image are treated like the edge pixels.
+	///
+	/// # Panics
+	/// Panics if the target size is larger than this image in any dimension.
+	pub fn lanczos_downsample(&self, target_width: usize, target_height: usize) -> Image {
+		assert!(
+			target_width <= self.width && target_height <= self.height,
+			"target size {}x{} should not exceed the image size {}x{}",
+			target_width,
+			target_height,
+			self.width,
+			self.height
+		);
+
+		// Horizontal pass
+		let weights = lanczos_weights(self.width, target_width);
+		let mut horizontal = Image::init(self.height, target_width);
+		for row in 0..self.height {
+			for (col, taps) in weights.iter().enumerate() {
+				let sum = taps.iter().fold(Vec3::zero(), |sum, &(src_col, weight)| {
+					sum + self[(row, src_col)].to_vec3().scale(weight)
+				});
+				horizontal[(row, col)] = sum.into();
+			}
+		}
+
+		// Vertical pass
+		let weights = lanczos_weights(self.height, target_height);
+		let mut output = Image::init(target_height, target_width);
+		for (row, taps) in weights.iter().enumerate() {
+			for col in 0..target_width {
+				let sum = taps.iter().fold(Vec3::zero(), |sum, &(src_row, weight)| {
+					sum + horizontal[(src_row, col)].to_vec3().scale(weight)
+				});
+				output[(row, col)] = sum.into();
+			}
+		}
+		output
+	}
+}
+
+/// Calculates, for each of the `target` output pixels along one dimension, the source pixels
+/// and their normalized weights, when resampling `source` pixels with a Lanczos filter.
+/// The kernel is stretched by the scale factor, so that it covers every source pixel.
+fn lanczos_weights(source: usize, target: usize) -> Vec<Vec<(usize, f64)>> {
+	let scale = source as f64 / target as f64;
+	let radius = LANCZOS_LOBES * scale;
+	(0..target)
+		.map(|i| {
+			let center = (i as f64 + 0.5) * scale - 0.5;
+			let first = (center - radius).floor() as isize;
+			let last = (center + radius).ceil() as isize;
+			let mut taps = (first..=last)
+				.map(|j| {
+					let index = j.clamp(0, source as isize - 1) as usize;
+					(index, lanczos((j as f64 - center) / scale))
+				})
+				.filter(|&(_, weight)| weight != 0.0)
+				.collect::<Vec<_>>();
+			let total = taps.iter().map(|(_, weight)| weight).sum::<f64>();
+			for (_, weight) in &mut taps {
+				*weight /= total;
+			}
+			taps
+		})
+		.collect()
+}
+
+/// The Lanczos kernel, a sinc function windowed by a wider sinc function.
+fn lanczos(x: f64) -> f64 {
+	if x == 0.0 {
+		1.0
+	} else if x.abs() < LANCZOS_LOBES {
+		let px = PI * x;
+		LANCZOS_LOBES * px.sin() * (px / LANCZOS_LOBES).sin() / (px * px)
+	} else {
+		0.0
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::core::types::{Color, Image};
+
+	#[test]
+	fn downscaled_image_averages_blocks() {
+		// This 2x4 image has a white and a black pixel in its left block:
+		let mut image = Image::init(2, 4);
+		image[(0, 0)] = Color::white();
+		image[(1, 1)] = Color::new(0.0, 0.0, 0.0);
+		image[(0, 2)] = Color::new(0.4, 0.4, 0.4);
+
+		let result = image.downscale(2);
+		assert_eq!(
+			(result.width(), result.height()),
+			(2, 1),
+			"image should be half as large"
+		);
+		assert_eq!(
+			result[(0, 0)],
+			Color::new(0.25, 0.25, 0.25),
+			"left block should be averaged"
+		);
+		assert_eq!(
+			result[(0, 1)],
+			Color::new(0.1, 0.1, 0.1),
+			"right block should be averaged"
+		);
+	}
+
+	#[test]
+	fn box_downsampled_uniform_image_stays_uniform() {
+		let red = Color::new(1.0, 0.0, 0.0);
+		let mut image = Image::init(4, 4);
+		for row in 0..4 {
+			for col in 0..4 {
+				image[(row, col)] = red;
+			}
+		}
+
+		let result = image.box_downsample(2);
+		assert_eq!(
+			(result.width(), result.height()),
+			(2, 2),
+			"image should be half as large"
+		);
+		for row in 0..2 {
+			for col in 0..2 {
+				assert_eq!(result[(row, col)], red, "every pixel should be red");
+			}
+		}
+	}
+
+	#[test]
+	fn lanczos_downsampled_image_keeps_brightness() {
+		// This 9x12 image has a dark left half, and a bright right half:
+		let (dark, bright) = (Color::new(0.2, 0.2, 0.2), Color::new(0.8, 0.8, 0.8));
+		let mut image = Image::init(9, 12);
+		for row in 0..9 {
+			for col in 0..12 {
+				image[(row, col)] = if col < 6 { dark } else { bright };
+			}
+		}
+
+		let result = image.lanczos_downsample(4, 3);
+		assert_eq!(
+			(result.width(), result.height()),
+			(4, 3),
+			"image should have the target size"
+		);
+		for row in 0..3 {
+			// Each row should be uniform across the rows of the input:
+			assert_eq!(result[(row, 0)], result[(0, 0)], "rows should be equal");
+			// The halves should stay dark and bright, with an average brightness of 0.5:
+			let (left, right) = (result[(row, 0)].0, result[(row, 3)].0);
+			assert!(
+				left < 0.35 && right > 0.65,
+				"halves should stay dark and bright, but were {} and {}",
+				left,
+				right
+			);
+			let average = (0..4).map(|col| result[(row, col)].0).sum::<f64>() / 4.0;
+			assert!(
+				(average - 0.5).abs() < 1e-6,
+				"average brightness should be 0.5, but was {}",
+				average
+			);
+		}
+	}
+}
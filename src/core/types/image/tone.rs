@@ -0,0 +1,96 @@
+use crate::core::types::{Color, ToVec3, Vec3};
+
+use super::Image;
+
+/// An operator that maps colors of any brightness into the range [0; 1] displayable on screens.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ToneMap {
+	/// Channels above 1 are cut off, which loses all detail in bright areas.
+	#[default]
+	Clamp,
+	/// Each channel `x` is mapped to `x / (1 + x)`, which compresses bright values smoothly,
+	/// but also darkens the midtones.
+	Reinhard,
+	/// The curve of the Academy Color Encoding System (as fitted by Narkowicz), which keeps more
+	/// contrast in the midtones, similar to photographic film.
+	Aces,
+}
+
+impl ToneMap {
+	/// Maps the `color` with this operator. Negative channels are clamped to 0.
+	pub fn apply(self, color: Color) -> Color {
+		let map = |x: f64| {
+			let x = f64::max(0.0, x);
+			match self {
+				Self::Clamp => f64::min(x, 1.0),
+				Self::Reinhard => x / (1.0 + x),
+				Self::Aces => {
+					let (a, b, c, d, e) = (2.51, 0.03, 2.43, 0.59, 0.14);
+					((x * (a * x + b)) / (x * (c * x + d) + e)).clamp(0.0, 1.0)
+				}
+			}
+		};
+		let (r, g, b) = color.to_vec3().to_tuple(map);
+		Color::new(r, g, b)
+	}
+}
+
+// Tone mapping & gamma correction
+impl Image {
+	/// Maps the colors of this image into the range [0; 1] with the specified operator.
+	pub fn tone_map_in_place(&mut self, mode: ToneMap) {
+		for pixel in self.pixels.iter_mut() {
+			*pixel = mode.apply(*pixel);
+		}
+	}
+	/// Applies gamma correction to the colors of this image, raising each channel to `1 / gamma`.
+	///
+	/// The output formats apply gamma correction when writing, so images corrected with this
+	/// method should be written with a gamma of 1.
+	pub fn gamma_correct_in_place(&mut self, gamma: f64) {
+		for pixel in self.pixels.iter_mut() {
+			let rgb = pixel.to_vec3().to_tuple(|x| f64::max(0.0, x));
+			*pixel = Vec3(rgb.0, rgb.1, rgb.2).exp(1.0 / gamma).into();
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::ToneMap;
+	use crate::core::types::{Color, Image};
+
+	#[test]
+	fn tone_mapped_colors_are_displayable_and_ordered() {
+		let values = [0.0, 0.1, 0.5, 1.0, 4.0, 100.0];
+		for mode in [ToneMap::Clamp, ToneMap::Reinhard, ToneMap::Aces] {
+			let mapped = values.map(|x| mode.apply(Color::new(x, x, x)).r());
+			assert!(
+				mapped.iter().all(|x| (0.0..=1.0).contains(x)),
+				"{:?} should map into [0; 1], but mapped to {:?}",
+				mode,
+				mapped
+			);
+			assert!(
+				mapped.windows(2).all(|w| w[0] <= w[1]),
+				"{:?} should keep brighter values brighter, but mapped to {:?}",
+				mode,
+				mapped
+			);
+		}
+	}
+
+	#[test]
+	fn gamma_correction_brightens_midtones() {
+		let mut image = Image::init(1, 2);
+		image[(0, 0)] = Color::new(0.25, 0.25, 0.25);
+		image[(0, 1)] = Color::new(1, 1, 1);
+		image.gamma_correct_in_place(2.0);
+		assert_eq!(
+			image[(0, 0)],
+			Color::new(0.5, 0.5, 0.5),
+			"0.25 should be 0.5"
+		);
+		assert_eq!(image[(0, 1)], Color::new(1, 1, 1), "white should stay");
+	}
+}
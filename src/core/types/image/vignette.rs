@@ -0,0 +1,50 @@
+use crate::core::types::ToVec3;
+
+use super::Image;
+
+// Vignetting
+impl Image {
+	/// Darkens this image towards its corners, and returns the result.
+	///
+	/// Each pixel is weighted like the pixels of a camera with vignetting (see
+	/// [`crate::camera::Camera::with_vignette`]). A `strength` of `0` leaves the image unchanged.
+	pub fn vignette(&self, strength: f64, falloff: f64) -> Image {
+		let mut output = self.clone();
+		if strength == 0.0 {
+			return output;
+		}
+		for row in 0..self.height {
+			for col in 0..self.width {
+				let weight =
+					vignette_weight((row, col), (self.height, self.width), strength, falloff);
+				output[(row, col)] = self[(row, col)].to_vec3().scale(weight).into();
+			}
+		}
+		output
+	}
+}
+
+/// Calculates the factor by which vignetting scales the pixel at `(row, col)` of an image
+/// of the specified `(height, width)`: `1 - strength * smoothstep(r * falloff)`, where `r` is
+/// the distance from the image center, normalized so that the corners lie at 1.
+pub(crate) fn vignette_weight(
+	(row, col): (usize, usize),
+	(height, width): (usize, usize),
+	strength: f64,
+	falloff: f64,
+) -> f64 {
+	let center_row = (height as f64 - 1.0) / 2.0;
+	let center_col = (width as f64 - 1.0) / 2.0;
+	let max_distance = f64::hypot(center_row, center_col);
+	if max_distance == 0.0 {
+		return 1.0;
+	}
+	let distance = f64::hypot(row as f64 - center_row, col as f64 - center_col) / max_distance;
+	1.0 - strength * smoothstep(0.0, 1.0, distance * falloff)
+}
+
+/// Interpolates smoothly between 0 (if `x <= edge_0`) and 1 (if `x >= edge_1`).
+fn smoothstep(edge_0: f64, edge_1: f64, x: f64) -> f64 {
+	let t = ((x - edge_0) / (edge_1 - edge_0)).clamp(0.0, 1.0);
+	t * t * (3.0 - 2.0 * t)
+}
@@ -1,5 +1,7 @@
+use std::ops;
+
 /// An interval or range between two floating point values.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Interval {
 	pub start: f64,
 	pub end: f64,
@@ -27,6 +29,24 @@ impl Interval {
 			end: f64::INFINITY,
 		}
 	}
+	/// Returns the smallest [`Interval`] that contains all of the specified values,
+	/// or `None` if there are no values.
+	pub fn from_values(values: &[f64]) -> Option<Self> {
+		values
+			.iter()
+			.map(|&value| Self::containing_point(value))
+			.reduce(Self::merge)
+	}
+	/// Returns a new degenerate [`Interval`], which only contains the specified value.
+	pub fn containing_point(value: f64) -> Self {
+		Self::new(value, value)
+	}
+	/// Returns a new degenerate [`Interval`], which only contains the specified value.
+	///
+	/// This is the same as [`Interval::containing_point`].
+	pub fn from_point(value: f64) -> Self {
+		Self::containing_point(value)
+	}
 	// /// Returns a new empty [`Interval`], which does not contain any number.
 	// pub fn empty() -> Self {
 	// 	Self::new(f64::INFINITY, -f64::INFINITY)
@@ -49,10 +69,149 @@ impl Interval {
 	// 	self.start <= value && value <= self.end
 	// }
 
+	/// Indicates if this interval doesn't contain any value, i.e. it starts after it ends.
+	/// Degenerate intervals, which start where they end, are not empty.
+	pub fn is_empty(&self) -> bool {
+		self.start > self.end
+	}
 	/// Indicates if a specified value is surrounded by this interval.
 	/// If the value is at the interval's ends, returns false.
 	pub fn surrounds<F: Into<f64>>(&self, value: F) -> bool {
 		let value: f64 = value.into();
 		self.start < value && value < self.end
 	}
+	/// Indicates if this interval and another interval have at least one value in common.
+	pub fn overlaps(&self, other: &Self) -> bool {
+		self.start <= other.end && other.start <= self.end
+	}
+	/// Returns this interval widened by `amount` in total, half of it on each end.
+	/// Useful to give degenerate (zero-width) intervals some thickness.
+	pub fn pad(self, amount: f64) -> Self {
+		Self::new(self.start - amount / 2.0, self.end + amount / 2.0)
+	}
+	/// Returns the smallest interval that contains both this and another interval.
+	pub fn merge(self, other: Self) -> Self {
+		Self {
+			start: f64::min(self.start, other.start),
+			end: f64::max(self.end, other.end),
+		}
+	}
+}
+
+// Arithmetic
+impl ops::Add<f64> for Interval {
+	type Output = Self;
+	/// Translates the interval by the specified amount.
+	fn add(self, rhs: f64) -> Self::Output {
+		Self::new(self.start + rhs, self.end + rhs)
+	}
+}
+impl ops::Mul<f64> for Interval {
+	type Output = Self;
+	/// Scales the interval by the specified factor, relative to zero.
+	/// A negative factor mirrors the interval, which still starts at its lower end.
+	fn mul(self, rhs: f64) -> Self::Output {
+		let (start, end) = (self.start * rhs, self.end * rhs);
+		Self::new(f64::min(start, end), f64::max(start, end))
+	}
+}
+impl ops::Add<Interval> for Interval {
+	type Output = Self;
+	/// Returns the smallest interval that contains both intervals, see [`Interval::merge`].
+	fn add(self, rhs: Interval) -> Self::Output {
+		self.merge(rhs)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Interval;
+
+	#[test]
+	fn from_values_returns_tight_interval() {
+		assert_eq!(
+			Interval::from_values(&[3.0, 1.0, 2.0]),
+			Some(Interval::new(1.0, 3.0)),
+			"interval should span from the smallest to the largest value"
+		);
+		assert_eq!(
+			Interval::from_values(&[]),
+			None,
+			"there should be no interval without values"
+		);
+	}
+
+	#[test]
+	fn overlapping_and_touching_intervals_overlap() {
+		let interval = Interval::new(0, 2);
+		// These intervals overlap partly, contain each other, and touch at one end:
+		for other in [
+			Interval::new(1, 3),
+			Interval::new(0.5, 1),
+			Interval::new(2, 4),
+		] {
+			assert!(
+				interval.overlaps(&other) && other.overlaps(&interval),
+				"{:?} and {:?} should overlap",
+				interval,
+				other
+			);
+		}
+	}
+
+	#[test]
+	fn disjoint_intervals_do_not_overlap() {
+		let interval = Interval::new(0, 2);
+		for other in [Interval::new(2.5, 4), Interval::new(-3, -1)] {
+			assert!(
+				!interval.overlaps(&other) && !other.overlaps(&interval),
+				"{:?} and {:?} should not overlap",
+				interval,
+				other
+			);
+		}
+	}
+
+	#[test]
+	fn padded_point_has_width_of_padding() {
+		let padded = Interval::containing_point(0.0).pad(1e-4);
+		assert_eq!(
+			padded,
+			Interval::new(-0.5e-4, 0.5e-4),
+			"interval should be widened equally on both ends"
+		);
+	}
+
+	#[test]
+	fn arithmetic_translates_scales_and_merges() {
+		let interval = Interval::new(1, 3);
+		assert_eq!(
+			interval + 2.0,
+			Interval::new(3, 5),
+			"interval should be translated"
+		);
+		assert_eq!(
+			interval * 2.0,
+			Interval::new(2, 6),
+			"interval should be scaled"
+		);
+		let sum = interval + Interval::new(5, 8);
+		assert!(
+			sum.start <= 1.0 && sum.end >= 8.0,
+			"sum {:?} should contain both intervals",
+			sum
+		);
+	}
+
+	#[test]
+	fn degenerate_interval_is_not_empty() {
+		assert!(
+			!Interval::from_point(0.0).is_empty(),
+			"[0, 0] should not be empty"
+		);
+		assert!(
+			Interval::new(1, 0).is_empty(),
+			"interval ending before its start should be empty"
+		);
+	}
 }
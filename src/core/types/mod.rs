@@ -1,9 +1,17 @@
+mod aabb;
 mod image;
 mod interval;
 mod ray;
+mod transform;
 mod vector;
 
-pub use image::Image;
+pub use aabb::Aabb;
+pub(crate) use image::vignette_weight;
+pub use image::{
+	BloomPass, Channel, ChromaticAberrationPass, GammaCorrectionPass, Image, PostProcess,
+	PostProcessPipeline, ToneMap, ToneMapPass, VignettePass,
+};
 pub use interval::Interval;
 pub use ray::Ray;
+pub use transform::Transform;
 pub use vector::{Color, Point, ToVec3, Vec3};
@@ -1,5 +1,5 @@
-use crate::core::objects::Hittable;
-use crate::scene::Scene;
+use crate::core::objects::Hit;
+use crate::scene::{HitOrBackground, Scene};
 
 use super::vector::ToVec3;
 use super::{Color, Interval, Point, Vec3};
@@ -13,24 +13,51 @@ pub struct Ray {
 	pub direction: Vec3,
 	/// A measure of how much luminance this ray keeps.
 	pub attenuation: Color,
+	/// The point in time at which this ray is cast, used for motion blur.
+	/// Animated objects are intersected in the state they are in at this time.
+	pub time: f64,
 }
 
 // Constructors
 impl Ray {
-	/// Creates a ray with full attenuation (factor of 1).
+	/// Creates a ray with full attenuation (factor of 1), cast at time 0.
 	pub fn new(origin: Point, direction: Vec3) -> Self {
+		Self::with_time(origin, direction, 0.0)
+	}
+	/// Creates a ray with full attenuation (factor of 1), cast at time 0,
+	/// whose direction is scaled to unit length.
+	///
+	/// Unlike [`Ray::new`], the parameter `t` of [`Ray::at`] then equals the distance from the origin.
+	/// The `direction` must not be (near) zero.
+	pub fn new_normalized(origin: Point, direction: Vec3) -> Self {
+		debug_assert!(
+			!direction.is_near_zero(),
+			"ray direction should not be zero, but was {}",
+			direction
+		);
+		Self::new(origin, direction.unit())
+	}
+	/// Creates a ray with a specified color/attenuation, cast at time 0.
+	pub fn newc(origin: Point, direction: Vec3, color: Color) -> Self {
 		Ray {
 			origin,
 			direction,
-			attenuation: Color::new(1, 1, 1),
+			attenuation: color,
+			time: 0.0,
 		}
 	}
-	/// Creates a ray with a specified color/attenuation.
-	pub fn newc(origin: Point, direction: Vec3, color: Color) -> Self {
+	/// Creates a ray with full attenuation (factor of 1), cast at the specified point in `time`.
+	pub fn with_time(origin: Point, direction: Vec3, time: f64) -> Self {
+		debug_assert!(
+			direction.is_finite(),
+			"ray direction should be finite, but was {}",
+			direction
+		);
 		Ray {
 			origin,
 			direction,
-			attenuation: color,
+			attenuation: Color::new(1, 1, 1),
+			time,
 		}
 	}
 }
@@ -43,28 +70,46 @@ impl Ray {
 		let point = self.origin.to_vec3() + self.direction.scale(t);
 		point.into()
 	}
+	/// Calculates the point where a ray is located at the parameter `t_param` along its direction.
+	///
+	/// This is the same as [`Ray::at`], and is unrelated to the [`Ray::time`] at which the ray is cast.
+	pub fn at_time(&self, t_param: f64) -> Point {
+		self.at(t_param)
+	}
 	/// Calculates the color of a ray in the specified scene.
 	pub fn color(self, scene: &Scene, bounces: u32) -> Color {
+		let color = self.trace(scene, bounces).0;
+		// Colors in the input are validated when it is parsed, so an invalid color was computed
+		// by the renderer itself (e.g. a NaN from a degenerate scattering direction)
+		debug_assert!(
+			color.is_valid(),
+			"ray color should have non-negative channels, but was {:?}",
+			color
+		);
+		color
+	}
+	/// Calculates the color of a ray in the specified scene, along with the first hit of the ray
+	/// (or `None` if it hit nothing, or has no bounces left).
+	pub(crate) fn trace(self, scene: &Scene, bounces: u32) -> (Color, Option<Hit<'_>>) {
 		if bounces == 0 {
-			return Color::black();
+			return (Color::black(), None);
 		}
-		// find intersection with an object
-		let Some(hit) = scene.hit(self, Interval::from(0.001)) else {
-			// background
-			let a = 0.5 * (self.direction.unit().y() + 1.0);
-			let white = Color::new(1.0, 1.0, 1.0).to_vec3().scale(1.0 - a);
-			let blue = Color::new(0.5, 0.7, 1.0).to_vec3().scale(a);
-			return (white + blue).into();
+		// find intersection with an object, or the background
+		let hit = match scene.hit_with_background(self, Interval::from(0.001)) {
+			HitOrBackground::Hit(hit) => hit,
+			HitOrBackground::Background(color) => return (color, None),
 		};
-		// determine color recursively
-		if let Some(scattered_ray) = hit.material.scatter(self, hit) {
+		// determine color recursively, on top of the light emitted by the surface
+		let emitted = hit.material.emittance().to_vec3();
+		let color = if let Some(scattered_ray) = hit.material.scatter_in(self, hit, scene) {
 			// ray was scattered
 			let color = scattered_ray.color(scene, bounces - 1);
-			(scattered_ray.attenuation.to_vec3() * color.to_vec3()).into()
+			(emitted + scattered_ray.attenuation.to_vec3() * color.to_vec3()).into()
 		} else {
 			// ray was absorbed
-			Color::black()
-		}
+			emitted.into()
+		};
+		(color, Some(hit))
 	}
 }
 
@@ -76,6 +121,21 @@ mod tests {
 
 	use super::Ray;
 
+	#[test]
+	fn normalized_ray_has_unit_direction() {
+		let ray = Ray::new_normalized(Point::origin(), Vec3::new(3, -4, 12));
+		assert!(
+			(ray.direction.norm() - 1.0).abs() < 1e-12,
+			"direction should have unit length, but had {}",
+			ray.direction.norm()
+		);
+		assert_eq!(
+			ray.at(13.0),
+			Point::new(3, -4, 12),
+			"parameter should equal the distance from the origin"
+		);
+	}
+
 	#[test]
 	fn ray_color_recursion_stops() {
 		// This scene has two spheres:
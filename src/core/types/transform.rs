@@ -0,0 +1,237 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Point, ToVec3, Vec3};
+
+/// A transformation in 3D space, consisting of a uniform scale,
+/// a rotation around the y-axis, and a translation (applied in this order).
+///
+/// The scale must not be zero, since the transform could not be inverted otherwise.
+/// A negative scale mirrors through the origin.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(try_from = "TransformData", into = "TransformData")]
+pub struct Transform {
+	/// The translation vector.
+	pub translation: Vec3,
+	/// The angle of the rotation around the y-axis, in degrees.
+	pub rotation_y: f64,
+	/// The uniform scale factor.
+	pub scale: f64,
+}
+impl Default for Transform {
+	fn default() -> Self {
+		Self::identity()
+	}
+}
+
+/// The representation of a transform in the input.
+#[derive(Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+struct TransformData {
+	translation: Vec3,
+	rotation_y: f64,
+	scale: f64,
+}
+impl Default for TransformData {
+	fn default() -> Self {
+		Transform::identity().into()
+	}
+}
+impl TryFrom<TransformData> for Transform {
+	type Error = String;
+	fn try_from(data: TransformData) -> Result<Self, Self::Error> {
+		if data.scale == 0.0 || !data.scale.is_finite() {
+			return Err(format!(
+				"transform scale should be finite and non-zero, but was {}",
+				data.scale
+			));
+		}
+		Ok(Self {
+			translation: data.translation,
+			rotation_y: data.rotation_y,
+			scale: data.scale,
+		})
+	}
+}
+impl From<Transform> for TransformData {
+	fn from(transform: Transform) -> Self {
+		Self {
+			translation: transform.translation,
+			rotation_y: transform.rotation_y,
+			scale: transform.scale,
+		}
+	}
+}
+
+// Constructors
+impl Transform {
+	/// Creates a transform that does not change anything.
+	pub const fn identity() -> Self {
+		Self {
+			translation: Vec3::zero(),
+			rotation_y: 0.0,
+			scale: 1.0,
+		}
+	}
+	/// Creates a transform that only translates by the specified vector.
+	pub fn translation(translation: Vec3) -> Self {
+		Self {
+			translation,
+			..Self::identity()
+		}
+	}
+	/// Creates a transform that only scales by the specified factor, which must not be zero.
+	pub fn scaling(scale: f64) -> Self {
+		debug_assert!(
+			scale != 0.0,
+			"transform scale should be non-zero, but was {}",
+			scale
+		);
+		Self {
+			scale,
+			..Self::identity()
+		}
+	}
+	/// Combines this transform with the `next` one, which is applied after this one.
+	///
+	/// Since rotations around the y-axis commute and the scale is uniform,
+	/// the combination is again a scale, a rotation and a translation.
+	pub fn then(&self, next: &Transform) -> Self {
+		Self {
+			translation: next.apply_point(self.translation.into()).to_vec3(),
+			rotation_y: self.rotation_y + next.rotation_y,
+			scale: self.scale * next.scale,
+		}
+	}
+}
+
+// Application
+impl Transform {
+	/// Applies this transform to a point.
+	pub fn apply_point(&self, point: Point) -> Point {
+		(self.apply_vector(point.to_vec3()) + self.translation).into()
+	}
+	/// Applies this transform to a direction vector (the translation is not applied).
+	pub fn apply_vector(&self, vector: Vec3) -> Vec3 {
+		self.rotate(vector, self.rotation_y).scale(self.scale)
+	}
+	/// Applies this transform to a normal vector.
+	/// Since the scale is uniform, normal vectors are only rotated, and flipped if it is negative.
+	pub fn apply_normal(&self, normal: Vec3) -> Vec3 {
+		self.rotate(normal, self.rotation_y) * self.scale.signum()
+	}
+	/// Applies the inverse of this transform to a point.
+	pub fn inverse_point(&self, point: Point) -> Point {
+		self.inverse_vector(point.to_vec3() - self.translation)
+			.into()
+	}
+	/// Applies the inverse of this transform to a direction vector (the translation is not applied).
+	pub fn inverse_vector(&self, vector: Vec3) -> Vec3 {
+		self.rotate(vector, -self.rotation_y) / self.scale
+	}
+	/// Rotates a vector around the y-axis by the specified angle in degrees.
+	fn rotate(&self, vector: Vec3, degrees: f64) -> Vec3 {
+		let (sin, cos) = degrees.to_radians().sin_cos();
+		Vec3(
+			cos * vector.x() + sin * vector.z(),
+			vector.y(),
+			-sin * vector.x() + cos * vector.z(),
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Transform;
+	use crate::core::types::{Point, Vec3};
+
+	/// Checks whether two points are approximately equal.
+	fn point_approx_eq(a: Point, b: Point) -> bool {
+		let eq = |a: f64, b: f64| f64::abs(a - b) < 1e-10;
+		eq(a.x(), b.x()) && eq(a.y(), b.y()) && eq(a.z(), b.z())
+	}
+
+	#[test]
+	fn transform_scales_then_rotates_then_translates() {
+		// This transform doubles, rotates by 90 degrees, and moves up:
+		let transform = Transform {
+			translation: Vec3::new(0, 1, 0),
+			rotation_y: 90.0,
+			scale: 2.0,
+		};
+
+		// A point on the x-axis should land on the negative z-axis, above the origin:
+		let actual = transform.apply_point(Point::new(1, 0, 0));
+		let expected = Point::new(0, 1, -2);
+		assert!(
+			point_approx_eq(expected, actual),
+			"point should be transformed to {}, but was {}",
+			expected,
+			actual
+		);
+	}
+
+	#[test]
+	fn combined_transform_applies_both_in_order() {
+		let first = Transform {
+			translation: Vec3::new(1, 2, 3),
+			rotation_y: 30.0,
+			scale: 2.0,
+		};
+		let second = Transform {
+			translation: Vec3::new(-4, 0, 1),
+			rotation_y: 75.0,
+			scale: 0.5,
+		};
+		let point = Point::new(0.5, -1.0, 2.0);
+
+		let expected = second.apply_point(first.apply_point(point));
+		let actual = first.then(&second).apply_point(point);
+		assert!(
+			point_approx_eq(expected, actual),
+			"combined transform should give {}, but was {}",
+			expected,
+			actual
+		);
+	}
+
+	#[test]
+	fn inverse_undoes_transform() {
+		// This transform changes everything:
+		let transform = Transform {
+			translation: Vec3::new(3, -1, 2),
+			rotation_y: 33.0,
+			scale: 0.5,
+		};
+		let point = Point::new(-1.5, 2.0, 7.0);
+
+		let actual = transform.inverse_point(transform.apply_point(point));
+		assert!(
+			point_approx_eq(point, actual),
+			"inverse should give back {}, but was {}",
+			point,
+			actual
+		);
+	}
+
+	#[test]
+	fn if_scale_negative_then_normal_is_flipped() {
+		// This transform mirrors through the origin:
+		let transform = Transform::scaling(-1.0);
+
+		let actual = transform.apply_normal(Vec3::new(0, 0, 1));
+		assert_eq!(
+			actual,
+			Vec3::new(0, 0, -1),
+			"normal should be flipped, but was {}",
+			actual
+		);
+	}
+
+	#[test]
+	fn if_scale_zero_then_transform_not_parsed() {
+		let input = r#"{ "translation": [1, 2, 3], "scale": 0 }"#;
+
+		let result = serde_json::from_str::<Transform>(input);
+		assert!(result.is_err(), "zero scale should be rejected, but wasn't");
+	}
+}
@@ -1,12 +1,43 @@
+use std::fmt;
 use std::ops;
 
-use serde::Deserialize;
+use serde::de::{self, MapAccess, SeqAccess, Visitor};
+use serde::ser::SerializeTuple;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use super::Vec3;
 use super::vec3::ToVec3;
 
+/// The matrix converting linear sRGB colors to CIE XYZ coordinates (with a D65 white point).
+const RGB_TO_XYZ: [[f64; 3]; 3] = [
+	[0.4124, 0.3576, 0.1805],
+	[0.2126, 0.7152, 0.0722],
+	[0.0193, 0.1192, 0.9505],
+];
+/// The inverse of [`RGB_TO_XYZ`], converting CIE XYZ coordinates to linear sRGB colors.
+const XYZ_TO_RGB: [[f64; 3]; 3] = [
+	[3.2406254773200533, -1.5372079722103187, -0.4986285986982479],
+	[-0.9689307147293194, 1.875756060885241, 0.04151752384295394],
+	[0.05571012044551061, -0.2040210505984867, 1.0569959422543882],
+];
+/// The CIE XYZ coordinates of the white point (white, converted with [`RGB_TO_XYZ`]).
+const WHITE_XYZ: (f64, f64, f64) = (0.9505, 1.0, 1.089);
+/// The weights of the red, green and blue channels in the luminance of a color.
+const LUMINANCE_WEIGHTS: [f64; 3] = [0.2126, 0.7152, 0.0722];
+/// The temperature of neutral (white) light, in Kelvin, from which temperature shifts are measured.
+const NEUTRAL_KELVIN: f64 = 6500.0;
+/// The threshold `δ` of CIE L\*a\*b\*, below which its cube root is replaced by a linear function.
+const LAB_DELTA: f64 = 6.0 / 29.0;
+
 /// A vector that represents a color with its red, green, and blue values.
-#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+///
+/// A color can be deserialized from either of the following representations:
+/// - an array of three values: `[0.5, 0.2, 0.1]`;
+/// - a hex string of six digits: `"#80331a"`;
+/// - an object with the channels as fields: `{"r": 0.5, "g": 0.2, "b": 0.1}`.
+///
+/// It is always serialized as an array.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Color(pub f64, pub f64, pub f64);
 
 // Constructors
@@ -25,6 +56,42 @@ impl Color {
 	pub const fn black() -> Self {
 		Self(0.0, 0.0, 0.0)
 	}
+	/// Creates a white color value, where each color channel has value one.
+	pub const fn white() -> Self {
+		Self(1.0, 1.0, 1.0)
+	}
+	/// Creates a color from an array of channel values `[r, g, b]`.
+	pub const fn from_array(arr: [f64; 3]) -> Self {
+		Self(arr[0], arr[1], arr[2])
+	}
+	/// Creates the color of a black body radiating at the specified temperature, in Kelvin.
+	///
+	/// This uses Tanner Helland's approximation of black-body radiation, which fits the sRGB values
+	/// of the Planckian locus with curves. Temperatures are clamped to the range 1000K–40000K,
+	/// in which the approximation is valid. Daylight (6500K) is close to white, lower temperatures
+	/// are warmer (redder), and higher temperatures are cooler (bluer).
+	pub fn from_kelvin(temp: f64) -> Self {
+		let t = temp.clamp(1000.0, 40000.0) / 100.0;
+		let red = if t <= 66.0 {
+			255.0
+		} else {
+			329.698_727_446 * (t - 60.0).powf(-0.133_204_759_2)
+		};
+		let green = if t <= 66.0 {
+			99.470_802_586_1 * t.ln() - 161.119_568_166_1
+		} else {
+			288.122_169_528_3 * (t - 60.0).powf(-0.075_514_849_2)
+		};
+		let blue = if t >= 66.0 {
+			255.0
+		} else if t <= 19.0 {
+			0.0
+		} else {
+			138.517_731_223_1 * (t - 10.0).ln() - 305.044_792_730_7
+		};
+		let channel = |c: f64| c.clamp(0.0, 255.0) / 255.0;
+		Self(channel(red), channel(green), channel(blue))
+	}
 }
 
 // Getters
@@ -41,6 +108,45 @@ impl Color {
 	pub fn b(&self) -> f64 {
 		self.2
 	}
+	/// Returns the channel values of this color as an array `[r, g, b]`.
+	pub fn to_array(self) -> [f64; 3] {
+		[self.0, self.1, self.2]
+	}
+	/// Formats this color as a hex string of the form `#rrggbb`.
+	/// Channel values outside of the `0..=1` range are clamped.
+	pub fn to_hex(&self) -> String {
+		let channel = |c: f64| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+		format!(
+			"#{:02x}{:02x}{:02x}",
+			channel(self.0),
+			channel(self.1),
+			channel(self.2)
+		)
+	}
+	/// The relative luminance of this color (using Rec. 709 coefficients).
+	pub fn luminance(&self) -> f64 {
+		let [wr, wg, wb] = LUMINANCE_WEIGHTS;
+		wr * self.0 + wg * self.1 + wb * self.2
+	}
+}
+
+// Validation
+impl Color {
+	/// Checks if every channel of this color lies in `[0, infinity]`.
+	/// Infinite channels are allowed (for HDR), negative and NaN channels are not.
+	pub fn is_valid(&self) -> bool {
+		self.to_array().iter().all(|&c| c >= 0.0)
+	}
+	/// Checks if any channel of this color is NaN.
+	pub fn has_nan(&self) -> bool {
+		self.to_array().iter().any(|c| c.is_nan())
+	}
+	/// Returns this color with NaN and negative channels replaced by 0.
+	/// Infinite channels are kept (for HDR).
+	pub fn sanitize(self) -> Color {
+		let channel = |c: f64| if c >= 0.0 { c } else { 0.0 };
+		Self(channel(self.0), channel(self.1), channel(self.2))
+	}
 }
 
 // Transform between Color & Vec3
@@ -60,6 +166,151 @@ impl From<Color> for Vec3 {
 	}
 }
 
+// Transform between Color, arrays & tuples
+impl From<[f64; 3]> for Color {
+	fn from(value: [f64; 3]) -> Self {
+		Self::from_array(value)
+	}
+}
+impl From<[f32; 3]> for Color {
+	fn from(value: [f32; 3]) -> Self {
+		Self::from_array(value.map(f64::from))
+	}
+}
+impl From<Color> for [f64; 3] {
+	fn from(value: Color) -> Self {
+		value.to_array()
+	}
+}
+impl From<(f64, f64, f64)> for Color {
+	fn from(value: (f64, f64, f64)) -> Self {
+		Self(value.0, value.1, value.2)
+	}
+}
+impl From<Color> for (f64, f64, f64) {
+	fn from(value: Color) -> Self {
+		(value.0, value.1, value.2)
+	}
+}
+
+// Compositing
+impl Color {
+	/// Linearly interpolates between the colors `a` and `b`.
+	/// A value of `t = 0` yields `a`, and a value of `t = 1` yields `b`.
+	pub fn mix(a: Color, b: Color, t: f64) -> Color {
+		(a.to_vec3().scale(1.0 - t) + b.to_vec3().scale(t)).into()
+	}
+	/// Adds another color to this one, channel by channel, for example to accumulate light.
+	/// The result is not clamped. This is the same as the `+` operator.
+	// Named like the other compositing operators, and callable without importing `ops::Add`
+	#[allow(clippy::should_implement_trait)]
+	pub fn add(self, other: Color) -> Color {
+		(self.to_vec3() + other.to_vec3()).into()
+	}
+	/// Multiplies this color with another one, channel by channel.
+	pub fn multiply(self, other: Color) -> Color {
+		(self.to_vec3() * other.to_vec3()).into()
+	}
+	/// Composites the foreground color `fg` with opacity `fg_alpha` over an opaque background color `bg`
+	/// (the Porter-Duff *over* operator).
+	pub fn over(fg: Color, fg_alpha: f64, bg: Color) -> Color {
+		Color::mix(bg, fg, fg_alpha)
+	}
+}
+
+// Color spaces
+impl Color {
+	/// Converts this color to CIE XYZ coordinates `(x, y, z)`.
+	/// Colors are treated as linear sRGB values, as used throughout rendering (without gamma).
+	pub fn to_xyz(&self) -> (f64, f64, f64) {
+		let Vec3(x, y, z) = self.to_vec3().apply_matrix(RGB_TO_XYZ);
+		(x, y, z)
+	}
+	/// Creates a (linear sRGB) color from CIE XYZ coordinates, see [`Color::to_xyz`].
+	/// Coordinates outside of the sRGB gamut produce channel values outside of `0..=1`.
+	pub fn from_xyz(x: f64, y: f64, z: f64) -> Color {
+		Vec3(x, y, z).apply_matrix(XYZ_TO_RGB).into()
+	}
+	/// Converts this color to CIE L\*a\*b\* coordinates `(l, a, b)`, via [`Color::to_xyz`].
+	///
+	/// The lightness `l` ranges from 0 (black) to 100 (white), and `a` and `b` are the green–red
+	/// and blue–yellow components. Distances in this space approximate perceived color differences.
+	pub fn to_lab(&self) -> (f64, f64, f64) {
+		let (x, y, z) = self.to_xyz();
+		let (xn, yn, zn) = WHITE_XYZ;
+		let f = |t: f64| {
+			if t > LAB_DELTA.powi(3) {
+				t.cbrt()
+			} else {
+				t / (3.0 * LAB_DELTA * LAB_DELTA) + 4.0 / 29.0
+			}
+		};
+		let (fx, fy, fz) = (f(x / xn), f(y / yn), f(z / zn));
+		(116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+	}
+	/// Creates a (linear sRGB) color from CIE L\*a\*b\* coordinates, see [`Color::to_lab`].
+	pub fn from_lab(l: f64, a: f64, b: f64) -> Color {
+		let (xn, yn, zn) = WHITE_XYZ;
+		let f_inv = |t: f64| {
+			if t > LAB_DELTA {
+				t.powi(3)
+			} else {
+				3.0 * LAB_DELTA * LAB_DELTA * (t - 4.0 / 29.0)
+			}
+		};
+		let fy = (l + 16.0) / 116.0;
+		Color::from_xyz(
+			xn * f_inv(fy + a / 500.0),
+			yn * f_inv(fy),
+			zn * f_inv(fy - b / 200.0),
+		)
+	}
+}
+
+// Color grading
+impl Color {
+	/// Multiplies this color, as a column vector of its channels, by the row-major `matrix`.
+	pub fn apply_matrix(&self, matrix: [[f64; 3]; 3]) -> Color {
+		self.to_vec3().apply_matrix(matrix).into()
+	}
+	/// Creates a matrix that changes the saturation of colors (see [`Color::apply_matrix`]),
+	/// keeping their luminance.
+	///
+	/// An `amount` of 0 turns colors greyscale, 1 keeps them unchanged,
+	/// and values larger than 1 oversaturate them.
+	pub fn saturation_matrix(amount: f64) -> [[f64; 3]; 3] {
+		let mut matrix = [LUMINANCE_WEIGHTS.map(|w| (1.0 - amount) * w); 3];
+		for (i, row) in matrix.iter_mut().enumerate() {
+			row[i] += amount;
+		}
+		matrix
+	}
+	/// Creates a matrix that shifts the color temperature of colors (see [`Color::apply_matrix`]),
+	/// by scaling each channel like light of a different temperature would.
+	///
+	/// The `shift` is given in Kelvin, relative to neutral daylight (6500K): a positive shift makes
+	/// colors warmer (like lighting at `6500 - shift` Kelvin), and a negative shift makes them cooler.
+	/// See [`Color::from_kelvin`].
+	pub fn temperature_shift_matrix(shift: f64) -> [[f64; 3]; 3] {
+		let light = Color::from_kelvin(NEUTRAL_KELVIN - shift);
+		let neutral = Color::from_kelvin(NEUTRAL_KELVIN);
+		[
+			[light.0 / neutral.0, 0.0, 0.0],
+			[0.0, light.1 / neutral.1, 0.0],
+			[0.0, 0.0, light.2 / neutral.2],
+		]
+	}
+}
+
+// Operators
+impl ops::Add for Color {
+	type Output = Color;
+	/// Blends two colors additively, see [`Color::add`].
+	fn add(self, rhs: Self) -> Self::Output {
+		Color::add(self, rhs)
+	}
+}
+
 // Assignment operators
 impl ops::AddAssign for Color {
 	fn add_assign(&mut self, rhs: Self) {
@@ -68,3 +319,384 @@ impl ops::AddAssign for Color {
 		self.2 += rhs.2;
 	}
 }
+
+// Serialization
+impl Serialize for Color {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let mut tuple = serializer.serialize_tuple(3)?;
+		tuple.serialize_element(&self.0)?;
+		tuple.serialize_element(&self.1)?;
+		tuple.serialize_element(&self.2)?;
+		tuple.end()
+	}
+}
+impl<'de> Deserialize<'de> for Color {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		deserializer.deserialize_any(ColorVisitor)
+	}
+}
+
+/// A visitor that accepts all supported representations of a [`Color`].
+struct ColorVisitor;
+
+impl ColorVisitor {
+	/// Parses a hex string of the form `#rrggbb` into a color.
+	fn parse_hex(value: &str) -> Option<Color> {
+		let digits = value.strip_prefix('#')?;
+		if digits.len() != 6 || !digits.is_ascii() {
+			return None;
+		}
+		let channel = |i: usize| {
+			u8::from_str_radix(&digits[i..i + 2], 16)
+				.ok()
+				.map(|c| c as f64 / 255.0)
+		};
+		Some(Color(channel(0)?, channel(2)?, channel(4)?))
+	}
+}
+
+impl<'de> Visitor<'de> for ColorVisitor {
+	type Value = Color;
+
+	fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+		formatter.write_str("an array [r, g, b], a hex string '#rrggbb', or an object {r, g, b}")
+	}
+
+	fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+		Self::parse_hex(value)
+			.ok_or_else(|| de::Error::invalid_value(de::Unexpected::Str(value), &self))
+	}
+
+	fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+		let r = seq
+			.next_element()?
+			.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+		let g = seq
+			.next_element()?
+			.ok_or_else(|| de::Error::invalid_length(1, &self))?;
+		let b = seq
+			.next_element()?
+			.ok_or_else(|| de::Error::invalid_length(2, &self))?;
+		if seq.next_element::<de::IgnoredAny>()?.is_some() {
+			return Err(de::Error::invalid_length(4, &self));
+		}
+		Ok(Color(r, g, b))
+	}
+
+	fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+		let (mut r, mut g, mut b) = (None, None, None);
+		while let Some(key) = map.next_key::<String>()? {
+			let (name, channel) = match key.as_str() {
+				"r" => ("r", &mut r),
+				"g" => ("g", &mut g),
+				"b" => ("b", &mut b),
+				_ => return Err(de::Error::unknown_field(&key, &["r", "g", "b"])),
+			};
+			if channel.is_some() {
+				return Err(de::Error::duplicate_field(name));
+			}
+			*channel = Some(map.next_value()?);
+		}
+		let r = r.ok_or_else(|| de::Error::missing_field("r"))?;
+		let g = g.ok_or_else(|| de::Error::missing_field("g"))?;
+		let b = b.ok_or_else(|| de::Error::missing_field("b"))?;
+		Ok(Color(r, g, b))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::Color;
+
+	/// Checks whether two colors are approximately equal, channel by channel.
+	fn color_approx_eq(a: Color, b: Color) -> bool {
+		let eq = |x: f64, y: f64| f64::abs(x - y) < 1e-10;
+		eq(a.0, b.0) && eq(a.1, b.1) && eq(a.2, b.2)
+	}
+
+	#[test]
+	fn red_converts_to_first_column_of_xyz_matrix() {
+		let (x, y, z) = Color::new(1, 0, 0).to_xyz();
+		let eq = |a: f64, b: f64| f64::abs(a - b) < 1e-10;
+		assert!(
+			eq(x, 0.4124) && eq(y, 0.2126) && eq(z, 0.0193),
+			"red should be converted to (0.4124, 0.2126, 0.0193), but was ({}, {}, {})",
+			x,
+			y,
+			z
+		);
+	}
+
+	#[test]
+	fn xyz_and_lab_conversions_round_trip() {
+		let eq = |a: Color, b: Color| {
+			let eq = |x: f64, y: f64| f64::abs(x - y) < 1e-6;
+			eq(a.0, b.0) && eq(a.1, b.1) && eq(a.2, b.2)
+		};
+		// These colors include black, white, and very dark colors (in the linear part of L*a*b*):
+		let colors = [
+			Color::black(),
+			Color::white(),
+			Color::new(0.001, 0.002, 0.0005),
+			Color::new(0.8, 0.3, 0.1),
+			Color::new(0.05, 0.6, 0.9),
+		];
+		for color in colors {
+			let (x, y, z) = color.to_xyz();
+			let from_xyz = Color::from_xyz(x, y, z);
+			assert!(
+				eq(color, from_xyz),
+				"color {:?} should be unchanged by XYZ, but was {:?}",
+				color,
+				from_xyz
+			);
+			let (l, a, b) = color.to_lab();
+			let from_lab = Color::from_lab(l, a, b);
+			assert!(
+				eq(color, from_lab),
+				"color {:?} should be unchanged by L*a*b*, but was {:?}",
+				color,
+				from_lab
+			);
+		}
+
+		// White should have full lightness, and no color components:
+		let (l, a, b) = Color::white().to_lab();
+		assert!(
+			f64::abs(l - 100.0) < 1e-6 && a.abs() < 1e-6 && b.abs() < 1e-6,
+			"white should be (100, 0, 0), but was ({}, {}, {})",
+			l,
+			a,
+			b
+		);
+	}
+
+	#[test]
+	fn daylight_temperature_is_close_to_white() {
+		let color = Color::from_kelvin(6500.0);
+		for channel in [color.r(), color.g(), color.b()] {
+			assert!(
+				channel > 0.95 && channel <= 1.0,
+				"6500K should be approximately white, but was {:?}",
+				color
+			);
+		}
+	}
+
+	#[test]
+	fn warm_white_temperature_is_reddish() {
+		let color = Color::from_kelvin(2700.0);
+		assert!(
+			color.r() > color.b() + 0.3,
+			"2700K should have a clearly higher red than blue channel, but was {:?}",
+			color
+		);
+	}
+
+	#[test]
+	fn add_accumulates_light_without_clamping() {
+		let sum = Color::new(0.75, 0.5, 0.0).add(Color::new(0.5, 0.25, 0.0));
+		assert_eq!(
+			sum,
+			Color::new(1.25, 0.75, 0.0),
+			"channels should be added without clamping"
+		);
+		assert_eq!(
+			sum,
+			Color::new(0.75, 0.5, 0.0) + Color::new(0.5, 0.25, 0.0),
+			"method should match the operator"
+		);
+	}
+
+	#[test]
+	fn all_representations_parse_to_same_color() {
+		// This is the color every representation describes:
+		let expected = Color::new(1.0, 0.2, 0.0);
+		// These are the supported representations:
+		let inputs = [
+			r#"[1.0, 0.2, 0.0]"#,
+			r##""#ff3300""##,
+			r#"{"r": 1.0, "g": 0.2, "b": 0.0}"#,
+		];
+
+		for input in inputs {
+			let result = serde_json::from_str::<Color>(input);
+			assert!(
+				result.is_ok(),
+				"input {} should be parsed, but error occurred: {:?}",
+				input,
+				result.err()
+			);
+			let actual = result.unwrap();
+			assert!(
+				color_approx_eq(expected, actual),
+				"input {} should be parsed to {:?}, but was {:?}",
+				input,
+				expected,
+				actual
+			);
+		}
+	}
+
+	#[test]
+	fn if_representation_malformed_then_parsing_should_error() {
+		// None of these inputs describe a color:
+		let inputs = [
+			r#"[1.0, 0.2]"#,
+			r#"[1.0, 0.2, 0.0, 0.5]"#,
+			r##""#ff33""##,
+			r##""ff3300""##,
+			r##""#gg3300""##,
+			r#"{"r": 1.0, "g": 0.2}"#,
+			r#"{"r": 1.0, "g": 0.2, "b": 0.0, "a": 1.0}"#,
+		];
+
+		for input in inputs {
+			let result = serde_json::from_str::<Color>(input);
+			assert!(
+				result.is_err(),
+				"input {} should not be parsed, but was: {:?}",
+				input,
+				result.ok()
+			);
+		}
+	}
+
+	#[test]
+	fn color_is_serialized_as_array() {
+		// This color should be written in array form:
+		let color = Color::new(0.5, 0.25, 1.0);
+
+		let json = serde_json::to_string(&color).unwrap();
+		assert_eq!(
+			json, "[0.5,0.25,1.0]",
+			"color should be serialized as array"
+		);
+
+		// ...which should be parsed back to the same value:
+		let parsed = serde_json::from_str::<Color>(&json).unwrap();
+		assert_eq!(parsed, color, "serialized color should round-trip");
+	}
+
+	#[test]
+	fn mix_of_black_and_white_is_grey() {
+		// Mixing black and white halfway should produce grey:
+		let actual = Color::mix(Color::black(), Color::white(), 0.5);
+		let expected = Color::new(0.5, 0.5, 0.5);
+		assert!(
+			color_approx_eq(expected, actual),
+			"mix should be {:?}, but was {:?}",
+			expected,
+			actual
+		);
+	}
+
+	#[test]
+	fn opaque_foreground_over_background_is_foreground() {
+		// A fully opaque red over blue should be red:
+		let red = Color::new(1, 0, 0);
+		let actual = Color::over(red, 1.0, Color::new(0, 0, 1));
+		assert!(
+			color_approx_eq(red, actual),
+			"composite should be {:?}, but was {:?}",
+			red,
+			actual
+		);
+	}
+
+	#[test]
+	fn multiply_and_add_are_component_wise_and_unclamped() {
+		let a = Color::new(0.5, 2.0, 0.0);
+		let b = Color::new(0.5, 0.75, 1.0);
+
+		// The product should be taken channel by channel:
+		let product = a.multiply(b);
+		assert!(
+			color_approx_eq(Color::new(0.25, 1.5, 0.0), product),
+			"product should be (0.25, 1.5, 0), but was {:?}",
+			product
+		);
+		// The sum should exceed 1 without clamping:
+		let sum = a + b;
+		assert!(
+			color_approx_eq(Color::new(1.0, 2.75, 1.0), sum),
+			"sum should be (1, 2.75, 1), but was {:?}",
+			sum
+		);
+	}
+
+	#[test]
+	fn if_saturation_zero_then_channels_equal() {
+		let grey = Color::new(1, 0, 0).apply_matrix(Color::saturation_matrix(0.0));
+		let luminance = Color::new(1, 0, 0).luminance();
+		assert!(
+			color_approx_eq(grey, Color::new(luminance, luminance, luminance)),
+			"red should become grey with its luminance, but was {:?}",
+			grey
+		);
+
+		// A saturation of 1 should keep colors unchanged:
+		let color = Color::new(0.2, 0.5, 0.9);
+		let unchanged = color.apply_matrix(Color::saturation_matrix(1.0));
+		assert!(
+			color_approx_eq(unchanged, color),
+			"color should be unchanged, but was {:?}",
+			unchanged
+		);
+	}
+
+	#[test]
+	fn positive_temperature_shift_makes_colors_warmer() {
+		let grey = Color::new(0.5, 0.5, 0.5);
+		let warm = grey.apply_matrix(Color::temperature_shift_matrix(2000.0));
+		let cool = grey.apply_matrix(Color::temperature_shift_matrix(-2000.0));
+		assert!(
+			warm.r() > warm.b(),
+			"warm color should be more red than blue, but was {:?}",
+			warm
+		);
+		assert!(
+			cool.b() > cool.r(),
+			"cool color should be more blue than red, but was {:?}",
+			cool
+		);
+		let neutral = grey.apply_matrix(Color::temperature_shift_matrix(0.0));
+		assert!(
+			color_approx_eq(neutral, grey),
+			"no shift should keep colors unchanged, but was {:?}",
+			neutral
+		);
+	}
+
+	#[test]
+	fn hex_representation_round_trips() {
+		// This color is given as hex:
+		let hex = "#336699";
+		let color = serde_json::from_str::<Color>(&format!("\"{}\"", hex)).unwrap();
+
+		// Formatting it should produce the same string:
+		assert_eq!(color.to_hex(), hex, "hex string should be {}", hex);
+	}
+
+	#[test]
+	fn if_nan_channel_then_has_nan() {
+		let color = Color(f64::NAN, 0.0, 0.0);
+		assert!(color.has_nan(), "color with NaN should have NaN");
+		assert!(!color.is_valid(), "color with NaN should be invalid");
+		assert!(
+			!Color::new(0.5, 0.5, 0.5).has_nan(),
+			"grey should not have NaN"
+		);
+	}
+
+	#[test]
+	fn sanitize_replaces_nan_and_negative_channels() {
+		let color = Color(f64::NAN, -1.0, f64::INFINITY).sanitize();
+		assert_eq!(
+			color,
+			Color(0.0, 0.0, f64::INFINITY),
+			"NaN and negative channels should be 0, infinite channels kept"
+		);
+		assert!(color.is_valid(), "sanitized color should be valid");
+	}
+}
@@ -1,13 +1,18 @@
+use std::cmp;
 use std::fmt::Display;
+use std::hash::{Hash, Hasher};
 use std::str::FromStr;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use super::Vec3;
 use super::vec3::ToVec3;
 
 /// A representation of a point in 3D space.
-#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+///
+/// Like [`Vec3`], points are totally ordered and hashable, and can be used as keys in maps.
+/// Points containing `NaN` can't be retrieved from maps reliably.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
 pub struct Point(pub f64, pub f64, pub f64);
 
 // Constructors
@@ -25,6 +30,16 @@ impl Point {
 	pub const fn origin() -> Self {
 		Self(0.0, 0.0, 0.0)
 	}
+	/// Creates a point from an array of coordinates `[x, y, z]`.
+	pub const fn from_array(arr: [f64; 3]) -> Self {
+		Self(arr[0], arr[1], arr[2])
+	}
+	/// Creates a point from spherical coordinates around the origin: the distance `r`, the polar
+	/// angle `theta` from the Y axis (up), and the azimuthal angle `phi` from the X axis towards
+	/// the Z axis. See [`Vec3::from_spherical`].
+	pub fn from_spherical(r: f64, theta: f64, phi: f64) -> Self {
+		Vec3::from_spherical(r, theta, phi).into()
+	}
 }
 
 // Getters
@@ -41,6 +56,10 @@ impl Point {
 	pub fn z(&self) -> f64 {
 		self.2
 	}
+	/// Returns the coordinates of this point as an array `[x, y, z]`.
+	pub fn to_array(self) -> [f64; 3] {
+		[self.0, self.1, self.2]
+	}
 }
 
 // Operations
@@ -49,6 +68,29 @@ impl Point {
 	pub fn distance(&self, other: Self) -> f64 {
 		(self.to_vec3() - other.to_vec3()).norm()
 	}
+	/// Converts this point to spherical coordinates `(r, theta, phi)` around the origin.
+	/// See [`Vec3::to_spherical`].
+	pub fn to_spherical(&self) -> (f64, f64, f64) {
+		self.to_vec3().to_spherical()
+	}
+}
+
+// Ordering & hashing
+impl Eq for Point {}
+impl PartialOrd for Point {
+	fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+		Some(self.cmp(other))
+	}
+}
+impl Ord for Point {
+	fn cmp(&self, other: &Self) -> cmp::Ordering {
+		self.to_vec3().cmp(&other.to_vec3())
+	}
+}
+impl Hash for Point {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.to_vec3().hash(state);
+	}
 }
 
 // Display
@@ -80,3 +122,124 @@ impl From<Point> for Vec3 {
 		Vec3(value.0, value.1, value.2)
 	}
 }
+
+// Transform between Point, arrays & tuples
+impl From<[f64; 3]> for Point {
+	fn from(value: [f64; 3]) -> Self {
+		Self::from_array(value)
+	}
+}
+impl From<[f32; 3]> for Point {
+	fn from(value: [f32; 3]) -> Self {
+		Self::from_array(value.map(f64::from))
+	}
+}
+impl From<Point> for [f64; 3] {
+	fn from(value: Point) -> Self {
+		value.to_array()
+	}
+}
+impl From<(f64, f64, f64)> for Point {
+	fn from(value: (f64, f64, f64)) -> Self {
+		Self(value.0, value.1, value.2)
+	}
+}
+impl From<Point> for (f64, f64, f64) {
+	fn from(value: Point) -> Self {
+		(value.0, value.1, value.2)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::{BTreeMap, HashSet};
+	use std::f64::consts::PI;
+	use std::hash::{BuildHasher, RandomState};
+
+	use super::Point;
+	use crate::core::types::ToVec3;
+
+	#[test]
+	fn array_and_tuple_conversions_round_trip() {
+		let point = Point::new(-1, 0.5, 4);
+		assert_eq!(
+			Point::from(<[f64; 3]>::from(point)),
+			point,
+			"point should survive the round trip through an array"
+		);
+		assert_eq!(
+			Point::from((-1.0, 0.5, 4.0)),
+			point,
+			"point should be created from a tuple"
+		);
+	}
+
+	#[test]
+	fn point_from_spherical_coordinates() {
+		// On the equator, at an azimuth of 0, is the X axis:
+		let point = Point::from_spherical(1.0, PI / 2.0, 0.0);
+		assert!(
+			(point.to_vec3() - Point::new(1, 0, 0).to_vec3()).is_near_zero(),
+			"point should lie on the X axis, but was {}",
+			point
+		);
+
+		// Converting back should yield the same coordinates:
+		let (r, theta, phi) = Point::from_spherical(2.0, 0.7, 5.9).to_spherical();
+		for (actual, expected) in [(r, 2.0), (theta, 0.7), (phi, 5.9)] {
+			assert!(
+				(actual - expected).abs() < 1e-10,
+				"coordinate should be {}, but was {}",
+				expected,
+				actual
+			);
+		}
+	}
+
+	#[test]
+	fn points_can_be_used_as_btree_keys() {
+		let mut map = BTreeMap::new();
+		map.insert(Point::new(1, 2, 3), String::from("a"));
+		map.insert(Point::new(-1, 5, 0), String::from("b"));
+		map.insert(Point::new(1, 0, 9), String::from("c"));
+
+		assert_eq!(
+			map.get(&Point::new(1, 2, 3)),
+			Some(&String::from("a")),
+			"point should be retrieved"
+		);
+		// Keys should be ordered by x, then y, then z:
+		let keys = map.keys().copied().collect::<Vec<_>>();
+		assert_eq!(
+			keys,
+			vec![
+				Point::new(-1, 5, 0),
+				Point::new(1, 0, 9),
+				Point::new(1, 2, 3)
+			],
+			"points should be ordered lexicographically"
+		);
+	}
+
+	#[test]
+	fn equal_points_hash_and_compare_equal() {
+		// These points have the same coordinates (zeros of different sign are equal):
+		let (a, b) = (Point::new(0.5, 0.0, -2), Point::new(0.5, -0.0, -2));
+		let state = RandomState::new();
+		assert_eq!(a, b, "points should be equal");
+		assert_eq!(
+			a.cmp(&b),
+			std::cmp::Ordering::Equal,
+			"points should compare equal"
+		);
+		assert_eq!(
+			state.hash_one(a),
+			state.hash_one(b),
+			"points should hash equally"
+		);
+
+		// Deduplicating should keep only one of them:
+		let set = HashSet::from([a, b, Point::origin()]);
+		assert_eq!(set.len(), 2, "set should contain two distinct points");
+	}
+}
@@ -1,8 +1,23 @@
+use std::cmp;
+use std::f64::consts::PI;
 use std::fmt::Display;
+use std::hash::{Hash, Hasher};
 use std::ops;
 use std::str::FromStr;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+use crate::core::random;
+
+// Arithmetic is implemented with SIMD instructions if the `simd` feature is enabled
+#[cfg(not(feature = "simd"))]
+mod scalar;
+#[cfg(not(feature = "simd"))]
+use scalar as arith;
+#[cfg(feature = "simd")]
+mod simd;
+#[cfg(feature = "simd")]
+use simd as arith;
 
 /// An epsilon value used for near zero comparisons.
 /// Two values are considered to be equal if their absolute
@@ -10,7 +25,12 @@ use serde::Deserialize;
 const NEAR_ZERO_EPSILON: f64 = 1e-8;
 
 /// A vector of three floating-point values.
-#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+///
+/// Vectors are totally ordered (lexicographically by `x`, `y`, then `z`) and hashable,
+/// so they can be used as keys in a `BTreeMap` or `HashMap`.
+/// As a limitation, vectors containing `NaN` don't equal themselves, so they can't be
+/// retrieved from maps reliably.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
 pub struct Vec3(pub f64, pub f64, pub f64);
 
 /// Denotes an object that can be converted to [`Vec3`].
@@ -35,6 +55,10 @@ impl Vec3 {
 	pub const fn zero() -> Self {
 		Self(0.0, 0.0, 0.0)
 	}
+	/// Creates a new vector from an array of coordinates `[x, y, z]`.
+	pub const fn from_array(arr: [f64; 3]) -> Self {
+		Self(arr[0], arr[1], arr[2])
+	}
 	/// Creates a new vector where each value is the same as the specified one in the parameter.
 	pub fn diagonal<A: Into<f64>>(xyz: A) -> Self {
 		let val = xyz.into();
@@ -44,9 +68,9 @@ impl Vec3 {
 	pub fn random<A: Into<f64>>(range: ops::Range<A>) -> Self {
 		let (start, end): (f64, f64) = (range.start.into(), range.end.into());
 		Self::new(
-			rand::random_range(start..end),
-			rand::random_range(start..end),
-			rand::random_range(start..end),
+			random::random_range(start..end),
+			random::random_range(start..end),
+			random::random_range(start..end),
 		)
 	}
 	/// Creates a new random unit vector.
@@ -60,12 +84,43 @@ impl Vec3 {
 		}
 	}
 	/// Creates a new random (not necessarily unit) vector.
+	/// This method uniformly distributes the coordinates inside the unit sphere.
+	pub fn random_in_unit_sphere() -> Self {
+		loop {
+			let vec = Self::random(-1..1);
+			if vec.norm_sq() < 1.0 {
+				return vec;
+			}
+		}
+	}
+	/// Creates a new random (not necessarily unit) vector.
+	/// This method uniformly distributes the coordinates inside the half of the unit sphere
+	/// that the specified `normal` points into.
+	pub fn random_in_unit_hemisphere(normal: Vec3) -> Self {
+		let vec = Self::random_in_unit_sphere();
+		if vec.dot(normal) > 0.0 { vec } else { -vec }
+	}
+	/// Creates a new random unit vector in the upper hemisphere (z > 0).
+	/// The directions are distributed proportionally to the cosine of their angle to the z-axis.
+	///
+	/// Use [`Vec3::orient`] to rotate the direction around an arbitrary normal.
+	pub fn random_cosine_direction() -> Self {
+		Self::cosine_direction(random::random(), random::random())
+	}
+	/// Maps two uniformly distributed values `r1`, `r2` in `[0, 1)` to a cosine-weighted
+	/// direction in the upper hemisphere (z > 0).
+	pub(crate) fn cosine_direction(r1: f64, r2: f64) -> Self {
+		let phi = 2.0 * PI * r1;
+		let radius = r2.sqrt();
+		Self(phi.cos() * radius, phi.sin() * radius, (1.0 - r2).sqrt())
+	}
+	/// Creates a new random (not necessarily unit) vector.
 	/// This method randomly distributes the coordinates across the unit disk (z = 0).
 	pub fn random_in_unit_disk() -> Self {
 		loop {
 			let vec = Self::new(
-				rand::random_range(-1.0..1.0),
-				rand::random_range(-1.0..1.0),
+				random::random_range(-1.0..1.0),
+				random::random_range(-1.0..1.0),
 				0.0,
 			);
 			if vec.norm_sq() < 1.0 {
@@ -73,10 +128,24 @@ impl Vec3 {
 			}
 		}
 	}
+	/// Creates a vector from spherical coordinates: the length `r`, the polar angle `theta`
+	/// measured from the Y axis (up), and the azimuthal angle `phi` measured from the X axis
+	/// towards the Z axis. Angles are in radians.
+	pub fn from_spherical(r: f64, theta: f64, phi: f64) -> Self {
+		Self(
+			r * theta.sin() * phi.cos(),
+			r * theta.cos(),
+			r * theta.sin() * phi.sin(),
+		)
+	}
 }
 
 // Getters
 impl Vec3 {
+	/// Returns the coordinates of this vector as an array `[x, y, z]`.
+	pub fn to_array(self) -> [f64; 3] {
+		[self.0, self.1, self.2]
+	}
 	/// The x coordinate of this vector.
 	pub fn x(&self) -> f64 {
 		self.0
@@ -92,26 +161,52 @@ impl Vec3 {
 }
 
 // String conversions
+// Formatted as `[x y z]`, which can be parsed back
 impl Display for Vec3 {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		write!(f, "[{} {} {}]", self.0, self.1, self.2)
 	}
 }
+// Parsed from three coordinates separated by whitespace, commas or semicolons,
+// optionally enclosed in brackets, e.g. `[1 2 3]`, `1,2,3` or `(1, 2, 3)`;
+// a comma or semicolon must be followed by a coordinate, so `1,,2,3` is rejected;
+// or from the name of a direction (ignoring case), e.g. `up` or `forward`
 impl FromStr for Vec3 {
 	type Err = String;
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let named = [
+			("up", Self(0.0, 1.0, 0.0)),
+			("down", Self(0.0, -1.0, 0.0)),
+			("right", Self(1.0, 0.0, 0.0)),
+			("left", Self(-1.0, 0.0, 0.0)),
+			("forward", Self(0.0, 0.0, -1.0)),
+			("back", Self(0.0, 0.0, 1.0)),
+			("zero", Self(0.0, 0.0, 0.0)),
+		];
+		if let Some((_, vec)) = named
+			.iter()
+			.find(|(name, _)| name.eq_ignore_ascii_case(s.trim()))
+		{
+			return Ok(*vec);
+		}
+
 		let opening_brackets = ['[', '(', '<', '{'];
 		let closing_brackets = [']', ')', '>', '}'];
-		let separators = [' ', ',', ';'];
+		let separators = [',', ';'];
 
 		let trimmed = s
 			.trim()
 			.replace(opening_brackets, "")
 			.replace(closing_brackets, "");
-		let values: Vec<f64> = trimmed
-			.split(separators)
-			.map(|val| val.parse::<f64>().map_err(|e| format!("{} '{}'", e, val)))
-			.collect::<Result<_, _>>()?;
+		let mut values = Vec::new();
+		for field in trimmed.split(separators) {
+			if field.trim().is_empty() {
+				return Err(format!("empty coordinate in '{}'", s));
+			}
+			for val in field.split_whitespace() {
+				values.push(val.parse::<f64>().map_err(|e| format!("{} '{}'", e, val))?);
+			}
+		}
 
 		if values.len() != 3 {
 			return Err(format!("expected 3 coordinates, got {}", values.len()));
@@ -120,6 +215,31 @@ impl FromStr for Vec3 {
 	}
 }
 
+// Ordering & hashing
+impl Eq for Vec3 {}
+impl PartialOrd for Vec3 {
+	fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+		Some(self.cmp(other))
+	}
+}
+impl Ord for Vec3 {
+	fn cmp(&self, other: &Self) -> cmp::Ordering {
+		let compare = |a: f64, b: f64| a.partial_cmp(&b).unwrap_or_else(|| a.total_cmp(&b));
+		compare(self.0, other.0)
+			.then_with(|| compare(self.1, other.1))
+			.then_with(|| compare(self.2, other.2))
+	}
+}
+impl Hash for Vec3 {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		// Zeros are equal regardless of sign, so they must hash equally as well
+		let bits = |value: f64| if value == 0.0 { 0 } else { value.to_bits() };
+		state.write_u64(bits(self.0));
+		state.write_u64(bits(self.1));
+		state.write_u64(bits(self.2));
+	}
+}
+
 // Indexes
 impl ops::Index<usize> for Vec3 {
 	type Output = f64;
@@ -143,6 +263,33 @@ impl ops::IndexMut<usize> for Vec3 {
 	}
 }
 
+// Transform between Vec3, arrays & tuples
+impl From<[f64; 3]> for Vec3 {
+	fn from(value: [f64; 3]) -> Self {
+		Self::from_array(value)
+	}
+}
+impl From<[f32; 3]> for Vec3 {
+	fn from(value: [f32; 3]) -> Self {
+		Self::from_array(value.map(f64::from))
+	}
+}
+impl From<Vec3> for [f64; 3] {
+	fn from(value: Vec3) -> Self {
+		value.to_array()
+	}
+}
+impl From<(f64, f64, f64)> for Vec3 {
+	fn from(value: (f64, f64, f64)) -> Self {
+		Self(value.0, value.1, value.2)
+	}
+}
+impl From<Vec3> for (f64, f64, f64) {
+	fn from(value: Vec3) -> Self {
+		(value.0, value.1, value.2)
+	}
+}
+
 // Operators
 impl ops::Neg for Vec3 {
 	type Output = Self;
@@ -156,8 +303,7 @@ where
 {
 	type Output = Self;
 	fn add(self, rhs: T) -> Self::Output {
-		let other = rhs.into();
-		Vec3(self.0 + other.0, self.1 + other.1, self.2 + other.2)
+		arith::add(self, rhs.into())
 	}
 }
 impl<T> ops::Sub<T> for Vec3
@@ -166,8 +312,7 @@ where
 {
 	type Output = Self;
 	fn sub(self, rhs: T) -> Self::Output {
-		let other = rhs.into();
-		Vec3(self.0 - other.0, self.1 - other.1, self.2 - other.2)
+		arith::sub(self, rhs.into())
 	}
 }
 impl<T> ops::Mul<T> for Vec3
@@ -176,8 +321,7 @@ where
 {
 	type Output = Self;
 	fn mul(self, rhs: T) -> Self::Output {
-		let other = rhs.into();
-		Vec3(self.0 * other.0, self.1 * other.1, self.2 * other.2)
+		arith::mul(self, rhs.into())
 	}
 }
 impl<T> ops::Div<T> for Vec3
@@ -195,7 +339,7 @@ where
 impl ops::Mul<f64> for Vec3 {
 	type Output = Self;
 	fn mul(self, rhs: f64) -> Self::Output {
-		Vec3(rhs * self.0, rhs * self.1, rhs * self.2)
+		arith::scale(self, rhs)
 	}
 }
 impl ops::Div<f64> for Vec3 {
@@ -240,12 +384,24 @@ impl Vec3 {
 	/// Calculates the squared norm `||v||^2` of this vector `v = (x, y, z)`, that is
 	/// the value `x^2 + y^2 + z^2`.
 	pub fn norm_sq(&self) -> f64 {
-		self.0 * self.0 + self.1 * self.1 + self.2 * self.2
+		arith::dot(*self, *self)
 	}
 	/// Calculates the norm (distance from origin) `||v||` of this vector `v`.
 	pub fn norm(&self) -> f64 {
 		self.norm_sq().sqrt()
 	}
+	/// Converts this vector to spherical coordinates `(r, theta, phi)`, as described in
+	/// [`Vec3::from_spherical`]. `theta` lies in `[0, π]`, and `phi` in `[0, 2π)`.
+	/// The zero vector has both angles set to 0.
+	pub fn to_spherical(&self) -> (f64, f64, f64) {
+		let r = self.norm();
+		if r == 0.0 {
+			return (0.0, 0.0, 0.0);
+		}
+		let theta = f64::acos((self.1 / r).clamp(-1.0, 1.0));
+		let phi = f64::atan2(self.2, self.0).rem_euclid(2.0 * PI);
+		(r, theta, phi)
+	}
 }
 
 // Operations
@@ -262,20 +418,86 @@ impl Vec3 {
 	}
 	/// Calculates the dot product `v * u` of this vector `v` and another vector `u`.
 	pub fn dot(self, rhs: Self) -> f64 {
-		self.0 * rhs.0 + self.1 * rhs.1 + self.2 * rhs.2
+		arith::dot(self, rhs)
 	}
 	/// Calculates the cross product `v x u` of this vector `v` and another vector `u`.
 	pub fn cross(self, rhs: Self) -> Self {
-		Vec3(
-			self.1 * rhs.2 - self.2 * rhs.1,
-			self.2 * rhs.0 - self.0 * rhs.2,
-			self.0 * rhs.1 - self.1 * rhs.0,
-		)
+		arith::cross(self, rhs)
+	}
+	/// Rotates this vector, which is given relative to the z-axis, so that it is relative to `normal` instead.
+	///
+	/// The `normal` is expected to be a unit vector.
+	pub fn orient(self, normal: Vec3) -> Self {
+		// Orthonormal basis with the normal as its third axis
+		let helper = if normal.0.abs() > 0.9 {
+			Vec3(0.0, 1.0, 0.0)
+		} else {
+			Vec3(1.0, 0.0, 0.0)
+		};
+		let v = normal.cross(helper).unit();
+		let u = normal.cross(v);
+		u.scale(self.0) + v.scale(self.1) + normal.scale(self.2)
 	}
 	/// Returns a new unit vector (vector of norm 1) pointing in the same direction as this vector.
 	pub fn unit(self) -> Self {
 		self / self.norm()
 	}
+	/// Interpolates between this unit vector and another unit vector along the great circle
+	/// connecting them (spherical linear interpolation), so that the angle changes at a constant rate.
+	/// A `t` of 0 returns this vector, a `t` of 1 returns `other`.
+	///
+	/// If the vectors are (almost) parallel, falls back to [`Vec3::nlerp`].
+	/// If they point in opposite directions, the rotation happens around an arbitrary perpendicular axis.
+	pub fn slerp(self, other: Vec3, t: f64) -> Self {
+		let cos_theta = self.dot(other).clamp(-1.0, 1.0);
+		if cos_theta > 1.0 - NEAR_ZERO_EPSILON {
+			return self.nlerp(other, t);
+		}
+		if cos_theta < -1.0 + NEAR_ZERO_EPSILON {
+			// Any great circle connects opposite vectors, so pick one through a perpendicular vector
+			let perpendicular = Vec3(1.0, 0.0, 0.0).orient(self);
+			let angle = t * std::f64::consts::PI;
+			return self.scale(angle.cos()) + perpendicular.scale(angle.sin());
+		}
+		let theta = cos_theta.acos();
+		let sin_theta = theta.sin();
+		self.scale(((1.0 - t) * theta).sin() / sin_theta)
+			+ other.scale((t * theta).sin() / sin_theta)
+	}
+	/// Interpolates linearly between this vector and another vector, and normalizes the result.
+	///
+	/// This is cheaper than [`Vec3::slerp`], but the angle does not change at a constant rate.
+	pub fn nlerp(self, other: Vec3, t: f64) -> Self {
+		(self.scale(1.0 - t) + other.scale(t)).unit()
+	}
+}
+
+// Rotation
+impl Vec3 {
+	/// Rotates this vector around `axis` by `angle_radians` (counterclockwise, when looking
+	/// against the axis), using Rodrigues' rotation formula. The `axis` doesn't need to be a unit vector.
+	pub fn rotate_around(self, axis: Vec3, angle_radians: f64) -> Self {
+		let k = axis.unit();
+		let (sin, cos) = angle_radians.sin_cos();
+		self.scale(cos) + k.cross(self).scale(sin) + k.scale(k.dot(self) * (1.0 - cos))
+	}
+	/// Calculates the 3×3 matrix (in row-major order) that rotates vectors around `axis`
+	/// by `angle_radians`, see [`Vec3::rotate_around`] and [`Vec3::apply_matrix`].
+	pub fn rotation_matrix(axis: Vec3, angle_radians: f64) -> [[f64; 3]; 3] {
+		let Vec3(x, y, z) = axis.unit();
+		let (sin, cos) = angle_radians.sin_cos();
+		let t = 1.0 - cos;
+		[
+			[t * x * x + cos, t * x * y - sin * z, t * x * z + sin * y],
+			[t * x * y + sin * z, t * y * y + cos, t * y * z - sin * x],
+			[t * x * z - sin * y, t * y * z + sin * x, t * z * z + cos],
+		]
+	}
+	/// Multiplies the 3×3 `matrix` (in row-major order) with this vector.
+	pub fn apply_matrix(self, matrix: [[f64; 3]; 3]) -> Self {
+		let row = |i: usize| matrix[i][0] * self.0 + matrix[i][1] * self.1 + matrix[i][2] * self.2;
+		Vec3(row(0), row(1), row(2))
+	}
 }
 
 // Miscellaneous
@@ -305,10 +527,40 @@ impl Vec3 {
 			&& f64::abs(self.1) < NEAR_ZERO_EPSILON
 			&& f64::abs(self.2) < NEAR_ZERO_EPSILON
 	}
+	/// Checks if all values of this vector are finite, that is neither infinite nor NaN.
+	pub fn is_finite(&self) -> bool {
+		self.0.is_finite() && self.1.is_finite() && self.2.is_finite()
+	}
+	/// Checks if any value of this vector is NaN.
+	pub fn is_nan(&self) -> bool {
+		self.0.is_nan() || self.1.is_nan() || self.2.is_nan()
+	}
+	/// Checks if any value of this vector is NaN, same as [`Vec3::is_nan`].
+	pub fn has_nans(&self) -> bool {
+		self.is_nan()
+	}
+	/// Replaces the values of this vector that are NaN by 0, and the infinite values by
+	/// [`f64::MAX`] (keeping their sign).
+	pub fn sanitize(self) -> Self {
+		let sanitize = |x: f64| {
+			if x.is_nan() {
+				0.0
+			} else if x.is_infinite() {
+				f64::MAX.copysign(x)
+			} else {
+				x
+			}
+		};
+		Vec3(sanitize(self.0), sanitize(self.1), sanitize(self.2))
+	}
 }
 
 #[cfg(test)]
 mod tests {
+	use std::f64::consts::PI;
+
+	use serde_test::{Token, assert_tokens};
+
 	use super::Vec3;
 
 	/// Checks whether two `f64` values are approximately equal within [`super::NEAR_ZERO_EPSILON`].
@@ -316,6 +568,272 @@ mod tests {
 		f64::abs(a - b) < super::NEAR_ZERO_EPSILON
 	}
 
+	/// Checks whether two vectors are approximately equal within [`super::NEAR_ZERO_EPSILON`].
+	fn vec_approx_eq(a: Vec3, b: Vec3) -> bool {
+		(a - b).is_near_zero()
+	}
+
+	#[test]
+	fn array_and_tuple_conversions_round_trip() {
+		let vector = Vec3::new(1, 2, 3);
+		assert_eq!(vector.to_array(), [1.0, 2.0, 3.0], "array should match");
+		assert_eq!(
+			Vec3::from_array(vector.to_array()),
+			vector,
+			"vector should survive the round trip through an array"
+		);
+		assert_eq!(
+			Vec3::from(<(f64, f64, f64)>::from(vector)),
+			vector,
+			"vector should survive the round trip through a tuple"
+		);
+		assert_eq!(
+			Vec3::from([1.0f32, 2.5, -3.0]),
+			Vec3::new(1, 2.5, -3),
+			"f32 components should be converted"
+		);
+	}
+
+	#[test]
+	fn rotating_around_y_axis_by_quarter_turn() {
+		// Rotating the x-axis around the y-axis by 90 degrees turns it into the negative z-axis:
+		let expected = Vec3::new(0, 0, -1);
+		let rotated = Vec3::new(1, 0, 0).rotate_around(Vec3::new(0, 1, 0), PI / 2.0);
+		assert!(
+			vec_approx_eq(rotated, expected),
+			"rotated vector should be {}, but was {}",
+			expected,
+			rotated
+		);
+
+		// The rotation matrix should do the same:
+		let matrix = Vec3::rotation_matrix(Vec3::new(0, 1, 0), PI / 2.0);
+		let applied = Vec3::new(1, 0, 0).apply_matrix(matrix);
+		assert!(
+			vec_approx_eq(applied, expected),
+			"vector rotated by matrix should be {}, but was {}",
+			expected,
+			applied
+		);
+	}
+
+	#[test]
+	fn rotating_by_full_turn_returns_original() {
+		let vector = Vec3::new(0.3, -1.2, 2.5);
+		let rotated = vector.rotate_around(Vec3::new(1, 2, 3), 2.0 * PI);
+		assert!(
+			vec_approx_eq(rotated, vector),
+			"vector should be unchanged, but was {}",
+			rotated
+		);
+	}
+
+	#[test]
+	fn rotation_preserves_length() {
+		// This is a unit normal, rotated around various axes by various angles:
+		let normal = Vec3::new(1, 1, -1).unit();
+		let axes = [Vec3::new(1, 0, 0), Vec3::new(0, 3, 4), Vec3::new(-2, 1, 5)];
+		for axis in axes {
+			for angle in [0.1, 1.0, 2.5, -4.0] {
+				let rotated = normal.rotate_around(axis, angle);
+				let by_matrix = normal.apply_matrix(Vec3::rotation_matrix(axis, angle));
+				assert!(
+					f64_approx_eq(rotated.norm(), 1.0),
+					"rotated normal should have length 1, but had {}",
+					rotated.norm()
+				);
+				assert!(
+					vec_approx_eq(rotated, by_matrix),
+					"matrix should rotate like rotate_around, but gave {} instead of {}",
+					by_matrix,
+					rotated
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn spherical_coordinates_round_trip() {
+		// These vectors point in various directions, in all octants:
+		let vectors = [
+			Vec3::new(1, 0, 0),
+			Vec3::new(0, 2, 0),
+			Vec3::new(-1, -2, 3),
+			Vec3::new(0.5, 1.5, -2.5),
+			Vec3::new(-3, 0.1, -0.2),
+		];
+		for vec in vectors {
+			let (r, theta, phi) = vec.to_spherical();
+			assert!(
+				(0.0..=PI).contains(&theta) && (0.0..2.0 * PI).contains(&phi),
+				"angles of {} should be in range, but were {} and {}",
+				vec,
+				theta,
+				phi
+			);
+			let actual = Vec3::from_spherical(r, theta, phi);
+			assert!(
+				(actual - vec).is_near_zero(),
+				"vector {} should round-trip, but was {}",
+				vec,
+				actual
+			);
+		}
+	}
+
+	#[test]
+	fn vec3_serde_round_trip() {
+		let vec = Vec3::new(1.5, -2.0, 3.25);
+		assert_tokens(
+			&vec,
+			&[
+				Token::TupleStruct {
+					name: "Vec3",
+					len: 3,
+				},
+				Token::F64(1.5),
+				Token::F64(-2.0),
+				Token::F64(3.25),
+				Token::TupleStructEnd,
+			],
+		);
+
+		// In JSON, a vector is an array of its coordinates:
+		let json = serde_json::to_string(&vec).unwrap();
+		assert_eq!(
+			json, "[1.5,-2.0,3.25]",
+			"vector should be serialized as array"
+		);
+		let parsed = serde_json::from_str::<Vec3>(&json).unwrap();
+		assert_eq!(parsed, vec, "vector should be deserialized unchanged");
+	}
+
+	#[test]
+	fn displayed_vec3_can_be_parsed() {
+		let vec = Vec3::new(1.5, -2.0, 3.25);
+		assert_eq!(
+			vec.to_string(),
+			"[1.5 -2 3.25]",
+			"vector should be displayed with brackets"
+		);
+		assert_eq!(
+			vec.to_string().parse::<Vec3>(),
+			Ok(vec),
+			"displayed vector should be parsed unchanged"
+		);
+	}
+
+	#[test]
+	fn vec3_parses_from_separated_forms() {
+		// These inputs all describe the same vector:
+		let inputs = [
+			"[1 2 3]",
+			"1  2\t3",
+			"1,2,3",
+			"[1, 2, 3]",
+			"(1,2,3)",
+			"1; 2; 3",
+		];
+		for input in inputs {
+			assert_eq!(
+				input.parse::<Vec3>(),
+				Ok(Vec3::new(1, 2, 3)),
+				"input {} should be parsed",
+				input
+			);
+		}
+	}
+
+	#[test]
+	fn if_coordinate_empty_then_vec3_parsing_fails() {
+		// These inputs have a separator without a coordinate:
+		let inputs = ["1,,2,3", "1;2;;3", "[1, 2, 3,]", ", 1, 2, 3", ""];
+		for input in inputs {
+			assert!(
+				input.parse::<Vec3>().is_err(),
+				"input {} should not be parsed",
+				input
+			);
+		}
+	}
+
+	#[test]
+	fn vec3_parses_from_direction_names() {
+		assert_eq!(
+			"up".parse::<Vec3>(),
+			Ok(Vec3::new(0, 1, 0)),
+			"up should be the y-axis"
+		);
+		assert_eq!(
+			"Forward".parse::<Vec3>(),
+			Ok(Vec3::new(0, 0, -1)),
+			"forward should be the negative z-axis"
+		);
+		assert!(
+			"sideways".parse::<Vec3>().is_err(),
+			"unknown name should not be parsed"
+		);
+	}
+
+	#[test]
+	fn slerp_halfway_between_axes_is_unit_and_at_45_degrees() {
+		let (x, y) = (Vec3::new(1, 0, 0), Vec3::new(0, 1, 0));
+		let mid = x.slerp(y, 0.5);
+		assert!(
+			f64_approx_eq(mid.norm(), 1.0),
+			"result should have length 1, but had {}",
+			mid.norm()
+		);
+		for axis in [x, y] {
+			let angle = mid.dot(axis).acos().to_degrees();
+			assert!(
+				f64_approx_eq(angle, 45.0),
+				"result should be at 45° from {}, but was at {}°",
+				axis,
+				angle
+			);
+		}
+	}
+
+	#[test]
+	fn slerp_at_bounds_returns_inputs() {
+		let (a, b) = (Vec3::new(1, 0, 0), Vec3::new(0, 0.6, 0.8));
+		assert!(
+			vec_approx_eq(a.slerp(b, 0.0), a),
+			"t = 0 should return self"
+		);
+		assert!(
+			vec_approx_eq(a.slerp(b, 1.0), b),
+			"t = 1 should return other"
+		);
+	}
+
+	#[test]
+	fn if_vectors_opposite_then_slerp_stays_on_unit_sphere() {
+		// These vectors point in opposite directions:
+		let (a, b) = (Vec3::new(0, 0, 1), Vec3::new(0, 0, -1));
+		let mid = a.slerp(b, 0.5);
+		assert!(
+			f64_approx_eq(mid.norm(), 1.0) && f64_approx_eq(mid.dot(a), 0.0),
+			"result should be a unit vector perpendicular to both, but was {}",
+			mid
+		);
+		assert!(
+			vec_approx_eq(a.slerp(b, 1.0), b),
+			"t = 1 should return other"
+		);
+	}
+
+	#[test]
+	fn nlerp_returns_unit_vector() {
+		let mid = Vec3::new(1, 0, 0).nlerp(Vec3::new(0, 1, 0), 0.3);
+		assert!(
+			f64_approx_eq(mid.norm(), 1.0),
+			"result should have length 1, but had {}",
+			mid.norm()
+		);
+	}
+
 	#[test]
 	fn random_unit_has_length_one() {
 		let vec = Vec3::random_unit();
@@ -338,6 +856,47 @@ mod tests {
 		)
 	}
 
+	#[test]
+	fn random_in_unit_sphere_has_length_less_than_one() {
+		for _ in 0..100 {
+			let vec = Vec3::random_in_unit_sphere();
+			let length = vec.norm();
+			assert!(
+				length < 1.0,
+				"length of random vector in unit sphere should be < 1.0, but was {}",
+				length
+			)
+		}
+	}
+
+	#[test]
+	fn random_in_unit_hemisphere_lies_on_side_of_normal() {
+		let normal = Vec3::new(1, -2, 0.5).unit();
+		for _ in 0..100 {
+			let vec = Vec3::random_in_unit_hemisphere(normal);
+			let dot = vec.dot(normal);
+			assert!(
+				dot >= 0.0 && vec.norm() < 1.0,
+				"random vector should lie in the hemisphere, but had dot product {} and length {}",
+				dot,
+				vec.norm()
+			)
+		}
+	}
+
+	#[test]
+	fn oriented_cosine_direction_lies_around_normal() {
+		let normal = Vec3::new(0.3, -1, 0.2).unit();
+		for _ in 0..100 {
+			let vec = Vec3::random_cosine_direction().orient(normal);
+			assert!(
+				f64_approx_eq(1.0, vec.norm()) && vec.dot(normal) >= 0.0,
+				"direction should be a unit vector around the normal, but was {}",
+				vec
+			)
+		}
+	}
+
 	#[test]
 	fn norm_is_correct_length() {
 		let vec = Vec3::new(2, 10, 11);
@@ -459,4 +1018,79 @@ mod tests {
 			length_of_unit
 		)
 	}
+
+	#[test]
+	fn nan_and_infinite_values_are_detected() {
+		let nan = Vec3::new(f64::NAN, 0, 0);
+		assert!(nan.is_nan(), "{} should contain NaN", nan);
+		assert!(nan.has_nans(), "{} should contain NaN", nan);
+		assert!(!nan.is_finite(), "{} shouldn't be finite", nan);
+
+		let infinite = Vec3::new(1, f64::INFINITY, 3);
+		assert!(!infinite.is_nan(), "{} shouldn't contain NaN", infinite);
+		assert!(!infinite.is_finite(), "{} shouldn't be finite", infinite);
+
+		let finite = Vec3::new(1, 2, 3);
+		assert!(finite.is_finite(), "{} should be finite", finite);
+	}
+
+	#[test]
+	fn sanitize_replaces_nan_and_infinite_values() {
+		let vec = Vec3::new(f64::NAN, f64::NEG_INFINITY, 2.5).sanitize();
+		assert_eq!(
+			vec,
+			Vec3::new(0, -f64::MAX, 2.5),
+			"NaN should be 0, and infinity the largest value"
+		);
+	}
+
+	#[test]
+	fn arithmetic_matches_coordinate_wise_calculation() {
+		// These vectors have arbitrary, non-trivial coordinates:
+		let (a, b) = (Vec3::new(-1.5, 2.4, 3.8), Vec3::new(0.7, -0.5, 5.5));
+
+		// Each operation should match its definition, regardless of whether SIMD is used:
+		let cases = [
+			("add", a + b, Vec3(a.0 + b.0, a.1 + b.1, a.2 + b.2)),
+			("sub", a - b, Vec3(a.0 - b.0, a.1 - b.1, a.2 - b.2)),
+			("mul", a * b, Vec3(a.0 * b.0, a.1 * b.1, a.2 * b.2)),
+			(
+				"scale",
+				a.scale(-2.5),
+				Vec3(-2.5 * a.0, -2.5 * a.1, -2.5 * a.2),
+			),
+			(
+				"cross",
+				a.cross(b),
+				Vec3(
+					a.1 * b.2 - a.2 * b.1,
+					a.2 * b.0 - a.0 * b.2,
+					a.0 * b.1 - a.1 * b.0,
+				),
+			),
+		];
+		for (name, actual, expected) in cases {
+			assert!(
+				(actual - expected).is_near_zero(),
+				"{} should be {}, but was {}",
+				name,
+				expected,
+				actual
+			);
+		}
+		let dot = a.0 * b.0 + a.1 * b.1 + a.2 * b.2;
+		assert!(
+			f64_approx_eq(dot, a.dot(b)),
+			"dot should be {}, but was {}",
+			dot,
+			a.dot(b)
+		);
+		let norm_sq = a.0 * a.0 + a.1 * a.1 + a.2 * a.2;
+		assert!(
+			f64_approx_eq(norm_sq, a.norm_sq()),
+			"norm_sq should be {}, but was {}",
+			norm_sq,
+			a.norm_sq()
+		);
+	}
 }
@@ -0,0 +1,26 @@
+//! Arithmetic on [`Vec3`], computed one coordinate at a time.
+
+use super::Vec3;
+
+pub(super) fn add(a: Vec3, b: Vec3) -> Vec3 {
+	Vec3(a.0 + b.0, a.1 + b.1, a.2 + b.2)
+}
+pub(super) fn sub(a: Vec3, b: Vec3) -> Vec3 {
+	Vec3(a.0 - b.0, a.1 - b.1, a.2 - b.2)
+}
+pub(super) fn mul(a: Vec3, b: Vec3) -> Vec3 {
+	Vec3(a.0 * b.0, a.1 * b.1, a.2 * b.2)
+}
+pub(super) fn scale(a: Vec3, f: f64) -> Vec3 {
+	Vec3(f * a.0, f * a.1, f * a.2)
+}
+pub(super) fn dot(a: Vec3, b: Vec3) -> f64 {
+	a.0 * b.0 + a.1 * b.1 + a.2 * b.2
+}
+pub(super) fn cross(a: Vec3, b: Vec3) -> Vec3 {
+	Vec3(
+		a.1 * b.2 - a.2 * b.1,
+		a.2 * b.0 - a.0 * b.2,
+		a.0 * b.1 - a.1 * b.0,
+	)
+}
@@ -0,0 +1,43 @@
+//! Arithmetic on [`Vec3`], computed with SIMD instructions.
+//!
+//! The coordinates are loaded into four `f64` lanes, with the fourth lane set to zero.
+
+use std::simd::num::SimdFloat;
+use std::simd::{f64x4, simd_swizzle};
+
+use super::Vec3;
+
+/// Loads the vector into SIMD lanes.
+fn load(v: Vec3) -> f64x4 {
+	f64x4::from_array([v.0, v.1, v.2, 0.0])
+}
+/// Stores the first three SIMD lanes into a vector.
+fn store(lanes: f64x4) -> Vec3 {
+	let [x, y, z, _] = lanes.to_array();
+	Vec3(x, y, z)
+}
+
+pub(super) fn add(a: Vec3, b: Vec3) -> Vec3 {
+	store(load(a) + load(b))
+}
+pub(super) fn sub(a: Vec3, b: Vec3) -> Vec3 {
+	store(load(a) - load(b))
+}
+pub(super) fn mul(a: Vec3, b: Vec3) -> Vec3 {
+	store(load(a) * load(b))
+}
+pub(super) fn scale(a: Vec3, f: f64) -> Vec3 {
+	store(load(a) * f64x4::splat(f))
+}
+pub(super) fn dot(a: Vec3, b: Vec3) -> f64 {
+	(load(a) * load(b)).reduce_sum()
+}
+pub(super) fn cross(a: Vec3, b: Vec3) -> Vec3 {
+	let (a, b) = (load(a), load(b));
+	// (a.y * b.z - a.z * b.y, a.z * b.x - a.x * b.z, a.x * b.y - a.y * b.x)
+	let a_yzx = simd_swizzle!(a, [1, 2, 0, 3]);
+	let a_zxy = simd_swizzle!(a, [2, 0, 1, 3]);
+	let b_yzx = simd_swizzle!(b, [1, 2, 0, 3]);
+	let b_zxy = simd_swizzle!(b, [2, 0, 1, 3]);
+	store(a_yzx * b_zxy - a_zxy * b_yzx)
+}
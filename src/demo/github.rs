@@ -3,15 +3,15 @@ use raytracer::objects::{Material, Sphere};
 use raytracer::scene::Scene;
 use raytracer::types::{Color, Point};
 
-use super::Demo;
+use super::SceneDescription;
 
 // Banner in the Github repository
 
-pub fn build() -> Demo {
-	Demo {
-		scene: scene(),
-		setup: setup(),
-	}
+pub const NAME: &str = "github";
+pub const DESCRIPTION: &str = "The picture shown in the Github repository";
+
+pub fn build() -> SceneDescription {
+	SceneDescription::new(scene(), setup()).named(NAME, DESCRIPTION)
 }
 
 fn scene() -> Scene {
@@ -1,14 +1,25 @@
 #![allow(dead_code)]
-#![deprecated = "use json input instead"]
 
 mod github;
 mod spheres;
 mod spheromania;
+mod terrain;
+
+use std::convert::Infallible;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
 
 use raytracer::camera::CameraSetup;
+use raytracer::input::RaytracerInput;
 use raytracer::scene::Scene;
 
-#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+/// The name of demos loaded from a file.
+const CUSTOM_NAME: &str = "custom";
+/// The description of demos loaded from a file.
+const CUSTOM_DESCRIPTION: &str = "A scene loaded from a JSON input file";
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum AvailableDemo {
 	/// A hollow glass sphere, a matte sphere, and a metal sphere next to each other,
 	/// with a matte bottom sphere below.
@@ -17,27 +28,381 @@ pub enum AvailableDemo {
 	Spheromania,
 	/// The picture shown in the Github repository.
 	Github,
+	/// A flyover of procedurally generated hills with a few spheres.
+	Terrain,
+	/// A scene loaded from the JSON input file at the path.
+	Custom(PathBuf),
 }
 impl AvailableDemo {
-	pub fn build(&self) -> Demo {
+	/// All demos that are built into the program.
+	pub const BUILT_IN: [AvailableDemo; 4] = [
+		AvailableDemo::Spheres,
+		AvailableDemo::Spheromania,
+		AvailableDemo::Github,
+		AvailableDemo::Terrain,
+	];
+
+	/// Returns the built-in demo named `s` (ignoring case), or a custom demo loaded from the
+	/// file at path `s` if there is no such demo.
+	pub fn from_str_or_path(s: &str) -> AvailableDemo {
+		Self::BUILT_IN
+			.into_iter()
+			.find(|demo| demo.name().eq_ignore_ascii_case(s))
+			.unwrap_or_else(|| AvailableDemo::Custom(PathBuf::from(s)))
+	}
+	/// Returns the names of all built-in demos, along with their descriptions.
+	pub fn list() -> Vec<(&'static str, &'static str)> {
+		Self::BUILT_IN
+			.iter()
+			.map(|demo| (demo.name(), demo.description()))
+			.collect()
+	}
+	/// Writes the names and descriptions of all built-in demos to the `writer`, one per line.
+	pub fn print_list<W: Write>(writer: &mut W) -> io::Result<()> {
+		let list = Self::list();
+		let max_len = list.iter().map(|(name, _)| name.len()).max();
+		for (name, desc) in list {
+			writeln!(
+				writer,
+				"{:width$}  {}",
+				name,
+				desc,
+				width = max_len.unwrap_or(0)
+			)?;
+		}
+		Ok(())
+	}
+	/// Returns the help text of the CLI option that selects a demo.
+	pub fn help() -> String {
+		let names = Self::list()
+			.iter()
+			.map(|(name, _)| *name)
+			.collect::<Vec<_>>();
+		format!(
+			"Render a built-in demo scene, or a demo JSON file, instead of an input file [possible values: {}]",
+			names.join(", ")
+		)
+	}
+	/// Returns the detailed help text of the CLI option that selects a demo,
+	/// which also describes every built-in demo.
+	pub fn long_help() -> String {
+		let mut help = String::from(
+			"Render a built-in demo scene, or a demo JSON file, instead of an input file\n\nPossible values:",
+		);
+		for (name, desc) in Self::list() {
+			help.push_str(&format!("\n- {}: {}", name, desc));
+		}
+		help.push_str("\n- <PATH>: a path to a JSON file in the input format");
+		help
+	}
+	/// Returns the metadata of this demo.
+	///
+	/// Built-in demos are built to determine their object count. If a custom demo can't be loaded,
+	/// the default camera setup and an object count of 0 are returned.
+	pub fn info(&self) -> DemoInfo {
+		match self.build() {
+			Ok(demo) => demo.describe(),
+			Err(_) => DemoInfo {
+				name: self.name(),
+				description: self.description(),
+				suggested_setup: CameraSetup::default(),
+				object_count: 0,
+			},
+		}
+	}
+	/// Builds the scene and camera setup of this demo.
+	///
+	/// Returns an error if this is a custom demo, and its file can't be read or parsed.
+	pub fn build(&self) -> Result<SceneDescription, String> {
 		match self {
-			AvailableDemo::Spheres => spheres::build(),
-			AvailableDemo::Spheromania => spheromania::build(),
-			AvailableDemo::Github => github::build(),
+			AvailableDemo::Spheres => Ok(spheres::build()),
+			AvailableDemo::Spheromania => Ok(spheromania::build()),
+			AvailableDemo::Github => Ok(github::build()),
+			AvailableDemo::Terrain => Ok(terrain::build()),
+			AvailableDemo::Custom(path) => {
+				let input = RaytracerInput::try_from_file_with_includes(path)
+					.map_err(|e| format!("{}: {}", path.display(), e))?;
+				Ok(SceneDescription::from_input(input))
+			}
 		}
 	}
+	/// The name of this demo, as used in the CLI.
+	fn name(&self) -> &'static str {
+		match self {
+			AvailableDemo::Spheres => spheres::NAME,
+			AvailableDemo::Spheromania => spheromania::NAME,
+			AvailableDemo::Github => github::NAME,
+			AvailableDemo::Terrain => terrain::NAME,
+			AvailableDemo::Custom(_) => CUSTOM_NAME,
+		}
+	}
+	/// A short description of this demo.
+	fn description(&self) -> &'static str {
+		match self {
+			AvailableDemo::Spheres => spheres::DESCRIPTION,
+			AvailableDemo::Spheromania => spheromania::DESCRIPTION,
+			AvailableDemo::Github => github::DESCRIPTION,
+			AvailableDemo::Terrain => terrain::DESCRIPTION,
+			AvailableDemo::Custom(_) => CUSTOM_DESCRIPTION,
+		}
+	}
+}
+impl FromStr for AvailableDemo {
+	type Err = Infallible;
+	/// Parses the name of a built-in demo (ignoring case).
+	/// Any other value is treated as the path to a custom demo.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Ok(Self::from_str_or_path(s))
+	}
 }
 
-pub struct Demo {
+/// Metadata describing a demo.
+#[derive(Debug, Clone, Copy)]
+pub struct DemoInfo {
+	/// The name of the demo, as used in the CLI.
+	pub name: &'static str,
+	/// A short description of the demo.
+	pub description: &'static str,
+	/// The camera setup the demo is intended to be rendered with.
+	pub suggested_setup: CameraSetup,
+	/// The amount of objects in the demo's scene.
+	pub object_count: usize,
+}
+
+/// A scene along with the camera setup and rendering parameters it is intended to be rendered with.
+pub struct SceneDescription {
+	name: &'static str,
+	description: &'static str,
 	scene: Scene,
 	setup: CameraSetup,
+	samples: Option<u32>,
+	bounces: Option<u32>,
 }
 
-impl Demo {
+impl SceneDescription {
+	/// Creates a new description of the `scene`, to be rendered with the camera `setup`.
+	pub fn new(scene: Scene, setup: CameraSetup) -> Self {
+		Self {
+			name: CUSTOM_NAME,
+			description: CUSTOM_DESCRIPTION,
+			scene,
+			setup,
+			samples: None,
+			bounces: None,
+		}
+	}
+	/// Sets the amount of samples per pixel and bounces per ray the scene should be rendered with.
+	pub fn with_render_params(self, samples: u32, bounces: u32) -> Self {
+		Self {
+			samples: Some(samples),
+			bounces: Some(bounces),
+			..self
+		}
+	}
+	/// Sets the name and description, as shown in the CLI.
+	fn named(self, name: &'static str, description: &'static str) -> Self {
+		Self {
+			name,
+			description,
+			..self
+		}
+	}
+	/// Creates a custom demo from the camera and scene of an input file.
+	fn from_input(input: RaytracerInput) -> Self {
+		let camera = input.camera;
+		let setup = CameraSetup {
+			v_fov: camera.fov,
+			lookfrom: camera.source,
+			lookat: camera.target,
+			view_up: camera.view_up.unwrap_or(CameraSetup::default().view_up),
+			defocus_angle: camera.aperture,
+			focus_distance: camera.focus_distance,
+			..Default::default()
+		};
+		Self {
+			samples: camera.samples,
+			bounces: camera.bounces,
+			..Self::new(Scene::from_objs(input.scene), setup)
+		}
+	}
 	pub fn scene(&self) -> &Scene {
 		&self.scene
 	}
 	pub fn setup(&self) -> CameraSetup {
 		self.setup
 	}
+	/// Returns the amount of samples per pixel and bounces per ray, if they have been specified.
+	pub fn render_params(&self) -> (Option<u32>, Option<u32>) {
+		(self.samples, self.bounces)
+	}
+	/// Calculates a camera setup that shows the whole scene, based on its bounding box,
+	/// see [`CameraSetup::auto_place`].
+	///
+	/// The image size is taken from this description's setup, which is returned unchanged if the
+	/// scene has no bounding box. Objects without a bounding box (see [`Scene::bounding_box`])
+	/// aren't framed.
+	pub fn recommended_setup(&self) -> CameraSetup {
+		if self.scene.bounding_box().is_none() {
+			return self.setup;
+		}
+		CameraSetup {
+			width: self.setup.width,
+			height: self.setup.height,
+			..CameraSetup::auto_place(&self.scene)
+		}
+	}
+	/// Returns the metadata of this demo.
+	pub fn describe(&self) -> DemoInfo {
+		DemoInfo {
+			name: self.name,
+			description: self.description,
+			suggested_setup: self.setup,
+			object_count: self.scene.len(),
+		}
+	}
+	/// Splits the demo into its camera setup and scene.
+	pub fn into_parts(self) -> (CameraSetup, Scene) {
+		(self.setup, self.scene)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::path::PathBuf;
+
+	use raytracer::camera::CameraSetup;
+	use raytracer::objects::{Material, Sphere};
+	use raytracer::scene::Scene;
+	use raytracer::types::{Color, Point};
+
+	use super::{AvailableDemo, SceneDescription};
+
+	#[test]
+	fn list_contains_every_demo() {
+		// Every listed name should select the corresponding built-in demo in the CLI:
+		let list = AvailableDemo::list();
+		assert_eq!(
+			list.len(),
+			AvailableDemo::BUILT_IN.len(),
+			"every built-in demo should be listed"
+		);
+		for (demo, (name, _)) in AvailableDemo::BUILT_IN.iter().zip(list) {
+			assert_eq!(
+				&name.parse::<AvailableDemo>().unwrap(),
+				demo,
+				"name {} should select its demo",
+				name
+			);
+		}
+	}
+
+	#[test]
+	fn if_not_a_demo_name_then_parsed_as_custom() {
+		let demo = "scenes/my-scene.json".parse::<AvailableDemo>().unwrap();
+		assert_eq!(
+			demo,
+			AvailableDemo::Custom(PathBuf::from("scenes/my-scene.json")),
+			"unknown value should be treated as a path"
+		);
+		assert_eq!(
+			AvailableDemo::from_str_or_path("SPHERES"),
+			AvailableDemo::Spheres,
+			"name should select the built-in demo regardless of case"
+		);
+	}
+
+	#[test]
+	fn info_of_every_demo_has_name_and_description() {
+		// This custom demo doesn't exist, but should still have metadata:
+		let custom = AvailableDemo::Custom(PathBuf::from("does-not-exist.json"));
+		for demo in AvailableDemo::BUILT_IN.into_iter().chain([custom]) {
+			let info = demo.info();
+			assert!(!info.name.is_empty(), "{:?} should have a name", demo);
+			assert!(
+				!info.description.is_empty(),
+				"{:?} should have a description",
+				demo
+			);
+		}
+	}
+
+	#[test]
+	fn info_of_built_in_demo_matches_build() {
+		let demo = AvailableDemo::Github.build().unwrap();
+		let info = AvailableDemo::Github.info();
+		assert_eq!(info.name, "github", "name should match the CLI value");
+		assert_eq!(
+			info.object_count,
+			demo.scene().len(),
+			"object count should match the scene"
+		);
+	}
+
+	#[test]
+	fn custom_demo_is_loaded_from_file() {
+		let path = std::env::temp_dir().join(format!("raytracer-demo-{}.json", std::process::id()));
+		let json = r#"{
+			"camera": { "fov": 30, "source": [0, 1, 2], "target": [0, 0, 0], "aperture": 0, "focusDistance": 2 },
+			"scene": [{ "type": "sphere", "center": [0, 0, 0], "radius": 1, "material": { "type": "matte", "color": [1, 0, 0] } }]
+		}"#;
+		std::fs::write(&path, json).unwrap();
+
+		let result = AvailableDemo::Custom(path.clone()).build();
+		std::fs::remove_file(&path).ok();
+
+		assert!(result.is_ok(), "custom demo should load, but didn't");
+		let info = result.unwrap().describe();
+		assert_eq!(info.object_count, 1, "scene should contain the sphere");
+		assert_eq!(
+			info.suggested_setup.v_fov, 30.0,
+			"camera should be taken from the file"
+		);
+	}
+
+	#[test]
+	fn recommended_setup_places_camera_outside_scene() {
+		// These spheres surround the origin, where the default camera is placed:
+		let material = Material::Matte {
+			color: Color::new(0.5, 0.5, 0.5),
+		};
+		let scene = Scene::from([
+			Sphere::new(Point::new(-1, 0, 0), 0.5, material.clone()),
+			Sphere::new(Point::new(1, 0.5, -1), 1, material),
+		]);
+		let description = SceneDescription::new(scene, CameraSetup::default());
+
+		let setup = description.recommended_setup();
+		let aabb = description.scene().bounding_box().unwrap();
+		let Point(x, y, z) = setup.lookfrom;
+		let inside = aabb.x.surrounds(x) && aabb.y.surrounds(y) && aabb.z.surrounds(z);
+		assert!(
+			!inside,
+			"camera at {} should lie outside the bounding box {:?}",
+			setup.lookfrom, aabb
+		);
+		let Point(x, y, z) = setup.lookat;
+		let inside = aabb.x.surrounds(x) && aabb.y.surrounds(y) && aabb.z.surrounds(z);
+		assert!(
+			inside,
+			"camera should look into the scene, but looks at {}",
+			setup.lookat
+		);
+	}
+
+	#[test]
+	fn printed_list_contains_every_name() {
+		let mut buf: Vec<u8> = Vec::new();
+		let result = AvailableDemo::print_list(&mut buf);
+		assert!(result.is_ok(), "printing should succeed, but didn't");
+
+		let output = String::from_utf8(buf).unwrap();
+		for (name, _) in AvailableDemo::list() {
+			assert!(
+				output.contains(name),
+				"output should contain {}, but was:\n{}",
+				name,
+				output
+			);
+		}
+	}
 }
@@ -4,15 +4,16 @@ use raytracer::scene::Scene;
 
 use raytracer::types::{Color, Point};
 
-use super::Demo;
+use super::SceneDescription;
 
 // Built upon scene during the first book
 
-pub fn build() -> Demo {
-	Demo {
-		scene: scene(),
-		setup: setup(),
-	}
+pub const NAME: &str = "spheres";
+pub const DESCRIPTION: &str =
+	"A hollow glass sphere, a matte sphere, and a metal sphere next to each other";
+
+pub fn build() -> SceneDescription {
+	SceneDescription::new(scene(), setup()).named(NAME, DESCRIPTION)
 }
 
 fn scene() -> Scene {
@@ -30,15 +31,11 @@ fn scene() -> Scene {
 			color: Color::new(0, 0.2, 0.1),
 		},
 	);
-	let sphere_left = Sphere::new(
+	let (sphere_left, sphere_left_air) = Sphere::shell(
 		Point::new(-1, 0, -1),
 		0.5,
-		Material::Dielectric { ridx: 1.5 },
-	);
-	let sphere_left_air = Sphere::new(
-		Point::new(-1, 0, -1),
 		0.4,
-		Material::Dielectric { ridx: 1.0 / 1.5 },
+		Material::Dielectric { ridx: 1.5 },
 	);
 	let sphere_right = Sphere::new(
 		Point::new(1, 0, -1),
@@ -4,15 +4,15 @@ use raytracer::objects::{Material, Sphere};
 use raytracer::scene::Scene;
 use raytracer::types::{Color, Point, ToVec3, Vec3};
 
-use super::Demo;
+use super::SceneDescription;
 
 // Final scene of the first book
 
-pub fn build() -> Demo {
-	Demo {
-		scene: scene(),
-		setup: setup(),
-	}
+pub const NAME: &str = "spheromania";
+pub const DESCRIPTION: &str = "Three big spheres of different materials among many smaller spheres";
+
+pub fn build() -> SceneDescription {
+	SceneDescription::new(scene(), setup()).named(NAME, DESCRIPTION)
 }
 
 fn random(a: f64, b: f64) -> f64 {
@@ -0,0 +1,56 @@
+use raytracer::camera::CameraSetup;
+use raytracer::objects::{Ground, Material, Sphere};
+use raytracer::scene::Scene;
+use raytracer::types::{Color, Point};
+
+use super::SceneDescription;
+
+// Procedural terrain, seen from above
+
+pub const NAME: &str = "terrain";
+pub const DESCRIPTION: &str = "A flyover of procedurally generated hills with a few spheres";
+
+pub fn build() -> SceneDescription {
+	SceneDescription::new(scene(), setup()).named(NAME, DESCRIPTION)
+}
+
+fn scene() -> Scene {
+	let ground = Ground::new(
+		Point::new(0, -1, 0),
+		60.0,
+		3.0,
+		0.08,
+		Material::Matte {
+			color: Color(0.32, 0.45, 0.21),
+		},
+	);
+
+	// The spheres rest on the hills
+	let on_ground = |x: f64, z: f64| Point::new(x, ground.height_at(x, z) + 1.0, z);
+	let sphere_glass = Sphere::new(on_ground(0.0, 1.0), 1.0, Material::Dielectric { ridx: 1.5 });
+	let sphere_metal = Sphere::new(
+		on_ground(-3.2, -2.5),
+		1.0,
+		Material::Metal {
+			color: Color(0.8, 0.6, 0.3),
+			fuzz: 0.05,
+		},
+	);
+	let sphere_matte = Sphere::new(
+		on_ground(3.4, -1.5),
+		1.0,
+		Material::Matte {
+			color: Color(0.6, 0.2, 0.2),
+		},
+	);
+	Scene::from([ground]).append([sphere_glass, sphere_metal, sphere_matte])
+}
+
+fn setup() -> CameraSetup {
+	CameraSetup {
+		v_fov: 40.0,
+		lookat: Point::new(0, 0, -2),
+		lookfrom: Point::new(0, 6, 16),
+		..Default::default()
+	}
+}
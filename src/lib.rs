@@ -1,9 +1,18 @@
+#![cfg_attr(feature = "simd", feature(portable_simd))]
+
 mod core;
 
 // Public API
 
 pub mod camera {
-	pub use super::core::camera::{Camera, CameraSetup};
+	pub use super::core::camera::{
+		AlbedoAov, Aov, Camera, CameraSetup, DepthAov, JitterPattern, MaterialIdAov, NormalAov,
+		RenderOutput, RenderProgress,
+	};
+}
+
+pub mod error {
+	pub use super::core::error::RaytracerError;
 }
 
 pub mod input {
@@ -11,7 +20,10 @@ pub mod input {
 }
 
 pub mod objects {
-	pub use super::core::objects::{Material, Object, Sphere, ToObject};
+	pub use super::core::objects::{
+		Ground, Group, GroupId, Hit, HitBuilder, Hittable, InfiniteCylinder, Material,
+		MovingSphere, Object, ShellSphere, Sphere, ToObject, TriangleMesh,
+	};
 }
 
 pub mod output {
@@ -19,7 +31,11 @@ pub mod output {
 }
 
 pub mod types {
-	pub use super::core::types::{Color, Point, ToVec3, Vec3};
+	pub use super::core::types::{
+		Aabb, BloomPass, Channel, ChromaticAberrationPass, Color, GammaCorrectionPass, Image,
+		Interval, Point, PostProcess, PostProcessPipeline, Ray, ToVec3, ToneMap, ToneMapPass,
+		Transform, Vec3, VignettePass,
+	};
 }
 
 pub mod scene {
@@ -1,38 +1,227 @@
 mod args;
 mod demo;
+mod watch;
 
-use std::fs::File;
+use std::fs::{self, File};
 use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 
-use args::Args;
+#[cfg(feature = "png-output")]
+use args::ChannelDepth;
+use args::{Args, InputSource, OutputFormat, OutputTarget};
+use demo::{AvailableDemo, SceneDescription};
 use raytracer::camera::{Camera, CameraSetup};
+use raytracer::error::RaytracerError;
 use raytracer::input::RaytracerInput;
 use raytracer::output;
-use raytracer::scene::Scene;
-use raytracer::types::ToVec3;
+use raytracer::scene::{Background, Scene};
+use raytracer::types::{Image, ToVec3};
 
 fn main() {
 	let args = Args::parse();
+	if args.list_demos {
+		AvailableDemo::print_list(&mut io::stdout()).unwrap();
+		return;
+	}
+	if args.watch {
+		run_watch(&args);
+		return;
+	}
+	if let (Some(index), Some(count)) = (args.tile_index, args.tile_count) {
+		if let Err(e) = run_tile(&args, index, count) {
+			eprintln!("error: {}", e);
+			std::process::exit(1);
+		}
+		return;
+	}
+	if args.animate {
+		if let Err(e) = run_animation(&args) {
+			eprintln!("error: {}", e);
+			std::process::exit(1);
+		}
+		return;
+	}
 
-	let json = File::open(&args.input).unwrap();
-	let input = RaytracerInput::try_from(json).unwrap();
+	let (setup, scene, (samples, bounces)) = load(&args).unwrap();
 
 	// Check if we can write at all and hold onto the handle
-	let mut writer: Box<dyn io::Write> = if let Some(ref path) = args.output {
-		let file = File::create(path).unwrap();
-		Box::new(file)
-	} else {
-		Box::new(io::stdout())
+	let mut writer: Box<dyn io::Write> = match args.output {
+		Some(OutputTarget::File(ref path)) => Box::new(File::create(path).unwrap()),
+		Some(OutputTarget::Stdout) | None => Box::new(io::stdout()),
 	};
 
-	let (setup, scene) = prepare(&args, input);
+	let camera = build_camera(&args, setup, &scene, samples, bounces);
+	let image = camera.try_render(&scene).unwrap_or_else(|e| {
+		eprintln!("error: {}", e);
+		std::process::exit(1);
+	});
+	write_image(&args, &image, &mut writer).unwrap();
+}
+
+/// Loads the camera setup, scene, and rendering parameters from the demo or input specified in `args`.
+fn load(args: &Args) -> Result<(CameraSetup, Scene, (u32, u32)), String> {
+	if let Some(demo) = &args.demo {
+		let description = demo.build()?;
+		let params = render_params(args, description.render_params());
+		let (setup, scene) = prepare_demo(args, description);
+		return Ok((setup, scene, params));
+	}
+	let input = read_input(args)?;
+	let params = render_params(args, (input.camera.samples, input.camera.bounces));
+	let (setup, scene) = prepare(args, input);
+	Ok((setup, scene, params))
+}
+
+/// Reads and parses the input file (or stdin) specified in `args`.
+fn read_input(args: &Args) -> Result<RaytracerInput, String> {
+	match args.input_source().expect("input should be present") {
+		InputSource::File(path) => RaytracerInput::try_from_file_with_includes(path)
+			.map_err(|e| format!("{}: {}", path.display(), e)),
+		InputSource::Stdin => RaytracerInput::from_reader(io::stdin().lock()),
+	}
+}
+
+/// Writes the `image` in the output format specified in `args`.
+fn write_image<W: io::Write>(
+	args: &Args,
+	image: &Image,
+	writer: &mut W,
+) -> Result<(), RaytracerError> {
+	match args.format {
+		OutputFormat::Ppm => {
+			let ppm = args.metadata.iter().fold(
+				output::ppm::RawWriter::new(args.gamma),
+				|ppm, (key, value)| ppm.with_comment(key, value),
+			);
+			ppm.write(image, writer)?
+		}
+		OutputFormat::Bmp => output::bmp::write(image, args.gamma, writer)?,
+		#[cfg(feature = "exr-output")]
+		OutputFormat::Exr => {
+			// EXR encoding requires seeking, which stdout doesn't support
+			let mut buf = io::Cursor::new(Vec::new());
+			output::exr::write(image, &mut buf)?;
+			writer.write_all(buf.get_ref())?;
+		}
+		#[cfg(feature = "png-output")]
+		OutputFormat::Png => match args.bits.unwrap_or(ChannelDepth::Eight) {
+			ChannelDepth::Eight => output::png::write(image, args.gamma, writer)?,
+			ChannelDepth::Sixteen => output::png::write_16bit(image, args.gamma, writer)?,
+		},
+		#[cfg(feature = "jpeg-output")]
+		OutputFormat::Jpeg => output::jpeg::write(image, args.gamma, args.quality, writer)?,
+	}
+	Ok(())
+}
+
+/// Renders the input to the output file, and re-renders whenever the input file changes.
+/// Errors (e.g. invalid JSON) are printed, and watching continues.
+fn run_watch(args: &Args) {
+	let (Some(InputSource::File(input)), Some(OutputTarget::File(output))) =
+		(args.input_source(), &args.output)
+	else {
+		eprintln!("error: --watch requires an input file and an output file");
+		return;
+	};
+	let render = || {
+		if let Err(e) = render_to_file(args, output) {
+			eprintln!("error: {}", e);
+		}
+	};
+	render();
+	let debounce = Duration::from_millis(args.debounce_ms);
+	if let Err(e) = watch::watch(input, debounce, render) {
+		eprintln!("error: could not watch {}: {}", input.display(), e);
+	}
+}
+
+/// Renders every frame of the input's animation to a numbered file next to the output file.
+/// For example, with an output file `out.ppm`, frames are written to `out-0000.ppm`, `out-0001.ppm`, etc.
+fn run_animation(args: &Args) -> Result<(), String> {
+	let Some(OutputTarget::File(output)) = &args.output else {
+		return Err(String::from("--animate requires an output file"));
+	};
+	let input = read_input(args)?;
+	let Some(animation) = &input.animation else {
+		return Err(String::from("--animate requires an animation in the input"));
+	};
+	let frames = animation.frame_count();
+	for frame in 0..frames {
+		let frame_input = input.at_time(animation.frame_time(frame));
+		let suggested = (frame_input.camera.samples, frame_input.camera.bounces);
+		let (samples, bounces) = render_params(args, suggested);
+		let (setup, scene) = prepare(args, frame_input);
+		let camera = build_camera(args, setup, &scene, samples, bounces);
+		eprintln!("Frame {}/{}", frame + 1, frames);
+		let image = camera.try_render(&scene).map_err(|e| e.to_string())?;
+		write_to_file(args, &image, &frame_path(output, frame))?;
+	}
+	Ok(())
+}
+
+/// Renders the tile with the specified `index` out of `count` tiles, and writes it to a file
+/// next to the output file. For example, with an output file `out.ppm`, tile 2 is written to `out_2.ppm`.
+fn run_tile(args: &Args, index: usize, count: usize) -> Result<(), String> {
+	let Some(OutputTarget::File(output)) = &args.output else {
+		return Err(String::from("--tile-index requires an output file"));
+	};
+	if index >= count {
+		return Err(format!(
+			"tile index {} should be smaller than the tile count {}",
+			index, count
+		));
+	}
+	let (setup, scene, (samples, bounces)) = load(args)?;
+	let camera = build_camera(args, setup, &scene, samples, bounces);
+	camera
+		.check_memory_limit(&scene)
+		.map_err(|e| e.to_string())?;
+	let (start_row, start_col, height, width) = camera.tile_bounds(index, count);
+	let tile = (camera, start_row, start_col, height, width);
+	let images = Camera::render_region_parallel(&[tile], Arc::new(scene), Arc::new(()));
+	write_to_file(args, &images[0], &tile_path(output, index))
+}
 
-	let camera = Camera::from(setup)
-		.anti_aliasing(args.samples)
-		.bounces(args.bounces);
-	let image = camera.render(&scene);
+/// Returns the path of the tile with the specified index, derived from the output `path`.
+fn tile_path(path: &Path, tile: usize) -> PathBuf {
+	let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+	let file_name = match path.extension() {
+		Some(extension) => format!("{}_{}.{}", stem, tile, extension.to_string_lossy()),
+		None => format!("{}_{}", stem, tile),
+	};
+	path.with_file_name(file_name)
+}
+
+/// Returns the path of the frame with the specified index, derived from the output `path`.
+fn frame_path(path: &Path, frame: usize) -> PathBuf {
+	let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+	let file_name = match path.extension() {
+		Some(extension) => format!("{}-{:04}.{}", stem, frame, extension.to_string_lossy()),
+		None => format!("{}-{:04}", stem, frame),
+	};
+	path.with_file_name(file_name)
+}
 
-	output::ppm::raw(&image, args.gamma, &mut writer).unwrap();
+/// Loads the input, renders it, and writes the image to the file at `path`.
+fn render_to_file(args: &Args, path: &Path) -> Result<(), String> {
+	let (setup, scene, (samples, bounces)) = load(args)?;
+	let camera = build_camera(args, setup, &scene, samples, bounces);
+	let image = camera.try_render(&scene).map_err(|e| e.to_string())?;
+	write_to_file(args, &image, path)
+}
+
+/// Writes the `image` to the file at `path`.
+///
+/// The image is written to a temporary file first, which then replaces the output file,
+/// so that the output file never contains a partially written image.
+fn write_to_file(args: &Args, image: &Image, path: &Path) -> Result<(), String> {
+	let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+	let temp_path = path.with_file_name(format!(".{}.tmp", file_name));
+	let mut file = File::create(&temp_path).map_err(|e| e.to_string())?;
+	write_image(args, image, &mut file).map_err(|e| e.to_string())?;
+	fs::rename(&temp_path, path).map_err(|e| e.to_string())
 }
 
 fn prepare(args: &Args, input: RaytracerInput) -> (CameraSetup, Scene) {
@@ -40,17 +229,384 @@ fn prepare(args: &Args, input: RaytracerInput) -> (CameraSetup, Scene) {
 	let target = args.target.unwrap_or(input.camera.target);
 	let default_focus_distance = (center.to_vec3() - target.to_vec3()).norm();
 
+	let (width, height) = args.image_size();
 	let setup = CameraSetup {
-		width: args.width,
-		height: args.height,
+		width,
+		height,
 		v_fov: args.fov.unwrap_or(input.camera.fov),
 		lookfrom: center,
 		lookat: target,
+		view_up: input
+			.camera
+			.view_up
+			.unwrap_or(CameraSetup::default().view_up),
 		defocus_angle: args.aperture.unwrap_or(input.camera.aperture),
 		focus_distance: args.focus.unwrap_or(default_focus_distance),
-		..Default::default()
 	};
-	let scene = Scene::from_objs(input.scene);
+	let scene = with_background(args, Scene::from_objs(input.scene));
 
 	(setup, scene)
 }
+
+/// Prepares the camera setup and scene of a demo.
+/// CLI arguments take precedence over the demo's camera setup.
+fn prepare_demo(args: &Args, description: SceneDescription) -> (CameraSetup, Scene) {
+	let (demo_setup, scene) = description.into_parts();
+	let (width, height) = args.image_size();
+	let setup = CameraSetup {
+		width,
+		height,
+		v_fov: args.fov.unwrap_or(demo_setup.v_fov),
+		lookfrom: args.center.unwrap_or(demo_setup.lookfrom),
+		lookat: args.target.unwrap_or(demo_setup.lookat),
+		view_up: demo_setup.view_up,
+		defocus_angle: args.aperture.unwrap_or(demo_setup.defocus_angle),
+		focus_distance: args.focus.unwrap_or(demo_setup.focus_distance),
+	};
+	(setup, with_background(args, scene))
+}
+
+/// Replaces the background of the `scene` with a gradient, if its colors are specified in `args`.
+/// A color that isn't specified is taken from the scene's background.
+fn with_background(args: &Args, scene: Scene) -> Scene {
+	if args.background_top.is_none() && args.background_bottom.is_none() {
+		return scene;
+	}
+	let (top, bottom) = match scene.background() {
+		Background::Gradient { top, bottom } => (top, bottom),
+		Background::Solid(color) => (color, color),
+	};
+	scene.with_background(Background::Gradient {
+		top: args.background_top.unwrap_or(top),
+		bottom: args.background_bottom.unwrap_or(bottom),
+	})
+}
+
+/// Creates the camera for rendering the `scene`.
+/// With a time budget (`--samples-auto`), the amount of samples is chosen to fit into the budget.
+fn build_camera(
+	args: &Args,
+	setup: CameraSetup,
+	scene: &Scene,
+	samples: u32,
+	bounces: u32,
+) -> Camera {
+	let mut camera = Camera::from(setup).bounces(bounces);
+	if let Some(limit) = args.max_memory {
+		camera = camera.with_memory_limit(limit);
+	}
+	let samples = match args.samples_auto {
+		Some(budget) => auto_samples(&camera, scene, budget),
+		None => samples,
+	};
+	camera.anti_aliasing(samples)
+}
+
+/// The largest amount of samples per pixel chosen by [`auto_samples`].
+const MAX_AUTO_SAMPLES: u32 = 10_000;
+
+/// Determines the largest amount of samples per pixel with which rendering the `scene` is estimated
+/// to take at most `budget` seconds. At least one and at most [`MAX_AUTO_SAMPLES`] samples are taken,
+/// even if the budget is exceeded or the render is too fast to be measured.
+fn auto_samples(camera: &Camera, scene: &Scene, budget: f64) -> u32 {
+	if budget <= 0.0 {
+		return 1;
+	}
+	let time_per_sample = camera.estimate_render_time(scene, 1).as_secs_f64();
+	if time_per_sample == 0.0 {
+		return MAX_AUTO_SAMPLES;
+	}
+	let samples = (budget / time_per_sample).floor();
+	samples.clamp(1.0, MAX_AUTO_SAMPLES as f64) as u32
+}
+
+/// Determines the amount of samples per pixel and bounces per ray.
+/// CLI arguments take precedence over the `suggested` values of the input or demo.
+fn render_params(args: &Args, suggested: (Option<u32>, Option<u32>)) -> (u32, u32) {
+	let defaults = Args::default();
+	let samples = args.samples.or(suggested.0).or(defaults.samples);
+	let bounces = args.bounces.or(suggested.1).or(defaults.bounces);
+	(samples.unwrap_or_default(), bounces.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+	use raytracer::input::RaytracerInput;
+	use raytracer::types::Vec3;
+
+	use std::fs;
+
+	use std::path::{Path, PathBuf};
+
+	use raytracer::camera::{Camera, CameraSetup};
+	use raytracer::objects::{Material, Sphere};
+	use raytracer::scene::{Background, Scene};
+	use raytracer::types::{Color, Point};
+
+	use super::{
+		MAX_AUTO_SAMPLES, auto_samples, frame_path, prepare, prepare_demo, render_params,
+		render_to_file, tile_path,
+	};
+	use crate::args::{Args, InputSource};
+	use crate::demo::AvailableDemo;
+
+	/// Arguments without any overrides for the values in the input.
+	fn args_without_overrides() -> Args {
+		Args {
+			center: None,
+			target: None,
+			aperture: None,
+			focus: None,
+			fov: None,
+			samples: None,
+			bounces: None,
+			..Default::default()
+		}
+	}
+
+	/// Input with the specified camera fields appended to the mandatory ones.
+	fn input_with_camera_fields(fields: &str) -> RaytracerInput {
+		let json = format!(
+			r#"{{
+				"camera": {{
+					"fov": 27.0,
+					"source": [0.0, 0.0, -1.0],
+					"target": [0.0, 0.0, 0.0],
+					"aperture": 0.0,
+					"focusDistance": 0.0{}
+				}},
+				"scene": []
+			}}"#,
+			fields
+		);
+		RaytracerInput::try_from(json.as_str()).unwrap()
+	}
+
+	#[test]
+	fn if_view_up_in_input_and_no_override_then_setup_has_view_up() {
+		// This input specifies a view up vector along the x-axis:
+		let input = input_with_camera_fields(r#", "viewUp": [1, 0, 0]"#);
+		let args = args_without_overrides();
+
+		// The camera should use the vector from the input:
+		let (setup, _) = prepare(&args, input);
+		assert_eq!(
+			setup.view_up,
+			Vec3::new(1, 0, 0),
+			"view up vector should be taken from input"
+		);
+	}
+
+	#[test]
+	fn if_view_up_not_in_input_then_setup_points_upwards() {
+		// This input does not specify a view up vector:
+		let input = input_with_camera_fields("");
+		let args = args_without_overrides();
+
+		// The camera should use the default vector (y-axis):
+		let (setup, _) = prepare(&args, input);
+		assert_eq!(
+			setup.view_up,
+			Vec3::new(0, 1, 0),
+			"view up vector should default to the y-axis"
+		);
+	}
+
+	#[test]
+	fn if_render_params_in_input_then_cli_args_take_precedence() {
+		// This input specifies samples and bounces:
+		let input = input_with_camera_fields(r#", "samples": 50, "bounces": 20"#);
+
+		// Without overrides, the values from the input should be used:
+		let args = args_without_overrides();
+		let params = render_params(&args, (input.camera.samples, input.camera.bounces));
+		assert_eq!(params, (50, 20), "values should be taken from input");
+
+		// With overrides, the CLI arguments should be used:
+		let args = Args {
+			samples: Some(5),
+			bounces: Some(2),
+			..args_without_overrides()
+		};
+		let params = render_params(&args, (input.camera.samples, input.camera.bounces));
+		assert_eq!(params, (5, 2), "values should be taken from CLI arguments");
+	}
+
+	#[test]
+	fn if_demo_and_no_overrides_then_setup_has_demo_camera() {
+		// This demo has its own camera setup:
+		let demo = AvailableDemo::Spheres;
+		let expected = demo.build().unwrap().setup();
+		let args = Args {
+			input: None,
+			demo: Some(demo.clone()),
+			width: Some(40),
+			height: Some(30),
+			..args_without_overrides()
+		};
+
+		// The camera should be placed as in the demo, with the size from the CLI arguments:
+		let (setup, _) = prepare_demo(&args, demo.build().unwrap());
+		assert_eq!(
+			(setup.lookfrom, setup.lookat, setup.v_fov),
+			(expected.lookfrom, expected.lookat, expected.v_fov),
+			"camera should be placed as in the demo"
+		);
+		assert_eq!(
+			(setup.width, setup.height),
+			(40, 30),
+			"size should be taken from CLI arguments"
+		);
+	}
+
+	#[test]
+	fn if_background_top_specified_then_bottom_kept() {
+		let args = Args {
+			background_top: Some(Color::new(1, 0, 0)),
+			..args_without_overrides()
+		};
+		let (_, scene) = prepare(&args, input_with_camera_fields(""));
+		assert_eq!(
+			scene.background(),
+			Background::Gradient {
+				top: Color::new(1, 0, 0),
+				bottom: Color::white(),
+			},
+			"top should be taken from CLI arguments, and bottom from the default sky"
+		);
+	}
+
+	#[test]
+	fn if_input_invalid_then_rerender_fails_without_output() {
+		let dir = std::env::temp_dir();
+		let input = dir.join(format!("raytracer-watch-{}.json", std::process::id()));
+		let output = dir.join(format!("raytracer-watch-{}.ppm", std::process::id()));
+		// This input was saved while being edited, and is not valid JSON:
+		fs::write(&input, r#"{"camera": {"fov": 27.0,"#).unwrap();
+		let args = Args {
+			input: Some(InputSource::File(input.clone())),
+			width: Some(2),
+			height: Some(2),
+			..Default::default()
+		};
+
+		// Rendering should report an error instead of panicking, and leave the output untouched:
+		let result = render_to_file(&args, &output);
+		let output_exists = output.exists();
+		fs::remove_file(&input).ok();
+		assert!(result.is_err(), "rendering should fail, but didn't");
+		assert!(!output_exists, "output should not be written");
+	}
+
+	#[test]
+	fn if_input_valid_then_rerender_writes_output() {
+		let dir = std::env::temp_dir();
+		let input = dir.join(format!("raytracer-rerender-{}.json", std::process::id()));
+		let output = dir.join(format!("raytracer-rerender-{}.ppm", std::process::id()));
+		fs::write(
+			&input,
+			r#"{
+				"camera": {
+					"fov": 27.0,
+					"source": [0.0, 0.0, -1.0],
+					"target": [0.0, 0.0, 0.0],
+					"aperture": 0.0,
+					"focusDistance": 1.0
+				},
+				"scene": []
+			}"#,
+		)
+		.unwrap();
+		let args = Args {
+			input: Some(InputSource::File(input.clone())),
+			width: Some(2),
+			height: Some(2),
+			samples: Some(1),
+			..Default::default()
+		};
+
+		let result = render_to_file(&args, &output);
+		let written = fs::read(&output);
+		fs::remove_file(&input).ok();
+		fs::remove_file(&output).ok();
+		assert!(result.is_ok(), "rendering should succeed, but didn't");
+		assert!(
+			written.is_ok_and(|bytes| bytes.starts_with(b"P6")),
+			"output should contain a PPM image"
+		);
+	}
+
+	#[test]
+	fn frame_paths_are_numbered_before_extension() {
+		assert_eq!(
+			frame_path(Path::new("out/image.ppm"), 12),
+			PathBuf::from("out/image-0012.ppm"),
+			"frame number should be inserted before the extension"
+		);
+		assert_eq!(
+			frame_path(Path::new("image"), 3),
+			PathBuf::from("image-0003"),
+			"frame number should be appended without an extension"
+		);
+	}
+
+	#[test]
+	fn tile_paths_are_numbered_before_extension() {
+		assert_eq!(
+			tile_path(Path::new("out/image.ppm"), 2),
+			PathBuf::from("out/image_2.ppm"),
+			"tile index should be inserted before the extension"
+		);
+	}
+
+	/// Creates a small camera, and a scene with a single sphere in front of it.
+	fn small_setup() -> (Camera, Scene) {
+		let setup = CameraSetup {
+			width: 32,
+			height: 18,
+			..Default::default()
+		};
+		let scene = Scene::from([Sphere::new(
+			Point::new(0, 0, -2),
+			1.0,
+			Material::Matte {
+				color: Color::new(0.5, 0.5, 0.5),
+			},
+		)]);
+		(Camera::from(setup).bounces(5), scene)
+	}
+
+	#[test]
+	fn if_time_budget_zero_then_one_sample() {
+		let (camera, scene) = small_setup();
+
+		// Even without any time, at least one sample has to be taken:
+		let samples = auto_samples(&camera, &scene, 0.0);
+		assert_eq!(samples, 1, "should take a single sample");
+	}
+
+	#[test]
+	fn if_time_budget_large_then_many_samples() {
+		let (camera, scene) = small_setup();
+
+		// A small image renders many times within a minute:
+		let samples = auto_samples(&camera, &scene, 60.0);
+		assert!(
+			samples >= 10,
+			"should take at least 10 samples, but took {}",
+			samples
+		);
+	}
+
+	#[test]
+	fn if_time_budget_huge_then_samples_capped() {
+		let (camera, scene) = small_setup();
+
+		// A year is enough for far more samples than are useful:
+		let samples = auto_samples(&camera, &scene, 365.0 * 24.0 * 3600.0);
+		assert_eq!(
+			samples, MAX_AUTO_SAMPLES,
+			"should take the maximum amount of samples"
+		);
+	}
+}
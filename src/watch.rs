@@ -0,0 +1,117 @@
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+
+/// Watches the file at `path`, and calls `on_change` whenever it has been modified.
+///
+/// Changes that occur within `debounce` of each other are handled as one, since editors
+/// often write a file in several steps. This function blocks for as long as the watcher runs.
+pub fn watch<F: FnMut()>(path: &Path, debounce: Duration, on_change: F) -> notify::Result<()> {
+	let (sender, receiver) = mpsc::channel();
+	let mut watcher = notify::recommended_watcher(sender)?;
+	// Editors may replace the file instead of modifying it, so the parent directory is watched
+	let parent = match path.parent() {
+		Some(parent) if !parent.as_os_str().is_empty() => parent,
+		_ => Path::new("."),
+	};
+	watcher.watch(parent, RecursiveMode::NonRecursive)?;
+	handle_events(&receiver, path, debounce, on_change);
+	Ok(())
+}
+
+/// Receives filesystem events until the sender is dropped, and calls `on_change`
+/// once the events concerning the file at `path` have settled for `debounce`.
+fn handle_events<F: FnMut()>(
+	receiver: &Receiver<notify::Result<Event>>,
+	path: &Path,
+	debounce: Duration,
+	mut on_change: F,
+) {
+	while let Ok(event) = receiver.recv() {
+		if !is_change_of(&event, path) {
+			continue;
+		}
+		// Wait until no further events arrive for the debounce duration
+		loop {
+			match receiver.recv_timeout(debounce) {
+				Ok(_) => continue,
+				Err(RecvTimeoutError::Timeout) => break,
+				Err(RecvTimeoutError::Disconnected) => {
+					on_change();
+					return;
+				}
+			}
+		}
+		on_change();
+	}
+}
+
+/// Checks if the event is a modification or creation of the file at `path`.
+fn is_change_of(event: &notify::Result<Event>, path: &Path) -> bool {
+	let Ok(event) = event else {
+		return false;
+	};
+	let is_change = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_));
+	is_change
+		&& event
+			.paths
+			.iter()
+			.any(|p| p.file_name() == path.file_name())
+}
+
+#[cfg(test)]
+mod tests {
+	use std::path::Path;
+	use std::sync::mpsc;
+	use std::time::Duration;
+
+	use notify::event::{AccessKind, ModifyKind};
+	use notify::{Event, EventKind};
+
+	use super::handle_events;
+
+	#[test]
+	fn if_file_modified_then_callback_invoked_once() {
+		let path = Path::new("scene.json");
+		let (sender, receiver) = mpsc::channel();
+
+		// These events modify the file several times in quick succession:
+		for _ in 0..3 {
+			let event = Event::new(EventKind::Modify(ModifyKind::Any)).add_path(path.into());
+			sender.send(Ok(event)).unwrap();
+		}
+		drop(sender);
+
+		// The changes should be handled together:
+		let mut calls = 0;
+		handle_events(&receiver, path, Duration::from_millis(10), || calls += 1);
+		assert_eq!(
+			calls, 1,
+			"callback should be invoked once, but was {}",
+			calls
+		);
+	}
+
+	#[test]
+	fn if_other_file_or_access_then_callback_not_invoked() {
+		let path = Path::new("scene.json");
+		let (sender, receiver) = mpsc::channel();
+
+		// These events don't modify the watched file:
+		let other = Event::new(EventKind::Modify(ModifyKind::Any)).add_path("other.json".into());
+		let access = Event::new(EventKind::Access(AccessKind::Any)).add_path(path.into());
+		sender.send(Ok(other)).unwrap();
+		sender.send(Ok(access)).unwrap();
+		drop(sender);
+
+		let mut calls = 0;
+		handle_events(&receiver, path, Duration::from_millis(10), || calls += 1);
+		assert_eq!(
+			calls, 0,
+			"callback should not be invoked, but was {}",
+			calls
+		);
+	}
+}
@@ -0,0 +1,48 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+#[test]
+fn if_input_is_stdin_then_image_is_written_to_stdout() {
+	// This input has a camera and no objects:
+	let json = r#"{
+		"camera": {
+			"fov": 90.0,
+			"source": [0.0, 0.0, 1.0],
+			"target": [0.0, 0.0, 0.0],
+			"aperture": 0.0,
+			"focusDistance": 1.0
+		},
+		"scene": []
+	}"#;
+
+	let mut child = Command::new(env!("CARGO_BIN_EXE_raytracer"))
+		.args(["--input", "-", "-w=1", "-h=1", "-s=1", "-o", "-"])
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::null())
+		.spawn()
+		.expect("binary should start");
+	child
+		.stdin
+		.take()
+		.unwrap()
+		.write_all(json.as_bytes())
+		.unwrap();
+	let output = child.wait_with_output().unwrap();
+
+	// A 1x1 binary PPM has a header followed by three bytes:
+	assert!(
+		output.status.success(),
+		"raytracer should exit successfully"
+	);
+	assert!(
+		output.stdout.starts_with(b"P6\n1 1\n255\n"),
+		"output should be a 1x1 PPM image, but was {:?}",
+		String::from_utf8_lossy(&output.stdout)
+	);
+	assert_eq!(
+		output.stdout.len(),
+		b"P6\n1 1\n255\n".len() + 3,
+		"output should contain exactly one pixel"
+	);
+}